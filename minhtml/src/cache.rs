@@ -0,0 +1,189 @@
+use crate::progress::report;
+use minify_html::cfg_options;
+use minify_html::Cfg;
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+// Increment when the format changes, so that older caches are regenerated instead of misread.
+const HEADER: &str = "minhtml-cache 1";
+
+// 64-bit FNV-1a, which is stable across platforms and Rust versions unlike `DefaultHasher`.
+pub fn hash(bytes: &[u8]) -> u64 {
+  bytes.iter().fold(0xcbf29ce484222325, |h, &b| {
+    (h ^ b as u64).wrapping_mul(0x100000001b3)
+  })
+}
+
+macro_rules! fingerprint {
+  ($out:ident, $($field:ident),* $(,)?) => {
+    $(
+      let _ = writeln!($out, "{}={:?}", stringify!($field), $field);
+    )*
+  };
+}
+
+macro_rules! cfg_fingerprint {
+  (bool: [$($bool:ident,)*], other: [$($other:ident,)*],) => {
+    // Returns a description of every option that affects the output, so that cached results are discarded when any of them or the minifier version change.
+    fn cfg_fingerprint(cfg: &Cfg) -> String {
+      // Destructure without `..` so that this fails to compile when an option that isn't a boolean is added but not handled here.
+      let Cfg {
+        $($bool: _,)*
+        base_url,
+        // Visitors can't be provided to the CLI.
+        element_visitor: _,
+        framework_binding_attr_prefixes,
+        html_attributes,
+        ignored_region_markers,
+        keep_bangs_matching,
+        keep_processing_instructions_matching,
+        max_depth,
+        never_unquote_attributes,
+        normalize_line_endings,
+        output_encoding,
+        remove_data_attrs,
+        remove_elements,
+        text_visitor: _,
+        void_self_closing_style,
+        whitespace_mode,
+      } = cfg;
+      // Sets are unordered.
+      let mut never_unquote_attributes = never_unquote_attributes.iter().collect::<Vec<_>>();
+      never_unquote_attributes.sort();
+      let mut out = format!("minhtml {}\n", env!("CARGO_PKG_VERSION"));
+      $(
+        let _ = writeln!(out, "{}={:?}", stringify!($bool), cfg.$bool);
+      )*
+      fingerprint!(
+        out,
+        base_url,
+        framework_binding_attr_prefixes,
+        html_attributes,
+        ignored_region_markers,
+        keep_bangs_matching,
+        keep_processing_instructions_matching,
+        max_depth,
+        never_unquote_attributes,
+        normalize_line_endings,
+        output_encoding,
+        remove_data_attrs,
+        remove_elements,
+        void_self_closing_style,
+        whitespace_mode,
+      );
+      out
+    }
+  };
+}
+
+cfg_options!(cfg_fingerprint!());
+
+// Each line after the header is the hash of the configuration, the input, and the output, in hexadecimal.
+fn parse_entries(src: &str, cfg_hash: u64) -> Option<HashMap<u64, u64>> {
+  let mut lines = src.lines();
+  if lines.next()? != HEADER {
+    return None;
+  };
+  let mut outputs = HashMap::new();
+  for line in lines {
+    let mut hashes = line.split(' ').map(|h| u64::from_str_radix(h, 16));
+    match (hashes.next(), hashes.next(), hashes.next(), hashes.next()) {
+      (Some(Ok(cfg)), Some(Ok(input)), Some(Ok(output)), None) => {
+        if cfg == cfg_hash {
+          outputs.insert(output, input);
+        };
+      }
+      _ => return None,
+    };
+  }
+  Some(outputs)
+}
+
+// Records the hash of each minified file, so that files minified in place by a previous run with the same configuration can be skipped.
+pub struct Cache {
+  path: PathBuf,
+  cfg_hash: u64,
+  // Input hashes from the previous run, keyed by output hash.
+  previous: HashMap<u64, u64>,
+  // Input and output hashes of files minified or skipped in this run.
+  entries: Mutex<Vec<(u64, u64)>>,
+  hits: AtomicUsize,
+}
+
+impl Cache {
+  // Loads the cache at `path`, or starts an empty one if it doesn't exist, is corrupt, or is from an incompatible version.
+  pub fn load(path: &Path, cfg: &Cfg) -> Cache {
+    let cfg_hash = hash(cfg_fingerprint(cfg).as_bytes());
+    let previous = match fs::read_to_string(path) {
+      Ok(src) => parse_entries(&src, cfg_hash).unwrap_or_else(|| {
+        report(format!(
+          "[{}] Cache is corrupt or from an incompatible version, so it will be regenerated",
+          path.display()
+        ));
+        HashMap::new()
+      }),
+      Err(e) if e.kind() == ErrorKind::NotFound => HashMap::new(),
+      Err(e) => {
+        report(format!(
+          "[{}] Could not read cache, so it will be regenerated: {}",
+          path.display(),
+          e
+        ));
+        HashMap::new()
+      }
+    };
+    Cache {
+      path: path.to_path_buf(),
+      cfg_hash,
+      previous,
+      entries: Mutex::new(Vec::new()),
+      hits: AtomicUsize::new(0),
+    }
+  }
+
+  // Returns true if `current` is the output of a file minified in place by the previous run, in which case it's kept in the cache.
+  pub fn is_unchanged(&self, current: u64) -> bool {
+    match self.previous.get(&current) {
+      Some(&input) => {
+        self.insert(input, current);
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        true
+      }
+      None => false,
+    }
+  }
+
+  pub fn insert(&self, input: u64, output: u64) {
+    if let Ok(mut entries) = self.entries.lock() {
+      entries.push((input, output));
+    };
+  }
+
+  pub fn hits(&self) -> usize {
+    self.hits.load(Ordering::Relaxed)
+  }
+
+  // Replaces the cache with the files minified or skipped in this run. Entries for other files are dropped, so the cache doesn't grow forever.
+  pub fn save(&self) -> std::io::Result<()> {
+    let mut out = format!("{}\n", HEADER);
+    if let Ok(mut entries) = self.entries.lock() {
+      // Files are minified concurrently, so sort to write the same cache regardless of the number of threads.
+      entries.sort_unstable();
+      for (input, output) in entries.iter() {
+        let _ = writeln!(out, "{:x} {:x} {:x}", self.cfg_hash, input, output);
+      }
+    };
+    // Write to a temporary file first, so that an interrupted write doesn't leave a corrupt cache.
+    let mut tmp_path = self.path.clone().into_os_string();
+    tmp_path.push(".tmp");
+    fs::write(&tmp_path, out)?;
+    fs::rename(&tmp_path, &self.path)
+  }
+}
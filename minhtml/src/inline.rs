@@ -0,0 +1,199 @@
+use minify_html::parse;
+use minify_html::parse_with_cfg;
+use minify_html::Cfg;
+use minify_html::Document;
+use minify_html::ElementClosingTag;
+use minify_html::Namespace;
+use minify_html::NodeData;
+use std::fs;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+// Attributes that can be on an inlined `<link>`. Others, like `title` (which makes it an alternative stylesheet), `disabled`, or `integrity`, change how it's applied, so those links are left as is.
+const INLINABLE_LINK_ATTRS: &[&[u8]] = &[b"href", b"media", b"rel", b"type"];
+
+fn has_token(value: &[u8], token: &[u8]) -> bool {
+  value
+    .split(|c| c.is_ascii_whitespace())
+    .any(|t| t.eq_ignore_ascii_case(token))
+}
+
+/// Where --inline-css looks for stylesheets, and which of them it inlines.
+pub struct InlineCss {
+  /// The directory of the document, which relative hrefs are resolved against.
+  pub document_dir: PathBuf,
+  /// The directory that hrefs starting with `/` are resolved against. Stylesheets outside it are never inlined.
+  pub root_dir: PathBuf,
+  /// Stylesheets larger than this many bytes are left as links.
+  pub max_size: u64,
+}
+
+// Makes `path` absolute and removes `.` and `..` components without touching the file system, or returns `None` if `..` would go above the root.
+fn normalize(path: &Path) -> Option<PathBuf> {
+  let mut out = PathBuf::new();
+  for c in std::env::current_dir().ok()?.join(path).components() {
+    match c {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        if !out.pop() {
+          return None;
+        };
+      }
+      c => out.push(c),
+    };
+  }
+  Some(out)
+}
+
+// Returns whether `url` is resolved against the location of the stylesheet or document it's in, i.e. it isn't empty, a fragment, root-relative, protocol-relative, or absolute with a scheme.
+fn is_relative_url(url: &str) -> bool {
+  !url.is_empty()
+    && !url.starts_with(['/', '#'])
+    && url.find(':').is_none_or(|i| url[..i].contains('/'))
+}
+
+// Returns whether the CSS has a `url(...)` or `@import` with a relative URL, which would resolve differently once the CSS is moved into the document. This is conservative: for example, it also looks inside comments.
+fn has_relative_reference(css: &[u8]) -> bool {
+  let lower = css.to_ascii_lowercase();
+  (0..lower.len()).any(|i| {
+    let (rest, allow_unquoted) = if lower[i..].starts_with(b"url(") {
+      (&css[i + 4..], true)
+    } else if lower[i..].starts_with(b"@import") {
+      (&css[i + 7..], false)
+    } else {
+      return false;
+    };
+    let rest = &rest[rest
+      .iter()
+      .position(|c| !c.is_ascii_whitespace())
+      .unwrap_or(rest.len())..];
+    let url = match rest.first() {
+      Some(&q @ (b'"' | b'\'')) => {
+        let rest = &rest[1..];
+        &rest[..rest.iter().position(|&c| c == q).unwrap_or(rest.len())]
+      }
+      // `@import url(...)` is checked when the loop reaches `url(`.
+      _ if !allow_unquoted => return false,
+      _ => {
+        &rest[..rest
+          .iter()
+          .position(|&c| c == b')' || c.is_ascii_whitespace())
+          .unwrap_or(rest.len())]
+      }
+    };
+    // Treat URLs that aren't UTF-8 as relative, to be safe.
+    std::str::from_utf8(url)
+      .ok()
+      .is_none_or(|url| is_relative_url(url.trim()))
+  })
+}
+
+// Returns the path of the local file referenced by `href`, or `None` if it's a remote or data URL, or refers to a file outside the root directory. Relative hrefs are resolved against the document's directory, and hrefs starting with `/` against the root directory.
+fn local_stylesheet_path(opts: &InlineCss, href: &[u8]) -> Option<PathBuf> {
+  let href = std::str::from_utf8(href).ok()?.trim();
+  let end = href.find(['?', '#']).unwrap_or(href.len());
+  let href = &href[..end];
+  if href.is_empty() || href.starts_with("//") || href.contains('\\') {
+    return None;
+  };
+  let path = match href.strip_prefix('/') {
+    Some(rel) => opts.root_dir.join(rel),
+    None if is_relative_url(href) => opts.document_dir.join(href),
+    None => return None,
+  };
+  normalize(&path).filter(|p| p.starts_with(&opts.root_dir))
+}
+
+// Reads the stylesheet at `href` if it's a local file that's small enough, and moving its content into the document doesn't change what its relative URLs refer to.
+fn read_stylesheet(opts: &InlineCss, href: &[u8]) -> Option<Vec<u8>> {
+  let path = local_stylesheet_path(opts, href)?;
+  if fs::metadata(&path).ok()?.len() > opts.max_size {
+    return None;
+  };
+  let css = fs::read(&path).ok()?;
+  // Relative URLs in a `<style>` are resolved against the document instead of the stylesheet, which only gives the same result if they're in the same directory.
+  if path.parent() != Some(opts.document_dir.as_path()) && has_relative_reference(&css) {
+    return None;
+  };
+  Some(css)
+}
+
+// Returns the children of a `<style>` element containing `css`, or `None` if the CSS would end the element early.
+fn style_children(css: &[u8]) -> Option<Vec<NodeData>> {
+  if css.windows(7).any(|w| w.eq_ignore_ascii_case(b"</style")) {
+    return None;
+  };
+  let mut src = b"<style>".to_vec();
+  src.extend_from_slice(css);
+  src.extend_from_slice(b"</style>");
+  match parse(&src).children_mut().pop()? {
+    NodeData::Element { children, .. } => Some(children),
+    _ => None,
+  }
+}
+
+// Replaces `node` with a `<style>` element if it's a `<link rel=stylesheet>` to a local file that can be inlined.
+fn inline_link(node: &mut NodeData, opts: &InlineCss) {
+  let (attributes, children, closing_tag, name, namespace) = match node {
+    NodeData::Element {
+      attributes,
+      children,
+      closing_tag,
+      name,
+      namespace,
+      ..
+    } => (attributes, children, closing_tag, name, namespace),
+    _ => return,
+  };
+  let is_stylesheet = *namespace == Namespace::Html
+    && name.as_slice() == b"link"
+    && attributes
+      .keys()
+      .all(|k| INLINABLE_LINK_ATTRS.contains(&k.as_slice()))
+    && attributes.get(b"rel".as_slice()).is_some_and(|v| {
+      has_token(v.as_slice(), b"stylesheet") && !has_token(v.as_slice(), b"alternate")
+    })
+    && attributes
+      .get(b"type".as_slice())
+      .is_none_or(|v| v.as_slice().eq_ignore_ascii_case(b"text/css"));
+  if !is_stylesheet {
+    return;
+  };
+  let style = attributes
+    .get(b"href".as_slice())
+    .and_then(|href| read_stylesheet(opts, href.as_slice()))
+    .and_then(|css| style_children(&css));
+  if let Some(style) = style {
+    attributes.retain(|k, _| k.as_slice() == b"media");
+    *children = style;
+    *closing_tag = ElementClosingTag::Present;
+    *name = b"style".to_vec();
+  };
+}
+
+fn inline_in(nodes: &mut [NodeData], opts: &InlineCss) {
+  for node in nodes.iter_mut() {
+    inline_link(node, opts);
+    if let NodeData::Element { children, .. } = node {
+      inline_in(children, opts);
+    };
+  }
+}
+
+/// Parses `src` and replaces each `<link rel=stylesheet>` to a small local file under the root directory with a `<style>` containing the file's contents. Links to remote URLs, files that can't be read, and files with relative URLs that would resolve differently from the document are left as is.
+pub fn inline_stylesheets(src: &[u8], cfg: &Cfg, opts: &InlineCss) -> Document {
+  let mut doc = parse_with_cfg(src, cfg);
+  // Paths are compared after normalisation, so `..` can't escape the root directory.
+  if let (Some(document_dir), Some(root_dir)) =
+    (normalize(&opts.document_dir), normalize(&opts.root_dir))
+  {
+    let opts = InlineCss {
+      document_dir,
+      root_dir,
+      max_size: opts.max_size,
+    };
+    inline_in(doc.children_mut(), &opts);
+  };
+  doc
+}
@@ -1,31 +1,291 @@
-use minify_html::minify;
+use crate::cache::Cache;
+use crate::inline::inline_stylesheets;
+use crate::inline::InlineCss;
+use crate::progress::report;
+use ignore::WalkBuilder;
+use minify_html::minify_css_str;
+use minify_html::minify_js_str;
+use minify_html::minify_streaming_from_reader;
+use minify_html::minify_with_stats;
+use minify_html::parse_with_cfg;
 use minify_html::Cfg;
+use minify_html::LineEnding;
+use minify_html::MinifyStats;
+use minify_html::OutputEncoding;
+use minify_html::Selector;
+use minify_html::SimpleSelector;
+use minify_html::VoidSelfClosingStyle;
+use minify_html::WhitespaceMode;
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
+use rayon::ThreadPoolBuilder;
+use std::collections::BTreeMap;
+use std::fs::remove_file;
+use std::fs::rename;
+use std::fs::set_permissions;
 use std::fs::File;
 use std::io::stdin;
 use std::io::stdout;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::BufWriter;
+use std::io::ErrorKind;
 use std::io::Read;
 use std::io::Write;
+use std::panic::catch_unwind;
+use std::panic::resume_unwind;
+use std::panic::AssertUnwindSafe;
+use std::path::Path;
+use std::path::PathBuf;
 use std::process::exit;
+use std::sync::atomic::AtomicI32;
+use std::sync::atomic::Ordering;
+use std::sync::mpsc::channel;
 use std::sync::Arc;
+use std::thread;
 use structopt::StructOpt;
 
+mod cache;
+mod inline;
+mod progress;
+mod serve;
+#[cfg(test)]
+mod tests;
+
 #[derive(StructOpt)]
 #[structopt(
   name = "minhtml",
-  about = "Extremely fast and smart HTML + JS + CSS minifier"
+  about = "Extremely fast and smart HTML + JS + CSS minifier",
+  after_help = "EXIT CODES:
+    0    Success.
+    1    An input could not be read or an output could not be written.
+    2    The arguments are invalid.
+    3    Reserved for differences found when checking inputs.
+    4    An internal error occurred while minifying an input.
+If there are multiple failures, such as when minifying multiple inputs, the highest code is used."
 )]
 // WARNING: Keep descriptions in sync with Cfg.
 struct Cli {
-  /// Files to minify; omit for stdin. If more than one is provided, they will be parallel minified in place, and --output must be omitted.
+  /// Files to minify; omit for stdin. If more than one is provided, they will be parallel minified in place, and --output must be omitted. Files ending in `.css` or `.js` are minified as a standalone stylesheet or script if --minify-css or --minify-js is provided respectively. Directories are searched recursively for files ending in `.html` or `.htm`, as well as `.css` or `.js` if --minify-css or --minify-js is provided respectively, which are minified in place; hidden files and files ignored by `.gitignore` or `.ignore` files are skipped, but files provided directly are always minified.
   #[structopt(parse(from_os_str))]
   inputs: Vec<std::path::PathBuf>,
 
+  /// When searching directories provided as inputs, also minify hidden files and files in hidden directories.
+  #[structopt(long)]
+  hidden: bool,
+
+  /// Replace each `<link rel=stylesheet>` to a local file no larger than --inline-css-max-size with a `<style>` containing the file, which is minified if --minify-css is provided. Relative hrefs are resolved against the directory of the input, and hrefs starting with `/` against --base-dir. Links to remote URLs or files that can't be read, links with attributes other than `href`, `media`, `rel`, and `type`, and stylesheets in another directory with relative `url(...)` or `@import` references, are left as is. Any file under --base-dir that a link refers to is read and embedded in the output, so only use this with trusted HTML. Paths using `..` to go outside --base-dir are never inlined, but symlinks are followed.
+  #[structopt(long, conflicts_with_all = &["cache", "serve", "stdin-batch"])]
+  inline_css: bool,
+
+  /// The directory that stylesheet hrefs starting with `/` are relative to for --inline-css, and that inlined stylesheets must be in. Defaults to the directory of each input, or the current directory for stdin.
+  #[structopt(long, parse(from_os_str), requires = "inline-css")]
+  base_dir: Option<std::path::PathBuf>,
+
+  /// The largest stylesheet in bytes that --inline-css inlines.
+  #[structopt(long, default_value = "8192")]
+  inline_css_max_size: u64,
+
+  /// Insert `<meta charset=utf-8>` at the start of `<head>` if the document doesn't declare its character encoding with a `<meta charset>` or `<meta http-equiv=Content-Type>` element. Without one, browsers have to guess the encoding. Only use this if the source is UTF-8.
+  #[structopt(long)]
+  inject_charset_meta: bool,
+
+  /// Write soft hyphens (U+00AD) in text as `&shy;` instead of the raw character, which is invisible and can be mangled by tools that process the output.
+  #[structopt(long)]
+  keep_shy_entity: bool,
+
+  /// Collapse runs of whitespace in comments that are kept, such as with `keep_comments`. SSI, conditional, and license comments (starting with `!` or containing `@license` or `@preserve`) are left as is.
+  #[structopt(long)]
+  minify_kept_comment_whitespace: bool,
+
+  /// Remove attributes of SVG elements that are equal to their default values, such as `opacity="1"` or `x="0"` on a `<rect>`. Only well-known defaults of attributes that are not inherited from ancestors are removed.
+  #[structopt(long)]
+  minify_svg_default_attributes: bool,
+
+  /// When searching directories provided as inputs, don't skip files ignored by `.gitignore`, `.ignore`, and other ignore files.
+  #[structopt(long)]
+  no_ignore: bool,
+
+  /// Also minify in place each file listed in this file, or stdin if `-`, one path per line. Blank lines are ignored.
+  #[structopt(long, parse(from_os_str), conflicts_with_all = &["output", "selector", "serve", "stdin-batch"])]
+  files_from: Option<std::path::PathBuf>,
+
+  /// Replace the DOCTYPE with `<!doctype html>`, dropping any public and system identifiers, such as those of HTML 4.01 and XHTML DOCTYPEs. This switches pages that rely on them out of quirks or limited-quirks mode, which can change how they are rendered.
+  #[structopt(long)]
+  normalize_doctype_to_html5: bool,
+
+  /// Remove trailing zeros from numbers like `1.0` and empty entries in the `content` of `<meta name=viewport>`. Whitespace in it is always removed.
+  #[structopt(long)]
+  normalize_meta_viewport: bool,
+
+  /// When used with --files-from, paths are separated by NUL bytes instead of lines, like the output of `find -print0`.
+  #[structopt(long, requires = "files-from")]
+  null: bool,
+
+  /// When minifying files in place, record a hash of each minified file in this file, and skip files that haven't changed since they were minified by a previous run with the same options and version. The cache is regenerated if it's corrupt or from an incompatible version.
+  #[structopt(long, parse(from_os_str), conflicts_with_all = &["output", "selector", "serve", "stdin-batch", "streaming"])]
+  cache: Option<std::path::PathBuf>,
+
   /// Output destination; omit for stdout.
   #[structopt(short, long, parse(from_os_str))]
   output: Option<std::path::PathBuf>,
 
+  /// Always quote and never change whitespace in the values of framework binding attributes, like Vue's `:src` and `@click`, Angular's `*ngIf`, `[prop]`, and `(event)`, Svelte's `on:click`, and Alpine.js's `x-data`.
+  #[structopt(long)]
+  framework_bindings: bool,
+
+  /// Minify the value of an attribute containing an HTML fragment, such as `data-content`. Can be provided multiple times.
+  #[structopt(long, number_of_values = 1)]
+  html_attr: Vec<String>,
+
+  /// Keep comments whose trimmed content starts with `/`, like `<!-- /container -->`, which build tools emit to mark where an element ends.
+  #[structopt(long)]
+  keep_balancing_comments: bool,
+
+  /// Do not omit the `<head>` opening tag when it doesn't have attributes.
+  #[structopt(long)]
+  keep_head_opening_tag: bool,
+
+  /// Do not omit the `<html>` opening tag when it doesn't have attributes.
+  #[structopt(long)]
+  keep_html_opening_tag: bool,
+
+  /// Remove default ports and `.` path segments from absolute URLs in attributes like `href` and `src`, and lowercase their schemes and hosts.
+  #[structopt(long)]
+  normalize_urls: bool,
+
+  /// Convert every line ending in the output to `lf` or `crlf`, including inside `<pre>` and `<textarea>` elements, whose content will change byte for byte.
+  #[structopt(long, parse(try_from_str = parse_line_ending))]
+  normalize_line_endings: Option<LineEnding>,
+
+  /// Character encoding of the output: `utf-8`, `latin-1`, or `ascii-safe`. Characters that aren't in the encoding are written as numeric character references like `&#8364;` in text and attribute values, but are left as is in `<script>`, `<style>`, and comments.
+  #[structopt(long, default_value = "utf-8", parse(try_from_str = parse_output_encoding))]
+  output_encoding: OutputEncoding,
+
+  /// Omit the `<body>` opening tag when it doesn't have attributes and the spec allows it. Some parsers and tools expect `<body>` to be present.
+  #[structopt(long)]
+  omit_body_opening_tag: bool,
+
+  /// Output the content between a `<!-- htmlmin:ignore -->` comment and the next `<!-- htmlmin:endignore -->` comment as is, and remove both comments. If there is no end comment, everything after the start comment is output as is. Use `ignored_region_markers` to recognise different comments.
+  #[structopt(long)]
+  preserve_ignored_regions: bool,
+
+  /// Keep Knockout.js virtual element comments like `<!-- ko if: a -->` and `<!-- /ko -->`.
+  #[structopt(long)]
+  preserve_ko_comments: bool,
+
+  /// Never remove or normalize microdata attributes (`itemid`, `itemprop`, `itemref`, `itemscope`, `itemtype`), even if empty or matched by `--remove-attr`.
+  #[structopt(long)]
+  preserve_microdata_attributes: bool,
+
+  /// Keep everything up to and including the `<html>` start tag byte for byte, such as the doctype's case and the `<html>` element's attribute quoting, if only a doctype, comments, and whitespace precede it.
+  #[structopt(long)]
+  preserve_prolog: bool,
+
+  /// Remove `<meta name=generator>` tags, which are added by some site generators and CMSes but not used by browsers.
+  #[structopt(long)]
+  remove_generator_meta: bool,
+
+  /// Remove elements with a `hidden` attribute or a `style` attribute containing `display: none`, along with their descendants. `<template>` and `<datalist>` elements, elements with `hidden=until-found`, and elements that have an `id` or contain an element with one, such as an SVG sprite sheet, are never removed.
+  #[structopt(long)]
+  remove_hidden_elements: bool,
+
+  /// Remove `<script>` elements that load polyfills for old versions of Internet Explorer, like html5shiv, html5shim, respond.js, and selectivizr, based on the file name in `src`. Conditional comments that only contain such scripts are also removed, even if comments are kept.
+  #[structopt(long)]
+  remove_html5_shiv: bool,
+
+  /// Remove `async` and `defer` attributes on `<script>` elements where they have no effect, such as `defer` alongside `async` or on a module script, and both on inline classic scripts.
+  #[structopt(long)]
+  remove_redundant_script_attributes: bool,
+
+  /// Instead of the minified code, output each element in the minified code matching a CSS selector like `script[type="application/ld+json"]` or `head meta`, one per line. Only a single input is supported.
+  #[structopt(long)]
+  selector: Option<Selector>,
+
+  /// When used with --selector, output each matching element unminified and indented over multiple lines.
+  #[structopt(long, requires = "selector")]
+  pretty: bool,
+
+  /// Remove `type="module"` from `<script>` elements. WARNING: This changes module scripts into classic scripts, which are run immediately instead of deferred, in the global scope, in sloppy mode, and without support for `import`. Only enable this if every module script has been bundled into a classic script, e.g. by inlining its imports.
+  #[structopt(long)]
+  remove_type_module_attribute: bool,
+
+  /// Remove `id` attributes that aren't referenced within the document by a fragment like `href=#a` or `url(#a)`, an attribute like `for`, `headers`, or `aria-labelledby`, or any mention in a script, stylesheet, `style` attribute, or event handler attribute. IDs that are referenced from other documents, such as by links to `page.html#a`, or by scripts loaded with `src` will also be removed, so only use this if nothing outside the document refers to them.
+  #[structopt(long)]
+  remove_unreferenced_ids: bool,
+
+  /// Remove `<meta http-equiv=X-UA-Compatible>` tags, which are only used by Internet Explorer.
+  #[structopt(long)]
+  remove_x_ua_compatible_meta: bool,
+
+  /// Remove XML declarations like `<?xml version="1.0" encoding="UTF-8"?>` at the root of the document, which are common in XHTML files but meaningless in HTML, and put old versions of IE into quirks mode. The prolog is still kept as is with `preserve_prolog`.
+  #[structopt(long)]
+  remove_xml_declaration: bool,
+
+  /// Preserve whitespace inside elements with a `contenteditable` attribute whose value isn't `false`.
+  #[structopt(long)]
+  respect_contenteditable: bool,
+
+  /// Read newline-delimited JSON requests from stdin and write a JSON response for each to stdout, keeping the process running until stdin is closed. Each request can override the options provided as arguments. Requests are minified in parallel, so responses may be out of order. See the README for the protocol. No files can be provided.
+  #[structopt(long, conflicts_with_all = &["inputs", "output", "selector", "stdin-batch", "streaming"])]
+  serve: bool,
+
+  /// Don't minify `style` attribute values with the CSS minifier when `minify_css` is enabled, so only `<style>` contents are minified. Whitespace around them and trailing semicolons are still removed.
+  #[structopt(long)]
+  skip_style_attr_minification: bool,
+
+  /// Read documents from stdin and write the minified documents to stdout, each framed as its length in bytes in ASCII decimal digits, a line feed, and then exactly that many bytes. Documents are minified in parallel, but written in the order they were read, as soon as each one and all documents before it are done. Input ends at EOF after a complete document. No files can be provided.
+  #[structopt(long, conflicts_with_all = &["inputs", "output", "selector", "streaming"])]
+  stdin_batch: bool,
+
+  /// Read each input in chunks and write the output as it goes, so that huge files can be minified with bounded memory. Tags are never omitted and whitespace is only collapsed in this mode. Falls back to loading each input into memory if an option that requires it, like --minify-js, is also provided.
+  #[structopt(long, conflicts_with_all = &["selector", "stats"])]
+  streaming: bool,
+
+  /// Remove comments from `<script>` and `<style>` contents that aren't minified, such as when `minify_js` or `minify_css` is disabled, as well as `<!--` and `-->` wrappers like `strip_legacy_script_comments`. License comments like `/*! ... */` and source map comments are kept. Strings, template literals, regular expression literals, and CSS `url()` values are skipped, and code that can't be processed safely without fully parsing it, such as a `/` that could be either division or a regular expression, is left as is.
+  #[structopt(long)]
+  strip_code_comments: bool,
+
+  /// Remove legacy `<!--` and `-->` wrappers used to hide the contents of `<script>` and `<style>` from very old browsers.
+  #[structopt(long)]
+  strip_legacy_script_comments: bool,
+
+  /// When minifying multiple files in place, show a progress bar with the number of files done, throughput, and estimated time remaining on stderr instead of printing the name of each file to stdout. If stderr isn't a terminal, progress is printed as a line every few seconds instead.
+  #[structopt(long)]
+  progress: bool,
+
+  /// Print the number of bytes saved for each file to stderr.
+  #[structopt(long)]
+  stats: bool,
+
+  /// Trim whitespace at the start and end of text in `<option>` and `<button>` elements. Whitespace there is collapsed but kept by default, as it could be displayed.
+  #[structopt(long)]
+  trim_form_control_text: bool,
+
+  /// Replace `<div>` elements without attributes that only contain a single block element, and `<span>` elements without attributes that only contain a single inline element, with that element. This changes the document structure, so CSS selectors and scripts that depend on the wrappers, like `div > p` or `:nth-child`, may no longer match.
+  #[structopt(long)]
+  unwrap_redundant_wrappers: bool,
+
+  /// How the start tags of void elements like `<br>` are ended: `none` for `<br>`, `slash` for `<br/>`, or `space-slash` for `<br />`.
+  #[structopt(long, default_value = "none", parse(try_from_str = parse_void_self_closing_style))]
+  void_self_closing_style: VoidSelfClosingStyle,
+
+  /// How whitespace in text is minified: `default`, `aggressive` to also remove whitespace between sibling elements in any element that isn't inline, like `<p>` or `<li>`, even if it would be rendered, or `preserve` to keep all whitespace as is.
+  #[structopt(long, default_value = "default", parse(try_from_str = parse_whitespace_mode))]
+  whitespace_mode: WhitespaceMode,
+
+  /// Add an `as` attribute to `<link rel=preload>` elements that are missing one, inferred from the file extension of the `href`, such as `script` for `.js` or `font` for `.woff2`. Browsers fetch preloads without `as` twice, as they can't be matched with the request that uses them. Links with an unknown extension are left as is.
+  #[structopt(long)]
+  validate_preload_links: bool,
+
+  /// When used with --stats, also print a breakdown of which optimizations were applied. When used with --progress, also print the name of each file minified.
+  #[structopt(long)]
+  verbose: bool,
+
+  /// Maximum number of threads to use when minifying multiple inputs or with --serve or --stdin-batch; omit or use 0 for one per CPU core.
+  #[structopt(long, default_value = "0")]
+  threads: usize,
+
   /// Allow unquoted attribute values in the output to contain characters prohibited by the [WHATWG specification](https://html.spec.whatwg.org/multipage/syntax.html#attributes-2). These will still be parsed correctly by almost all browsers.
   #[structopt(long)]
   allow_noncompliant_unquoted_attribute_values: bool,
@@ -46,7 +306,7 @@ struct Cli {
   #[structopt(long)]
   keep_comments: bool,
 
-  /// Do not omit `<html>` and `<head>` opening tags when they don't have attributes.
+  /// Do not omit `<html>` and `<head>` opening tags when they don't have attributes. This is a shorthand for enabling both --keep-html-opening-tag and --keep-head-opening-tag.
   #[structopt(long)]
   keep_html_and_head_opening_tags: bool,
 
@@ -72,6 +332,8 @@ struct Cli {
   /// Only `<script>` tags with a valid or no
   /// [MIME type](https://mimesniff.spec.whatwg.org/#javascript-mime-type) is considered to
   /// contain JavaScript, as per the specification.
+  ///
+  /// The order of some declarations in its output depends on hash seeds, so the output of scripts can differ between processes.
   #[structopt(long)]
   minify_js: bool,
 
@@ -83,13 +345,44 @@ struct Cli {
   #[structopt(long)]
   preserve_chevron_percent_template_syntax: bool,
 
-  /// Remove all bangs.
+  /// Remove bangs like `<!FOO>`, except those kept by `--keep-bang`. Doctypes and CDATA sections in SVG and MathML are never removed.
   #[structopt(long)]
   remove_bangs: bool,
 
-  /// Remove all processing instructions.
+  /// Keep bangs whose content after `<!` starts with this prefix, such as `[if`, when removing bangs. Can be provided multiple times.
+  #[structopt(long, number_of_values = 1)]
+  keep_bang: Vec<String>,
+
+  /// Remove an attribute from every element, such as `data-testid`. End the name with `*` to remove all attributes starting with a prefix, e.g. `data-test*`. Can be provided multiple times.
+  #[structopt(long, number_of_values = 1)]
+  remove_attr: Vec<String>,
+
+  /// Remove elements matching a simple selector, along with their contents, e.g. `script[data-env=dev]`, `.debug`, or `#banner`. Combinators are not supported. Can be provided multiple times.
+  #[structopt(long, number_of_values = 1)]
+  remove_element: Vec<SimpleSelector>,
+
+  /// Always quote the values of an attribute, such as `content` or `:class`, even when unquoted would be shorter. Can be provided multiple times.
+  #[structopt(long, number_of_values = 1)]
+  never_unquote_attr: Vec<String>,
+
+  /// Remove all processing instructions, except those kept by `--keep-processing-instruction`.
   #[structopt(long)]
   remove_processing_instructions: bool,
+
+  /// Keep processing instructions with this target, such as `xml-stylesheet`, when removing processing instructions. Can be provided multiple times.
+  #[structopt(long, number_of_values = 1)]
+  keep_processing_instruction: Vec<String>,
+}
+
+const EXIT_IO_ERROR: i32 = 1;
+const EXIT_USAGE_ERROR: i32 = 2;
+const EXIT_INTERNAL_ERROR: i32 = 4;
+
+// The exit code for the most severe failure so far, which is shared by parallel workers.
+static EXIT_CODE: AtomicI32 = AtomicI32::new(0);
+
+fn record_failure(code: i32) {
+  EXIT_CODE.fetch_max(code, Ordering::Relaxed);
 }
 
 macro_rules! io_expect {
@@ -97,40 +390,522 @@ macro_rules! io_expect {
     match $expr {
       Ok(r) => r,
       Err(e) => {
-        eprintln!("[{}] {}: {}", $name, $msg, e);
+        report(format!("[{}] {}: {}", $name, $msg, e));
+        record_failure(EXIT_IO_ERROR);
         return;
       }
     }
   };
 }
 
+// Minification shouldn't panic on any input, but if it does, report it and continue with other inputs instead of aborting them all.
+fn catch_internal_error<T>(input_name: &str, f: impl FnOnce() -> T) -> Option<T> {
+  match catch_unwind(AssertUnwindSafe(f)) {
+    Ok(r) => Some(r),
+    Err(_) => {
+      report(format!("[{}] Internal error while minifying", input_name));
+      record_failure(EXIT_INTERNAL_ERROR);
+      None
+    }
+  }
+}
+
+fn parse_output_encoding(src: &str) -> Result<OutputEncoding, String> {
+  match src.to_ascii_lowercase().as_str() {
+    "utf-8" => Ok(OutputEncoding::Utf8),
+    "latin-1" => Ok(OutputEncoding::Latin1),
+    "ascii-safe" => Ok(OutputEncoding::AsciiSafe),
+    _ => Err(format!(
+      "unknown output encoding {}, expected utf-8, latin-1, or ascii-safe",
+      src
+    )),
+  }
+}
+
+fn parse_line_ending(src: &str) -> Result<LineEnding, String> {
+  match src.to_ascii_lowercase().as_str() {
+    "lf" => Ok(LineEnding::Lf),
+    "crlf" => Ok(LineEnding::CrLf),
+    _ => Err(format!("unknown line ending {}, expected lf or crlf", src)),
+  }
+}
+
+fn parse_void_self_closing_style(src: &str) -> Result<VoidSelfClosingStyle, String> {
+  match src.to_ascii_lowercase().as_str() {
+    "none" => Ok(VoidSelfClosingStyle::None),
+    "slash" => Ok(VoidSelfClosingStyle::Slash),
+    "space-slash" => Ok(VoidSelfClosingStyle::SpaceSlash),
+    _ => Err(format!(
+      "unknown void self closing style {}, expected none, slash, or space-slash",
+      src
+    )),
+  }
+}
+
+fn parse_whitespace_mode(src: &str) -> Result<WhitespaceMode, String> {
+  match src.to_ascii_lowercase().as_str() {
+    "default" => Ok(WhitespaceMode::Default),
+    "aggressive" => Ok(WhitespaceMode::Aggressive),
+    "preserve" => Ok(WhitespaceMode::Preserve),
+    _ => Err(format!(
+      "unknown whitespace mode {}, expected default, aggressive, or preserve",
+      src
+    )),
+  }
+}
+
+fn print_stats(input_name: &str, stats: &MinifyStats, verbose: bool) {
+  report(format!(
+    "[{}] {} -> {} bytes ({:.2}% saved)",
+    input_name, stats.input_bytes, stats.output_bytes, stats.percent_saved
+  ));
+  if verbose {
+    report(format!("  Changed: {}", stats.changed));
+    report(format!("  Elements minified: {}", stats.elements_minified));
+    report(format!(
+      "  Whitespace bytes removed: {}",
+      stats.whitespace_bytes_removed
+    ));
+    report(format!(
+      "  Opening tags omitted: {}",
+      stats.opening_tags_omitted
+    ));
+    report(format!(
+      "  Closing tags omitted: {}",
+      stats.closing_tags_omitted
+    ));
+    report(format!(
+      "  Attributes removed: {}",
+      stats.attributes_removed
+    ));
+    report(format!(
+      "  Attributes minified: {}",
+      stats.attributes_minified
+    ));
+    report(format!(
+      "  Attributes unquoted: {}",
+      stats.attributes_unquoted
+    ));
+    report(format!("  Comments removed: {}", stats.comments_removed));
+    report(format!("  CSS bytes saved: {}", stats.css_bytes_saved));
+    report(format!("  JS bytes saved: {}", stats.js_bytes_saved));
+  };
+}
+
+// Returns "css" or "js" if the input is a standalone stylesheet or script based on its file extension.
+fn standalone_code_ext(path: Option<&Path>) -> Option<&'static str> {
+  let ext = path?.extension()?.to_ascii_lowercase();
+  match ext.to_str()? {
+    "css" => Some("css"),
+    "js" => Some("js"),
+    _ => None,
+  }
+}
+
+// Returns where --inline-css looks for stylesheets linked from `input`, or `None` if it isn't enabled.
+fn inline_css_opts(args: &Cli, input: Option<&Path>) -> Option<InlineCss> {
+  if !args.inline_css {
+    return None;
+  };
+  let document_dir = input
+    .and_then(Path::parent)
+    .map(Path::to_path_buf)
+    .unwrap_or_default();
+  Some(InlineCss {
+    root_dir: args
+      .base_dir
+      .clone()
+      .unwrap_or_else(|| document_dir.clone()),
+    document_dir,
+    max_size: args.inline_css_max_size,
+  })
+}
+
+fn minify_input(
+  path: Option<&Path>,
+  src: &[u8],
+  cfg: &Cfg,
+  inline_css: Option<&InlineCss>,
+) -> (Vec<u8>, MinifyStats) {
+  let out = match (standalone_code_ext(path), inline_css) {
+    (Some("css"), _) => minify_css_str(src, cfg),
+    (Some(_), _) => minify_js_str(src, cfg),
+    (None, Some(opts)) => {
+      let (out, mut stats) = inline_stylesheets(src, cfg, opts).serialize_minified(cfg);
+      // The document doesn't keep its source, so compare it here.
+      stats.changed = out != src;
+      return (out, stats);
+    }
+    (None, None) => return minify_with_stats(src, cfg),
+  };
+  let bytes_saved = src.len().saturating_sub(out.len());
+  let stats = MinifyStats {
+    input_bytes: src.len(),
+    output_bytes: out.len(),
+    bytes_saved,
+    percent_saved: if src.is_empty() {
+      0.0
+    } else {
+      bytes_saved as f64 * 100.0 / src.len() as f64
+    },
+    changed: out != src,
+    ..MinifyStats::default()
+  };
+  (out, stats)
+}
+
+// Reads the next document framed as described by --stdin-batch, or `None` if the input has ended cleanly.
+fn read_batch_frame<R: BufRead>(src: &mut R) -> std::io::Result<Option<Vec<u8>>> {
+  let mut header = Vec::new();
+  src.read_until(b'\n', &mut header)?;
+  if header.is_empty() {
+    return Ok(None);
+  };
+  let len = header
+    .strip_suffix(b"\n")
+    .filter(|digits| !digits.is_empty() && digits.iter().all(|c| c.is_ascii_digit()))
+    .and_then(|digits| std::str::from_utf8(digits).ok()?.parse::<usize>().ok())
+    .ok_or_else(|| std::io::Error::new(ErrorKind::InvalidData, "invalid batch frame header"))?;
+  let mut doc = Vec::new();
+  src.take(len as u64).read_to_end(&mut doc)?;
+  if doc.len() != len {
+    return Err(std::io::Error::new(
+      ErrorKind::UnexpectedEof,
+      "batch input ended in the middle of a document",
+    ));
+  };
+  Ok(Some(doc))
+}
+
+fn write_batch_frame<W: Write>(out: &mut W, doc: &[u8]) -> std::io::Result<()> {
+  writeln!(out, "{}", doc.len())?;
+  out.write_all(doc)
+}
+
+// Minifies each document read from stdin in parallel, writing them to stdout in their original order.
+fn minify_stdin_batch(cfg: &Arc<Cfg>, stats: bool, verbose: bool) -> std::io::Result<()> {
+  let (tx, rx) = channel::<(usize, Vec<u8>)>();
+  // Write on a separate thread, so that documents are output while later ones are still being read, which allows a caller to wait for each result before sending the next document.
+  let writer = thread::spawn(move || -> std::io::Result<()> {
+    let mut out = BufWriter::new(stdout());
+    let mut pending = BTreeMap::new();
+    let mut next = 0;
+    for (i, doc) in rx {
+      pending.insert(i, doc);
+      while let Some(doc) = pending.remove(&next) {
+        write_batch_frame(&mut out, &doc)?;
+        next += 1;
+      }
+      out.flush()?;
+    }
+    Ok(())
+  });
+  let mut src = BufReader::new(stdin());
+  let mut read_res = Ok(());
+  for i in 0.. {
+    let doc = match read_batch_frame(&mut src) {
+      Ok(Some(doc)) => doc,
+      Ok(None) => break,
+      Err(e) => {
+        read_res = Err(e);
+        break;
+      }
+    };
+    let cfg = cfg.clone();
+    let tx = tx.clone();
+    rayon::spawn(move || {
+      let name = format!("stdin #{}", i);
+      let out = match catch_internal_error(&name, || minify_with_stats(&doc, &cfg)) {
+        Some((out, doc_stats)) => {
+          if stats {
+            print_stats(&name, &doc_stats, verbose);
+          };
+          out
+        }
+        // Keep the framing intact by writing the document as is.
+        None => doc,
+      };
+      // This only fails if the writer has stopped due to an error, which is reported below.
+      let _ = tx.send((i, out));
+    });
+  }
+  // The writer stops once all senders, including those held by pending tasks, are dropped.
+  drop(tx);
+  let write_res = writer.join().unwrap_or_else(|e| resume_unwind(e));
+  read_res.and(write_res)
+}
+
+fn init_thread_pool(threads: usize) {
+  // A thread count of zero means rayon's default. This can only fail if the global pool has already been initialized, which nothing else does.
+  ThreadPoolBuilder::new()
+    .num_threads(threads)
+    .build_global()
+    .unwrap();
+}
+
+fn path_from_bytes(path: &[u8]) -> PathBuf {
+  #[cfg(unix)]
+  {
+    use std::os::unix::ffi::OsStrExt;
+    PathBuf::from(std::ffi::OsStr::from_bytes(path))
+  }
+  #[cfg(not(unix))]
+  {
+    PathBuf::from(String::from_utf8_lossy(path).into_owned())
+  }
+}
+
+// Reads the paths listed in the file provided to --files-from, skipping empty entries such as blank lines and the trailing newline.
+fn read_files_from(list: &Path, null: bool) -> std::io::Result<Vec<PathBuf>> {
+  let mut src = Vec::new();
+  if list == Path::new("-") {
+    stdin().read_to_end(&mut src)?;
+  } else {
+    File::open(list)?.read_to_end(&mut src)?;
+  };
+  let sep = if null { b'\0' } else { b'\n' };
+  Ok(
+    src
+      .split(|&c| c == sep)
+      // Allow files with Windows line endings.
+      .map(|p| {
+        if null {
+          p
+        } else {
+          p.strip_suffix(b"\r").unwrap_or(p)
+        }
+      })
+      .filter(|p| !p.is_empty())
+      .map(path_from_bytes)
+      .collect(),
+  )
+}
+
+// Returns the files to minify in a directory provided as an input and its subdirectories.
+fn walk_dir(dir: &Path, cfg: &Cfg, hidden: bool, no_ignore: bool) -> Vec<PathBuf> {
+  let mut paths = Vec::new();
+  let walker = WalkBuilder::new(dir)
+    .standard_filters(!no_ignore)
+    .hidden(!hidden)
+    // Respect .gitignore files even if the directory isn't in a Git repository.
+    .require_git(false)
+    .build();
+  for entry in walker {
+    let entry = match entry {
+      Ok(entry) => entry,
+      Err(e) => {
+        report(format!(
+          "[{}] Could not read directory: {}",
+          dir.display(),
+          e
+        ));
+        record_failure(EXIT_IO_ERROR);
+        continue;
+      }
+    };
+    if !entry.file_type().is_some_and(|t| t.is_file()) {
+      continue;
+    };
+    let path = entry.into_path();
+    let ext = path
+      .extension()
+      .and_then(|e| e.to_str())
+      .map(|e| e.to_ascii_lowercase());
+    let minifiable = match ext.as_deref() {
+      Some("html" | "htm") => true,
+      Some("css") => cfg.minify_css,
+      Some("js") => cfg.minify_js,
+      _ => false,
+    };
+    if minifiable {
+      paths.push(path);
+    };
+  }
+  paths
+}
+
+// Returns the elements in already minified code matching `selector`, one per line.
+fn select(code: &[u8], cfg: &Cfg, selector: &Selector, pretty: bool) -> Vec<u8> {
+  let doc = parse_with_cfg(code, cfg);
+  let mut out = Vec::new();
+  for node in doc.select_with(selector) {
+    if pretty {
+      out.extend_from_slice(&node.serialize_pretty());
+    } else {
+      out.extend_from_slice(&node.serialize(cfg));
+      out.push(b'\n');
+    };
+  }
+  out
+}
+
 fn main() {
-  let args = Cli::from_args();
-  if args.output.is_some() && args.inputs.len() > 1 {
-    eprintln!("Cannot provide --output when multiple inputs are provided.");
-    exit(1);
+  let args = match Cli::from_iter_safe(std::env::args_os()) {
+    Ok(args) => args,
+    // Help and version information are also returned as errors, but aren't written to stderr.
+    Err(e) if e.use_stderr() => {
+      eprintln!("{}", e.message);
+      exit(EXIT_USAGE_ERROR);
+    }
+    Err(e) => e.exit(),
   };
+  let has_dir = args.inputs.iter().any(|p| p.is_dir());
+  if args.output.is_some() && (args.inputs.len() > 1 || has_dir) {
+    eprintln!("Cannot provide --output when multiple inputs or a directory are provided.");
+    exit(EXIT_USAGE_ERROR);
+  };
+  if args.selector.is_some() && (args.inputs.len() > 1 || has_dir) {
+    eprintln!("Cannot provide --selector when multiple inputs or a directory are provided.");
+    exit(EXIT_USAGE_ERROR);
+  };
+  run(args);
+  exit(EXIT_CODE.load(Ordering::Relaxed));
+}
 
+fn run(mut args: Cli) {
+  // Inputs listed in a file are always minified in place, even if there's only one.
+  let mut in_place = args.inputs.len() > 1;
+  if let Some(list) = &args.files_from {
+    let list_name = list.to_string_lossy().into_owned();
+    let paths = io_expect!(
+      list_name,
+      read_files_from(list, args.null),
+      "Could not read list of files"
+    );
+    args.inputs.extend(paths);
+    in_place = true;
+  };
   #[rustfmt::skip]
-  let cfg = Arc::new(Cfg {
+  let mut cfg = Cfg {
     allow_noncompliant_unquoted_attribute_values: args.allow_noncompliant_unquoted_attribute_values,
     allow_optimal_entities: args.allow_optimal_entities,
     allow_removing_spaces_between_attributes: args.allow_removing_spaces_between_attributes,
+    base_url: None,
+    element_visitor: None,
+    framework_binding_attr_prefixes: Vec::new(),
+    ignored_region_markers: None,
+    html_attributes: args.html_attr.iter().map(|a| a.as_bytes().to_ascii_lowercase()).collect(),
+    inject_charset_meta: args.inject_charset_meta,
+    keep_balancing_comments: args.keep_balancing_comments,
+    keep_bangs_matching: args.keep_bang.iter().map(|p| p.as_bytes().to_vec()).collect(),
     keep_closing_tags: args.keep_closing_tags,
     keep_comments: args.keep_comments,
+    keep_head_opening_tag: args.keep_head_opening_tag,
     keep_html_and_head_opening_tags: args.keep_html_and_head_opening_tags,
+    keep_html_opening_tag: args.keep_html_opening_tag,
     keep_input_type_text_attr: args.keep_input_type_text_attr,
+    keep_processing_instructions_matching: args.keep_processing_instruction.iter().map(|t| t.as_bytes().to_vec()).collect(),
+    keep_shy_entity: args.keep_shy_entity,
     keep_ssi_comments: args.keep_ssi_comments,
+    max_depth: None,
     minify_css: args.minify_css,
     minify_doctype: args.minify_doctype,
     minify_js: args.minify_js,
+    minify_kept_comment_whitespace: args.minify_kept_comment_whitespace,
+    minify_svg_default_attributes: args.minify_svg_default_attributes,
+    never_unquote_attributes: args.never_unquote_attr.iter().map(|a| a.as_bytes().to_ascii_lowercase()).collect(),
+    normalize_doctype_to_html5: args.normalize_doctype_to_html5,
+    normalize_line_endings: args.normalize_line_endings,
+    normalize_meta_viewport: args.normalize_meta_viewport,
+    normalize_urls: args.normalize_urls,
+    omit_body_opening_tag: args.omit_body_opening_tag,
+    output_encoding: args.output_encoding,
     preserve_brace_template_syntax: args.preserve_brace_template_syntax,
     preserve_chevron_percent_template_syntax: args.preserve_chevron_percent_template_syntax,
+    preserve_ignored_regions: args.preserve_ignored_regions,
+    preserve_ko_comments: args.preserve_ko_comments,
+    preserve_microdata_attributes: args.preserve_microdata_attributes,
+    preserve_prolog: args.preserve_prolog,
     remove_bangs: args.remove_bangs,
+    remove_data_attrs: args.remove_attr.iter().map(|a| a.as_bytes().to_vec()).collect(),
+    remove_elements: args.remove_element.clone(),
+    remove_generator_meta: args.remove_generator_meta,
+    remove_hidden_elements: args.remove_hidden_elements,
+    remove_html5_shiv: args.remove_html5_shiv,
     remove_processing_instructions: args.remove_processing_instructions,
-  });
+    remove_redundant_script_attributes: args.remove_redundant_script_attributes,
+    remove_type_module_attribute: args.remove_type_module_attribute,
+    remove_unreferenced_ids: args.remove_unreferenced_ids,
+    remove_x_ua_compatible_meta: args.remove_x_ua_compatible_meta,
+    remove_xml_declaration: args.remove_xml_declaration,
+    respect_contenteditable: args.respect_contenteditable,
+    skip_style_attr_minification: args.skip_style_attr_minification,
+    strip_code_comments: args.strip_code_comments,
+    strip_legacy_script_comments: args.strip_legacy_script_comments,
+    trim_form_control_text: args.trim_form_control_text,
+    unwrap_redundant_wrappers: args.unwrap_redundant_wrappers,
+    validate_preload_links: args.validate_preload_links,
+    void_self_closing_style: args.void_self_closing_style,
+    whitespace_mode: args.whitespace_mode,
+    text_visitor: None,
+  };
+  if args.framework_bindings {
+    cfg.enable_framework_bindings();
+  };
+  let cfg = Arc::new(cfg);
+
+  // Files in directories are always minified in place, even if there's only one.
+  if args.inputs.iter().any(|p| p.is_dir()) {
+    let inputs = std::mem::take(&mut args.inputs);
+    for input in inputs {
+      if input.is_dir() {
+        args
+          .inputs
+          .extend(walk_dir(&input, &cfg, args.hidden, args.no_ignore));
+      } else {
+        args.inputs.push(input);
+      };
+    }
+    in_place = true;
+  };
 
-  if args.inputs.len() <= 1 {
+  let unsupported_when_streaming = [
+    ("--inline-css", args.inline_css),
+    ("--minify-css", args.minify_css),
+    ("--minify-js", args.minify_js),
+    (
+      "--preserve-brace-template-syntax",
+      args.preserve_brace_template_syntax,
+    ),
+    (
+      "--preserve-chevron-percent-template-syntax",
+      args.preserve_chevron_percent_template_syntax,
+    ),
+    ("--preserve-ignored-regions", args.preserve_ignored_regions),
+    (
+      "--normalize-line-endings",
+      args.normalize_line_endings.is_some(),
+    ),
+    ("--remove-element", !args.remove_element.is_empty()),
+    ("--remove-hidden-elements", args.remove_hidden_elements),
+    (
+      "--unwrap-redundant-wrappers",
+      args.unwrap_redundant_wrappers,
+    ),
+  ]
+  .iter()
+  .filter(|(_, enabled)| *enabled)
+  .map(|(name, _)| *name)
+  .collect::<Vec<_>>();
+  let streaming = args.streaming && unsupported_when_streaming.is_empty();
+  if args.streaming && !streaming {
+    eprintln!(
+      "Warning: {} cannot be used with --streaming, so inputs will be loaded into memory.",
+      unsupported_when_streaming.join(", ")
+    );
+  };
+
+  if args.serve {
+    init_thread_pool(args.threads);
+    io_expect!("stdin", serve::serve(&cfg), "Could not read requests");
+  } else if args.stdin_batch {
+    init_thread_pool(args.threads);
+    io_expect!(
+      "stdin",
+      minify_stdin_batch(&cfg, args.stats, args.verbose),
+      "Could not minify batch"
+    );
+  } else if !in_place {
     // Single file mode or stdin mode.
     let input_name = args
       .inputs
@@ -145,13 +920,45 @@ fn main() {
       )),
       None => Box::new(stdin()),
     };
+    let input_path = args.inputs.first().map(|p| p.as_path());
+    if streaming && standalone_code_ext(input_path).is_none() {
+      let out_file: Box<dyn Write> = match args.output {
+        Some(p) => Box::new(io_expect!(
+          input_name,
+          File::create(p),
+          "Could not open output file"
+        )),
+        None => Box::new(stdout()),
+      };
+      let mut out_file = BufWriter::new(out_file);
+      let res = match catch_internal_error(&input_name, || {
+        minify_streaming_from_reader(src_file, &cfg, &mut out_file).and_then(|_| out_file.flush())
+      }) {
+        Some(res) => res,
+        None => return,
+      };
+      io_expect!(input_name, res, "Could not minify source code");
+      return;
+    };
     let mut src_code = Vec::<u8>::new();
     io_expect!(
       input_name,
       src_file.read_to_end(&mut src_code),
       "Could not load source code"
     );
-    let out_code = minify(&src_code, &cfg);
+    let inline_css = inline_css_opts(&args, input_path);
+    let (mut out_code, stats) = match catch_internal_error(&input_name, || {
+      minify_input(input_path, &src_code, &cfg, inline_css.as_ref())
+    }) {
+      Some(r) => r,
+      None => return,
+    };
+    if args.stats {
+      print_stats(&input_name, &stats, args.verbose);
+    };
+    if let Some(selector) = &args.selector {
+      out_code = select(&out_code, &cfg, selector, args.pretty);
+    };
     let mut out_file: Box<dyn Write> = match args.output {
       Some(p) => Box::new(io_expect!(
         input_name,
@@ -166,29 +973,151 @@ fn main() {
       "Could not save minified code"
     );
   } else {
+    init_thread_pool(args.threads);
+    let progress = args.progress.then(|| progress::start(args.inputs.len()));
+    let print_names = !args.progress || args.verbose;
+    let cache = args.cache.as_deref().map(|p| Cache::load(p, &cfg));
     args.inputs.par_iter().for_each(|input| {
-      let input_name = input.to_string_lossy().into_owned();
-
-      let mut src_file = io_expect!(input_name, File::open(input), "Could not open source file");
-      let mut src_code = Vec::<u8>::new();
-      io_expect!(
-        input_name,
-        src_file.read_to_end(&mut src_code),
-        "Could not load source code"
-      );
-      let out_code = minify(&src_code, &cfg);
-      let mut out_file = io_expect!(
-        input_name,
-        File::create(input),
-        "Could not open output file"
+      minify_in_place(
+        input,
+        &cfg,
+        inline_css_opts(&args, Some(input)).as_ref(),
+        streaming,
+        cache.as_ref(),
+        args.stats,
+        args.verbose,
+        print_names,
       );
+      if let Some(progress) = progress {
+        progress.inc();
+      };
+    });
+    if let Some(progress) = progress {
+      progress.finish();
+    };
+    if let (Some(cache), Some(cache_path)) = (&cache, &args.cache) {
+      if cache.hits() > 0 {
+        report(format!("{} unchanged files were cached", cache.hits()));
+      };
       io_expect!(
-        input_name,
-        out_file.write_all(&out_code),
-        "Could not save minified code"
+        cache_path.to_string_lossy(),
+        cache.save(),
+        "Could not save cache"
       );
-      // Just print the name, since this is the default output and any prefix becomes redundant. It'd also allow piping into another command (quite nice for something like `minify-html *.html | xargs gzip`), copying as list of files, etc.
-      println!("{}", input_name);
-    });
+    };
   }
 }
+
+// A temporary file that's removed when dropped, such as when returning early due to an error, unless it has replaced the file it was written for.
+struct TempFile {
+  path: PathBuf,
+  replaced: bool,
+}
+
+impl Drop for TempFile {
+  fn drop(&mut self) {
+    if !self.replaced {
+      let _ = remove_file(&self.path);
+    };
+  }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn minify_in_place(
+  input: &Path,
+  cfg: &Cfg,
+  inline_css: Option<&InlineCss>,
+  streaming: bool,
+  cache: Option<&Cache>,
+  stats_enabled: bool,
+  verbose: bool,
+  print_name: bool,
+) {
+  let input_name = input.to_string_lossy().into_owned();
+
+  let mut src_file = io_expect!(input_name, File::open(input), "Could not open source file");
+  if streaming && standalone_code_ext(Some(input)).is_none() {
+    let permissions = io_expect!(
+      input_name,
+      src_file.metadata(),
+      "Could not read source file metadata"
+    )
+    .permissions();
+    // Write to a temporary file, as the input is still being read while output is written.
+    let mut tmp_path = input.as_os_str().to_os_string();
+    tmp_path.push(".minhtml-tmp");
+    let mut tmp = TempFile {
+      path: PathBuf::from(tmp_path),
+      replaced: false,
+    };
+    let mut out_file = BufWriter::new(io_expect!(
+      input_name,
+      File::create(&tmp.path),
+      "Could not open temporary output file"
+    ));
+    let res = match catch_internal_error(&input_name, || {
+      minify_streaming_from_reader(src_file, cfg, &mut out_file).and_then(|_| out_file.flush())
+    }) {
+      Some(res) => res,
+      None => return,
+    };
+    io_expect!(input_name, res, "Could not minify source code");
+    drop(out_file);
+    io_expect!(
+      input_name,
+      set_permissions(&tmp.path, permissions),
+      "Could not set permissions of temporary output file"
+    );
+    io_expect!(
+      input_name,
+      rename(&tmp.path, input),
+      "Could not replace source file"
+    );
+    tmp.replaced = true;
+    if print_name {
+      println!("{}", input_name);
+    };
+    return;
+  };
+  let mut src_code = Vec::<u8>::new();
+  io_expect!(
+    input_name,
+    src_file.read_to_end(&mut src_code),
+    "Could not load source code"
+  );
+  let cache = cache.map(|cache| (cache, crate::cache::hash(&src_code)));
+  if let Some((cache, src_hash)) = cache {
+    if cache.is_unchanged(src_hash) {
+      if verbose {
+        report(format!("[{}] Cached", input_name));
+      };
+      return;
+    };
+  };
+  let (out_code, stats) = match catch_internal_error(&input_name, || {
+    minify_input(Some(input), &src_code, cfg, inline_css)
+  }) {
+    Some(r) => r,
+    None => return,
+  };
+  if stats_enabled {
+    print_stats(&input_name, &stats, verbose);
+  };
+  let mut out_file = io_expect!(
+    input_name,
+    File::create(input),
+    "Could not open output file"
+  );
+  io_expect!(
+    input_name,
+    out_file.write_all(&out_code),
+    "Could not save minified code"
+  );
+  if let Some((cache, src_hash)) = cache {
+    cache.insert(src_hash, crate::cache::hash(&out_code));
+  };
+  // Just print the name, since this is the default output and any prefix becomes redundant. It'd also allow piping into another command (quite nice for something like `minify-html *.html | xargs gzip`), copying as list of files, etc.
+  if print_name {
+    println!("{}", input_name);
+  };
+}
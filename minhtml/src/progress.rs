@@ -0,0 +1,91 @@
+use indicatif::ProgressBar;
+use indicatif::ProgressDrawTarget;
+use indicatif::ProgressStyle;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::time::Duration;
+use std::time::Instant;
+
+// How often to write a progress line when stderr isn't a terminal.
+const LINE_INTERVAL: Duration = Duration::from_secs(2);
+
+pub struct Progress {
+  // None if stderr isn't a terminal, in which case progress is written as periodic lines instead.
+  bar: Option<ProgressBar>,
+  total: u64,
+  done: AtomicU64,
+  started: Instant,
+  last_line: Mutex<Instant>,
+}
+
+static PROGRESS: OnceLock<Progress> = OnceLock::new();
+
+// Starts showing progress on stderr. Only one progress display can exist for the lifetime of the process.
+pub fn start(total: usize) -> &'static Progress {
+  let total = total as u64;
+  PROGRESS.get_or_init(|| {
+    let bar = ProgressBar::with_draw_target(Some(total), ProgressDrawTarget::stderr());
+    // The draw target is hidden if stderr isn't a terminal.
+    let bar = if bar.is_hidden() {
+      None
+    } else {
+      bar.set_style(
+        ProgressStyle::with_template("{wide_bar} {pos}/{len} files ({per_sec}, ETA {eta})")
+          .unwrap_or_else(|_| ProgressStyle::default_bar()),
+      );
+      Some(bar)
+    };
+    Progress {
+      bar,
+      total,
+      done: AtomicU64::new(0),
+      started: Instant::now(),
+      last_line: Mutex::new(Instant::now()),
+    }
+  })
+}
+
+// Writes a line to stderr without corrupting the progress bar, if one is shown.
+pub fn report(line: String) {
+  match PROGRESS.get().and_then(|p| p.bar.as_ref()) {
+    Some(bar) => bar.println(line),
+    None => eprintln!("{}", line),
+  };
+}
+
+impl Progress {
+  pub fn inc(&self) {
+    let done = self.done.fetch_add(1, Ordering::Relaxed) + 1;
+    if let Some(bar) = &self.bar {
+      bar.inc(1);
+      return;
+    };
+    // Another thread holding the lock is already writing a line.
+    if let Ok(mut last_line) = self.last_line.try_lock() {
+      if last_line.elapsed() >= LINE_INTERVAL && done < self.total {
+        *last_line = Instant::now();
+        let elapsed = self.started.elapsed().as_secs_f64();
+        let per_sec = done as f64 / elapsed;
+        let eta = (self.total - done) as f64 / per_sec;
+        eprintln!(
+          "{}/{} files ({:.0}/s, ETA {:.0}s)",
+          done, self.total, per_sec, eta
+        );
+      };
+    };
+  }
+
+  pub fn finish(&self) {
+    if let Some(bar) = &self.bar {
+      bar.finish_and_clear();
+    };
+    eprintln!(
+      "{}/{} files done in {:.1}s",
+      self.done.load(Ordering::Relaxed),
+      self.total,
+      self.started.elapsed().as_secs_f64()
+    );
+  }
+}
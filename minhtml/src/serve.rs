@@ -0,0 +1,103 @@
+use crate::catch_internal_error;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use minify_html::cfg_options;
+use minify_html::minify;
+use minify_html::Cfg;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+use std::io::stdin;
+use std::io::stdout;
+use std::io::BufRead;
+use std::io::Write;
+use std::mem::take;
+use std::sync::mpsc::channel;
+use std::sync::Arc;
+
+macro_rules! override_option {
+  ($cfg:ident, $name:ident, $value:ident, bool: [$($bool:ident,)*], other: [$($other:ident,)*],) => {
+    match $name {
+      $(stringify!($bool) => {
+        $cfg.$bool = $value
+          .as_bool()
+          .ok_or_else(|| format!("option {} must be a boolean", $name))?
+      })*
+      $(stringify!($other) => {
+        return Err(format!("option {} can only be set with command line arguments", $name))
+      })*
+      _ => return Err(format!("unknown option {}", $name)),
+    }
+  };
+}
+
+// Applies the options in a request's `cfg` object on top of the defaults from the command line arguments.
+fn apply_overrides(cfg: &mut Cfg, overrides: &Map<String, Value>) -> Result<(), String> {
+  for (name, value) in overrides {
+    let name = name.as_str();
+    cfg_options!(override_option!(cfg, name, value,));
+  }
+  Ok(())
+}
+
+fn minify_request(defaults: &Cfg, req: &Map<String, Value>) -> Result<Vec<u8>, String> {
+  let src = req
+    .get("src_base64")
+    .and_then(Value::as_str)
+    .ok_or("src_base64 must be a string")?;
+  let src = STANDARD
+    .decode(src)
+    .map_err(|e| format!("invalid src_base64: {}", e))?;
+  let cfg = match req.get("cfg") {
+    None | Some(Value::Null) => defaults.clone(),
+    Some(Value::Object(overrides)) => {
+      let mut cfg = defaults.clone();
+      apply_overrides(&mut cfg, overrides)?;
+      cfg
+    }
+    Some(_) => return Err("cfg must be an object".to_string()),
+  };
+  let id = req.get("id").unwrap_or(&Value::Null);
+  catch_internal_error(&format!("request {}", id), || minify(&src, &cfg))
+    .ok_or_else(|| "internal error while minifying".to_string())
+}
+
+// Returns the response for one line of input, which is an error response if the request is malformed.
+fn handle_request(defaults: &Cfg, line: &[u8]) -> Value {
+  let req = match serde_json::from_slice::<Value>(line) {
+    Ok(Value::Object(req)) => req,
+    Ok(_) => return json!({ "id": null, "error": "request must be an object" }),
+    Err(e) => return json!({ "id": null, "error": format!("invalid JSON: {}", e) }),
+  };
+  let id = req.get("id").cloned().unwrap_or(Value::Null);
+  match minify_request(defaults, &req) {
+    Ok(min) => json!({ "id": id, "min_base64": STANDARD.encode(min) }),
+    Err(error) => json!({ "id": id, "error": error }),
+  }
+}
+
+/// Reads newline-delimited JSON requests from stdin and writes a newline-delimited JSON response to stdout for each, as described in the README. Requests are handled in parallel, so responses may be written in a different order. Returns once stdin is closed and all responses have been written.
+pub fn serve(cfg: &Arc<Cfg>) -> std::io::Result<()> {
+  // No messages are sent; this is only used to wait until every handler has dropped its sender.
+  let (done_tx, done_rx) = channel::<()>();
+  let mut src = stdin().lock();
+  let mut line = Vec::new();
+  while src.read_until(b'\n', &mut line)? > 0 {
+    let req = take(&mut line);
+    if req.iter().all(u8::is_ascii_whitespace) {
+      continue;
+    };
+    let cfg = cfg.clone();
+    let done_tx = done_tx.clone();
+    rayon::spawn(move || {
+      let res = handle_request(&cfg, &req);
+      let mut out = stdout().lock();
+      // If stdout has been closed, there's nowhere to report the error, and the client is presumably gone.
+      let _ = writeln!(out, "{}", res).and_then(|_| out.flush());
+      drop(done_tx);
+    });
+  }
+  drop(done_tx);
+  while done_rx.recv().is_ok() {}
+  Ok(())
+}
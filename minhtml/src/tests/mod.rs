@@ -0,0 +1,22 @@
+use crate::catch_internal_error;
+use crate::record_failure;
+use crate::EXIT_CODE;
+use crate::EXIT_INTERNAL_ERROR;
+use crate::EXIT_IO_ERROR;
+use std::sync::atomic::Ordering;
+
+// No known input causes an internal error, so this is tested here instead of by running the binary.
+#[test]
+fn test_internal_error_exit_code() {
+  assert_eq!(catch_internal_error("a.html", || 1), Some(1));
+  assert_eq!(EXIT_CODE.load(Ordering::Relaxed), 0);
+  record_failure(EXIT_IO_ERROR);
+  assert_eq!(
+    catch_internal_error("b.html", || -> i32 { panic!("internal error") }),
+    None
+  );
+  // The internal error is more severe than the earlier I/O error, and later less severe failures don't lower it.
+  assert_eq!(EXIT_CODE.load(Ordering::Relaxed), EXIT_INTERNAL_ERROR);
+  record_failure(EXIT_IO_ERROR);
+  assert_eq!(EXIT_CODE.load(Ordering::Relaxed), EXIT_INTERNAL_ERROR);
+}
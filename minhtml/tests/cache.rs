@@ -0,0 +1,61 @@
+mod common;
+
+use common::minhtml_command;
+use common::temp_dir;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Output;
+
+fn site_dir(name: &str) -> PathBuf {
+  let dir = temp_dir(name);
+  fs::create_dir_all(dir.join("site")).unwrap();
+  fs::write(dir.join("site/a.html"), "<p>  a  </p>").unwrap();
+  fs::write(dir.join("site/b.html"), "<p>  b  </p>").unwrap();
+  dir
+}
+
+fn minhtml(dir: &Path, args: &[&str]) -> Output {
+  let out = minhtml_command()
+    .arg("--cache")
+    .arg(dir.join("cache"))
+    .args(args)
+    .arg(dir.join("site"))
+    .output()
+    .unwrap();
+  assert!(out.status.success());
+  out
+}
+
+fn minified(out: &Output) -> usize {
+  String::from_utf8_lossy(&out.stdout).lines().count()
+}
+
+#[test]
+fn test_cache_skips_unchanged_files() {
+  let dir = site_dir("unchanged");
+  assert_eq!(minified(&minhtml(&dir, &[])), 2);
+  let out = minhtml(&dir, &[]);
+  assert_eq!(minified(&out), 0);
+  assert!(String::from_utf8_lossy(&out.stderr).contains("2 unchanged files were cached"));
+  assert_eq!(fs::read_to_string(dir.join("site/a.html")).unwrap(), "<p>a");
+
+  // Changed files are minified again.
+  fs::write(dir.join("site/a.html"), "<p>  c  </p>").unwrap();
+  assert_eq!(minified(&minhtml(&dir, &[])), 1);
+  assert_eq!(fs::read_to_string(dir.join("site/a.html")).unwrap(), "<p>c");
+  assert_eq!(minified(&minhtml(&dir, &[])), 0);
+
+  // Changing options invalidates the cache.
+  assert_eq!(minified(&minhtml(&dir, &["--keep-closing-tags"])), 2);
+}
+
+#[test]
+fn test_cache_regenerated_if_corrupt() {
+  let dir = site_dir("corrupt");
+  fs::write(dir.join("cache"), "not a cache").unwrap();
+  let out = minhtml(&dir, &[]);
+  assert_eq!(minified(&out), 2);
+  assert!(String::from_utf8_lossy(&out.stderr).contains("Cache is corrupt"));
+  assert_eq!(minified(&minhtml(&dir, &[])), 0);
+}
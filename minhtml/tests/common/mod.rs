@@ -0,0 +1,36 @@
+// Helpers shared by the integration tests, each of which only uses some of them.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+// A command that runs the `minhtml` binary being tested.
+pub fn minhtml_command() -> Command {
+  Command::new(env!("CARGO_BIN_EXE_minhtml"))
+}
+
+// A path in the temporary directory that's unique to the test file and process, so that test files and concurrent runs don't clash.
+pub fn temp_path(name: &str) -> PathBuf {
+  std::env::temp_dir().join(format!(
+    "minhtml-{}-{}-{}",
+    env!("CARGO_CRATE_NAME"),
+    std::process::id(),
+    name
+  ))
+}
+
+// Creates an empty directory at `temp_path(name)`, replacing any left over from a previous run.
+pub fn temp_dir(name: &str) -> PathBuf {
+  let dir = temp_path(name);
+  let _ = fs::remove_dir_all(&dir);
+  fs::create_dir_all(&dir).unwrap();
+  dir
+}
+
+// Writes a file at `temp_path(name)`.
+pub fn temp_file(name: &str, contents: &str) -> PathBuf {
+  let path = temp_path(name);
+  fs::write(&path, contents).unwrap();
+  path
+}
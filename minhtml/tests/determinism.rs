@@ -0,0 +1,68 @@
+mod common;
+
+use common::minhtml_command;
+use common::temp_dir;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+// Copies the benchmark inputs, along with an element with many attributes, into a new directory.
+fn corpus(name: &str) -> PathBuf {
+  let dir = temp_dir(name);
+  fs::create_dir_all(dir.join("site")).unwrap();
+  let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("../bench/inputs");
+  for entry in fs::read_dir(inputs).unwrap() {
+    let entry = entry.unwrap();
+    let file_name = format!("{}.html", entry.file_name().to_string_lossy());
+    fs::copy(entry.path(), dir.join("site").join(file_name)).unwrap();
+  }
+  fs::write(
+    dir.join("site/attrs.html"),
+    "<div z=1 y=2 x=3 w=4 v=5 u=6 t=7 s=8 r=9 q=10 p=11 o=12 n=13 m=14 l=15 k=16 j=17 i=18 h=19 g=20 class=' a  b ' id=\"c d\"></div>",
+  )
+  .unwrap();
+  dir
+}
+
+fn minhtml(dir: &Path, threads: &str) {
+  assert!(minhtml_command()
+    // minify-js orders some declarations by hash, so scripts aren't minified.
+    .args(["--minify-css", "--threads", threads, "--cache"])
+    .arg(dir.join("cache"))
+    .arg(dir.join("site"))
+    .stdout(Stdio::null())
+    .status()
+    .unwrap()
+    .success());
+}
+
+fn read_site(dir: &Path) -> Vec<(String, Vec<u8>)> {
+  let mut files = fs::read_dir(dir.join("site"))
+    .unwrap()
+    .map(|e| {
+      let e = e.unwrap();
+      (
+        e.file_name().to_string_lossy().into_owned(),
+        fs::read(e.path()).unwrap(),
+      )
+    })
+    .collect::<Vec<_>>();
+  files.sort();
+  files
+}
+
+#[test]
+fn test_output_independent_of_thread_count() {
+  let first = corpus("1");
+  minhtml(&first, "1");
+  let minified = read_site(&first);
+  let cache = fs::read(first.join("cache")).unwrap();
+  // Each run is a new process that hashes attributes with a different random seed.
+  for threads in ["2", "8"] {
+    let dir = corpus(threads);
+    minhtml(&dir, threads);
+    assert_eq!(minified, read_site(&dir));
+    assert_eq!(cache, fs::read(dir.join("cache")).unwrap());
+  }
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use common::minhtml_command;
+use common::temp_file;
+use common::temp_path;
+use std::fs;
+
+fn minhtml(args: &[&str]) -> Option<i32> {
+  minhtml_command().args(args).output().unwrap().status.code()
+}
+
+#[test]
+fn test_exit_code_success() {
+  let input = temp_file("success.html", "<p>  a  </p>");
+  assert_eq!(minhtml(&[input.to_str().unwrap()]), Some(0));
+  assert_eq!(minhtml(&["--help"]), Some(0));
+}
+
+#[test]
+fn test_exit_code_io_error() {
+  let missing = temp_path("missing.html");
+  assert_eq!(minhtml(&[missing.to_str().unwrap()]), Some(1));
+  // A failure for one input is reported even if the others succeed.
+  let input = temp_file("io-error.html", "<p>  a  </p>");
+  assert_eq!(
+    minhtml(&[input.to_str().unwrap(), missing.to_str().unwrap()]),
+    Some(1)
+  );
+  assert_eq!(fs::read_to_string(&input).unwrap(), "<p>a");
+}
+
+#[test]
+fn test_exit_code_usage_error() {
+  assert_eq!(minhtml(&["--not-an-option"]), Some(2));
+  let a = temp_file("usage-a.html", "");
+  let b = temp_file("usage-b.html", "");
+  assert_eq!(
+    minhtml(&[
+      "--output",
+      "out.html",
+      a.to_str().unwrap(),
+      b.to_str().unwrap()
+    ]),
+    Some(2)
+  );
+}
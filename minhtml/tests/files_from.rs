@@ -0,0 +1,45 @@
+mod common;
+
+use common::minhtml_command;
+use common::temp_file;
+use std::fs;
+use std::io::Write;
+use std::process::Stdio;
+
+#[test]
+fn test_files_from_stdin() {
+  let a = temp_file("stdin-a.html", "<p>  a  </p>");
+  let b = temp_file("stdin-b.html", "<p>  b  </p>");
+  let mut child = minhtml_command()
+    .args(["--files-from", "-"])
+    .stdin(Stdio::piped())
+    .stdout(Stdio::null())
+    .spawn()
+    .unwrap();
+  write!(
+    child.stdin.take().unwrap(),
+    "{}\n\n{}\r\n\n",
+    a.display(),
+    b.display()
+  )
+  .unwrap();
+  assert!(child.wait().unwrap().success());
+  assert_eq!(fs::read_to_string(&a).unwrap(), "<p>a");
+  assert_eq!(fs::read_to_string(&b).unwrap(), "<p>b");
+}
+
+#[test]
+fn test_files_from_null_separated() {
+  let a = temp_file("null-a.html", "<p>  a  </p>");
+  let list = temp_file("null-list", &format!("{}\0", a.display()));
+  let status = minhtml_command()
+    .arg("--files-from")
+    .arg(&list)
+    .arg("--null")
+    .stdout(Stdio::null())
+    .status()
+    .unwrap();
+  assert!(status.success());
+  // A single listed file is still minified in place.
+  assert_eq!(fs::read_to_string(&a).unwrap(), "<p>a");
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::minhtml_command;
+use common::temp_dir;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+fn site_dir(name: &str) -> PathBuf {
+  let dir = temp_dir(name);
+  fs::create_dir_all(dir.join("site/css")).unwrap();
+  fs::write(dir.join("site/a.css"), "a { color: red; }\n").unwrap();
+  fs::write(dir.join("site/css/c.css"), "p{margin:0}").unwrap();
+  fs::write(dir.join("secret.css"), "secret{}").unwrap();
+  dir
+}
+
+fn minhtml(input: &Path, args: &[&str]) -> String {
+  let out = minhtml_command()
+    .arg("--inline-css")
+    .args(args)
+    .arg(input)
+    .output()
+    .unwrap();
+  assert!(out.status.success());
+  String::from_utf8(out.stdout).unwrap()
+}
+
+#[test]
+fn test_inline_css() {
+  let dir = site_dir("links");
+  let input = dir.join("site/index.html");
+  fs::write(
+    &input,
+    r#"<link rel=stylesheet href=a.css><link rel=stylesheet href=https://example.com/b.css><link rel=stylesheet href=missing.css><link rel=stylesheet media=print href="/css/c.css?v=1"><link rel=stylesheet href=../secret.css><link rel="alternate stylesheet" title=Alt href=a.css><p>x</p>"#,
+  )
+  .unwrap();
+  assert_eq!(
+    minhtml(&input, &["--minify-css"]),
+    r#"<style>a{color:red}</style><link href=https://example.com/b.css rel=stylesheet><link href=missing.css rel=stylesheet><style media=print>p{margin:0}</style><link href=../secret.css rel=stylesheet><link rel="alternate stylesheet" href=a.css title=Alt><p>x"#,
+  );
+  assert_eq!(
+    minhtml(&input, &[]),
+    r#"<style>a { color: red; }</style><link href=https://example.com/b.css rel=stylesheet><link href=missing.css rel=stylesheet><style media=print>p{margin:0}</style><link href=../secret.css rel=stylesheet><link rel="alternate stylesheet" href=a.css title=Alt><p>x"#,
+  );
+}
+
+#[test]
+fn test_inline_css_base_dir() {
+  let dir = site_dir("base-dir");
+  let input = dir.join("index.html");
+  fs::write(&input, "<link rel=stylesheet href=/a.css><p>x</p>").unwrap();
+  let base_dir = dir.join("site");
+  assert_eq!(
+    minhtml(&input, &["--base-dir", base_dir.to_str().unwrap()]),
+    "<style>a { color: red; }</style><p>x",
+  );
+}
+
+#[test]
+fn test_inline_css_relative_to_document() {
+  let dir = site_dir("nested");
+  fs::create_dir_all(dir.join("site/blog/css")).unwrap();
+  fs::write(dir.join("site/blog/css/post.css"), "h1{margin:0}").unwrap();
+  let input = dir.join("site/blog/post.html");
+  fs::write(
+    &input,
+    "<link rel=stylesheet href=css/post.css><link rel=stylesheet href=../css/c.css><link rel=stylesheet href=/a.css><link rel=stylesheet href=../../secret.css><p>x</p>",
+  )
+  .unwrap();
+  let base_dir = dir.join("site");
+  assert_eq!(
+    minhtml(&input, &["--base-dir", base_dir.to_str().unwrap()]),
+    "<style>h1{margin:0}</style><style>p{margin:0}</style><style>a { color: red; }</style><link href=../../secret.css rel=stylesheet><p>x",
+  );
+}
+
+#[test]
+fn test_inline_css_relative_urls() {
+  let dir = site_dir("urls");
+  fs::write(dir.join("site/css/bg.css"), "body{background:url(bg.png)}").unwrap();
+  fs::write(dir.join("site/css/import.css"), "@import 'c.css';").unwrap();
+  fs::write(
+    dir.join("site/css/abs.css"),
+    "body{background:url(/img/bg.png)}i{background:url(\"data:,\")}",
+  )
+  .unwrap();
+  fs::write(dir.join("site/same.css"), "body{background:url(bg.png)}").unwrap();
+  let input = dir.join("site/index.html");
+  fs::write(
+    &input,
+    "<link rel=stylesheet href=css/bg.css><link rel=stylesheet href=css/import.css><link rel=stylesheet href=css/abs.css><link rel=stylesheet href=same.css><p>x</p>",
+  )
+  .unwrap();
+  // Relative URLs are only kept working if the stylesheet is in the same directory as the document.
+  assert_eq!(
+    minhtml(&input, &[]),
+    "<link href=css/bg.css rel=stylesheet><link href=css/import.css rel=stylesheet><style>body{background:url(/img/bg.png)}i{background:url(\"data:,\")}</style><style>body{background:url(bg.png)}</style><p>x",
+  );
+}
+
+#[test]
+fn test_inline_css_max_size() {
+  let dir = site_dir("max-size");
+  let input = dir.join("site/index.html");
+  fs::write(
+    &input,
+    "<link rel=stylesheet href=a.css><link rel=stylesheet href=css/c.css><p>x</p>",
+  )
+  .unwrap();
+  assert_eq!(
+    minhtml(&input, &["--inline-css-max-size", "11"]),
+    "<link href=a.css rel=stylesheet><style>p{margin:0}</style><p>x",
+  );
+}
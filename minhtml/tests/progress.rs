@@ -0,0 +1,49 @@
+mod common;
+
+use common::minhtml_command;
+use common::temp_file;
+use common::temp_path;
+use std::fs;
+
+#[test]
+fn test_progress() {
+  let inputs = ["a.html", "b.html", "c.html"].map(|n| temp_file(n, "<p>  a  </p>"));
+  let out = minhtml_command()
+    .arg("--progress")
+    .args(&inputs)
+    .output()
+    .unwrap();
+  assert!(out.status.success());
+  // Names aren't printed unless --verbose is also provided.
+  assert!(out.stdout.is_empty());
+  // stderr isn't a terminal, so only lines are written.
+  let stderr = String::from_utf8(out.stderr).unwrap();
+  assert!(stderr
+    .lines()
+    .last()
+    .unwrap()
+    .starts_with("3/3 files done in "));
+  for input in &inputs {
+    assert_eq!(fs::read_to_string(input).unwrap(), "<p>a");
+  }
+
+  let missing = temp_path("missing.html");
+  let out = minhtml_command()
+    .args(["--progress", "--verbose"])
+    .args(&inputs[..1])
+    .arg(&missing)
+    .output()
+    .unwrap();
+  assert_eq!(out.status.code(), Some(1));
+  assert_eq!(
+    String::from_utf8(out.stdout).unwrap(),
+    format!("{}\n", inputs[0].display())
+  );
+  let stderr = String::from_utf8(out.stderr).unwrap();
+  assert!(stderr.contains("Could not open source file"));
+  assert!(stderr
+    .lines()
+    .last()
+    .unwrap()
+    .starts_with("2/2 files done in "));
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::minhtml_command;
+use common::temp_dir;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::slice;
+
+fn site_dir(name: &str) -> PathBuf {
+  let dir = temp_dir(name);
+  fs::create_dir_all(dir.join("node_modules/pkg")).unwrap();
+  fs::create_dir_all(dir.join(".cache")).unwrap();
+  fs::write(dir.join(".gitignore"), "node_modules/\n").unwrap();
+  fs::write(dir.join("index.html"), "<p>  a  </p>").unwrap();
+  fs::write(dir.join("notes.txt"), "<p>  a  </p>").unwrap();
+  fs::write(dir.join("node_modules/pkg/index.html"), "<p>  a  </p>").unwrap();
+  fs::write(dir.join(".cache/index.html"), "<p>  a  </p>").unwrap();
+  dir
+}
+
+fn minhtml(args: &[&str], inputs: &[PathBuf]) -> bool {
+  minhtml_command()
+    .args(args)
+    .args(inputs)
+    .stdout(Stdio::null())
+    .status()
+    .unwrap()
+    .success()
+}
+
+#[test]
+fn test_recursive_skips_ignored_and_hidden_files() {
+  let dir = site_dir("default");
+  let explicit = dir.join("node_modules/pkg/index.html");
+  assert!(minhtml(&[], slice::from_ref(&dir)));
+  assert_eq!(fs::read_to_string(dir.join("index.html")).unwrap(), "<p>a");
+  assert_eq!(
+    fs::read_to_string(dir.join("notes.txt")).unwrap(),
+    "<p>  a  </p>"
+  );
+  assert_eq!(fs::read_to_string(&explicit).unwrap(), "<p>  a  </p>");
+  assert_eq!(
+    fs::read_to_string(dir.join(".cache/index.html")).unwrap(),
+    "<p>  a  </p>"
+  );
+
+  // Files provided directly are always minified, even if ignored. A second input is provided so that they're minified in place.
+  assert!(minhtml(&[], &[explicit.clone(), dir.join("notes.txt")]));
+  assert_eq!(fs::read_to_string(&explicit).unwrap(), "<p>a");
+  assert_eq!(fs::read_to_string(dir.join("notes.txt")).unwrap(), "<p>a");
+}
+
+#[test]
+fn test_recursive_no_ignore_and_hidden() {
+  let dir = site_dir("no-ignore");
+  assert!(minhtml(&["--no-ignore"], slice::from_ref(&dir)));
+  assert_eq!(
+    fs::read_to_string(dir.join("node_modules/pkg/index.html")).unwrap(),
+    "<p>a"
+  );
+  assert_eq!(
+    fs::read_to_string(dir.join(".cache/index.html")).unwrap(),
+    "<p>  a  </p>"
+  );
+
+  assert!(minhtml(&["--hidden"], slice::from_ref(&dir)));
+  assert_eq!(
+    fs::read_to_string(dir.join(".cache/index.html")).unwrap(),
+    "<p>a"
+  );
+}
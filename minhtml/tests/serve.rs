@@ -0,0 +1,90 @@
+mod common;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use common::minhtml_command;
+use serde_json::json;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::Stdio;
+
+// Sends each line as a request and returns the responses by ID.
+fn serve(lines: &[String]) -> HashMap<String, Value> {
+  let mut child = minhtml_command()
+    .args(["--serve", "--keep-comments"])
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .spawn()
+    .unwrap();
+  let mut stdin = child.stdin.take().unwrap();
+  for line in lines {
+    writeln!(stdin, "{}", line).unwrap();
+  }
+  drop(stdin);
+  let output = child.wait_with_output().unwrap();
+  assert!(output.status.success());
+  String::from_utf8(output.stdout)
+    .unwrap()
+    .lines()
+    .map(|l| {
+      let res = serde_json::from_str::<Value>(l).unwrap();
+      (res["id"].to_string(), res)
+    })
+    .collect()
+}
+
+fn request(id: Value, cfg: Value, src: &str) -> String {
+  json!({ "id": id, "cfg": cfg, "src_base64": STANDARD.encode(src) }).to_string()
+}
+
+fn minified(res: &Value) -> String {
+  String::from_utf8(
+    STANDARD
+      .decode(res["min_base64"].as_str().unwrap())
+      .unwrap(),
+  )
+  .unwrap()
+}
+
+#[test]
+fn test_serve_minifies_requests_with_overrides() {
+  let res = serve(&[
+    request(json!(1), Value::Null, "<p>  a  <!-- b --></p>"),
+    request(
+      json!("two"),
+      json!({ "keep_comments": false }),
+      "<p>  a  <!-- b --></p>",
+    ),
+    String::new(),
+    request(json!(3), json!({}), ""),
+  ]);
+  assert_eq!(res.len(), 3);
+  assert_eq!(minified(&res["1"]), "<p>a<!-- b -->");
+  assert_eq!(minified(&res["\"two\""]), "<p>a");
+  assert_eq!(minified(&res["3"]), "");
+}
+
+#[test]
+fn test_serve_responds_to_malformed_requests_with_errors() {
+  let res = serve(&[
+    "{not json".to_string(),
+    request(json!(1), json!({ "minify_everything": true }), "<p>"),
+    request(json!(2), json!({ "keep_comments": "yes" }), "<p>"),
+    json!({ "id": 3, "src_base64": "!" }).to_string(),
+    json!({ "id": 4 }).to_string(),
+    request(json!(5), Value::Null, "<p>a</p>"),
+    request(json!(6), json!({ "whitespace_mode": "Aggressive" }), "<p>"),
+  ]);
+  assert_eq!(res.len(), 7);
+  assert_eq!(res["1"]["error"], "unknown option minify_everything");
+  assert_eq!(
+    res["6"]["error"],
+    "option whitespace_mode can only be set with command line arguments"
+  );
+  for id in ["null", "1", "2", "3", "4", "6"] {
+    assert!(res[id]["error"].is_string(), "{}", id);
+    assert!(res[id].get("min_base64").is_none(), "{}", id);
+  }
+  assert_eq!(minified(&res["5"]), "<p>a");
+}
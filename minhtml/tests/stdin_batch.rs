@@ -0,0 +1,56 @@
+mod common;
+
+use common::minhtml_command;
+use std::io::Write;
+use std::process::Stdio;
+
+fn frame(docs: &[&[u8]]) -> Vec<u8> {
+  let mut out = Vec::new();
+  for doc in docs {
+    out.extend_from_slice(format!("{}\n", doc.len()).as_bytes());
+    out.extend_from_slice(doc);
+  }
+  out
+}
+
+fn run(input: &[u8], extra_args: &[&str]) -> std::process::Output {
+  let mut child = minhtml_command()
+    .arg("--stdin-batch")
+    .args(extra_args)
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .unwrap();
+  child.stdin.take().unwrap().write_all(input).unwrap();
+  child.wait_with_output().unwrap()
+}
+
+#[test]
+fn test_stdin_batch_round_trips_documents_in_order() {
+  let output = run(
+    &frame(&[
+      b"<p>  Hello,   world!  </p>",
+      b"",
+      b"<div class=\" a \">\n  <span> 1 </span>\n</div>\n",
+    ]),
+    &["--threads", "2"],
+  );
+  assert!(output.status.success());
+  assert_eq!(
+    String::from_utf8(output.stdout).unwrap(),
+    String::from_utf8(frame(&[
+      b"<p>Hello, world!",
+      b"",
+      b"<div class=a><span> 1 </span></div>",
+    ]))
+    .unwrap(),
+  );
+}
+
+#[test]
+fn test_stdin_batch_rejects_truncated_document() {
+  let output = run(b"10\n<p>a</p>", &[]);
+  assert!(output.stdout.is_empty());
+  assert!(!output.stderr.is_empty());
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::minhtml_command;
+use common::temp_dir;
+use std::fs;
+use std::process::Stdio;
+
+#[test]
+fn test_streaming_in_place() {
+  let dir = temp_dir("in-place");
+  let input = dir.join("a.html");
+  fs::write(&input, "<p>  a  </p>").unwrap();
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(&input, fs::Permissions::from_mode(0o640)).unwrap();
+  };
+  // Files in directories are minified in place.
+  assert!(minhtml_command()
+    .arg("--streaming")
+    .arg(&dir)
+    .stdout(Stdio::null())
+    .status()
+    .unwrap()
+    .success());
+  assert_eq!(fs::read_to_string(&input).unwrap(), "<p> a </p>");
+  // The minified file replaces the source with the same permissions, and no temporary file is left behind.
+  #[cfg(unix)]
+  {
+    use std::os::unix::fs::PermissionsExt;
+    assert_eq!(
+      fs::metadata(&input).unwrap().permissions().mode() & 0o777,
+      0o640
+    );
+  };
+  assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+}
@@ -72,8 +72,8 @@ fn gen_attr_min_struct(
 
 fn gen_attrs_rs(html_data: &HtmlData) -> String {
   let mut code = r#"
-    use once_cell::sync::Lazy;
-    use ahash::AHashMap;
+    use crate::lazy::Lazy;
+    use hashbrown::HashMap;
     use crate::spec::tag::ns::Namespace;
 
     pub struct AttributeMinification {
@@ -87,7 +87,7 @@ fn gen_attrs_rs(html_data: &HtmlData) -> String {
 
     pub enum AttrMapEntry {
         AllNamespaceElements(AttributeMinification),
-        SpecificNamespaceElements(AHashMap<&'static [u8], AttributeMinification>),
+        SpecificNamespaceElements(HashMap<&'static [u8], AttributeMinification>),
     }
 
     pub struct ByNamespace {
@@ -101,14 +101,15 @@ fn gen_attrs_rs(html_data: &HtmlData) -> String {
             match ns {
                 Namespace::Html => self.html.as_ref(),
                 Namespace::Svg => self.svg.as_ref(),
+                Namespace::MathML => None,
             }
         }
     }
 
-    pub struct AttrMap(AHashMap<&'static [u8], ByNamespace>);
+    pub struct AttrMap(HashMap<&'static [u8], ByNamespace>);
 
     impl AttrMap {
-        pub const fn new(map: AHashMap<&'static [u8], ByNamespace>) -> AttrMap {
+        pub const fn new(map: HashMap<&'static [u8], ByNamespace>) -> AttrMap {
             AttrMap(map)
         }
 
@@ -122,7 +123,7 @@ fn gen_attrs_rs(html_data: &HtmlData) -> String {
 
     pub static ATTRS: Lazy<AttrMap> = Lazy::new(|| {
       #[allow(unused_mut)]
-      let mut m = AHashMap::<&'static [u8], ByNamespace>::default();
+      let mut m = HashMap::<&'static [u8], ByNamespace>::default();
   "#.to_string();
 
   for (attr_name, namespaces) in html_data.attributes.iter() {
@@ -149,7 +150,7 @@ fn gen_attrs_rs(html_data: &HtmlData) -> String {
                 r#"
                   Some({{
                     #[allow(unused_mut)]
-                    let mut m = AHashMap::<&'static [u8], AttributeMinification>::default();
+                    let mut m = HashMap::<&'static [u8], AttributeMinification>::default();
                 "#
               )
               .unwrap();
@@ -321,7 +322,7 @@ fn gen_codepoints_rs() -> String {
       table: [bool; 256],
     }
 
-    impl std::ops::Index<u8> for Lookup {
+    impl core::ops::Index<u8> for Lookup {
       type Output = bool;
 
       fn index(&self, c: u8) -> &Self::Output {
@@ -487,12 +488,22 @@ fn gen_entities_rs() -> String {
     vec![
       c(b'&'),
       c(b'#'),
-      c(b'x'),
+      c(b'x') & b'X',
       CodePoints::new() + (b'0'..=b'9') + (b'a'..=b'f') + (b'A'..=b'F'),
     ],
     "EntityType::Hex".to_string(),
   );
   let mut shorter_encoded_entities = vec![];
+  // Sort so that the generated code is deterministic, with shorter names first.
+  let entities = entities
+    .into_iter()
+    .sorted_by(|(a, _), (b, _)| a.len().cmp(&b.len()).then(a.cmp(b)))
+    .collect_vec();
+  let names_rs = entities
+    .iter()
+    .map(|(encoded, _)| format!(r#"b{:?}"#, &encoded[1..]))
+    .sorted()
+    .join(",\n  ");
   for (encoded, entity) in entities {
     let val = format!(r#"&{:?}"#, entity.characters.as_bytes());
     trie_builder.add(
@@ -520,6 +531,9 @@ fn gen_entities_rs() -> String {
   let trie_rs = trie_builder.generate("ENTITY", "EntityType");
   format!(
     r#"
+      pub static ENTITY_NAMES: &[&[u8]] = &[
+        {names_rs}
+      ];
       pub static SHORTER_ENCODED_ENTITIES_ENCODED: &[&[u8]] = &[
         {shorter_encoded_entities_encoded_rs}
       ];
@@ -0,0 +1,26 @@
+use alloc::boxed::Box;
+use core::ops::Deref;
+use once_cell::race::OnceBox;
+
+/// A value that's computed on first access, like `once_cell::sync::Lazy`, but without requiring `std`. If several threads access it for the first time at once, each may compute it, but only one value is kept and returned to all of them.
+pub struct Lazy<T> {
+  cell: OnceBox<T>,
+  init: fn() -> T,
+}
+
+impl<T> Lazy<T> {
+  pub const fn new(init: fn() -> T) -> Lazy<T> {
+    Lazy {
+      cell: OnceBox::new(),
+      init,
+    }
+  }
+}
+
+impl<T> Deref for Lazy<T> {
+  type Target = T;
+
+  fn deref(&self) -> &T {
+    self.cell.get_or_init(|| Box::new((self.init)()))
+  }
+}
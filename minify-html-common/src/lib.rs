@@ -1,5 +1,11 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod gen;
+pub mod lazy;
 pub mod pattern;
 pub mod spec;
+#[cfg(feature = "std")]
 pub mod tests;
 pub mod whitespace;
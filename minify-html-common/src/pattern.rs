@@ -1,4 +1,5 @@
 use aho_corasick::AhoCorasick;
+use alloc::vec::Vec;
 
 // Can't use pub const fn constructor due to Copy trait, so allow directly creating struct publicly for now.
 pub struct TrieNode<V: 'static + Copy> {
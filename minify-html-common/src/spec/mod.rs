@@ -1,2 +1,4 @@
 pub mod script;
+pub mod svg;
 pub mod tag;
+pub mod url;
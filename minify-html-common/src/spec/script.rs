@@ -1,8 +1,8 @@
-use ahash::AHashSet;
-use once_cell::sync::Lazy;
+use crate::lazy::Lazy;
+use hashbrown::HashSet;
 
-pub static JAVASCRIPT_MIME_TYPES: Lazy<AHashSet<&'static [u8]>> = Lazy::new(|| {
-  let mut s = AHashSet::<&'static [u8]>::default();
+pub static JAVASCRIPT_MIME_TYPES: Lazy<HashSet<&'static [u8]>> = Lazy::new(|| {
+  let mut s = HashSet::<&'static [u8]>::default();
   s.insert(b"application/ecmascript");
   s.insert(b"application/javascript");
   s.insert(b"application/x-ecmascript");
@@ -0,0 +1,25 @@
+// Default values of well-known SVG attributes, used when the attribute is omitted. Tag and attribute names are lowercase, as they are after parsing.
+// Presentation attributes for inherited properties, like `fill`, `fill-opacity`, and `stroke-width`, are excluded, as without the attribute the value would be inherited from an ancestor instead of being the default. Attributes of elements that can copy attributes from another element using `href`, like gradients and patterns, are also excluded.
+// Sourced from https://www.w3.org/TR/SVG2/propidx.html and the element definitions in https://www.w3.org/TR/SVG2/.
+pub fn svg_attr_default(tag: &[u8], name: &[u8]) -> Option<&'static [u8]> {
+  let default: &'static [u8] = match name {
+    // Presentation attributes for properties that aren't inherited.
+    b"clip-path" | b"filter" | b"mask" => b"none",
+    b"flood-color" | b"stop-color" => b"black",
+    b"flood-opacity" | b"opacity" | b"stop-opacity" => b"1",
+    b"lighting-color" => b"white",
+    // Element attributes.
+    b"clippathunits" if tag == b"clippath" => b"userSpaceOnUse",
+    b"cx" | b"cy" if matches!(tag, b"circle" | b"ellipse") => b"0",
+    b"maskcontentunits" if tag == b"mask" => b"userSpaceOnUse",
+    b"maskunits" if tag == b"mask" => b"objectBoundingBox",
+    b"offset" if tag == b"stop" => b"0",
+    b"preserveaspectratio" if matches!(tag, b"image" | b"marker" | b"svg" | b"symbol") => {
+      b"xMidYMid meet"
+    }
+    b"x" | b"y" if matches!(tag, b"rect" | b"svg") => b"0",
+    b"x1" | b"x2" | b"y1" | b"y2" if tag == b"line" => b"0",
+    _ => return None,
+  };
+  Some(default)
+}
@@ -1,5 +1,6 @@
 pub mod ns;
 pub mod omission;
+pub mod rawtext;
 pub mod void;
 pub mod whitespace;
 
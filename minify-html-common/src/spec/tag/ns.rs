@@ -1,5 +1,57 @@
-#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::str::FromStr;
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Namespace {
   Html,
   Svg,
+  MathML,
+}
+
+// Returns the namespace that an element with this name and its descendants are in, if it's an element that switches to foreign content.
+pub fn namespace_for(tag_name: &[u8]) -> Option<Namespace> {
+  match tag_name {
+    b"svg" => Some(Namespace::Svg),
+    b"math" => Some(Namespace::MathML),
+    _ => None,
+  }
+}
+
+impl Display for Namespace {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.write_str(match self {
+      Namespace::Html => "html",
+      Namespace::Svg => "svg",
+      Namespace::MathML => "mathml",
+    })
+  }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseNamespaceError;
+
+impl Display for ParseNamespaceError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.write_str("expected one of `html`, `svg`, or `mathml`")
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseNamespaceError {}
+
+impl FromStr for Namespace {
+  type Err = ParseNamespaceError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if s.eq_ignore_ascii_case("html") {
+      Ok(Namespace::Html)
+    } else if s.eq_ignore_ascii_case("svg") {
+      Ok(Namespace::Svg)
+    } else if s.eq_ignore_ascii_case("mathml") {
+      Ok(Namespace::MathML)
+    } else {
+      Err(ParseNamespaceError)
+    }
+  }
 }
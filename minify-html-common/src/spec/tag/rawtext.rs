@@ -0,0 +1,15 @@
+use crate::lazy::Lazy;
+use hashbrown::HashSet;
+
+// HTML elements whose content is text up to the closing tag, rather than HTML. This includes escapable raw text elements, and the obsolete `<plaintext>` and `<xmp>`.
+// https://html.spec.whatwg.org/multipage/syntax.html#elements-2.
+pub static RAW_TEXT_TAGS: Lazy<HashSet<&'static [u8]>> = Lazy::new(|| {
+  let mut s = HashSet::<&'static [u8]>::default();
+  s.insert(b"plaintext");
+  s.insert(b"script");
+  s.insert(b"style");
+  s.insert(b"textarea");
+  s.insert(b"title");
+  s.insert(b"xmp");
+  s
+});
@@ -1,8 +1,8 @@
-use ahash::AHashSet;
-use once_cell::sync::Lazy;
+use crate::lazy::Lazy;
+use hashbrown::HashSet;
 
-pub static VOID_TAGS: Lazy<AHashSet<&'static [u8]>> = Lazy::new(|| {
-  let mut s = AHashSet::<&'static [u8]>::default();
+pub static VOID_TAGS: Lazy<HashSet<&'static [u8]>> = Lazy::new(|| {
+  let mut s = HashSet::<&'static [u8]>::default();
   s.insert(b"area");
   s.insert(b"base");
   s.insert(b"br");
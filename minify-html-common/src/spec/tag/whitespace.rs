@@ -1,6 +1,6 @@
+use crate::lazy::Lazy;
 use crate::spec::tag::ns::Namespace;
-use ahash::AHashMap;
-use once_cell::sync::Lazy;
+use hashbrown::HashMap;
 
 pub struct WhitespaceMinification {
   pub collapse: bool,
@@ -59,9 +59,9 @@ static DEFAULT_SVG: &WhitespaceMinification = &WhitespaceMinification {
 };
 
 static HTML_TAG_WHITESPACE_MINIFICATION: Lazy<
-  AHashMap<&'static [u8], &'static WhitespaceMinification>,
+  HashMap<&'static [u8], &'static WhitespaceMinification>,
 > = Lazy::new(|| {
-  let mut m = AHashMap::<&'static [u8], &'static WhitespaceMinification>::default();
+  let mut m = HashMap::<&'static [u8], &'static WhitespaceMinification>::default();
   // Content tags.
   m.insert(b"address", CONTENT);
   m.insert(b"audio", CONTENT);
@@ -169,15 +169,16 @@ static HTML_TAG_WHITESPACE_MINIFICATION: Lazy<
   // Whitespace-sensitive tags.
   m.insert(b"textarea", WHITESPACE_SENSITIVE);
   m.insert(b"code", WHITESPACE_SENSITIVE);
+  m.insert(b"listing", WHITESPACE_SENSITIVE);
   m.insert(b"pre", WHITESPACE_SENSITIVE);
 
   m
 });
 
 static SVG_TAG_WHITESPACE_MINIFICATION: Lazy<
-  AHashMap<&'static [u8], &'static WhitespaceMinification>,
+  HashMap<&'static [u8], &'static WhitespaceMinification>,
 > = Lazy::new(|| {
-  let mut m = AHashMap::<&'static [u8], &'static WhitespaceMinification>::default();
+  let mut m = HashMap::<&'static [u8], &'static WhitespaceMinification>::default();
 
   // Content tags.
   m.insert(b"desc", CONTENT);
@@ -274,6 +275,13 @@ static SVG_TAG_WHITESPACE_MINIFICATION: Lazy<
   m
 });
 
+// Returns whether the HTML element is one of the inline formatting elements, whose whitespace is collapsed but never trimmed.
+pub fn is_formatting_tag(tag_name: &[u8]) -> bool {
+  HTML_TAG_WHITESPACE_MINIFICATION
+    .get(tag_name)
+    .is_some_and(|m| core::ptr::eq(*m, FORMATTING))
+}
+
 pub fn get_whitespace_minification_for_tag(
   ns: Namespace,
   // Use empty slice if root.
@@ -295,5 +303,7 @@ pub fn get_whitespace_minification_for_tag(
     Namespace::Svg => SVG_TAG_WHITESPACE_MINIFICATION
       .get(tag_name)
       .unwrap_or(&DEFAULT_SVG),
+    // We don't have MathML-specific rules, so only do what is safe for any unknown HTML element.
+    Namespace::MathML => DEFAULT_HTML,
   }
 }
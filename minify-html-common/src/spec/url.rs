@@ -0,0 +1,47 @@
+// Attributes whose entire value is a single URL.
+// Sourced from https://html.spec.whatwg.org/multipage/indices.html#attributes-3.
+pub fn is_url_attr(tag: &[u8], name: &[u8]) -> bool {
+  match name {
+    b"action" => tag == b"form",
+    b"background" => tag == b"body",
+    b"cite" => matches!(tag, b"blockquote" | b"del" | b"ins" | b"q"),
+    b"data" => tag == b"object",
+    b"formaction" => matches!(tag, b"button" | b"input"),
+    b"href" => matches!(tag, b"a" | b"area" | b"base" | b"link"),
+    b"longdesc" => matches!(tag, b"img" | b"frame" | b"iframe"),
+    b"manifest" => tag == b"html",
+    b"poster" => tag == b"video",
+    b"src" => matches!(
+      tag,
+      b"audio"
+        | b"embed"
+        | b"frame"
+        | b"iframe"
+        | b"img"
+        | b"input"
+        | b"script"
+        | b"source"
+        | b"track"
+        | b"video"
+    ),
+    _ => false,
+  }
+}
+
+// Attributes whose value is a set of space-separated URLs.
+pub fn is_url_list_attr(tag: &[u8], name: &[u8]) -> bool {
+  match name {
+    b"ping" => matches!(tag, b"a" | b"area"),
+    _ => false,
+  }
+}
+
+// Returns the port that is implied when the URL has no port, if the scheme is known.
+pub fn default_port_for_scheme(scheme: &[u8]) -> Option<&'static [u8]> {
+  match scheme {
+    b"ftp" => Some(b"21"),
+    b"http" | b"ws" => Some(b"80"),
+    b"https" | b"wss" => Some(b"443"),
+    _ => None,
+  }
+}
@@ -260,7 +260,10 @@ pub fn create_common_test_data() -> HashMap<&'static [u8], &'static [u8]> {
   t.insert(b"&#x2E", b".");
   t.insert(b"&#x2F", b"/");
   t.insert(b"&#x2f", b"/");
-  t.insert(b"&#x00", b"\0");
+  t.insert(b"&#x00", b"\xef\xbf\xbd");
+  t.insert(b"&#X30", b"0");
+  t.insert(b"&#xD800;", b"\xef\xbf\xbd");
+  t.insert(b"&#x80;", b"\xe2\x82\xac");
   t.insert(b"&#x30", b"0");
   t.insert(b"&#x0030", b"0");
   t.insert(b"&#x000000000000000000000000000000000000000000030", b"0");
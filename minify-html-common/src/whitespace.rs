@@ -1,4 +1,5 @@
 use crate::gen::codepoints::WHITESPACE;
+use alloc::vec::Vec;
 
 pub fn trimmed(val: &[u8]) -> &[u8] {
   let mut start = 0;
@@ -73,3 +74,23 @@ pub fn is_all_whitespace(val: &[u8]) -> bool {
   }
   true
 }
+
+// Replaces every `\r\n`, `\r`, and `\n` with `ending`.
+pub fn normalize_line_endings(val: &[u8], ending: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(val.len());
+  let mut i = 0;
+  while i < val.len() {
+    match val[i] {
+      b'\r' => {
+        out.extend_from_slice(ending);
+        if val.get(i + 1) == Some(&b'\n') {
+          i += 1;
+        };
+      }
+      b'\n' => out.extend_from_slice(ending),
+      c => out.push(c),
+    };
+    i += 1;
+  }
+  out
+}
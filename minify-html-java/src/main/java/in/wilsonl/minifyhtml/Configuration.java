@@ -9,51 +9,141 @@ public class Configuration {
   public final boolean allow_noncompliant_unquoted_attribute_values;
   public final boolean allow_optimal_entities;
   public final boolean allow_removing_spaces_between_attributes;
+  public final boolean inject_charset_meta;
+  public final boolean keep_balancing_comments;
   public final boolean keep_closing_tags;
   public final boolean keep_comments;
+  public final boolean keep_head_opening_tag;
   public final boolean keep_html_and_head_opening_tags;
+  public final boolean keep_html_opening_tag;
   public final boolean keep_input_type_text_attr;
+  public final boolean keep_shy_entity;
   public final boolean keep_ssi_comments;
   public final boolean minify_css;
   public final boolean minify_doctype;
   public final boolean minify_js;
+  public final boolean minify_kept_comment_whitespace;
+  public final boolean minify_svg_default_attributes;
+  public final boolean normalize_doctype_to_html5;
+  public final boolean normalize_meta_viewport;
+  public final boolean normalize_urls;
+  public final boolean omit_body_opening_tag;
   public final boolean preserve_brace_template_syntax;
   public final boolean preserve_chevron_percent_template_syntax;
+  public final boolean preserve_ignored_regions;
+  public final boolean preserve_ko_comments;
+  public final boolean preserve_microdata_attributes;
+  public final boolean preserve_prolog;
   public final boolean remove_bangs;
+  public final boolean remove_generator_meta;
+  public final boolean remove_hidden_elements;
+  public final boolean remove_html5_shiv;
   public final boolean remove_processing_instructions;
+  public final boolean remove_redundant_script_attributes;
+  public final boolean remove_type_module_attribute;
+  public final boolean remove_unreferenced_ids;
+  public final boolean remove_x_ua_compatible_meta;
+  public final boolean remove_xml_declaration;
+  public final boolean respect_contenteditable;
+  public final boolean skip_style_attr_minification;
+  public final boolean strip_code_comments;
+  public final boolean strip_legacy_script_comments;
+  public final boolean trim_form_control_text;
+  public final boolean unwrap_redundant_wrappers;
+  public final boolean validate_preload_links;
 
   private Configuration(
     boolean allow_noncompliant_unquoted_attribute_values,
     boolean allow_optimal_entities,
     boolean allow_removing_spaces_between_attributes,
+    boolean inject_charset_meta,
+    boolean keep_balancing_comments,
     boolean keep_closing_tags,
     boolean keep_comments,
+    boolean keep_head_opening_tag,
     boolean keep_html_and_head_opening_tags,
+    boolean keep_html_opening_tag,
     boolean keep_input_type_text_attr,
+    boolean keep_shy_entity,
     boolean keep_ssi_comments,
     boolean minify_css,
     boolean minify_doctype,
     boolean minify_js,
+    boolean minify_kept_comment_whitespace,
+    boolean minify_svg_default_attributes,
+    boolean normalize_doctype_to_html5,
+    boolean normalize_meta_viewport,
+    boolean normalize_urls,
+    boolean omit_body_opening_tag,
     boolean preserve_brace_template_syntax,
     boolean preserve_chevron_percent_template_syntax,
+    boolean preserve_ignored_regions,
+    boolean preserve_ko_comments,
+    boolean preserve_microdata_attributes,
+    boolean preserve_prolog,
     boolean remove_bangs,
-    boolean remove_processing_instructions
+    boolean remove_generator_meta,
+    boolean remove_hidden_elements,
+    boolean remove_html5_shiv,
+    boolean remove_processing_instructions,
+    boolean remove_redundant_script_attributes,
+    boolean remove_type_module_attribute,
+    boolean remove_unreferenced_ids,
+    boolean remove_x_ua_compatible_meta,
+    boolean remove_xml_declaration,
+    boolean respect_contenteditable,
+    boolean skip_style_attr_minification,
+    boolean strip_code_comments,
+    boolean strip_legacy_script_comments,
+    boolean trim_form_control_text,
+    boolean unwrap_redundant_wrappers,
+    boolean validate_preload_links
   ) {
     this.allow_noncompliant_unquoted_attribute_values = allow_noncompliant_unquoted_attribute_values;
     this.allow_optimal_entities = allow_optimal_entities;
     this.allow_removing_spaces_between_attributes = allow_removing_spaces_between_attributes;
+    this.inject_charset_meta = inject_charset_meta;
+    this.keep_balancing_comments = keep_balancing_comments;
     this.keep_closing_tags = keep_closing_tags;
     this.keep_comments = keep_comments;
+    this.keep_head_opening_tag = keep_head_opening_tag;
     this.keep_html_and_head_opening_tags = keep_html_and_head_opening_tags;
+    this.keep_html_opening_tag = keep_html_opening_tag;
     this.keep_input_type_text_attr = keep_input_type_text_attr;
+    this.keep_shy_entity = keep_shy_entity;
     this.keep_ssi_comments = keep_ssi_comments;
     this.minify_css = minify_css;
     this.minify_doctype = minify_doctype;
     this.minify_js = minify_js;
+    this.minify_kept_comment_whitespace = minify_kept_comment_whitespace;
+    this.minify_svg_default_attributes = minify_svg_default_attributes;
+    this.normalize_doctype_to_html5 = normalize_doctype_to_html5;
+    this.normalize_meta_viewport = normalize_meta_viewport;
+    this.normalize_urls = normalize_urls;
+    this.omit_body_opening_tag = omit_body_opening_tag;
     this.preserve_brace_template_syntax = preserve_brace_template_syntax;
     this.preserve_chevron_percent_template_syntax = preserve_chevron_percent_template_syntax;
+    this.preserve_ignored_regions = preserve_ignored_regions;
+    this.preserve_ko_comments = preserve_ko_comments;
+    this.preserve_microdata_attributes = preserve_microdata_attributes;
+    this.preserve_prolog = preserve_prolog;
     this.remove_bangs = remove_bangs;
+    this.remove_generator_meta = remove_generator_meta;
+    this.remove_hidden_elements = remove_hidden_elements;
+    this.remove_html5_shiv = remove_html5_shiv;
     this.remove_processing_instructions = remove_processing_instructions;
+    this.remove_redundant_script_attributes = remove_redundant_script_attributes;
+    this.remove_type_module_attribute = remove_type_module_attribute;
+    this.remove_unreferenced_ids = remove_unreferenced_ids;
+    this.remove_x_ua_compatible_meta = remove_x_ua_compatible_meta;
+    this.remove_xml_declaration = remove_xml_declaration;
+    this.respect_contenteditable = respect_contenteditable;
+    this.skip_style_attr_minification = skip_style_attr_minification;
+    this.strip_code_comments = strip_code_comments;
+    this.strip_legacy_script_comments = strip_legacy_script_comments;
+    this.trim_form_control_text = trim_form_control_text;
+    this.unwrap_redundant_wrappers = unwrap_redundant_wrappers;
+    this.validate_preload_links = validate_preload_links;
   }
 
   /**
@@ -63,18 +153,48 @@ public class Configuration {
     private boolean allow_noncompliant_unquoted_attribute_values = false;
     private boolean allow_optimal_entities = false;
     private boolean allow_removing_spaces_between_attributes = false;
+    private boolean inject_charset_meta = false;
+    private boolean keep_balancing_comments = false;
     private boolean keep_closing_tags = false;
     private boolean keep_comments = false;
+    private boolean keep_head_opening_tag = false;
     private boolean keep_html_and_head_opening_tags = false;
+    private boolean keep_html_opening_tag = false;
     private boolean keep_input_type_text_attr = false;
+    private boolean keep_shy_entity = false;
     private boolean keep_ssi_comments = false;
     private boolean minify_css = false;
     private boolean minify_doctype = false;
     private boolean minify_js = false;
+    private boolean minify_kept_comment_whitespace = false;
+    private boolean minify_svg_default_attributes = false;
+    private boolean normalize_doctype_to_html5 = false;
+    private boolean normalize_meta_viewport = false;
+    private boolean normalize_urls = false;
+    private boolean omit_body_opening_tag = false;
     private boolean preserve_brace_template_syntax = false;
     private boolean preserve_chevron_percent_template_syntax = false;
+    private boolean preserve_ignored_regions = false;
+    private boolean preserve_ko_comments = false;
+    private boolean preserve_microdata_attributes = false;
+    private boolean preserve_prolog = false;
     private boolean remove_bangs = false;
+    private boolean remove_generator_meta = false;
+    private boolean remove_hidden_elements = false;
+    private boolean remove_html5_shiv = false;
     private boolean remove_processing_instructions = false;
+    private boolean remove_redundant_script_attributes = false;
+    private boolean remove_type_module_attribute = false;
+    private boolean remove_unreferenced_ids = false;
+    private boolean remove_x_ua_compatible_meta = false;
+    private boolean remove_xml_declaration = false;
+    private boolean respect_contenteditable = false;
+    private boolean skip_style_attr_minification = false;
+    private boolean strip_code_comments = false;
+    private boolean strip_legacy_script_comments = false;
+    private boolean trim_form_control_text = false;
+    private boolean unwrap_redundant_wrappers = false;
+    private boolean validate_preload_links = false;
 
     public Builder setAllowNoncompliantUnquotedAttributeValues(boolean v) {
       this.allow_noncompliant_unquoted_attribute_values = v;
@@ -88,6 +208,14 @@ public class Configuration {
       this.allow_removing_spaces_between_attributes = v;
       return this;
     }
+    public Builder setInjectCharsetMeta(boolean v) {
+      this.inject_charset_meta = v;
+      return this;
+    }
+    public Builder setKeepBalancingComments(boolean v) {
+      this.keep_balancing_comments = v;
+      return this;
+    }
     public Builder setKeepClosingTags(boolean v) {
       this.keep_closing_tags = v;
       return this;
@@ -96,14 +224,26 @@ public class Configuration {
       this.keep_comments = v;
       return this;
     }
+    public Builder setKeepHeadOpeningTag(boolean v) {
+      this.keep_head_opening_tag = v;
+      return this;
+    }
     public Builder setKeepHtmlAndHeadOpeningTags(boolean v) {
       this.keep_html_and_head_opening_tags = v;
       return this;
     }
+    public Builder setKeepHtmlOpeningTag(boolean v) {
+      this.keep_html_opening_tag = v;
+      return this;
+    }
     public Builder setKeepInputTypeTextAttr(boolean v) {
       this.keep_input_type_text_attr = v;
       return this;
     }
+    public Builder setKeepShyEntity(boolean v) {
+      this.keep_shy_entity = v;
+      return this;
+    }
     public Builder setKeepSsiComments(boolean v) {
       this.keep_ssi_comments = v;
       return this;
@@ -120,6 +260,30 @@ public class Configuration {
       this.minify_js = v;
       return this;
     }
+    public Builder setMinifyKeptCommentWhitespace(boolean v) {
+      this.minify_kept_comment_whitespace = v;
+      return this;
+    }
+    public Builder setMinifySvgDefaultAttributes(boolean v) {
+      this.minify_svg_default_attributes = v;
+      return this;
+    }
+    public Builder setNormalizeDoctypeToHtml5(boolean v) {
+      this.normalize_doctype_to_html5 = v;
+      return this;
+    }
+    public Builder setNormalizeMetaViewport(boolean v) {
+      this.normalize_meta_viewport = v;
+      return this;
+    }
+    public Builder setNormalizeUrls(boolean v) {
+      this.normalize_urls = v;
+      return this;
+    }
+    public Builder setOmitBodyOpeningTag(boolean v) {
+      this.omit_body_opening_tag = v;
+      return this;
+    }
     public Builder setPreserveBraceTemplateSyntax(boolean v) {
       this.preserve_brace_template_syntax = v;
       return this;
@@ -128,32 +292,138 @@ public class Configuration {
       this.preserve_chevron_percent_template_syntax = v;
       return this;
     }
+    public Builder setPreserveIgnoredRegions(boolean v) {
+      this.preserve_ignored_regions = v;
+      return this;
+    }
+    public Builder setPreserveKoComments(boolean v) {
+      this.preserve_ko_comments = v;
+      return this;
+    }
+    public Builder setPreserveMicrodataAttributes(boolean v) {
+      this.preserve_microdata_attributes = v;
+      return this;
+    }
+    public Builder setPreserveProlog(boolean v) {
+      this.preserve_prolog = v;
+      return this;
+    }
     public Builder setRemoveBangs(boolean v) {
       this.remove_bangs = v;
       return this;
     }
+    public Builder setRemoveGeneratorMeta(boolean v) {
+      this.remove_generator_meta = v;
+      return this;
+    }
+    public Builder setRemoveHiddenElements(boolean v) {
+      this.remove_hidden_elements = v;
+      return this;
+    }
+    public Builder setRemoveHtml5Shiv(boolean v) {
+      this.remove_html5_shiv = v;
+      return this;
+    }
     public Builder setRemoveProcessingInstructions(boolean v) {
       this.remove_processing_instructions = v;
       return this;
     }
+    public Builder setRemoveRedundantScriptAttributes(boolean v) {
+      this.remove_redundant_script_attributes = v;
+      return this;
+    }
+    public Builder setRemoveTypeModuleAttribute(boolean v) {
+      this.remove_type_module_attribute = v;
+      return this;
+    }
+    public Builder setRemoveUnreferencedIds(boolean v) {
+      this.remove_unreferenced_ids = v;
+      return this;
+    }
+    public Builder setRemoveXUaCompatibleMeta(boolean v) {
+      this.remove_x_ua_compatible_meta = v;
+      return this;
+    }
+    public Builder setRemoveXmlDeclaration(boolean v) {
+      this.remove_xml_declaration = v;
+      return this;
+    }
+    public Builder setRespectContenteditable(boolean v) {
+      this.respect_contenteditable = v;
+      return this;
+    }
+    public Builder setSkipStyleAttrMinification(boolean v) {
+      this.skip_style_attr_minification = v;
+      return this;
+    }
+    public Builder setStripCodeComments(boolean v) {
+      this.strip_code_comments = v;
+      return this;
+    }
+    public Builder setStripLegacyScriptComments(boolean v) {
+      this.strip_legacy_script_comments = v;
+      return this;
+    }
+    public Builder setTrimFormControlText(boolean v) {
+      this.trim_form_control_text = v;
+      return this;
+    }
+    public Builder setUnwrapRedundantWrappers(boolean v) {
+      this.unwrap_redundant_wrappers = v;
+      return this;
+    }
+    public Builder setValidatePreloadLinks(boolean v) {
+      this.validate_preload_links = v;
+      return this;
+    }
 
     public Configuration build() {
       return new Configuration(
         this.allow_noncompliant_unquoted_attribute_values,
         this.allow_optimal_entities,
         this.allow_removing_spaces_between_attributes,
+        this.inject_charset_meta,
+        this.keep_balancing_comments,
         this.keep_closing_tags,
         this.keep_comments,
+        this.keep_head_opening_tag,
         this.keep_html_and_head_opening_tags,
+        this.keep_html_opening_tag,
         this.keep_input_type_text_attr,
+        this.keep_shy_entity,
         this.keep_ssi_comments,
         this.minify_css,
         this.minify_doctype,
         this.minify_js,
+        this.minify_kept_comment_whitespace,
+        this.minify_svg_default_attributes,
+        this.normalize_doctype_to_html5,
+        this.normalize_meta_viewport,
+        this.normalize_urls,
+        this.omit_body_opening_tag,
         this.preserve_brace_template_syntax,
         this.preserve_chevron_percent_template_syntax,
+        this.preserve_ignored_regions,
+        this.preserve_ko_comments,
+        this.preserve_microdata_attributes,
+        this.preserve_prolog,
         this.remove_bangs,
-        this.remove_processing_instructions
+        this.remove_generator_meta,
+        this.remove_hidden_elements,
+        this.remove_html5_shiv,
+        this.remove_processing_instructions,
+        this.remove_redundant_script_attributes,
+        this.remove_type_module_attribute,
+        this.remove_unreferenced_ids,
+        this.remove_x_ua_compatible_meta,
+        this.remove_xml_declaration,
+        this.respect_contenteditable,
+        this.skip_style_attr_minification,
+        this.strip_code_comments,
+        this.strip_legacy_script_comments,
+        this.trim_form_control_text,
+        this.unwrap_redundant_wrappers,
+        this.validate_preload_links
       );
     }
   }
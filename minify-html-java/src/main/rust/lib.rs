@@ -14,18 +14,49 @@ fn build_cfg(env: &JNIEnv, obj: &JObject) -> Cfg {
     allow_noncompliant_unquoted_attribute_values: env.get_field(*obj, "allow_noncompliant_unquoted_attribute_values", "Z").unwrap().z().unwrap(),
     allow_optimal_entities: env.get_field(*obj, "allow_optimal_entities", "Z").unwrap().z().unwrap(),
     allow_removing_spaces_between_attributes: env.get_field(*obj, "allow_removing_spaces_between_attributes", "Z").unwrap().z().unwrap(),
+    inject_charset_meta: env.get_field(*obj, "inject_charset_meta", "Z").unwrap().z().unwrap(),
+    keep_balancing_comments: env.get_field(*obj, "keep_balancing_comments", "Z").unwrap().z().unwrap(),
     keep_closing_tags: env.get_field(*obj, "keep_closing_tags", "Z").unwrap().z().unwrap(),
     keep_comments: env.get_field(*obj, "keep_comments", "Z").unwrap().z().unwrap(),
+    keep_head_opening_tag: env.get_field(*obj, "keep_head_opening_tag", "Z").unwrap().z().unwrap(),
     keep_html_and_head_opening_tags: env.get_field(*obj, "keep_html_and_head_opening_tags", "Z").unwrap().z().unwrap(),
+    keep_html_opening_tag: env.get_field(*obj, "keep_html_opening_tag", "Z").unwrap().z().unwrap(),
     keep_input_type_text_attr: env.get_field(*obj, "keep_input_type_text_attr", "Z").unwrap().z().unwrap(),
+    keep_shy_entity: env.get_field(*obj, "keep_shy_entity", "Z").unwrap().z().unwrap(),
     keep_ssi_comments: env.get_field(*obj, "keep_ssi_comments", "Z").unwrap().z().unwrap(),
     minify_css: env.get_field(*obj, "minify_css", "Z").unwrap().z().unwrap(),
     minify_doctype: env.get_field(*obj, "minify_doctype", "Z").unwrap().z().unwrap(),
     minify_js: env.get_field(*obj, "minify_js", "Z").unwrap().z().unwrap(),
+    minify_kept_comment_whitespace: env.get_field(*obj, "minify_kept_comment_whitespace", "Z").unwrap().z().unwrap(),
+    minify_svg_default_attributes: env.get_field(*obj, "minify_svg_default_attributes", "Z").unwrap().z().unwrap(),
+    normalize_doctype_to_html5: env.get_field(*obj, "normalize_doctype_to_html5", "Z").unwrap().z().unwrap(),
+    normalize_meta_viewport: env.get_field(*obj, "normalize_meta_viewport", "Z").unwrap().z().unwrap(),
+    normalize_urls: env.get_field(*obj, "normalize_urls", "Z").unwrap().z().unwrap(),
+    omit_body_opening_tag: env.get_field(*obj, "omit_body_opening_tag", "Z").unwrap().z().unwrap(),
     preserve_brace_template_syntax: env.get_field(*obj, "preserve_brace_template_syntax", "Z").unwrap().z().unwrap(),
     preserve_chevron_percent_template_syntax: env.get_field(*obj, "preserve_chevron_percent_template_syntax", "Z").unwrap().z().unwrap(),
+    preserve_ignored_regions: env.get_field(*obj, "preserve_ignored_regions", "Z").unwrap().z().unwrap(),
+    preserve_ko_comments: env.get_field(*obj, "preserve_ko_comments", "Z").unwrap().z().unwrap(),
+    preserve_microdata_attributes: env.get_field(*obj, "preserve_microdata_attributes", "Z").unwrap().z().unwrap(),
+    preserve_prolog: env.get_field(*obj, "preserve_prolog", "Z").unwrap().z().unwrap(),
     remove_bangs: env.get_field(*obj, "remove_bangs", "Z").unwrap().z().unwrap(),
+    remove_generator_meta: env.get_field(*obj, "remove_generator_meta", "Z").unwrap().z().unwrap(),
+    remove_hidden_elements: env.get_field(*obj, "remove_hidden_elements", "Z").unwrap().z().unwrap(),
+    remove_html5_shiv: env.get_field(*obj, "remove_html5_shiv", "Z").unwrap().z().unwrap(),
     remove_processing_instructions: env.get_field(*obj, "remove_processing_instructions", "Z").unwrap().z().unwrap(),
+    remove_redundant_script_attributes: env.get_field(*obj, "remove_redundant_script_attributes", "Z").unwrap().z().unwrap(),
+    remove_type_module_attribute: env.get_field(*obj, "remove_type_module_attribute", "Z").unwrap().z().unwrap(),
+    remove_unreferenced_ids: env.get_field(*obj, "remove_unreferenced_ids", "Z").unwrap().z().unwrap(),
+    remove_x_ua_compatible_meta: env.get_field(*obj, "remove_x_ua_compatible_meta", "Z").unwrap().z().unwrap(),
+    remove_xml_declaration: env.get_field(*obj, "remove_xml_declaration", "Z").unwrap().z().unwrap(),
+    respect_contenteditable: env.get_field(*obj, "respect_contenteditable", "Z").unwrap().z().unwrap(),
+    skip_style_attr_minification: env.get_field(*obj, "skip_style_attr_minification", "Z").unwrap().z().unwrap(),
+    strip_code_comments: env.get_field(*obj, "strip_code_comments", "Z").unwrap().z().unwrap(),
+    strip_legacy_script_comments: env.get_field(*obj, "strip_legacy_script_comments", "Z").unwrap().z().unwrap(),
+    trim_form_control_text: env.get_field(*obj, "trim_form_control_text", "Z").unwrap().z().unwrap(),
+    unwrap_redundant_wrappers: env.get_field(*obj, "unwrap_redundant_wrappers", "Z").unwrap().z().unwrap(),
+    validate_preload_links: env.get_field(*obj, "validate_preload_links", "Z").unwrap().z().unwrap(),
+    ..Default::default()
   };
   cfg
 }
@@ -14,29 +14,89 @@ export function minify(
     allow_optimal_entities?: boolean;
     /** Allow removing_spaces between attributes when possible, which may not be spec compliant. These will still be parsed correctly by almost all browsers. */
     allow_removing_spaces_between_attributes?: boolean;
+    /** Insert `<meta charset=utf-8>` at the start of `<head>` if the document doesn't declare its character encoding with a `<meta charset>` or `<meta http-equiv=Content-Type>` element. Without one, browsers have to guess the encoding. Only use this if the source is UTF-8. */
+    inject_charset_meta?: boolean;
+    /** Keep comments whose trimmed content starts with `/`, like `<!-- /container -->`, which build tools emit to mark where an element ends. */
+    keep_balancing_comments?: boolean;
     /** Do not omit closing tags when possible. */
     keep_closing_tags?: boolean;
     /** Keep all comments. */
     keep_comments?: boolean;
-    /** Do not omit `<html>` and `<head>` opening tags when they don't have attributes. */
+    /** Do not omit the `<head>` opening tag when it doesn't have attributes. */
+    keep_head_opening_tag?: boolean;
+    /** Do not omit `<html>` and `<head>` opening tags when they don't have attributes. This is a shorthand for enabling both `keep_html_opening_tag` and `keep_head_opening_tag`. */
     keep_html_and_head_opening_tags?: boolean;
+    /** Do not omit the `<html>` opening tag when it doesn't have attributes. */
+    keep_html_opening_tag?: boolean;
     /** Keep `type=text` attribute name and value on `<input>` elements. */
     keep_input_type_text_attr?: boolean;
+    /** Write soft hyphens (U+00AD) in text as `&shy;` instead of the raw character, which is invisible and can be mangled by tools that process the output. */
+    keep_shy_entity?: boolean;
     /** Keep SSI comments. */
     keep_ssi_comments?: boolean;
     /** Minify CSS in `<style>` tags and `style` attributes using [https://github.com/parcel-bundler/lightningcss](lightningcss). */
     minify_css?: boolean;
     /** Minify DOCTYPEs. Minified DOCTYPEs may not be spec compliant, but will still be parsed correctly by almost all browsers. */
     minify_doctype?: boolean;
-    /** Minify JavaScript in `<script>` tags using [minify-js](https://github.com/wilsonzlin/minify-js). */
+    /** Minify JavaScript in `<script>` tags using [minify-js](https://github.com/wilsonzlin/minify-js). The order of some declarations in its output depends on hash seeds, so the output of scripts can differ between processes. */
     minify_js?: boolean;
+    /** Collapse runs of whitespace in comments that are kept, such as with `keep_comments`. SSI, conditional, and license comments (starting with `!` or containing `@license` or `@preserve`) are left as is. */
+    minify_kept_comment_whitespace?: boolean;
+    /** Remove attributes of SVG elements that are equal to their default values, such as `opacity="1"` or `x="0"` on a `<rect>`. Only well-known defaults of attributes that are not inherited from ancestors are removed. */
+    minify_svg_default_attributes?: boolean;
+    /** Replace the DOCTYPE with `<!doctype html>`, dropping any public and system identifiers, such as those of HTML 4.01 and XHTML DOCTYPEs. This switches pages that rely on them out of quirks or limited-quirks mode, which can change how they are rendered. */
+    normalize_doctype_to_html5?: boolean;
+    /** Remove trailing zeros from numbers like `1.0` and empty entries in the `content` of `<meta name=viewport>`. Whitespace in it is always removed. */
+    normalize_meta_viewport?: boolean;
+    /** Remove default ports and `.` path segments from absolute URLs in attributes like `href` and `src`, and lowercase their schemes and hosts. */
+    normalize_urls?: boolean;
+    /** Omit the `<body>` opening tag when it doesn't have attributes and the spec allows it. Some parsers and tools expect `<body>` to be present. */
+    omit_body_opening_tag?: boolean;
     /** When `{{`, `{#`, or `{%` are seen in content, all source code until the subsequent matching closing `}}`, `#}`, or `%}` respectively gets piped through untouched. */
     preserve_brace_template_syntax?: boolean;
     /** When `<%` is seen in content, all source code until the subsequent matching closing `%>` gets piped through untouched. */
     preserve_chevron_percent_template_syntax?: boolean;
-    /** Remove all bangs. */
+    /** Output the content between a `<!-- htmlmin:ignore -->` comment and the next `<!-- htmlmin:endignore -->` comment as is, and remove both comments. If there is no end comment, everything after the start comment is output as is. Use `ignored_region_markers` to recognise different comments. */
+    preserve_ignored_regions?: boolean;
+    /** Keep Knockout.js virtual element comments like `<!-- ko if: a -->` and `<!-- /ko -->`. */
+    preserve_ko_comments?: boolean;
+    /** Never remove or normalize microdata attributes (`itemid`, `itemprop`, `itemref`, `itemscope`, `itemtype`), even if empty or matched by `remove_data_attrs`. */
+    preserve_microdata_attributes?: boolean;
+    /** Keep everything up to and including the `<html>` start tag byte for byte, such as the doctype's case and the `<html>` element's attribute quoting, if only a doctype, comments, and whitespace precede it. */
+    preserve_prolog?: boolean;
+    /** Remove bangs like `<!FOO>`, except those matching `keep_bangs_matching`. Doctypes are never removed, nor are CDATA sections in SVG and MathML, as they contain text. */
     remove_bangs?: boolean;
+    /** Remove `<meta name=generator>` tags, which are added by some site generators and CMSes but not used by browsers. */
+    remove_generator_meta?: boolean;
+    /** Remove elements with a `hidden` attribute or a `style` attribute containing `display: none`, along with their descendants. `<template>` and `<datalist>` elements are never removed. */
+    remove_hidden_elements?: boolean;
+    /** Remove `<script>` elements that load polyfills for old versions of Internet Explorer, like html5shiv, html5shim, respond.js, and selectivizr, based on the file name in `src`. Conditional comments that only contain such scripts are also removed, even if comments are kept. */
+    remove_html5_shiv?: boolean;
     /** Remove all processing instructions. */
     remove_processing_instructions?: boolean;
+    /** Remove `async` and `defer` attributes on `<script>` elements where they have no effect, such as `defer` alongside `async` or on a module script, and both on inline classic scripts. */
+    remove_redundant_script_attributes?: boolean;
+    /** Remove `type="module"` from `<script>` elements. WARNING: This changes module scripts into classic scripts, which are run immediately instead of deferred, in the global scope, in sloppy mode, and without support for `import`. Only enable this if every module script has been bundled into a classic script, e.g. by inlining its imports. */
+    remove_type_module_attribute?: boolean;
+    /** Remove `id` attributes that aren't referenced within the document by a fragment like `href=#a` or `url(#a)`, an attribute like `for`, `headers`, or `aria-labelledby`, or any mention in a script, stylesheet, `style` attribute, or event handler attribute. IDs that are referenced from other documents, such as by links to `page.html#a`, or by scripts loaded with `src` will also be removed, so only use this if nothing outside the document refers to them. */
+    remove_unreferenced_ids?: boolean;
+    /** Remove `<meta http-equiv=X-UA-Compatible>` tags, which are only used by Internet Explorer. */
+    remove_x_ua_compatible_meta?: boolean;
+    /** Remove XML declarations like `<?xml version="1.0" encoding="UTF-8"?>` at the root of the document, which are common in XHTML files but meaningless in HTML, and put old versions of IE into quirks mode. The prolog is still kept as is with `preserve_prolog`. */
+    remove_xml_declaration?: boolean;
+    /** Preserve whitespace inside elements with a `contenteditable` attribute whose value isn't `false`. */
+    respect_contenteditable?: boolean;
+    /** Don't minify `style` attribute values with the CSS minifier when `minify_css` is enabled, so only `<style>` contents are minified. Whitespace around them and trailing semicolons are still removed. */
+    skip_style_attr_minification?: boolean;
+    /** Remove comments from `<script>` and `<style>` contents that aren't minified, such as when `minify_js` or `minify_css` is disabled, as well as `<!--` and `-->` wrappers like `strip_legacy_script_comments`. License comments like `/*! ... */` and source map comments are kept. Strings, template literals, regular expression literals, and CSS `url()` values are skipped, and code that can't be processed safely without fully parsing it, such as a `/` that could be either division or a regular expression, is left as is. */
+    strip_code_comments?: boolean;
+    /** Remove legacy `<!--` and `-->` wrappers used to hide the contents of `<script>` and `<style>` from very old browsers. */
+    strip_legacy_script_comments?: boolean;
+    /** Trim whitespace at the start and end of text in `<option>` and `<button>` elements. Whitespace there is collapsed but kept by default, as it could be displayed. */
+    trim_form_control_text?: boolean;
+    /** Replace `<div>` elements without attributes that only contain a single block element, and `<span>` elements without attributes that only contain a single inline element, with that element. This changes the document structure, so CSS selectors and scripts that depend on the wrappers, like `div > p` or `:nth-child`, may no longer match. */
+    unwrap_redundant_wrappers?: boolean;
+    /** Add an `as` attribute to `<link rel=preload>` elements that are missing one, inferred from the file extension of the `href`, such as `script` for `.js` or `font` for `.woff2`. Browsers fetch preloads without `as` twice, as they can't be matched with the request that uses them. Links with an unknown extension are left as is. */
+    validate_preload_links?: boolean;
   }
 ): Buffer;
@@ -22,18 +22,49 @@ fn minify(mut cx: FunctionContext) -> JsResult<JsBuffer> {
     allow_noncompliant_unquoted_attribute_values: get_bool!(cx, opt, "allow_noncompliant_unquoted_attribute_values"),
     allow_optimal_entities: get_bool!(cx, opt, "allow_optimal_entities"),
     allow_removing_spaces_between_attributes: get_bool!(cx, opt, "allow_removing_spaces_between_attributes"),
+    inject_charset_meta: get_bool!(cx, opt, "inject_charset_meta"),
+    keep_balancing_comments: get_bool!(cx, opt, "keep_balancing_comments"),
     keep_closing_tags: get_bool!(cx, opt, "keep_closing_tags"),
     keep_comments: get_bool!(cx, opt, "keep_comments"),
+    keep_head_opening_tag: get_bool!(cx, opt, "keep_head_opening_tag"),
     keep_html_and_head_opening_tags: get_bool!(cx, opt, "keep_html_and_head_opening_tags"),
+    keep_html_opening_tag: get_bool!(cx, opt, "keep_html_opening_tag"),
     keep_input_type_text_attr: get_bool!(cx, opt, "keep_input_type_text_attr"),
+    keep_shy_entity: get_bool!(cx, opt, "keep_shy_entity"),
     keep_ssi_comments: get_bool!(cx, opt, "keep_ssi_comments"),
     minify_css: get_bool!(cx, opt, "minify_css"),
     minify_doctype: get_bool!(cx, opt, "minify_doctype"),
     minify_js: get_bool!(cx, opt, "minify_js"),
+    minify_kept_comment_whitespace: get_bool!(cx, opt, "minify_kept_comment_whitespace"),
+    minify_svg_default_attributes: get_bool!(cx, opt, "minify_svg_default_attributes"),
+    normalize_doctype_to_html5: get_bool!(cx, opt, "normalize_doctype_to_html5"),
+    normalize_meta_viewport: get_bool!(cx, opt, "normalize_meta_viewport"),
+    normalize_urls: get_bool!(cx, opt, "normalize_urls"),
+    omit_body_opening_tag: get_bool!(cx, opt, "omit_body_opening_tag"),
     preserve_brace_template_syntax: get_bool!(cx, opt, "preserve_brace_template_syntax"),
     preserve_chevron_percent_template_syntax: get_bool!(cx, opt, "preserve_chevron_percent_template_syntax"),
+    preserve_ignored_regions: get_bool!(cx, opt, "preserve_ignored_regions"),
+    preserve_ko_comments: get_bool!(cx, opt, "preserve_ko_comments"),
+    preserve_microdata_attributes: get_bool!(cx, opt, "preserve_microdata_attributes"),
+    preserve_prolog: get_bool!(cx, opt, "preserve_prolog"),
     remove_bangs: get_bool!(cx, opt, "remove_bangs"),
+    remove_generator_meta: get_bool!(cx, opt, "remove_generator_meta"),
+    remove_hidden_elements: get_bool!(cx, opt, "remove_hidden_elements"),
+    remove_html5_shiv: get_bool!(cx, opt, "remove_html5_shiv"),
     remove_processing_instructions: get_bool!(cx, opt, "remove_processing_instructions"),
+    remove_redundant_script_attributes: get_bool!(cx, opt, "remove_redundant_script_attributes"),
+    remove_type_module_attribute: get_bool!(cx, opt, "remove_type_module_attribute"),
+    remove_unreferenced_ids: get_bool!(cx, opt, "remove_unreferenced_ids"),
+    remove_x_ua_compatible_meta: get_bool!(cx, opt, "remove_x_ua_compatible_meta"),
+    remove_xml_declaration: get_bool!(cx, opt, "remove_xml_declaration"),
+    respect_contenteditable: get_bool!(cx, opt, "respect_contenteditable"),
+    skip_style_attr_minification: get_bool!(cx, opt, "skip_style_attr_minification"),
+    strip_code_comments: get_bool!(cx, opt, "strip_code_comments"),
+    strip_legacy_script_comments: get_bool!(cx, opt, "strip_legacy_script_comments"),
+    trim_form_control_text: get_bool!(cx, opt, "trim_form_control_text"),
+    unwrap_redundant_wrappers: get_bool!(cx, opt, "unwrap_redundant_wrappers"),
+    validate_preload_links: get_bool!(cx, opt, "validate_preload_links"),
+    ..Default::default()
   };
   let out = minify_html::minify(src.as_slice(&cx), &cfg);
   Ok(JsBuffer::external(&mut cx, out))
@@ -3,16 +3,46 @@ def minify(
     allow_noncompliant_unquoted_attribute_values: bool = False,
     allow_optimal_entities: bool = False,
     allow_removing_spaces_between_attributes: bool = False,
+    inject_charset_meta: bool = False,
+    keep_balancing_comments: bool = False,
     keep_closing_tags: bool = False,
     keep_comments: bool = False,
+    keep_head_opening_tag: bool = False,
     keep_html_and_head_opening_tags: bool = False,
+    keep_html_opening_tag: bool = False,
     keep_input_type_text_attr: bool = False,
+    keep_shy_entity: bool = False,
     keep_ssi_comments: bool = False,
     minify_css: bool = False,
     minify_doctype: bool = False,
     minify_js: bool = False,
+    minify_kept_comment_whitespace: bool = False,
+    minify_svg_default_attributes: bool = False,
+    normalize_doctype_to_html5: bool = False,
+    normalize_meta_viewport: bool = False,
+    normalize_urls: bool = False,
+    omit_body_opening_tag: bool = False,
     preserve_brace_template_syntax: bool = False,
     preserve_chevron_percent_template_syntax: bool = False,
+    preserve_ignored_regions: bool = False,
+    preserve_ko_comments: bool = False,
+    preserve_microdata_attributes: bool = False,
+    preserve_prolog: bool = False,
     remove_bangs: bool = False,
+    remove_generator_meta: bool = False,
+    remove_hidden_elements: bool = False,
+    remove_html5_shiv: bool = False,
     remove_processing_instructions: bool = False,
+    remove_redundant_script_attributes: bool = False,
+    remove_type_module_attribute: bool = False,
+    remove_unreferenced_ids: bool = False,
+    remove_x_ua_compatible_meta: bool = False,
+    remove_xml_declaration: bool = False,
+    respect_contenteditable: bool = False,
+    skip_style_attr_minification: bool = False,
+    strip_code_comments: bool = False,
+    strip_legacy_script_comments: bool = False,
+    trim_form_control_text: bool = False,
+    unwrap_redundant_wrappers: bool = False,
+    validate_preload_links: bool = False,
 ) -> str: ...
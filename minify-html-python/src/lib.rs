@@ -10,54 +10,145 @@ use std::string::String;
   allow_noncompliant_unquoted_attribute_values = "false",
   allow_optimal_entities = "false",
   allow_removing_spaces_between_attributes = "false",
+  inject_charset_meta = "false",
+  keep_balancing_comments = "false",
   keep_closing_tags = "false",
   keep_comments = "false",
+  keep_head_opening_tag = "false",
   keep_html_and_head_opening_tags = "false",
+  keep_html_opening_tag = "false",
   keep_input_type_text_attr = "false",
+  keep_shy_entity = "false",
   keep_ssi_comments = "false",
   minify_css = "false",
   minify_doctype = "false",
   minify_js = "false",
+  minify_kept_comment_whitespace = "false",
+  minify_svg_default_attributes = "false",
+  normalize_doctype_to_html5 = "false",
+  normalize_meta_viewport = "false",
+  normalize_urls = "false",
+  omit_body_opening_tag = "false",
   preserve_brace_template_syntax = "false",
   preserve_chevron_percent_template_syntax = "false",
+  preserve_ignored_regions = "false",
+  preserve_ko_comments = "false",
+  preserve_microdata_attributes = "false",
+  preserve_prolog = "false",
   remove_bangs = "false",
-  remove_processing_instructions = "false"
+  remove_generator_meta = "false",
+  remove_hidden_elements = "false",
+  remove_html5_shiv = "false",
+  remove_processing_instructions = "false",
+  remove_redundant_script_attributes = "false",
+  remove_type_module_attribute = "false",
+  remove_unreferenced_ids = "false",
+  remove_x_ua_compatible_meta = "false",
+  remove_xml_declaration = "false",
+  respect_contenteditable = "false",
+  skip_style_attr_minification = "false",
+  strip_code_comments = "false",
+  strip_legacy_script_comments = "false",
+  trim_form_control_text = "false",
+  unwrap_redundant_wrappers = "false",
+  validate_preload_links = "false"
 )]
 fn minify(
   code: String,
   allow_noncompliant_unquoted_attribute_values: bool,
   allow_optimal_entities: bool,
   allow_removing_spaces_between_attributes: bool,
+  inject_charset_meta: bool,
+  keep_balancing_comments: bool,
   keep_closing_tags: bool,
   keep_comments: bool,
+  keep_head_opening_tag: bool,
   keep_html_and_head_opening_tags: bool,
+  keep_html_opening_tag: bool,
   keep_input_type_text_attr: bool,
+  keep_shy_entity: bool,
   keep_ssi_comments: bool,
   minify_css: bool,
   minify_doctype: bool,
   minify_js: bool,
+  minify_kept_comment_whitespace: bool,
+  minify_svg_default_attributes: bool,
+  normalize_doctype_to_html5: bool,
+  normalize_meta_viewport: bool,
+  normalize_urls: bool,
+  omit_body_opening_tag: bool,
   preserve_brace_template_syntax: bool,
   preserve_chevron_percent_template_syntax: bool,
+  preserve_ignored_regions: bool,
+  preserve_ko_comments: bool,
+  preserve_microdata_attributes: bool,
+  preserve_prolog: bool,
   remove_bangs: bool,
+  remove_generator_meta: bool,
+  remove_hidden_elements: bool,
+  remove_html5_shiv: bool,
   remove_processing_instructions: bool,
+  remove_redundant_script_attributes: bool,
+  remove_type_module_attribute: bool,
+  remove_unreferenced_ids: bool,
+  remove_x_ua_compatible_meta: bool,
+  remove_xml_declaration: bool,
+  respect_contenteditable: bool,
+  skip_style_attr_minification: bool,
+  strip_code_comments: bool,
+  strip_legacy_script_comments: bool,
+  trim_form_control_text: bool,
+  unwrap_redundant_wrappers: bool,
+  validate_preload_links: bool,
 ) -> PyResult<String> {
   let code = code.into_bytes();
   let out_code = minify_html_native(&code, &Cfg {
     allow_noncompliant_unquoted_attribute_values,
     allow_optimal_entities,
     allow_removing_spaces_between_attributes,
+    inject_charset_meta,
+    keep_balancing_comments,
     keep_closing_tags,
     keep_comments,
+    keep_head_opening_tag,
     keep_html_and_head_opening_tags,
+    keep_html_opening_tag,
     keep_input_type_text_attr,
+    keep_shy_entity,
     keep_ssi_comments,
     minify_css,
     minify_doctype,
     minify_js,
+    minify_kept_comment_whitespace,
+    minify_svg_default_attributes,
+    normalize_doctype_to_html5,
+    normalize_meta_viewport,
+    normalize_urls,
+    omit_body_opening_tag,
     preserve_brace_template_syntax,
     preserve_chevron_percent_template_syntax,
+    preserve_ignored_regions,
+    preserve_ko_comments,
+    preserve_microdata_attributes,
+    preserve_prolog,
     remove_bangs,
+    remove_generator_meta,
+    remove_hidden_elements,
+    remove_html5_shiv,
     remove_processing_instructions,
+    remove_redundant_script_attributes,
+    remove_type_module_attribute,
+    remove_unreferenced_ids,
+    remove_x_ua_compatible_meta,
+    remove_xml_declaration,
+    respect_contenteditable,
+    skip_style_attr_minification,
+    strip_code_comments,
+    strip_legacy_script_comments,
+    trim_form_control_text,
+    unwrap_redundant_wrappers,
+    validate_preload_links,
+    ..Default::default()
   });
   Ok(String::from_utf8(out_code).unwrap())
 }
@@ -11,18 +11,49 @@ fn minify_html(source: String, cfg: RHash) -> String {
     allow_noncompliant_unquoted_attribute_values: cfg.aref(StaticSymbol::new("allow_noncompliant_unquoted_attribute_values")).unwrap_or_default(),
     allow_optimal_entities: cfg.aref(StaticSymbol::new("allow_optimal_entities")).unwrap_or_default(),
     allow_removing_spaces_between_attributes: cfg.aref(StaticSymbol::new("allow_removing_spaces_between_attributes")).unwrap_or_default(),
+    inject_charset_meta: cfg.aref(StaticSymbol::new("inject_charset_meta")).unwrap_or_default(),
+    keep_balancing_comments: cfg.aref(StaticSymbol::new("keep_balancing_comments")).unwrap_or_default(),
     keep_closing_tags: cfg.aref(StaticSymbol::new("keep_closing_tags")).unwrap_or_default(),
     keep_comments: cfg.aref(StaticSymbol::new("keep_comments")).unwrap_or_default(),
+    keep_head_opening_tag: cfg.aref(StaticSymbol::new("keep_head_opening_tag")).unwrap_or_default(),
     keep_html_and_head_opening_tags: cfg.aref(StaticSymbol::new("keep_html_and_head_opening_tags")).unwrap_or_default(),
+    keep_html_opening_tag: cfg.aref(StaticSymbol::new("keep_html_opening_tag")).unwrap_or_default(),
     keep_input_type_text_attr: cfg.aref(StaticSymbol::new("keep_input_type_text_attr")).unwrap_or_default(),
+    keep_shy_entity: cfg.aref(StaticSymbol::new("keep_shy_entity")).unwrap_or_default(),
     keep_ssi_comments: cfg.aref(StaticSymbol::new("keep_ssi_comments")).unwrap_or_default(),
     minify_css: cfg.aref(StaticSymbol::new("minify_css")).unwrap_or_default(),
     minify_doctype: cfg.aref(StaticSymbol::new("minify_doctype")).unwrap_or_default(),
     minify_js: cfg.aref(StaticSymbol::new("minify_js")).unwrap_or_default(),
+    minify_kept_comment_whitespace: cfg.aref(StaticSymbol::new("minify_kept_comment_whitespace")).unwrap_or_default(),
+    minify_svg_default_attributes: cfg.aref(StaticSymbol::new("minify_svg_default_attributes")).unwrap_or_default(),
+    normalize_doctype_to_html5: cfg.aref(StaticSymbol::new("normalize_doctype_to_html5")).unwrap_or_default(),
+    normalize_meta_viewport: cfg.aref(StaticSymbol::new("normalize_meta_viewport")).unwrap_or_default(),
+    normalize_urls: cfg.aref(StaticSymbol::new("normalize_urls")).unwrap_or_default(),
+    omit_body_opening_tag: cfg.aref(StaticSymbol::new("omit_body_opening_tag")).unwrap_or_default(),
     preserve_brace_template_syntax: cfg.aref(StaticSymbol::new("preserve_brace_template_syntax")).unwrap_or_default(),
     preserve_chevron_percent_template_syntax: cfg.aref(StaticSymbol::new("preserve_chevron_percent_template_syntax")).unwrap_or_default(),
+    preserve_ignored_regions: cfg.aref(StaticSymbol::new("preserve_ignored_regions")).unwrap_or_default(),
+    preserve_ko_comments: cfg.aref(StaticSymbol::new("preserve_ko_comments")).unwrap_or_default(),
+    preserve_microdata_attributes: cfg.aref(StaticSymbol::new("preserve_microdata_attributes")).unwrap_or_default(),
+    preserve_prolog: cfg.aref(StaticSymbol::new("preserve_prolog")).unwrap_or_default(),
     remove_bangs: cfg.aref(StaticSymbol::new("remove_bangs")).unwrap_or_default(),
+    remove_generator_meta: cfg.aref(StaticSymbol::new("remove_generator_meta")).unwrap_or_default(),
+    remove_hidden_elements: cfg.aref(StaticSymbol::new("remove_hidden_elements")).unwrap_or_default(),
+    remove_html5_shiv: cfg.aref(StaticSymbol::new("remove_html5_shiv")).unwrap_or_default(),
     remove_processing_instructions: cfg.aref(StaticSymbol::new("remove_processing_instructions")).unwrap_or_default(),
+    remove_redundant_script_attributes: cfg.aref(StaticSymbol::new("remove_redundant_script_attributes")).unwrap_or_default(),
+    remove_type_module_attribute: cfg.aref(StaticSymbol::new("remove_type_module_attribute")).unwrap_or_default(),
+    remove_unreferenced_ids: cfg.aref(StaticSymbol::new("remove_unreferenced_ids")).unwrap_or_default(),
+    remove_x_ua_compatible_meta: cfg.aref(StaticSymbol::new("remove_x_ua_compatible_meta")).unwrap_or_default(),
+    remove_xml_declaration: cfg.aref(StaticSymbol::new("remove_xml_declaration")).unwrap_or_default(),
+    respect_contenteditable: cfg.aref(StaticSymbol::new("respect_contenteditable")).unwrap_or_default(),
+    skip_style_attr_minification: cfg.aref(StaticSymbol::new("skip_style_attr_minification")).unwrap_or_default(),
+    strip_code_comments: cfg.aref(StaticSymbol::new("strip_code_comments")).unwrap_or_default(),
+    strip_legacy_script_comments: cfg.aref(StaticSymbol::new("strip_legacy_script_comments")).unwrap_or_default(),
+    trim_form_control_text: cfg.aref(StaticSymbol::new("trim_form_control_text")).unwrap_or_default(),
+    unwrap_redundant_wrappers: cfg.aref(StaticSymbol::new("unwrap_redundant_wrappers")).unwrap_or_default(),
+    validate_preload_links: cfg.aref(StaticSymbol::new("validate_preload_links")).unwrap_or_default(),
+    ..Default::default()
   });
   String::from_utf8(out_code).unwrap()
 }
@@ -25,18 +25,49 @@ pub fn minify(code: &[u8], cfg: &JsValue) -> Vec<u8> {
     allow_noncompliant_unquoted_attribute_values: get_prop!(cfg, "allow_noncompliant_unquoted_attribute_values"),
     allow_optimal_entities: get_prop!(cfg, "allow_optimal_entities"),
     allow_removing_spaces_between_attributes: get_prop!(cfg, "allow_removing_spaces_between_attributes"),
+    inject_charset_meta: get_prop!(cfg, "inject_charset_meta"),
+    keep_balancing_comments: get_prop!(cfg, "keep_balancing_comments"),
     keep_closing_tags: get_prop!(cfg, "keep_closing_tags"),
     keep_comments: get_prop!(cfg, "keep_comments"),
+    keep_head_opening_tag: get_prop!(cfg, "keep_head_opening_tag"),
     keep_html_and_head_opening_tags: get_prop!(cfg, "keep_html_and_head_opening_tags"),
+    keep_html_opening_tag: get_prop!(cfg, "keep_html_opening_tag"),
     keep_input_type_text_attr: get_prop!(cfg, "keep_input_type_text_attr"),
+    keep_shy_entity: get_prop!(cfg, "keep_shy_entity"),
     keep_ssi_comments: get_prop!(cfg, "keep_ssi_comments"),
     minify_css: get_prop!(cfg, "minify_css"),
     minify_doctype: get_prop!(cfg, "minify_doctype"),
     minify_js: get_prop!(cfg, "minify_js"),
+    minify_kept_comment_whitespace: get_prop!(cfg, "minify_kept_comment_whitespace"),
+    minify_svg_default_attributes: get_prop!(cfg, "minify_svg_default_attributes"),
+    normalize_doctype_to_html5: get_prop!(cfg, "normalize_doctype_to_html5"),
+    normalize_meta_viewport: get_prop!(cfg, "normalize_meta_viewport"),
+    normalize_urls: get_prop!(cfg, "normalize_urls"),
+    omit_body_opening_tag: get_prop!(cfg, "omit_body_opening_tag"),
     preserve_brace_template_syntax: get_prop!(cfg, "preserve_brace_template_syntax"),
     preserve_chevron_percent_template_syntax: get_prop!(cfg, "preserve_chevron_percent_template_syntax"),
+    preserve_ignored_regions: get_prop!(cfg, "preserve_ignored_regions"),
+    preserve_ko_comments: get_prop!(cfg, "preserve_ko_comments"),
+    preserve_microdata_attributes: get_prop!(cfg, "preserve_microdata_attributes"),
+    preserve_prolog: get_prop!(cfg, "preserve_prolog"),
     remove_bangs: get_prop!(cfg, "remove_bangs"),
+    remove_generator_meta: get_prop!(cfg, "remove_generator_meta"),
+    remove_hidden_elements: get_prop!(cfg, "remove_hidden_elements"),
+    remove_html5_shiv: get_prop!(cfg, "remove_html5_shiv"),
     remove_processing_instructions: get_prop!(cfg, "remove_processing_instructions"),
+    remove_redundant_script_attributes: get_prop!(cfg, "remove_redundant_script_attributes"),
+    remove_type_module_attribute: get_prop!(cfg, "remove_type_module_attribute"),
+    remove_unreferenced_ids: get_prop!(cfg, "remove_unreferenced_ids"),
+    remove_x_ua_compatible_meta: get_prop!(cfg, "remove_x_ua_compatible_meta"),
+    remove_xml_declaration: get_prop!(cfg, "remove_xml_declaration"),
+    respect_contenteditable: get_prop!(cfg, "respect_contenteditable"),
+    skip_style_attr_minification: get_prop!(cfg, "skip_style_attr_minification"),
+    strip_code_comments: get_prop!(cfg, "strip_code_comments"),
+    strip_legacy_script_comments: get_prop!(cfg, "strip_legacy_script_comments"),
+    trim_form_control_text: get_prop!(cfg, "trim_form_control_text"),
+    unwrap_redundant_wrappers: get_prop!(cfg, "unwrap_redundant_wrappers"),
+    validate_preload_links: get_prop!(cfg, "validate_preload_links"),
+    ..Default::default()
   };
   minify_html::minify(code, &cfg)
 }
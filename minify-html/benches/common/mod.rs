@@ -0,0 +1,28 @@
+use minify_html::Cfg;
+use std::fs;
+use std::path::PathBuf;
+
+pub const PAGES: &[&str] = &["app-shell", "article", "report", "scripts"];
+
+pub fn corpus_dir() -> PathBuf {
+  PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("benches/corpus")
+}
+
+pub fn read_page(name: &str) -> Vec<u8> {
+  fs::read(corpus_dir().join(format!("{}.html", name))).unwrap()
+}
+
+// Combinations of options that exercise different parts of the minifier. Changing these requires updating the expected sizes checked by the `corpus` test.
+pub fn cfgs() -> Vec<(&'static str, Cfg)> {
+  let mut code = Cfg::new();
+  code.minify_css = true;
+  code.minify_js = true;
+  let mut noncompliant = code.clone();
+  noncompliant.enable_possibly_noncompliant();
+  vec![
+    ("default", Cfg::new()),
+    ("code", code),
+    ("noncompliant", noncompliant),
+    ("email", Cfg::for_email_html()),
+  ]
+}
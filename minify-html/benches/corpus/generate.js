@@ -0,0 +1,147 @@
+// Generates the small, representative pages in this folder that are used by the `minify` benchmark and the `corpus` test. The output is deterministic, so rerunning this without changes leaves the pages as is.
+//
+// Usage: node generate.js
+
+const { writeFileSync } = require("fs");
+const path = require("path");
+
+const WORDS =
+  "lorem ipsum dolor sit amet consectetur adipiscing elit sed do eiusmod tempor incididunt ut labore et dolore magna aliqua enim ad minim veniam quis nostrud exercitation ullamco laboris nisi aliquip ex ea commodo consequat".split(
+    " ",
+  );
+
+// A linear congruential generator, so that the pages don't depend on Math.random.
+let seed = 1;
+const rand = (n) => {
+  seed = (seed * 1103515245 + 12345) % 2147483648;
+  return seed % n;
+};
+const words = (n) =>
+  Array.from({ length: n }, () => WORDS[rand(WORDS.length)]).join(" ");
+const repeat = (n, fn) => Array.from({ length: n }, (_, i) => fn(i)).join("\n");
+
+const page = (title, head, body) => `<!DOCTYPE html>
+<html lang="en">
+  <head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>${title}</title>
+${head}
+  </head>
+  <body>
+${body}
+  </body>
+</html>
+`;
+
+const pages = {
+  // Long runs of text with inline formatting, like a blog post.
+  article: () =>
+    page(
+      "An article",
+      `    <link rel="stylesheet" href="/style.css">`,
+      `    <header class="site-header">
+      <nav><ul>
+${repeat(6, (i) => `        <li><a href="/section-${i}/">${words(1)}</a></li>`)}
+      </ul></nav>
+    </header>
+    <main>
+      <article>
+        <h1>${words(6)}</h1>
+        <p class="byline">By <a href="/authors/a">${words(2)}</a> &middot; <time datetime="2024-01-01">January 1, 2024</time></p>
+${repeat(
+  40,
+  (i) => `        ${i % 8 == 0 ? `<h2 id="s${i}">${words(4)}</h2>\n        ` : ""}<p>
+          ${words(30)} <em>${words(3)}</em> ${words(20)} <a href="#s${i - (i % 8)}">${words(2)}</a>
+          ${words(25)} <strong>${words(2)}</strong>&nbsp;${words(15)}.
+        </p>`,
+)}
+        <blockquote>
+          <p>${words(40)}</p>
+        </blockquote>
+      </article>
+    </main>
+    <!-- Footer -->
+    <footer><p>&copy; 2024 ${words(3)}</p></footer>`,
+    ),
+
+  // Deeply nested elements with many attributes, like a single page app's markup.
+  "app-shell": () =>
+    page(
+      "An app",
+      `    <link rel="preload" href="/app.js" as="script">`,
+      `    <div id="app" class="app app--loading" data-theme="dark" data-version="1.2.3">
+${repeat(
+  60,
+  (i) => `      <div class="card card--${i % 4}" data-id="${i}" role="listitem" aria-labelledby="card-${i}-title">
+        <div class="card__header">
+          <h3 id="card-${i}-title" class="card__title">${words(3)}</h3>
+          <button type="button" class="btn btn--icon" aria-label="${words(2)}" data-action="toggle" tabindex="0" disabled="">
+            <svg viewBox="0 0 24 24" width="24" height="24" aria-hidden="true"><path d="M12 ${i} L${i} 12 Z" fill="none" stroke="currentColor" stroke-width="2"/></svg>
+          </button>
+        </div>
+        <div class="card__body"><input type="text" name="field-${i}" value="${words(1)}" placeholder="${words(2)}" autocomplete="off"></div>
+      </div>`,
+)}
+    </div>`,
+    ),
+
+  // Large tables with short cells, like a generated report.
+  report: () =>
+    page(
+      "A report",
+      `    <style>
+      table { border-collapse: collapse; width: 100%; }
+      td, th { border: 1px solid #cccccc; padding: 4px 8px; }
+    </style>`,
+      `    <h1>Quarterly report</h1>
+    <table>
+      <thead>
+        <tr>
+${repeat(8, (i) => `          <th scope="col">Column ${i}</th>`)}
+        </tr>
+      </thead>
+      <tbody>
+${repeat(
+  150,
+  (r) => `        <tr>
+${repeat(8, (c) => `          <td${c == 0 ? ' class="label"' : ""}>${c == 0 ? words(2) : rand(100000) / 100}</td>`)}
+        </tr>`,
+)}
+      </tbody>
+    </table>`,
+    ),
+
+  // Many inline scripts and styles, like a page with analytics and widgets.
+  scripts: () =>
+    page(
+      "Scripts",
+      repeat(
+        10,
+        (i) => `    <style>
+      .widget-${i} { display: flex; margin: 0 auto; padding: ${i}px; color: #ff0000; }
+      .widget-${i} > .item:hover { background-color: rgba(0, 0, 0, 0.5); }
+    </style>`,
+      ),
+      repeat(
+        20,
+        (i) => `    <div class="widget-${i % 10}"><span class="item">${words(5)}</span></div>
+    <script type="text/javascript">
+      (function () {
+        var element = document.querySelector(".widget-${i % 10}");
+        var items = ["${words(1)}", "${words(1)}", "${words(1)}"];
+        for (var index = 0; index < items.length; index++) {
+          element.setAttribute("data-item-" + index, items[index]);
+        }
+        window.addEventListener("load", function () {
+          console.log("Loaded widget ${i}", element);
+        });
+      })();
+    </script>`,
+      ),
+    ),
+};
+
+for (const [name, gen] of Object.entries(pages)) {
+  writeFileSync(path.join(__dirname, `${name}.html`), gen());
+}
@@ -0,0 +1,25 @@
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::BenchmarkId;
+use criterion::Criterion;
+use criterion::Throughput;
+use minify_html::minify;
+
+mod common;
+
+fn bench_minify(c: &mut Criterion) {
+  for (cfg_name, cfg) in common::cfgs() {
+    let mut group = c.benchmark_group(cfg_name);
+    for &page in common::PAGES {
+      let src = common::read_page(page);
+      group.throughput(Throughput::Bytes(src.len() as u64));
+      group.bench_with_input(BenchmarkId::from_parameter(page), &src, |b, src| {
+        b.iter(|| minify(src, &cfg))
+      });
+    }
+    group.finish();
+  }
+}
+
+criterion_group!(benches, bench_minify);
+criterion_main!(benches);
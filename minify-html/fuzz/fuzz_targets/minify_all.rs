@@ -0,0 +1,26 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use minify_html::minify;
+use minify_html::Cfg;
+use once_cell::sync::Lazy;
+
+// Every minification enabled, including CSS and JS.
+static CFG: Lazy<Cfg> = Lazy::new(|| {
+  let mut cfg = Cfg {
+    minify_css: true,
+    minify_js: true,
+    normalize_urls: true,
+    remove_bangs: true,
+    remove_processing_instructions: true,
+    remove_redundant_script_attributes: true,
+    strip_legacy_script_comments: true,
+    ..Cfg::default()
+  };
+  cfg.enable_possibly_noncompliant();
+  cfg
+});
+
+fuzz_target!(|data: &[u8]| {
+  minify(data, &CFG);
+});
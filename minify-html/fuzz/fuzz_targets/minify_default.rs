@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use minify_html::minify;
+use minify_html::Cfg;
+
+fuzz_target!(|data: &[u8]| {
+  minify(data, &Cfg::new());
+});
@@ -0,0 +1,21 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use minify_html::minify;
+use minify_html::Cfg;
+use once_cell::sync::Lazy;
+
+static CFG: Lazy<Cfg> = Lazy::new(|| {
+  let mut cfg = Cfg {
+    keep_comments: true,
+    preserve_brace_template_syntax: true,
+    preserve_chevron_percent_template_syntax: true,
+    ..Cfg::default()
+  };
+  cfg.enable_framework_bindings();
+  cfg
+});
+
+fuzz_target!(|data: &[u8]| {
+  minify(data, &CFG);
+});
@@ -0,0 +1,93 @@
+#ifndef MINIFY_HTML_H
+#define MINIFY_HTML_H
+
+/*
+ * C ABI for minify-html, available when the crate is built with the `capi` feature, e.g.:
+ *
+ *   cargo rustc --release --features capi --crate-type cdylib
+ *
+ * Input memory is only borrowed for the duration of a call. A MinifiedBuffer returned by
+ * minify_html is owned by the caller, and must be freed exactly once with minify_html_free.
+ */
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The value to set `MinifyHtmlCfg.version` to. It changes whenever the fields of MinifyHtmlCfg do,
+ * so that minify_html can reject a struct laid out by a different version of this header. */
+#define MINIFY_HTML_CFG_VERSION 1
+
+/* The boolean options of the Rust `Cfg` struct, with the same names and meanings. Zero-initialise
+ * this struct and set `version` to MINIFY_HTML_CFG_VERSION to use the defaults. */
+typedef struct MinifyHtmlCfg {
+  uint32_t version;
+  bool allow_noncompliant_unquoted_attribute_values;
+  bool allow_optimal_entities;
+  bool allow_removing_spaces_between_attributes;
+  bool inject_charset_meta;
+  bool keep_balancing_comments;
+  bool keep_closing_tags;
+  bool keep_comments;
+  bool keep_head_opening_tag;
+  bool keep_html_and_head_opening_tags;
+  bool keep_html_opening_tag;
+  bool keep_input_type_text_attr;
+  bool keep_shy_entity;
+  bool keep_ssi_comments;
+  bool minify_css;
+  bool minify_doctype;
+  bool minify_js;
+  bool minify_kept_comment_whitespace;
+  bool minify_svg_default_attributes;
+  bool normalize_doctype_to_html5;
+  bool normalize_meta_viewport;
+  bool normalize_urls;
+  bool omit_body_opening_tag;
+  bool preserve_brace_template_syntax;
+  bool preserve_chevron_percent_template_syntax;
+  bool preserve_ignored_regions;
+  bool preserve_ko_comments;
+  bool preserve_microdata_attributes;
+  bool preserve_prolog;
+  bool remove_bangs;
+  bool remove_generator_meta;
+  bool remove_hidden_elements;
+  bool remove_html5_shiv;
+  bool remove_processing_instructions;
+  bool remove_redundant_script_attributes;
+  bool remove_type_module_attribute;
+  bool remove_unreferenced_ids;
+  bool remove_x_ua_compatible_meta;
+  bool remove_xml_declaration;
+  bool respect_contenteditable;
+  bool skip_style_attr_minification;
+  bool strip_code_comments;
+  bool strip_legacy_script_comments;
+  bool trim_form_control_text;
+  bool unwrap_redundant_wrappers;
+  bool validate_preload_links;
+} MinifyHtmlCfg;
+
+/* Minified code. `ptr` is NULL if minification failed. */
+typedef struct MinifiedBuffer {
+  uint8_t *ptr;
+  size_t len;
+} MinifiedBuffer;
+
+/* Minifies the `len` bytes of UTF-8 HTML code at `src`. `src` may be NULL if `len` is zero. `cfg`
+ * may be NULL to use the defaults. Fails if `cfg->version` isn't MINIFY_HTML_CFG_VERSION. */
+MinifiedBuffer minify_html(const uint8_t *src, size_t len, const MinifyHtmlCfg *cfg);
+
+/* Frees a buffer returned by minify_html. Does nothing if `buf.ptr` is NULL. */
+void minify_html_free(MinifiedBuffer buf);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,138 @@
+use crate::ast::NodeData;
+use crate::parse;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Display;
+use core::fmt::Formatter;
+
+#[cfg(test)]
+mod tests;
+
+// https://amp.dev/documentation/guides-and-tutorials/learn/spec/amphtml/#stylesheets.
+const MAX_STYLE_ATTR_LEN: usize = 1_000;
+const MAX_CSS_LEN: usize = 75_000;
+
+/// A reason why minified code would fail AMP validation, as found by `check_amp_html`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AmpWarning {
+  /// The code doesn't start with `<!doctype html>`.
+  InvalidDoctype,
+  /// There's no `<html>` start tag with an `amp` or `⚡` attribute.
+  MissingAmpAttribute,
+  /// A `<script>` that isn't the AMP runtime, an AMP extension, or JSON data. Contains the `src` attribute value, if any.
+  ForbiddenScript { src: Option<Vec<u8>> },
+  /// A `style` attribute value is longer than 1,000 bytes.
+  StyleAttributeTooLong { len: usize },
+  /// The `<style amp-custom>` contents and all `style` attribute values are longer than 75,000 bytes in total.
+  CssTooLong { len: usize },
+}
+
+impl Display for AmpWarning {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      AmpWarning::InvalidDoctype => write!(f, "the doctype is not `<!doctype html>`"),
+      AmpWarning::MissingAmpAttribute => {
+        write!(f, "the `<html>` tag has no `amp` or `⚡` attribute")
+      }
+      AmpWarning::ForbiddenScript { src: Some(src) } => write!(
+        f,
+        "script `{}` is not an AMP script",
+        String::from_utf8_lossy(src)
+      ),
+      AmpWarning::ForbiddenScript { src: None } => write!(f, "inline scripts are not allowed"),
+      AmpWarning::StyleAttributeTooLong { len } => write!(
+        f,
+        "style attribute is {} bytes, more than the limit of {}",
+        len, MAX_STYLE_ATTR_LEN
+      ),
+      AmpWarning::CssTooLong { len } => write!(
+        f,
+        "CSS is {} bytes in total, more than the limit of {}",
+        len, MAX_CSS_LEN
+      ),
+    }
+  }
+}
+
+fn check_nodes(nodes: &[NodeData], css_len: &mut usize, warnings: &mut Vec<AmpWarning>) {
+  for n in nodes {
+    let (attributes, children, name) = match n {
+      NodeData::Element {
+        attributes,
+        children,
+        name,
+        ..
+      } => (attributes, children, name),
+      _ => continue,
+    };
+    if let Some(style) = attributes.get(b"style".as_ref()) {
+      let len = style.as_slice().len();
+      if len > MAX_STYLE_ATTR_LEN {
+        warnings.push(AmpWarning::StyleAttributeTooLong { len });
+      };
+      *css_len += len;
+    };
+    match name.as_slice() {
+      b"script" => {
+        let src = attributes.get(b"src".as_ref()).map(|v| v.as_slice());
+        let is_json = attributes
+          .get(b"type".as_ref())
+          .filter(|t| {
+            let t = t.as_slice();
+            t.eq_ignore_ascii_case(b"application/json")
+              || t.eq_ignore_ascii_case(b"application/ld+json")
+          })
+          .is_some();
+        let is_amp = src
+          .filter(|s| s.starts_with(b"https://cdn.ampproject.org/"))
+          .is_some();
+        if !is_json && !is_amp {
+          warnings.push(AmpWarning::ForbiddenScript {
+            src: src.map(|s| s.to_vec()),
+          });
+        };
+      }
+      b"style" if attributes.contains_key(b"amp-custom".as_ref()) => {
+        for c in children {
+          if let NodeData::ScriptOrStyleContent { code, .. } = c {
+            *css_len += code.len();
+          };
+        }
+      }
+      _ => {}
+    };
+    check_nodes(children, css_len, warnings);
+  }
+}
+
+/// Checks code minified with `Cfg::for_amp_html` for common reasons it would fail [AMP validation](https://amp.dev/documentation/guides-and-tutorials/learn/validation-workflow/validation_errors/), such as a `<script>` other than the AMP runtime and extensions, or CSS over the size limits. This is not a full validator, so an empty result doesn't guarantee the code is valid AMP.
+pub fn check_amp_html(minified: &[u8]) -> Vec<AmpWarning> {
+  let mut warnings = Vec::new();
+  if !minified
+    .get(..15)
+    .is_some_and(|d| d.eq_ignore_ascii_case(b"<!doctype html>"))
+  {
+    warnings.push(AmpWarning::InvalidDoctype);
+  };
+  let doc = parse(minified);
+  let is_amp = doc
+    .select("html")
+    .unwrap_or_default()
+    .first()
+    .filter(|html| match html {
+      NodeData::Element { attributes, .. } => {
+        attributes.contains_key(b"amp".as_ref()) || attributes.contains_key("⚡".as_bytes())
+      }
+      _ => false,
+    })
+    .is_some();
+  if !is_amp {
+    warnings.push(AmpWarning::MissingAmpAttribute);
+  };
+  let mut css_len = 0;
+  check_nodes(doc.children(), &mut css_len, &mut warnings);
+  if css_len > MAX_CSS_LEN {
+    warnings.push(AmpWarning::CssTooLong { len: css_len });
+  };
+  warnings
+}
@@ -0,0 +1,56 @@
+use crate::amp::check_amp_html;
+use crate::amp::AmpWarning;
+use crate::cfg::Cfg;
+use crate::minify;
+use std::str::from_utf8;
+
+#[test]
+fn test_for_amp_html() {
+  let src = "<!DOCTYPE html>
+<html ⚡ lang=\"en\">
+<head>
+  <meta charset=\"utf-8\">
+  <script async src=\"https://cdn.ampproject.org/v0.js\"></script>
+  <script type=\"application/ld+json\">{\"a\": 1}</script>
+  <style amp-custom>
+    body { color: red }
+  </style>
+</head>
+<body>
+  <p style=\"color: blue\">Hi</p>
+</body>
+</html>";
+  let min = minify(src.as_bytes(), &Cfg::for_amp_html());
+  assert_eq!(
+    from_utf8(&min).unwrap(),
+    "<!doctype html><html lang=en ⚡><head><meta charset=utf-8><script async src=https://cdn.ampproject.org/v0.js></script><script type=application/ld+json>{\"a\": 1}</script><style amp-custom>body { color: red }</style><body><p style=\"color: blue\">Hi"
+  );
+  assert_eq!(check_amp_html(&min), Vec::new());
+}
+
+#[test]
+fn test_check_amp_html() {
+  assert_eq!(
+    check_amp_html(
+      b"<html><script>alert(1)</script><script src=https://example.com/a.js></script><script type=application/json>{}</script>"
+    ),
+    vec![
+      AmpWarning::InvalidDoctype,
+      AmpWarning::MissingAmpAttribute,
+      AmpWarning::ForbiddenScript { src: None },
+      AmpWarning::ForbiddenScript {
+        src: Some(b"https://example.com/a.js".to_vec())
+      },
+    ]
+  );
+
+  let src = format!(
+    "<!doctype html><html amp><div style=\"{}\"></div><style amp-custom>{}</style>",
+    "a".repeat(1_001),
+    "b".repeat(74_000),
+  );
+  assert_eq!(check_amp_html(src.as_bytes()), vec![
+    AmpWarning::StyleAttributeTooLong { len: 1_001 },
+    AmpWarning::CssTooLong { len: 75_001 },
+  ]);
+}
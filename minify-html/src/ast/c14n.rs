@@ -1,12 +1,17 @@
+use crate::ast::AttrVal;
 use crate::ast::ElementClosingTag;
 use crate::ast::NodeData;
 use aho_corasick::AhoCorasickBuilder;
 use aho_corasick::AhoCorasickKind;
 use aho_corasick::MatchKind;
+use alloc::vec;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use minify_html_common::lazy::Lazy;
 use minify_html_common::pattern::Replacer;
-use once_cell::sync::Lazy;
-use std::io::Write;
 
+// Matchers are built from static patterns, so building them never fails.
+#[allow(clippy::unwrap_used)]
 static TEXT_REPLACER: Lazy<Replacer> = Lazy::new(|| {
   Replacer::new(
     AhoCorasickBuilder::new()
@@ -17,6 +22,7 @@ static TEXT_REPLACER: Lazy<Replacer> = Lazy::new(|| {
     vec![b"&amp;".to_vec(), b"&lt;".to_vec()],
   )
 });
+#[allow(clippy::unwrap_used)]
 static DOUBLE_QUOTED_REPLACER: Lazy<Replacer> = Lazy::new(|| {
   Replacer::new(
     AhoCorasickBuilder::new()
@@ -27,6 +33,7 @@ static DOUBLE_QUOTED_REPLACER: Lazy<Replacer> = Lazy::new(|| {
     vec![b"&amp;".to_vec(), b"&#34;".to_vec()],
   )
 });
+#[allow(clippy::unwrap_used)]
 static SINGLE_QUOTED_REPLACER: Lazy<Replacer> = Lazy::new(|| {
   Replacer::new(
     AhoCorasickBuilder::new()
@@ -37,6 +44,7 @@ static SINGLE_QUOTED_REPLACER: Lazy<Replacer> = Lazy::new(|| {
     vec![b"&amp;".to_vec(), b"&#39;".to_vec()],
   )
 });
+#[allow(clippy::unwrap_used)]
 static UNQUOTED_REPLACER: Lazy<Replacer> = Lazy::new(|| {
   Replacer::new(
     AhoCorasickBuilder::new()
@@ -68,25 +76,68 @@ static UNQUOTED_REPLACER: Lazy<Replacer> = Lazy::new(|| {
   )
 });
 
-pub fn c14n_serialise_ast<T: Write>(out: &mut T, node: &NodeData) -> std::io::Result<()> {
+pub fn c14n_serialise_opening_tag(
+  out: &mut Vec<u8>,
+  name: &[u8],
+  attributes: &HashMap<Vec<u8>, AttrVal>,
+  closing_tag: ElementClosingTag,
+) {
+  out.extend_from_slice(b"<");
+  out.extend_from_slice(name);
+  let mut attrs_sorted = attributes.iter().collect::<Vec<_>>();
+  attrs_sorted.sort_unstable_by(|a, b| a.0.cmp(b.0));
+  for (name, value) in attrs_sorted.iter() {
+    out.extend_from_slice(b" ");
+    out.extend_from_slice(name);
+    if !value.value.is_empty() {
+      out.extend_from_slice(b"=");
+      match value.quote {
+        Some(b'"') => {
+          out.extend_from_slice(b"\"");
+          out.extend_from_slice(&DOUBLE_QUOTED_REPLACER.replace_all(&value.value));
+          out.extend_from_slice(b"\"");
+        }
+        Some(b'\'') => {
+          out.extend_from_slice(b"'");
+          out.extend_from_slice(&SINGLE_QUOTED_REPLACER.replace_all(&value.value));
+          out.extend_from_slice(b"'");
+        }
+        None => {
+          out.extend_from_slice(&UNQUOTED_REPLACER.replace_all(&value.value));
+        }
+        _ => unreachable!(),
+      };
+    };
+  }
+  if closing_tag == ElementClosingTag::SelfClosing {
+    out.extend_from_slice(b" /");
+  };
+  out.extend_from_slice(b">");
+}
+
+pub fn c14n_serialise_text(out: &mut Vec<u8>, value: &[u8]) {
+  out.extend_from_slice(&TEXT_REPLACER.replace_all(value));
+}
+
+pub fn c14n_serialise_ast(out: &mut Vec<u8>, node: &NodeData) {
   match node {
     NodeData::Bang { code, .. } => {
-      out.write_all(b"<!")?;
-      out.write_all(code)?;
-      out.write_all(b">")?;
+      out.extend_from_slice(b"<!");
+      out.extend_from_slice(code);
+      out.extend_from_slice(b">");
     }
     NodeData::Comment { code, .. } => {
-      out.write_all(b"<!--")?;
-      out.write_all(code)?;
-      out.write_all(b"-->")?;
+      out.extend_from_slice(b"<!--");
+      out.extend_from_slice(code);
+      out.extend_from_slice(b"-->");
     }
     NodeData::Doctype { legacy, .. } => {
-      out.write_all(b"<!DOCTYPE html")?;
+      out.extend_from_slice(b"<!DOCTYPE html");
       if !legacy.is_empty() {
-        out.write_all(b" ")?;
-        out.write_all(legacy)?;
+        out.extend_from_slice(b" ");
+        out.extend_from_slice(legacy);
       };
-      out.write_all(b">")?;
+      out.extend_from_slice(b">");
     }
     NodeData::Element {
       attributes,
@@ -95,63 +146,30 @@ pub fn c14n_serialise_ast<T: Write>(out: &mut T, node: &NodeData) -> std::io::Re
       name,
       ..
     } => {
-      out.write_all(b"<")?;
-      out.write_all(name)?;
-      let mut attrs_sorted = attributes.iter().collect::<Vec<_>>();
-      attrs_sorted.sort_unstable_by(|a, b| a.0.cmp(b.0));
-      for (name, value) in attrs_sorted.iter() {
-        out.write_all(b" ")?;
-        out.write_all(name)?;
-        if !value.value.is_empty() {
-          out.write_all(b"=")?;
-          match value.quote {
-            Some(b'"') => {
-              out.write_all(b"\"")?;
-              out.write_all(&DOUBLE_QUOTED_REPLACER.replace_all(&value.value))?;
-              out.write_all(b"\"")?;
-            }
-            Some(b'\'') => {
-              out.write_all(b"'")?;
-              out.write_all(&SINGLE_QUOTED_REPLACER.replace_all(&value.value))?;
-              out.write_all(b"'")?;
-            }
-            None => {
-              out.write_all(&UNQUOTED_REPLACER.replace_all(&value.value))?;
-            }
-            _ => unreachable!(),
-          };
-        };
-      }
-      if closing_tag == &ElementClosingTag::SelfClosing {
-        out.write_all(b" /")?;
-      };
-      out.write_all(b">")?;
+      c14n_serialise_opening_tag(out, name, attributes, *closing_tag);
       for c in children {
-        c14n_serialise_ast(out, c)?;
+        c14n_serialise_ast(out, c);
       }
       if closing_tag == &ElementClosingTag::Present {
-        out.write_all(b"</")?;
-        out.write_all(name)?;
-        out.write_all(b">")?;
+        out.extend_from_slice(b"</");
+        out.extend_from_slice(name);
+        out.extend_from_slice(b">");
       };
     }
     NodeData::Instruction { code, .. } => {
-      out.write_all(b"<?")?;
-      out.write_all(code)?;
-      out.write_all(b"?>")?;
+      out.extend_from_slice(b"<?");
+      out.extend_from_slice(code);
+      out.extend_from_slice(b"?>");
     }
-    NodeData::RcdataContent { typ: _, text } => {
-      out.write_all(&TEXT_REPLACER.replace_all(text))?;
+    NodeData::RcdataContent { typ: _, text, .. } => {
+      out.extend_from_slice(&TEXT_REPLACER.replace_all(text));
     }
     NodeData::ScriptOrStyleContent { code, .. } => {
-      out.write_all(code)?;
-    }
-    NodeData::Text { value } => {
-      out.write_all(&TEXT_REPLACER.replace_all(value))?;
+      out.extend_from_slice(code);
     }
-    NodeData::Opaque { raw_source } => {
-      out.write_all(raw_source)?;
+    NodeData::Text { value, .. } => c14n_serialise_text(out, value),
+    NodeData::Opaque { raw_source, .. } => {
+      out.extend_from_slice(raw_source);
     }
   };
-  Ok(())
 }
@@ -1,10 +1,19 @@
-use ahash::AHashMap;
+use crate::ast::pretty::pretty_serialise_ast;
+use crate::cfg::Cfg;
+use crate::document::Document;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::fmt::Display;
+use core::fmt::Formatter;
+use hashbrown::HashMap;
 use minify_html_common::spec::tag::ns::Namespace;
-use std::fmt::Debug;
-use std::fmt::Formatter;
-use std::str::from_utf8;
+use minify_html_common::spec::tag::EMPTY_SLICE;
 
 pub mod c14n;
+pub mod pretty;
 
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 pub enum ElementClosingTag {
@@ -14,6 +23,43 @@ pub enum ElementClosingTag {
   Void,
 }
 
+impl Display for ElementClosingTag {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.write_str(match self {
+      ElementClosingTag::Omitted => "omitted",
+      ElementClosingTag::Present => "present",
+      ElementClosingTag::SelfClosing => "self-closing",
+      ElementClosingTag::Void => "void",
+    })
+  }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ParseElementClosingTagError;
+
+impl Display for ParseElementClosingTagError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.write_str("expected one of `omitted`, `present`, `self-closing`, or `void`")
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseElementClosingTagError {}
+
+impl TryFrom<&str> for ElementClosingTag {
+  type Error = ParseElementClosingTagError;
+
+  fn try_from(s: &str) -> Result<Self, Self::Error> {
+    match s {
+      "omitted" => Ok(ElementClosingTag::Omitted),
+      "present" => Ok(ElementClosingTag::Present),
+      "self-closing" => Ok(ElementClosingTag::SelfClosing),
+      "void" => Ok(ElementClosingTag::Void),
+      _ => Err(ParseElementClosingTagError),
+    }
+  }
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
 #[allow(clippy::upper_case_acronyms)]
 pub enum ScriptOrStyleLang {
@@ -23,6 +69,7 @@ pub enum ScriptOrStyleLang {
   JSModule,
 }
 
+#[derive(Clone)]
 pub struct AttrVal {
   // For serialisation only, not used for equality or value.
   pub quote: Option<u8>,
@@ -36,8 +83,8 @@ impl AttrVal {
 }
 
 impl Debug for AttrVal {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    f.write_str(from_utf8(&self.value).unwrap())
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.write_str(&String::from_utf8_lossy(&self.value))
   }
 }
 
@@ -49,32 +96,45 @@ impl PartialEq for AttrVal {
 
 impl Eq for AttrVal {}
 
-#[derive(Eq, PartialEq, Debug)]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum RcdataContentType {
   Textarea,
   Title,
 }
 
 // Derive Eq for testing.
-#[derive(Eq, PartialEq)]
+// With the `tracking` feature, every variant also has the byte offset and 1-based line number in the source where the node starts, as `source_offset` and `source_line`.
+#[derive(Clone, Eq, PartialEq)]
 pub enum NodeData {
   Bang {
     code: Vec<u8>,
     // If the source unexpectedly ended before `>`, we can't add it, as otherwise output could be longer than source.
     ended: bool,
+    #[cfg(feature = "tracking")]
+    source_offset: usize,
+    #[cfg(feature = "tracking")]
+    source_line: u32,
   },
   Comment {
     code: Vec<u8>,
     // If the source unexpectedly ended before `-->`, we can't add it, as otherwise output could be longer than source.
     ended: bool,
+    #[cfg(feature = "tracking")]
+    source_offset: usize,
+    #[cfg(feature = "tracking")]
+    source_line: u32,
   },
   Doctype {
     legacy: Vec<u8>,
     // If the source unexpectedly ended before `>`, we can't add it, as otherwise output could be longer than source.
     ended: bool,
+    #[cfg(feature = "tracking")]
+    source_offset: usize,
+    #[cfg(feature = "tracking")]
+    source_line: u32,
   },
   Element {
-    attributes: AHashMap<Vec<u8>, AttrVal>,
+    attributes: HashMap<Vec<u8>, AttrVal>,
     children: Vec<NodeData>,
     // If the source doesn't have a closing tag, then we can't add one, as otherwise output could be longer than source.
     closing_tag: ElementClosingTag,
@@ -84,47 +144,195 @@ pub enum NodeData {
     // If the next text or element sibling is an element, this will be set to its tag name.
     // Otherwise, this will be empty. It should be empty on creation.
     next_sibling_element_name: Vec<u8>,
+    #[cfg(feature = "tracking")]
+    source_offset: usize,
+    #[cfg(feature = "tracking")]
+    source_line: u32,
   },
   Instruction {
     code: Vec<u8>,
     // If the source unexpectedly ended before `?>`, we can't add it, as otherwise output could be longer than source.
     ended: bool,
+    #[cfg(feature = "tracking")]
+    source_offset: usize,
+    #[cfg(feature = "tracking")]
+    source_line: u32,
   },
   // <title> or <textarea> element contents.
   RcdataContent {
     typ: RcdataContentType,
     text: Vec<u8>,
+    #[cfg(feature = "tracking")]
+    source_offset: usize,
+    #[cfg(feature = "tracking")]
+    source_line: u32,
   },
   // Entities should not be decoded in ScriptOrStyleContent.
   ScriptOrStyleContent {
     code: Vec<u8>,
     lang: ScriptOrStyleLang,
+    #[cfg(feature = "tracking")]
+    source_offset: usize,
+    #[cfg(feature = "tracking")]
+    source_line: u32,
   },
   Text {
     value: Vec<u8>,
+    #[cfg(feature = "tracking")]
+    source_offset: usize,
+    #[cfg(feature = "tracking")]
+    source_line: u32,
   },
   // Usually templating syntax, including any opening and closing delimiters.
   Opaque {
     raw_source: Vec<u8>,
+    #[cfg(feature = "tracking")]
+    source_offset: usize,
+    #[cfg(feature = "tracking")]
+    source_line: u32,
   },
 }
 
+// Helpers for code that walks and mutates the tree, which avoid destructuring the enum.
+impl NodeData {
+  /// Returns the tag name if this is an element.
+  pub fn tag_name(&self) -> Option<&[u8]> {
+    match self {
+      NodeData::Element { name, .. } => Some(name),
+      _ => None,
+    }
+  }
+
+  /// Returns the byte offset and 1-based line number in the source where this node starts.
+  #[cfg(feature = "tracking")]
+  pub fn source_position(&self) -> (usize, u32) {
+    match self {
+      NodeData::Bang {
+        source_offset,
+        source_line,
+        ..
+      }
+      | NodeData::Comment {
+        source_offset,
+        source_line,
+        ..
+      }
+      | NodeData::Doctype {
+        source_offset,
+        source_line,
+        ..
+      }
+      | NodeData::Element {
+        source_offset,
+        source_line,
+        ..
+      }
+      | NodeData::Instruction {
+        source_offset,
+        source_line,
+        ..
+      }
+      | NodeData::RcdataContent {
+        source_offset,
+        source_line,
+        ..
+      }
+      | NodeData::ScriptOrStyleContent {
+        source_offset,
+        source_line,
+        ..
+      }
+      | NodeData::Text {
+        source_offset,
+        source_line,
+        ..
+      }
+      | NodeData::Opaque {
+        source_offset,
+        source_line,
+        ..
+      } => (*source_offset, *source_line),
+    }
+  }
+
+  /// Returns the children if this is an element.
+  pub fn children_mut(&mut self) -> Option<&mut Vec<NodeData>> {
+    match self {
+      NodeData::Element { children, .. } => Some(children),
+      _ => None,
+    }
+  }
+
+  /// Returns the decoded text of this node and all its descendants concatenated in order, like the DOM's `textContent`. Comments, bangs, processing instructions, `<script>` and `<style>` contents, and opaque template syntax are skipped.
+  pub fn text_content(&self) -> Vec<u8> {
+    let mut out = Vec::new();
+    self.write_text_content(&mut out);
+    out
+  }
+
+  fn write_text_content(&self, out: &mut Vec<u8>) {
+    match self {
+      NodeData::Element { children, .. } => {
+        for c in children {
+          c.write_text_content(out);
+        }
+      }
+      NodeData::RcdataContent { text, .. } => out.extend_from_slice(text),
+      NodeData::Text { value, .. } => out.extend_from_slice(value),
+      _ => {}
+    };
+  }
+
+  /// Adds or replaces an attribute, returning false if this is not an element. The value should not be encoded.
+  pub fn set_attribute(&mut self, name: Vec<u8>, value: Vec<u8>) -> bool {
+    match self {
+      NodeData::Element { attributes, .. } => {
+        attributes.insert(name, AttrVal { quote: None, value });
+        true
+      }
+      _ => false,
+    }
+  }
+
+  /// Removes an attribute, returning its decoded value if this is an element and it was present.
+  pub fn remove_attribute(&mut self, name: &[u8]) -> Option<Vec<u8>> {
+    match self {
+      NodeData::Element { attributes, .. } => attributes.remove(name).map(|v| v.value),
+      _ => None,
+    }
+  }
+
+  /// Minifies this node on its own, as if it were the only node in a document.
+  pub fn serialize(&self, cfg: &Cfg) -> Vec<u8> {
+    Document::new(vec![self.clone()], EMPTY_SLICE, false)
+      .serialize_minified_as(cfg, false)
+      .0
+  }
+
+  /// Serializes this node without minification, with each descendant element, text, and comment on its own line and indented by depth. The contents of `<listing>`, `<plaintext>`, `<pre>`, `<script>`, `<style>`, `<textarea>`, `<title>`, and `<xmp>` are written as is.
+  pub fn serialize_pretty(&self) -> Vec<u8> {
+    let mut out = Vec::new();
+    pretty_serialise_ast(&mut out, self, 0);
+    out
+  }
+}
+
 impl Debug for NodeData {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
     match self {
-      NodeData::Bang { code, ended } => f
+      NodeData::Bang { code, ended, .. } => f
         .debug_struct("Bang")
-        .field("code", &from_utf8(code).unwrap().to_string())
+        .field("code", &String::from_utf8_lossy(code))
         .field("ended", ended)
         .finish(),
-      NodeData::Comment { code, ended } => f
+      NodeData::Comment { code, ended, .. } => f
         .debug_struct("Comment")
-        .field("code", &from_utf8(code).unwrap().to_string())
+        .field("code", &String::from_utf8_lossy(code))
         .field("ended", ended)
         .finish(),
-      NodeData::Doctype { legacy, ended } => f
+      NodeData::Doctype { legacy, ended, .. } => f
         .debug_struct("Doctype")
-        .field("legacy", &from_utf8(legacy).unwrap().to_string())
+        .field("legacy", &String::from_utf8_lossy(legacy))
         .field("ended", ended)
         .finish(),
       NodeData::Element {
@@ -134,12 +342,15 @@ impl Debug for NodeData {
         name,
         namespace,
         next_sibling_element_name,
+        ..
       } => f
         .debug_struct("Element")
         .field("tag", &{
-          let mut out = format!("{:?}:{}", namespace, from_utf8(name).unwrap());
-          for (n, v) in attributes {
-            out.push_str(format!(" {}={:?}", from_utf8(n).unwrap(), v).as_str());
+          let mut out = format!("{:?}:{}", namespace, String::from_utf8_lossy(name));
+          let mut attrs = attributes.iter().collect::<Vec<_>>();
+          attrs.sort_unstable_by(|a, b| a.0.cmp(b.0));
+          for (n, v) in attrs {
+            out.push_str(format!(" {}={:?}", String::from_utf8_lossy(n), v).as_str());
           }
           out
         })
@@ -147,28 +358,28 @@ impl Debug for NodeData {
         .field("closing_tag", closing_tag)
         .field(
           "next_sibling_element_name",
-          &from_utf8(next_sibling_element_name).unwrap().to_string(),
+          &String::from_utf8_lossy(next_sibling_element_name),
         )
         .finish(),
-      NodeData::Instruction { code, ended } => f
+      NodeData::Instruction { code, ended, .. } => f
         .debug_struct("Instruction")
-        .field("code", &from_utf8(code).unwrap().to_string())
+        .field("code", &String::from_utf8_lossy(code))
         .field("ended", ended)
         .finish(),
-      NodeData::RcdataContent { typ, text } => f
+      NodeData::RcdataContent { typ, text, .. } => f
         .debug_struct("RcdataContent")
         .field("typ", typ)
-        .field("text", &from_utf8(text).unwrap().to_string())
+        .field("text", &String::from_utf8_lossy(text))
         .finish(),
-      NodeData::ScriptOrStyleContent { code, lang } => f
+      NodeData::ScriptOrStyleContent { code, lang, .. } => f
         .debug_struct("ScriptOrStyleContent")
-        .field("code", &from_utf8(code).unwrap().to_string())
+        .field("code", &String::from_utf8_lossy(code))
         .field("lang", lang)
         .finish(),
-      NodeData::Text { value } => f.write_str(from_utf8(value).unwrap()),
-      NodeData::Opaque { raw_source } => f
+      NodeData::Text { value, .. } => f.write_str(&String::from_utf8_lossy(value)),
+      NodeData::Opaque { raw_source, .. } => f
         .debug_struct("Opaque")
-        .field("raw_source", &from_utf8(raw_source).unwrap().to_string())
+        .field("raw_source", &String::from_utf8_lossy(raw_source))
         .finish(),
     }
   }
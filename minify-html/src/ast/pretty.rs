@@ -0,0 +1,60 @@
+use crate::ast::c14n::c14n_serialise_ast;
+use crate::ast::c14n::c14n_serialise_opening_tag;
+use crate::ast::c14n::c14n_serialise_text;
+use crate::ast::ElementClosingTag;
+use crate::ast::NodeData;
+use alloc::vec::Vec;
+use minify_html_common::whitespace::is_all_whitespace;
+use minify_html_common::whitespace::trimmed;
+
+fn write_indent(out: &mut Vec<u8>, depth: usize) {
+  for _ in 0..depth {
+    out.extend_from_slice(b"  ");
+  }
+}
+
+// Like c14n_serialise_ast, but with each element, text, and other node on its own line, indented by depth. Elements whose whitespace is significant or whose contents aren't HTML are written on one line as is.
+pub fn pretty_serialise_ast(out: &mut Vec<u8>, node: &NodeData, depth: usize) {
+  match node {
+    NodeData::Element {
+      attributes,
+      closing_tag,
+      children,
+      name,
+      ..
+    } if !matches!(
+      name.as_slice(),
+      b"listing" | b"plaintext" | b"pre" | b"script" | b"style" | b"textarea" | b"title" | b"xmp"
+    ) =>
+    {
+      write_indent(out, depth);
+      c14n_serialise_opening_tag(out, name, attributes, *closing_tag);
+      out.extend_from_slice(b"\n");
+      for c in children {
+        pretty_serialise_ast(out, c, depth + 1);
+      }
+      // Write omitted closing tags too, as the indentation would otherwise be misleading.
+      if matches!(
+        closing_tag,
+        ElementClosingTag::Omitted | ElementClosingTag::Present
+      ) {
+        write_indent(out, depth);
+        out.extend_from_slice(b"</");
+        out.extend_from_slice(name);
+        out.extend_from_slice(b">\n");
+      };
+    }
+    NodeData::Text { value, .. } => {
+      if !is_all_whitespace(value) {
+        write_indent(out, depth);
+        c14n_serialise_text(out, trimmed(value));
+        out.extend_from_slice(b"\n");
+      };
+    }
+    _ => {
+      write_indent(out, depth);
+      c14n_serialise_ast(out, node);
+      out.extend_from_slice(b"\n");
+    }
+  };
+}
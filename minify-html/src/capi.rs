@@ -0,0 +1,117 @@
+//! A C ABI for minifying from other languages, enabled with the `capi` feature. See `minify-html/include/minify_html.h` for the C declarations.
+//!
+//! Memory passed to these functions is only borrowed for the duration of the call. A `MinifiedBuffer` returned by `minify_html` is owned by the caller, and must be freed exactly once with `minify_html_free`, never with `free` or another allocator.
+#![allow(unsafe_code)]
+
+use crate::minify;
+use crate::Cfg;
+use std::panic::catch_unwind;
+use std::panic::AssertUnwindSafe;
+use std::ptr::null_mut;
+use std::slice;
+
+/// The value of `MinifyHtmlCfg::version` for the fields of this build. It's incremented whenever fields are added, removed, or reordered, so that code compiled against a different header is rejected instead of reading options from the wrong offsets.
+pub const MINIFY_HTML_CFG_VERSION: u32 = 1;
+
+macro_rules! minify_html_cfg {
+  (bool: [$($name:ident,)*], other: [$($other:ident,)*],) => {
+    /// The boolean options of `Cfg`, with the same names and meanings, in a C-compatible layout. Options that aren't booleans have their default values.
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct MinifyHtmlCfg {
+      /// Must be `MINIFY_HTML_CFG_VERSION`, or `minify_html` fails.
+      pub version: u32,
+      $(pub $name: bool,)*
+    }
+
+    impl Default for MinifyHtmlCfg {
+      fn default() -> MinifyHtmlCfg {
+        MinifyHtmlCfg {
+          version: MINIFY_HTML_CFG_VERSION,
+          $($name: false,)*
+        }
+      }
+    }
+
+    impl From<&MinifyHtmlCfg> for Cfg {
+      fn from(cfg: &MinifyHtmlCfg) -> Cfg {
+        Cfg {
+          $($name: cfg.$name,)*
+          ..Cfg::default()
+        }
+      }
+    }
+
+    // The names of the boolean fields in order, to check against the header.
+    #[cfg(test)]
+    pub(crate) const MINIFY_HTML_CFG_FIELDS: &[&str] = &[$(stringify!($name)),*];
+  };
+}
+
+// Adding a boolean option to `Cfg` changes this layout, so `MINIFY_HTML_CFG_VERSION` must be incremented and `minify_html.h` updated too.
+crate::cfg_options!(minify_html_cfg!());
+
+/// Minified code, owned by the caller until passed to `minify_html_free`. `ptr` is null if minification failed.
+#[repr(C)]
+pub struct MinifiedBuffer {
+  pub ptr: *mut u8,
+  pub len: usize,
+}
+
+impl MinifiedBuffer {
+  fn new(out: Vec<u8>) -> MinifiedBuffer {
+    // A boxed slice's capacity is its length, so it can be rebuilt from just the pointer and length when freed.
+    let out = Box::into_raw(out.into_boxed_slice());
+    MinifiedBuffer {
+      ptr: out.cast(),
+      len: out.len(),
+    }
+  }
+}
+
+/// Minifies the `len` bytes of UTF-8 HTML code at `src` using the options at `cfg`, or the defaults if `cfg` is null. Fails if `cfg.version` isn't `MINIFY_HTML_CFG_VERSION`.
+///
+/// # Safety
+///
+/// `src` must point to `len` readable bytes, or may be null if `len` is zero. `cfg` must be null or point to a valid `MinifyHtmlCfg`. Neither is retained after returning.
+#[no_mangle]
+pub unsafe extern "C" fn minify_html(
+  src: *const u8,
+  len: usize,
+  cfg: *const MinifyHtmlCfg,
+) -> MinifiedBuffer {
+  let src = if len == 0 {
+    &[]
+  } else {
+    slice::from_raw_parts(src, len)
+  };
+  let cfg = match cfg.as_ref() {
+    Some(cfg) if cfg.version != MINIFY_HTML_CFG_VERSION => {
+      return MinifiedBuffer {
+        ptr: null_mut(),
+        len: 0,
+      };
+    }
+    cfg => cfg.map(Cfg::from).unwrap_or_default(),
+  };
+  // Unwinding across the FFI boundary is undefined behaviour.
+  match catch_unwind(AssertUnwindSafe(|| minify(src, &cfg))) {
+    Ok(out) => MinifiedBuffer::new(out),
+    Err(_) => MinifiedBuffer {
+      ptr: null_mut(),
+      len: 0,
+    },
+  }
+}
+
+/// Frees a buffer returned by `minify_html`. Does nothing if `buf.ptr` is null.
+///
+/// # Safety
+///
+/// `buf` must have been returned by `minify_html` and not already freed.
+#[no_mangle]
+pub unsafe extern "C" fn minify_html_free(buf: MinifiedBuffer) {
+  if !buf.ptr.is_null() {
+    drop(Box::from_raw(slice::from_raw_parts_mut(buf.ptr, buf.len)));
+  };
+}
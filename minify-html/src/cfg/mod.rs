@@ -1,3 +1,63 @@
+use crate::selector::SimpleSelector;
+use crate::visitor::ElementVisitor;
+use crate::visitor::TextVisitor;
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+
+/// Character encoding of the minified code. See `Cfg::output_encoding`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputEncoding {
+  /// All characters are written as UTF-8.
+  #[default]
+  Utf8,
+  /// Characters up to U+00FF are written as single bytes, and the rest as numeric character references.
+  Latin1,
+  /// Non-ASCII characters are written as numeric character references, so the output is valid ASCII and UTF-8.
+  AsciiSafe,
+}
+
+/// How whitespace in text is minified. See `Cfg::whitespace_mode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WhitespaceMode {
+  /// Whitespace is collapsed, and trimmed or removed where it doesn't affect layout based on the parent element, such as between block elements.
+  #[default]
+  Default,
+  /// Like `Default`, but whitespace between sibling elements is also removed in any element that isn't inline, like `<p>` or `<li>`, even if it would be rendered, e.g. between two `<a>` elements. Use this when CSS layouts like flexbox or grid make such whitespace irrelevant.
+  Aggressive,
+  /// All whitespace is kept as is.
+  Preserve,
+}
+
+/// Line ending to convert line endings in the minified code to. See `Cfg::normalize_line_endings`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+  /// `\n`.
+  Lf,
+  /// `\r\n`.
+  CrLf,
+}
+
+/// How the start tags of void elements like `<br>` are ended. See `Cfg::void_self_closing_style`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VoidSelfClosingStyle {
+  /// `<br>`.
+  #[default]
+  None,
+  /// `<br/>`. A space is still added before the slash if the last attribute is unquoted, like `<img src=a.png />`, as the slash would otherwise be part of its value.
+  Slash,
+  /// `<br />`.
+  SpaceSlash,
+}
+
+impl LineEnding {
+  pub(crate) fn as_bytes(self) -> &'static [u8] {
+    match self {
+      LineEnding::Lf => b"\n",
+      LineEnding::CrLf => b"\r\n",
+    }
+  }
+}
+
 /// Configuration settings that can be adjusted and passed to a minification function to change the
 /// minification approach.
 #[derive(Clone, Default)]
@@ -8,41 +68,270 @@ pub struct Cfg {
   pub allow_optimal_entities: bool,
   /// Allow removing_spaces between attributes when possible, which may not be spec compliant. These will still be parsed correctly by almost all browsers.
   pub allow_removing_spaces_between_attributes: bool,
+  /// The absolute URL of the document, which relative URLs are resolved against when `normalize_urls` is enabled, so that each URL can be rewritten as whichever of a relative path, absolute path, or absolute URL is shortest. See `minify_with_base_url`. Don't set this if the document has a `<base>` element, as browsers resolve URLs against that instead.
+  pub base_url: Option<Vec<u8>>,
+  /// Called for every element before it's minified, allowing its attributes to be inspected and rewritten, e.g. to add a CDN prefix to asset URLs. Changed attribute values are minified like any other.
+  pub element_visitor: Option<ElementVisitor>,
+  /// Attributes whose names start with any of these prefixes are treated as framework bindings, like Vue's `:src` or Angular's `(click)`. Their values are expressions, so are always quoted and have their whitespace left as is. See `enable_framework_bindings` for a common set.
+  pub framework_binding_attr_prefixes: Vec<Vec<u8>>,
+  /// Names of attributes whose values are HTML fragments, like `data-content` for Bootstrap popovers. Their values are minified as HTML using the same configuration, unless that wouldn't make them shorter.
+  pub html_attributes: Vec<Vec<u8>>,
+  /// The trimmed contents of the comments that start and end a region kept as is by `preserve_ignored_regions`, instead of `htmlmin:ignore` and `htmlmin:endignore`.
+  pub ignored_region_markers: Option<(Vec<u8>, Vec<u8>)>,
+  /// Insert `<meta charset=utf-8>` at the start of `<head>` if the document doesn't declare its character encoding with a `<meta charset>` or `<meta http-equiv=Content-Type>` element. Without one, browsers have to guess the encoding. Only use this if the source is UTF-8.
+  pub inject_charset_meta: bool,
+  /// Prefixes of the content after `<!` of bangs to keep when `remove_bangs` is enabled, such as `[if` for downlevel-revealed conditional comments. Prefixes are matched case sensitively.
+  pub keep_bangs_matching: Vec<Vec<u8>>,
+  /// Keep comments whose trimmed content starts with `/`, like `<!-- /container -->`, which build tools emit to mark where an element ends.
+  pub keep_balancing_comments: bool,
   /// Do not omit closing tags when possible.
   pub keep_closing_tags: bool,
   /// Keep all comments.
   pub keep_comments: bool,
-  /// Do not omit `<html>` and `<head>` opening tags when they don't have attributes.
+  /// Do not omit the `<head>` opening tag when it doesn't have attributes.
+  pub keep_head_opening_tag: bool,
+  /// Do not omit `<html>` and `<head>` opening tags when they don't have attributes. This is a shorthand for enabling both `keep_html_opening_tag` and `keep_head_opening_tag`.
   pub keep_html_and_head_opening_tags: bool,
+  /// Do not omit the `<html>` opening tag when it doesn't have attributes.
+  pub keep_html_opening_tag: bool,
   /// Keep `type=text` attribute name and value on `<input>` elements.
   pub keep_input_type_text_attr: bool,
+  /// Targets of processing instructions to keep when `remove_processing_instructions` is enabled, such as `xml-stylesheet`. The target is the name immediately after `<?`, and is matched case sensitively. Kept processing instructions are output as is.
+  pub keep_processing_instructions_matching: Vec<Vec<u8>>,
+  /// Write soft hyphens (U+00AD) in text as `&shy;` instead of the raw character, which is invisible and can be mangled by tools that process the output.
+  pub keep_shy_entity: bool,
   /// Keep SSI comments.
   pub keep_ssi_comments: bool,
-  /// Minify CSS in `<style>` tags and `style` attributes using [https://github.com/parcel-bundler/lightningcss](lightningcss).
+  /// Maximum number of levels of nested elements to parse. An element nested more deeply is dropped along with everything after it in the source, and `try_minify` returns an error instead. Use this when minifying untrusted input, as very deeply nested elements could otherwise overflow the stack.
+  pub max_depth: Option<usize>,
+  /// Minify CSS in `<style>` tags and `style` attributes using [https://github.com/parcel-bundler/lightningcss](lightningcss). `style` attribute values that can't be minified safely, such as those with old IE `filter: progid:...` values, only have whitespace around them and trailing semicolons removed. Without the `css` feature, which is enabled by default, CSS is treated as if it can't be minified.
   pub minify_css: bool,
   /// Minify DOCTYPEs. Minified DOCTYPEs may not be spec compliant, but will still be parsed correctly by almost all browsers.
   pub minify_doctype: bool,
-  /// Minify JavaScript in `<script>` tags using [minify-js](https://github.com/wilsonzlin/minify-js).
+  /// Minify JavaScript in `<script>` tags using [minify-js](https://github.com/wilsonzlin/minify-js). The order of some declarations in its output depends on hash seeds, so the output of scripts can differ between processes. Without the `js` feature, which is enabled by default, JS is treated as if it can't be minified.
   pub minify_js: bool,
+  /// Collapse runs of whitespace in comments that are kept, such as with `keep_comments`. SSI, conditional, and license comments (starting with `!` or containing `@license` or `@preserve`) are left as is.
+  pub minify_kept_comment_whitespace: bool,
+  /// Remove attributes of SVG elements that are equal to their default values, such as `opacity="1"` or `x="0"` on a `<rect>`. Only well-known defaults of attributes that are not inherited from ancestors are removed.
+  pub minify_svg_default_attributes: bool,
+  /// Names of attributes whose values should always be quoted, even when unquoted would be shorter, e.g. `content` or framework bindings like `:class`. Names should be lowercase.
+  pub never_unquote_attributes: HashSet<Vec<u8>>,
+  /// Replace the DOCTYPE with `<!doctype html>`, dropping any public and system identifiers, such as those of HTML 4.01 and XHTML DOCTYPEs. This switches pages that rely on them out of quirks or limited-quirks mode, which can change how they are rendered.
+  pub normalize_doctype_to_html5: bool,
+  /// Remove trailing zeros from numbers like `1.0` and empty entries in the `content` of `<meta name=viewport>`. Whitespace in it is always removed.
+  pub normalize_meta_viewport: bool,
+  /// Remove default ports and `.` path segments from absolute URLs in attributes like `href` and `src`, and lowercase their schemes and hosts.
+  pub normalize_urls: bool,
+  /// Convert every line ending (`\r\n`, `\r`, or `\n`) in the minified code to this line ending, including inside `<pre>`, `<textarea>`, `<script>`, and `<style>` elements and attribute values. This changes the content of `<pre>` elements byte for byte, but browsers convert `\r\n` and `\r` to `\n` when parsing HTML, so converting to `LineEnding::Lf` doesn't change how the document is interpreted.
+  pub normalize_line_endings: Option<LineEnding>,
+  /// Omit the `<body>` opening tag when it doesn't have attributes and the spec allows it. Some parsers and tools expect `<body>` to be present.
+  pub omit_body_opening_tag: bool,
+  /// Write characters that aren't in this encoding as numeric character references like `&#8364;` in text, attribute values, and `<title>` and `<textarea>` contents. Other content where references aren't decoded, like `<script>`, `<style>`, and comments, is left as is. This doesn't add or change a `<meta charset>`.
+  pub output_encoding: OutputEncoding,
   /// When `{{`, `{#`, or `{%` are seen in content, all source code until the subsequent matching closing `}}`, `#}`, or `%}` respectively gets piped through untouched.
   pub preserve_brace_template_syntax: bool,
   /// When `<%` is seen in content, all source code until the subsequent matching closing `%>` gets piped through untouched.
   pub preserve_chevron_percent_template_syntax: bool,
-  /// Remove all bangs.
+  /// Output the content between a `<!-- htmlmin:ignore -->` comment and the next `<!-- htmlmin:endignore -->` comment as is, and remove both comments. If there is no end comment, everything after the start comment is output as is. Use `ignored_region_markers` to recognise different comments.
+  pub preserve_ignored_regions: bool,
+  /// Keep Knockout.js virtual element comments like `<!-- ko if: a -->` and `<!-- /ko -->`.
+  pub preserve_ko_comments: bool,
+  /// Never remove or normalize microdata attributes (`itemid`, `itemprop`, `itemref`, `itemscope`, `itemtype`), even if empty or matched by `remove_data_attrs`.
+  pub preserve_microdata_attributes: bool,
+  /// Keep everything up to and including the `<html>` start tag byte for byte, such as the doctype's case and the `<html>` element's attribute quoting, if only a doctype, comments, and whitespace precede it. When using `parse_with_cfg`, this must also be enabled when parsing, as the document only keeps the prolog if it is.
+  pub preserve_prolog: bool,
+  /// Remove bangs like `<!FOO>`, except those matching `keep_bangs_matching`. Doctypes are never removed, nor are CDATA sections in SVG and MathML, as they contain text.
   pub remove_bangs: bool,
-  /// Remove all processing instructions.
+  /// Names of attributes to remove from every element, such as testing hooks like `data-testid`. A name ending with `*` removes all attributes whose names start with the preceding prefix, e.g. `data-test*`. Names are matched case insensitively.
+  pub remove_data_attrs: Vec<Vec<u8>>,
+  /// Remove elements matching any of these selectors, along with their descendants, e.g. `script[data-env=dev]` or `.debug-panel`.
+  pub remove_elements: Vec<SimpleSelector>,
+  /// Remove `<meta name=generator>` tags, which are added by some site generators and CMSes but not used by browsers.
+  pub remove_generator_meta: bool,
+  /// Remove elements with a `hidden` attribute or a `style` attribute containing `display: none`, along with their descendants. `<template>` and `<datalist>` elements, elements with `hidden=until-found`, and elements that have an `id` or contain an element with one, such as an SVG sprite sheet, are never removed.
+  pub remove_hidden_elements: bool,
+  /// Remove `<script>` elements that load polyfills for old versions of Internet Explorer, like html5shiv, html5shim, respond.js, and selectivizr, based on the file name in `src`. Conditional comments that only contain such scripts are also removed, even if comments are kept.
+  pub remove_html5_shiv: bool,
+  /// Remove all processing instructions, except those with a target in `keep_processing_instructions_matching`.
   pub remove_processing_instructions: bool,
+  /// Remove `async` and `defer` attributes on `<script>` elements where they have no effect, such as `defer` alongside `async` or on a module script, and both on inline classic scripts.
+  pub remove_redundant_script_attributes: bool,
+  /// Remove `type="module"` from `<script>` elements. WARNING: This changes module scripts into classic scripts, which are run immediately instead of deferred, in the global scope, in sloppy mode, and without support for `import`. Only enable this if every module script has been bundled into a classic script, e.g. by inlining its imports.
+  pub remove_type_module_attribute: bool,
+  /// Remove `id` attributes that aren't referenced within the document by a fragment like `href=#a` or `url(#a)`, an attribute like `for`, `headers`, or `aria-labelledby`, or any mention in a script, stylesheet, `style` attribute, or event handler attribute. IDs that are referenced from other documents, such as by links to `page.html#a`, or by scripts loaded with `src` will also be removed, so only use this if nothing outside the document refers to them.
+  pub remove_unreferenced_ids: bool,
+  /// Remove `<meta http-equiv=X-UA-Compatible>` tags, which are only used by Internet Explorer.
+  pub remove_x_ua_compatible_meta: bool,
+  /// Remove XML declarations like `<?xml version="1.0" encoding="UTF-8"?>` at the root of the document, which are common in XHTML files but meaningless in HTML, and put old versions of IE into quirks mode. The prolog is still kept as is with `preserve_prolog`.
+  pub remove_xml_declaration: bool,
+  /// Preserve whitespace inside elements with a `contenteditable` attribute whose value isn't `false`.
+  pub respect_contenteditable: bool,
+  /// Don't minify `style` attribute values with the CSS minifier when `minify_css` is enabled, so only `<style>` contents are minified. Whitespace around them and trailing semicolons are still removed.
+  pub skip_style_attr_minification: bool,
+  /// Remove comments from `<script>` and `<style>` contents that aren't minified, such as when `minify_js` or `minify_css` is disabled, as well as `<!--` and `-->` wrappers like `strip_legacy_script_comments`. License comments like `/*! ... */` and source map comments are kept. Strings, template literals, regular expression literals, and CSS `url()` values are skipped, and code that can't be processed safely without fully parsing it, such as a `/` that could be either division or a regular expression, is left as is.
+  pub strip_code_comments: bool,
+  /// Remove legacy `<!--` and `-->` wrappers used to hide the contents of `<script>` and `<style>` from very old browsers.
+  pub strip_legacy_script_comments: bool,
+  /// Called with the decoded content of every text node before its whitespace is minified, allowing it to be inspected and rewritten, e.g. for typography or extracting visible text. Script, style, and preserved template content is not text and won't be passed to this.
+  pub text_visitor: Option<TextVisitor>,
+  /// Trim whitespace at the start and end of text in `<option>` and `<button>` elements. Whitespace there is collapsed but kept by default, as it could be displayed.
+  pub trim_form_control_text: bool,
+  /// Replace `<div>` elements without attributes that only contain a single block element, and `<span>` elements without attributes that only contain a single inline element, with that element. This changes the document structure, so CSS selectors and scripts that depend on the wrappers, like `div > p` or `:nth-child`, may no longer match.
+  pub unwrap_redundant_wrappers: bool,
+  /// Add an `as` attribute to `<link rel=preload>` elements that are missing one, inferred from the file extension of the `href`, such as `script` for `.js` or `font` for `.woff2`. Browsers fetch preloads without `as` twice, as they can't be matched with the request that uses them. Links with an unknown extension are left as is.
+  pub validate_preload_links: bool,
+  /// Whether void elements like `<br>` and `<img>` are written with a trailing slash, for tools that expect XHTML-like output. Browsers ignore the slash.
+  pub void_self_closing_style: VoidSelfClosingStyle,
+  /// How whitespace in text is minified.
+  pub whitespace_mode: WhitespaceMode,
 }
 
+/// Invokes the given macro with the names of every field of `Cfg` in alphabetical order, split into the `bool` fields and the rest, after any arguments already provided to it, e.g. `cfg_options!(my_macro!(a, b,))` expands to `my_macro! { a, b, bool: [...], other: [...] }`. This is used to generate code that must handle every boolean option, like the C API and the CLI's `--serve` overrides, from a single list.
+#[macro_export]
+macro_rules! cfg_options {
+  ($callback:ident!($($args:tt)*)) => {
+    $callback! {
+      $($args)*
+      bool: [
+        allow_noncompliant_unquoted_attribute_values,
+        allow_optimal_entities,
+        allow_removing_spaces_between_attributes,
+        inject_charset_meta,
+        keep_balancing_comments,
+        keep_closing_tags,
+        keep_comments,
+        keep_head_opening_tag,
+        keep_html_and_head_opening_tags,
+        keep_html_opening_tag,
+        keep_input_type_text_attr,
+        keep_shy_entity,
+        keep_ssi_comments,
+        minify_css,
+        minify_doctype,
+        minify_js,
+        minify_kept_comment_whitespace,
+        minify_svg_default_attributes,
+        normalize_doctype_to_html5,
+        normalize_meta_viewport,
+        normalize_urls,
+        omit_body_opening_tag,
+        preserve_brace_template_syntax,
+        preserve_chevron_percent_template_syntax,
+        preserve_ignored_regions,
+        preserve_ko_comments,
+        preserve_microdata_attributes,
+        preserve_prolog,
+        remove_bangs,
+        remove_generator_meta,
+        remove_hidden_elements,
+        remove_html5_shiv,
+        remove_processing_instructions,
+        remove_redundant_script_attributes,
+        remove_type_module_attribute,
+        remove_unreferenced_ids,
+        remove_x_ua_compatible_meta,
+        remove_xml_declaration,
+        respect_contenteditable,
+        skip_style_attr_minification,
+        strip_code_comments,
+        strip_legacy_script_comments,
+        trim_form_control_text,
+        unwrap_redundant_wrappers,
+        validate_preload_links,
+      ],
+      other: [
+        base_url,
+        element_visitor,
+        framework_binding_attr_prefixes,
+        html_attributes,
+        ignored_region_markers,
+        keep_bangs_matching,
+        keep_processing_instructions_matching,
+        max_depth,
+        never_unquote_attributes,
+        normalize_line_endings,
+        output_encoding,
+        remove_data_attrs,
+        remove_elements,
+        text_visitor,
+        void_self_closing_style,
+        whitespace_mode,
+      ],
+    }
+  };
+}
+
+macro_rules! check_cfg_options {
+  (bool: [$($bool:ident,)*], other: [$($other:ident,)*],) => {
+    // Fails to compile if a field of `Cfg` is missing from `cfg_options`, or is listed as a `bool` but isn't one.
+    #[allow(dead_code)]
+    fn check_cfg_options(cfg: &Cfg) {
+      let Cfg {
+        $($bool: _,)*
+        $($other: _,)*
+      } = cfg;
+      $(let _: bool = cfg.$bool;)*
+    }
+  };
+}
+
+cfg_options!(check_cfg_options!());
+
 impl Cfg {
   pub fn new() -> Cfg {
     Cfg::default()
   }
 
+  /// Conservative settings for HTML email, as email clients are far less forgiving than browsers:
+  /// - Closing tags and the `<html>`, `<head>`, and `<body>` opening tags are kept, as some clients restructure or drop content without them.
+  /// - Comments are kept, as Outlook relies on conditional comments like `<!--[if mso]>`.
+  /// - `type=text` is kept on `<input>` elements, which some clients need to render them.
+  /// - The doctype, CSS, and JS are left as is, as clients support a limited and inconsistent subset of CSS.
+  ///
+  /// Options that are unsafe for email clients, and shouldn't be enabled on top of these, include `enable_possibly_noncompliant` (and the options it sets), `remove_bangs`, `strip_code_comments`, and `strip_legacy_script_comments`.
+  pub fn for_email_html() -> Cfg {
+    Cfg {
+      keep_closing_tags: true,
+      keep_comments: true,
+      keep_html_and_head_opening_tags: true,
+      keep_input_type_text_attr: true,
+      minify_css: false,
+      minify_doctype: false,
+      minify_js: false,
+      ..Cfg::default()
+    }
+  }
+
+  /// Settings for [AMP](https://amp.dev/documentation/guides-and-tutorials/learn/spec/amphtml/) documents, which the AMP validator would otherwise reject:
+  /// - The doctype is written as `<!doctype html>`, and the `<html>`, `<head>`, and `<body>` opening tags are kept, as AMP requires them.
+  /// - CSS is left as is, as the `<style amp-boilerplate>` contents must match the boilerplate. Minify `<style amp-custom>` separately if needed to fit within the size limit.
+  /// - JS is left as is, as the only allowed inline scripts are JSON data.
+  ///
+  /// Use `check_amp_html` on the output to find common validation errors that minification can't fix, such as disallowed scripts.
+  pub fn for_amp_html() -> Cfg {
+    Cfg {
+      keep_html_and_head_opening_tags: true,
+      minify_css: false,
+      minify_doctype: false,
+      minify_js: false,
+      ..Cfg::default()
+    }
+  }
+
   pub fn enable_possibly_noncompliant(&mut self) {
     self.allow_noncompliant_unquoted_attribute_values = true;
     self.allow_optimal_entities = true;
     self.allow_removing_spaces_between_attributes = true;
     self.minify_doctype = true;
   }
+
+  /// Treat attributes using the binding syntax of Vue (`:`, `@`, `#`, `v-`), Angular (`*`, `[`, `(`), Svelte (`bind:`, `class:`, `on:`, `use:`), and Alpine.js (`x-`) as framework bindings. See `framework_binding_attr_prefixes`.
+  pub fn enable_framework_bindings(&mut self) {
+    self.framework_binding_attr_prefixes = [
+      "#", "(", "*", ":", "@", "[", "bind:", "class:", "on:", "use:", "v-", "x-",
+    ]
+    .iter()
+    .map(|p| p.as_bytes().to_vec())
+    .collect();
+  }
 }
@@ -0,0 +1,234 @@
+use alloc::vec::Vec;
+
+// Based on https://html.spec.whatwg.org/multipage/parsing.html#prescan-a-byte-stream-to-determine-its-encoding.
+
+// The prescan only looks at the start of the source.
+const PRESCAN_LIMIT: usize = 1024;
+
+fn is_whitespace(c: u8) -> bool {
+  matches!(c, b'\t' | b'\n' | b'\x0c' | b'\r' | b' ')
+}
+
+struct Prescan<'s> {
+  src: &'s [u8],
+  pos: usize,
+}
+
+impl<'s> Prescan<'s> {
+  fn peek(&self) -> Option<u8> {
+    self.src.get(self.pos).copied()
+  }
+
+  fn skip_while(&mut self, pred: impl Fn(u8) -> bool) {
+    while self.peek().filter(|&c| pred(c)).is_some() {
+      self.pos += 1;
+    }
+  }
+
+  // Returns the next attribute's lowercase name and value, or None if there are no more attributes in the tag or the end of the source was reached.
+  fn attribute(&mut self) -> Option<(Vec<u8>, Vec<u8>)> {
+    self.skip_while(|c| is_whitespace(c) || c == b'/');
+    if self.peek()? == b'>' {
+      return None;
+    };
+    let mut name = Vec::new();
+    let mut value = Vec::new();
+    loop {
+      let c = self.peek()?;
+      if c == b'=' && !name.is_empty() {
+        self.pos += 1;
+        break;
+      };
+      if is_whitespace(c) {
+        self.skip_while(is_whitespace);
+        if self.peek()? != b'=' {
+          return Some((name, value));
+        };
+        self.pos += 1;
+        break;
+      };
+      if c == b'/' || c == b'>' {
+        return Some((name, value));
+      };
+      name.push(c.to_ascii_lowercase());
+      self.pos += 1;
+    }
+    self.skip_while(is_whitespace);
+    let c = self.peek()?;
+    if c == b'"' || c == b'\'' {
+      self.pos += 1;
+      loop {
+        let v = self.peek()?;
+        self.pos += 1;
+        if v == c {
+          return Some((name, value));
+        };
+        value.push(v.to_ascii_lowercase());
+      }
+    };
+    if c == b'>' {
+      return Some((name, value));
+    };
+    loop {
+      let v = self.peek()?;
+      if is_whitespace(v) || v == b'>' {
+        return Some((name, value));
+      };
+      value.push(v.to_ascii_lowercase());
+      self.pos += 1;
+    }
+  }
+
+  // Returns the charset declared by a `<meta>` element whose attributes start at the current position, if any.
+  fn meta_charset(&mut self) -> Option<Option<Vec<u8>>> {
+    let mut names = Vec::new();
+    let mut got_pragma = false;
+    let mut need_pragma = None;
+    let mut charset = None;
+    while let Some((name, value)) = self.attribute() {
+      if names.contains(&name) {
+        continue;
+      };
+      match name.as_slice() {
+        b"http-equiv" if value == b"content-type" => got_pragma = true,
+        b"content" if charset.is_none() => {
+          if let Some(c) = charset_from_meta_content(&value) {
+            charset = Some(c);
+            need_pragma = Some(true);
+          };
+        }
+        b"charset" => {
+          charset = Some(value.clone());
+          need_pragma = Some(false);
+        }
+        _ => {}
+      };
+      names.push(name);
+    }
+    // Stop if the end of the source was reached within the tag.
+    self.peek()?;
+    Some(match need_pragma {
+      Some(true) if !got_pragma => None,
+      Some(_) => charset,
+      None => None,
+    })
+  }
+}
+
+// https://html.spec.whatwg.org/multipage/urls-and-fetching.html#algorithm-for-extracting-a-character-encoding-from-a-meta-element.
+fn charset_from_meta_content(value: &[u8]) -> Option<Vec<u8>> {
+  let mut pos = 0;
+  loop {
+    pos += value[pos..]
+      .windows(7)
+      .position(|w| w.eq_ignore_ascii_case(b"charset"))?
+      + 7;
+    while value.get(pos).filter(|&&c| is_whitespace(c)).is_some() {
+      pos += 1;
+    }
+    if value.get(pos) == Some(&b'=') {
+      pos += 1;
+      break;
+    };
+  }
+  while value.get(pos).filter(|&&c| is_whitespace(c)).is_some() {
+    pos += 1;
+  }
+  let rest = &value[pos..];
+  match rest.first()? {
+    &q @ (b'"' | b'\'') => {
+      let end = rest[1..].iter().position(|&c| c == q)?;
+      Some(rest[1..end + 1].to_vec())
+    }
+    _ => {
+      let end = rest
+        .iter()
+        .position(|&c| is_whitespace(c) || c == b';')
+        .unwrap_or(rest.len());
+      Some(rest[..end].to_vec())
+    }
+  }
+}
+
+/// Detects the character encoding declared by HTML code using the [prescan](https://html.spec.whatwg.org/multipage/parsing.html#prescan-a-byte-stream-to-determine-its-encoding) of the HTML encoding sniffing algorithm, which looks for a `<meta charset>` or `<meta http-equiv="Content-Type">` element in the first 1024 bytes. A byte order mark takes precedence, as it does in browsers.
+///
+/// The declared label is returned lowercased but otherwise as is, without checking whether it's a known encoding, except that UTF-16 labels are replaced with `utf-8` and `x-user-defined` with `windows-1252`, as browsers do for labels in `<meta>` elements.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::detect_charset;
+///
+/// assert_eq!(detect_charset(b"<!doctype html><meta charset=UTF-8>"), Some(b"utf-8".to_vec()));
+/// assert_eq!(
+///   detect_charset(b"<meta http-equiv=Content-Type content='text/html; charset=iso-8859-1'>"),
+///   Some(b"iso-8859-1".to_vec()),
+/// );
+/// assert_eq!(detect_charset(b"<p>No charset"), None);
+/// ```
+pub fn detect_charset(src: &[u8]) -> Option<Vec<u8>> {
+  if src.starts_with(b"\xEF\xBB\xBF") {
+    return Some(b"utf-8".to_vec());
+  };
+  if src.starts_with(b"\xFE\xFF") {
+    return Some(b"utf-16be".to_vec());
+  };
+  if src.starts_with(b"\xFF\xFE") {
+    return Some(b"utf-16le".to_vec());
+  };
+  let mut p = Prescan {
+    src: &src[..src.len().min(PRESCAN_LIMIT)],
+    pos: 0,
+  };
+  while p.pos < p.src.len() {
+    let rest = &p.src[p.pos..];
+    if rest.starts_with(b"<!--") {
+      // The `-->` can overlap with the `<!--`.
+      p.pos += rest[2..].windows(3).position(|w| w == b"-->")? + 4;
+    } else if rest.len() >= 6
+      && rest[..5].eq_ignore_ascii_case(b"<meta")
+      && (is_whitespace(rest[5]) || rest[5] == b'/')
+    {
+      p.pos += 6;
+      if let Some(charset) = p.meta_charset()? {
+        let charset = trimmed_label(&charset);
+        if charset.is_empty() {
+          continue;
+        };
+        return Some(match charset {
+          b"utf-16" | b"utf-16be" | b"utf-16le" | b"unicodefffe" | b"unicodefeff"
+          | b"csunicode" | b"iso-10646-ucs-2" | b"ucs-2" | b"unicode" | b"unicode11utf8" => {
+            b"utf-8".to_vec()
+          }
+          b"x-user-defined" => b"windows-1252".to_vec(),
+          _ => charset.to_vec(),
+        });
+      };
+    } else if rest[0] == b'<'
+      && rest.len() >= 2
+      && (rest[1].is_ascii_alphabetic()
+        || (rest[1] == b'/' && rest.get(2).filter(|c| c.is_ascii_alphabetic()).is_some()))
+    {
+      p.skip_while(|c| !is_whitespace(c) && c != b'>');
+      while p.attribute().is_some() {}
+      p.peek()?;
+    } else if rest.starts_with(b"<!") || rest.starts_with(b"</") || rest.starts_with(b"<?") {
+      p.pos += rest.iter().position(|&c| c == b'>')?;
+    };
+    p.pos += 1;
+  }
+  None
+}
+
+// Encoding labels have leading and trailing ASCII whitespace removed before matching.
+fn trimmed_label(label: &[u8]) -> &[u8] {
+  let start = label
+    .iter()
+    .position(|&c| !is_whitespace(c))
+    .unwrap_or(label.len());
+  let end = label
+    .iter()
+    .rposition(|&c| !is_whitespace(c))
+    .map_or(start, |p| p + 1);
+  &label[start..end]
+}
@@ -0,0 +1,291 @@
+use crate::ast::AttrVal;
+use crate::ast::ElementClosingTag;
+use crate::ast::NodeData;
+use crate::cfg::Cfg;
+use crate::minify::content::minify_content;
+use crate::minify::ids::remove_unreferenced_ids;
+use crate::minify::instruction::is_xml_declaration;
+use crate::selector::ParseSelectorError;
+use crate::selector::Selector;
+use crate::stats::MinifyStats;
+use crate::stream::prolog_len;
+use alloc::vec;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use minify_html_common::spec::tag::ns::Namespace;
+use minify_html_common::spec::tag::EMPTY_SLICE;
+use minify_html_common::whitespace::normalize_line_endings;
+use minify_html_common::whitespace::trimmed;
+
+/// A parsed HTML document, which can be serialised multiple times. Create one using `parse` or `parse_with_cfg`.
+pub struct Document {
+  pub(crate) children: Vec<NodeData>,
+  charset: Option<Vec<u8>>,
+  doctype: Option<Vec<u8>>,
+  input_len: usize,
+  // The source up to and including the `<html>` start tag, only kept if `Cfg::preserve_prolog` was enabled when parsing.
+  prolog: Option<Vec<u8>>,
+}
+
+// Extracts the charset from a `Content-Type` value like `text/html; charset=utf-8`.
+fn charset_from_content_type(value: &[u8]) -> Option<Vec<u8>> {
+  let start = value
+    .windows(8)
+    .position(|w| w.eq_ignore_ascii_case(b"charset="))?
+    + 8;
+  let rest = &value[start..];
+  let end = rest
+    .iter()
+    .position(|&c| c == b';' || c.is_ascii_whitespace())
+    .unwrap_or(rest.len());
+  let charset = rest[..end].strip_prefix(b"\"").unwrap_or(&rest[..end]);
+  let charset = charset.strip_suffix(b"\"").unwrap_or(charset);
+  Some(charset.to_vec()).filter(|c| !c.is_empty())
+}
+
+fn find_charset(nodes: &[NodeData]) -> Option<Vec<u8>> {
+  for n in nodes {
+    if let NodeData::Element {
+      attributes,
+      children,
+      name,
+      ..
+    } = n
+    {
+      if name == b"meta" {
+        if let Some(charset) = attributes.get(b"charset".as_ref()) {
+          return Some(trimmed(charset.as_slice()).to_vec());
+        };
+        let is_content_type = attributes
+          .get(b"http-equiv".as_ref())
+          .filter(|v| trimmed(v.as_slice()).eq_ignore_ascii_case(b"content-type"))
+          .is_some();
+        if let Some(charset) = attributes
+          .get(b"content".as_ref())
+          .filter(|_| is_content_type)
+          .and_then(|v| charset_from_content_type(v.as_slice()))
+        {
+          return Some(charset);
+        };
+      };
+      if let Some(charset) = find_charset(children) {
+        return Some(charset);
+      };
+    };
+  }
+  None
+}
+
+fn is_element(n: &NodeData, tag_name: &[u8]) -> bool {
+  matches!(n, NodeData::Element { name, .. } if name == tag_name)
+}
+
+// Inserts `<meta charset=utf-8>` as the first child of `<head>`. If there's no `<head>` element, it's inserted where the head would be implied, at the start of `<html>` or after the doctype.
+fn insert_charset_meta(nodes: &mut Vec<NodeData>) {
+  let mut attributes = HashMap::default();
+  attributes.insert(b"charset".to_vec(), AttrVal {
+    quote: None,
+    value: b"utf-8".to_vec(),
+  });
+  let meta = NodeData::Element {
+    attributes,
+    children: Vec::new(),
+    closing_tag: ElementClosingTag::Void,
+    name: b"meta".to_vec(),
+    namespace: Namespace::Html,
+    next_sibling_element_name: Vec::new(),
+    #[cfg(feature = "tracking")]
+    source_offset: 0,
+    #[cfg(feature = "tracking")]
+    source_line: 0,
+  };
+  let mut nodes = nodes;
+  for tag_name in [b"html".as_ref(), b"head"] {
+    let Some(i) = nodes.iter().position(|n| is_element(n, tag_name)) else {
+      break;
+    };
+    match nodes[i].children_mut() {
+      Some(children) => nodes = children,
+      None => return,
+    };
+  }
+  // A `<meta>` before the doctype would put the document in quirks mode.
+  let pos = nodes
+    .iter()
+    .position(|n| matches!(n, NodeData::Doctype { .. }))
+    .map_or(0, |i| i + 1);
+  nodes.insert(pos, meta);
+}
+
+fn select_in<'a>(
+  selector: &Selector,
+  ancestors: &mut Vec<&'a NodeData>,
+  nodes: &'a [NodeData],
+  matches: &mut Vec<&'a NodeData>,
+) {
+  for n in nodes {
+    if let NodeData::Element { children, .. } = n {
+      if selector.matches(ancestors, n) {
+        matches.push(n);
+      };
+      ancestors.push(n);
+      select_in(selector, ancestors, children, matches);
+      ancestors.pop();
+    };
+  }
+}
+
+impl Document {
+  pub(crate) fn new(children: Vec<NodeData>, src: &[u8], preserve_prolog: bool) -> Document {
+    let charset = find_charset(&children);
+    let doctype = children.iter().find_map(|n| match n {
+      NodeData::Doctype { legacy, .. } => Some(legacy.clone()),
+      _ => None,
+    });
+    Document {
+      children,
+      charset,
+      doctype,
+      input_len: src.len(),
+      prolog: preserve_prolog
+        .then(|| prolog_len(src))
+        .flatten()
+        .map(|len| src[..len].to_vec()),
+    }
+  }
+
+  /// The charset declared by the first `<meta charset>` or `<meta http-equiv=content-type>` element, if any. See [`detect_charset`](crate::detect_charset) to sniff it from the source without parsing.
+  pub fn charset(&self) -> Option<&[u8]> {
+    self.charset.as_deref()
+  }
+
+  /// The content of the doctype after `html`, which is empty for `<!DOCTYPE html>`, or `None` if there is no doctype.
+  pub fn doctype(&self) -> Option<&[u8]> {
+    self.doctype.as_deref()
+  }
+
+  /// The top-level nodes of the document.
+  pub fn children(&self) -> &[NodeData] {
+    &self.children
+  }
+
+  /// The top-level nodes of the document, which can be changed before serialising.
+  pub fn children_mut(&mut self) -> &mut Vec<NodeData> {
+    &mut self.children
+  }
+
+  /// Returns all elements matching a CSS selector in document order. Only tag, `.class`, `#id`, `[attr]`, and `[attr=value]` selectors and the descendant combinator are supported, e.g. `article img[alt]`.
+  pub fn select(&self, selector: &str) -> Result<Vec<&NodeData>, ParseSelectorError> {
+    let selector: Selector = selector.parse()?;
+    Ok(self.select_with(&selector))
+  }
+
+  /// Like `select`, but with an already parsed selector.
+  pub fn select_with(&self, selector: &Selector) -> Vec<&NodeData> {
+    let mut matches = Vec::new();
+    select_in(selector, &mut Vec::new(), &self.children, &mut matches);
+    matches
+  }
+
+  /// The length of the source code in bytes.
+  pub fn input_len(&self) -> usize {
+    self.input_len
+  }
+
+  /// Minifies the document. The document is left intact, so it can be serialised again with a different configuration.
+  ///
+  /// Options that affect parsing, such as `preserve_brace_template_syntax`, `preserve_prolog`, and `remove_elements`, only apply when passed to `parse_with_cfg`.
+  pub fn serialize(&self, cfg: &Cfg) -> Vec<u8> {
+    Document {
+      children: self.children.clone(),
+      charset: self.charset.clone(),
+      doctype: self.doctype.clone(),
+      input_len: self.input_len,
+      prolog: self.prolog.clone(),
+    }
+    .serialize_minified(cfg)
+    .0
+  }
+
+  /// Minifies the document and returns statistics about the minification alongside the minified code. This consumes the document, avoiding the copy made by `serialize`.
+  ///
+  /// The document doesn't keep its source, so `MinifyStats::changed` is only set if the length changed. Use `minify_with_stats` to compare the output with the source byte for byte.
+  pub fn serialize_minified(self, cfg: &Cfg) -> (Vec<u8>, MinifyStats) {
+    let input_len = self.input_len;
+    let (out, mut stats) = self.serialize_minified_as(cfg, true);
+    stats.record_sizes(input_len, out.len());
+    stats.changed = input_len != out.len();
+    (out, stats)
+  }
+
+  // A `<meta charset>` is only injected when serialising a whole document, not a single node taken out of one. The returned statistics don't include the input and output sizes, which callers record.
+  pub(crate) fn serialize_minified_as(
+    self,
+    cfg: &Cfg,
+    is_whole_document: bool,
+  ) -> (Vec<u8>, MinifyStats) {
+    let mut out = Vec::with_capacity(self.input_len);
+    let mut stats = MinifyStats::default();
+    let mut children = self.children;
+    if cfg.inject_charset_meta && is_whole_document && self.charset.is_none() {
+      insert_charset_meta(&mut children);
+    };
+    if cfg.remove_unreferenced_ids {
+      remove_unreferenced_ids(&mut children);
+    };
+    if cfg.remove_xml_declaration {
+      children
+        .retain(|n| !matches!(n, NodeData::Instruction { code, .. } if is_xml_declaration(code)));
+    };
+    match self.prolog.as_deref().filter(|_| cfg.preserve_prolog) {
+      Some(prolog) => {
+        out.extend_from_slice(prolog);
+        // Only the content of the `<html>` element, and anything after it, is minified.
+        let mut nodes = children
+          .into_iter()
+          .skip_while(|n| !matches!(n, NodeData::Element { name, .. } if name == b"html"));
+        if let Some(NodeData::Element { children, .. }) = nodes.next() {
+          minify_content(
+            cfg,
+            &mut out,
+            &mut stats,
+            Namespace::Html,
+            false,
+            &mut vec![b"html".to_vec()],
+            b"html",
+            children,
+          );
+        };
+        let html_end = out.len();
+        minify_content(
+          cfg,
+          &mut out,
+          &mut stats,
+          Namespace::Html,
+          false,
+          &mut Vec::new(),
+          EMPTY_SLICE,
+          nodes.collect(),
+        );
+        // Anything after the `<html>` element, such as a comment, must stay outside it.
+        if out.len() > html_end {
+          out.splice(html_end..html_end, b"</html>".iter().copied());
+        };
+      }
+      None => minify_content(
+        cfg,
+        &mut out,
+        &mut stats,
+        Namespace::Html,
+        false,
+        &mut Vec::new(),
+        EMPTY_SLICE,
+        children,
+      ),
+    };
+    if let Some(ending) = cfg.normalize_line_endings {
+      out = normalize_line_endings(&out, ending.as_bytes());
+    };
+    (out, stats)
+  }
+}
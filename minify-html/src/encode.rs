@@ -0,0 +1,57 @@
+//! Encoding of attribute values and text with the same rules used when minifying, for HTML generated outside of the minifier.
+
+use crate::minify::attr::encode_value;
+use crate::minify::content;
+use crate::Cfg;
+use alloc::vec::Vec;
+
+/// An attribute value encoded by `encode_attr_value`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AttrEncoded {
+  /// The encoded value, including quotes if `needs_quotes`, to be written directly after `name=`. If it's empty, the attribute should be written as just its name.
+  pub quoted: Vec<u8>,
+  /// Whether `quoted` is wrapped in quotes.
+  pub needs_quotes: bool,
+}
+
+/// Encodes an attribute value as it would be written when minifying, using whichever of double quotes, single quotes, or no quotes is shortest, and the shortest entities. Options that apply only to specific attributes, like `never_unquote_attributes`, aren't applied.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::Cfg;
+/// use minify_html::encode::encode_attr_value;
+///
+/// let encoded = encode_attr_value(b"a \"b\"", &Cfg::new());
+/// assert_eq!(encoded.quoted, b"'a \"b\"'".to_vec());
+/// assert!(encoded.needs_quotes);
+/// ```
+pub fn encode_attr_value(value: &[u8], cfg: &Cfg) -> AttrEncoded {
+  if value.is_empty() {
+    return AttrEncoded {
+      quoted: Vec::new(),
+      needs_quotes: false,
+    };
+  };
+  let min = encode_value(cfg, value, true);
+  let mut quoted = Vec::with_capacity(min.len());
+  min.out(&mut quoted);
+  AttrEncoded {
+    quoted,
+    needs_quotes: min.quoted(),
+  }
+}
+
+/// Encodes text as it would be written when minifying, using the shortest entities. Whitespace is left as is.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::Cfg;
+/// use minify_html::encode::encode_text;
+///
+/// assert_eq!(encode_text(b"a < b & c", &Cfg::new()), b"a &lt; b & c".to_vec());
+/// ```
+pub fn encode_text(value: &[u8], cfg: &Cfg) -> Vec<u8> {
+  content::encode_text(cfg, value)
+}
@@ -0,0 +1,75 @@
+//! The named character references from the [HTML spec](https://html.spec.whatwg.org/multipage/named-characters.html), as used when decoding and encoding entities while minifying.
+//!
+//! Names are given without the leading `&`, and include the trailing `;` if the reference has one, e.g. `amp;`. A few references are also recognised without the semicolon for legacy reasons, e.g. `amp`.
+
+use alloc::vec::Vec;
+use minify_html_common::gen::entities::EntityType;
+use minify_html_common::gen::entities::ENTITY;
+use minify_html_common::gen::entities::ENTITY_NAMES;
+use minify_html_common::gen::entities::SHORTER_ENCODED_ENTITIES_DECODED;
+use minify_html_common::gen::entities::SHORTER_ENCODED_ENTITIES_ENCODED;
+use minify_html_common::pattern::TrieNodeMatch;
+
+/// Returns the UTF-8 characters that the named character reference `&name` decodes to, or `None` if it isn't one. Names are case sensitive.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::entities::decode_named;
+///
+/// assert_eq!(decode_named(b"amp;"), Some("&".as_bytes()));
+/// assert_eq!(decode_named(b"amp"), Some("&".as_bytes()));
+/// assert_eq!(decode_named(b"mdash"), None);
+/// assert_eq!(decode_named(b"AMP;"), Some("&".as_bytes()));
+/// assert_eq!(decode_named(b"Amp;"), None);
+/// ```
+pub fn decode_named(name: &[u8]) -> Option<&'static [u8]> {
+  let mut code = Vec::with_capacity(name.len() + 1);
+  code.push(b'&');
+  code.extend_from_slice(name);
+  match ENTITY.longest_matching_prefix(&code) {
+    TrieNodeMatch::Found {
+      len,
+      value: EntityType::Named(decoded),
+    } if len == code.len() => Some(decoded),
+    _ => None,
+  }
+}
+
+/// Returns whether the named character reference `&name;` is also recognised without its semicolon, like `&amp` or `&copy`. `name` may be given with or without the semicolon.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::entities::is_legacy_no_semicolon;
+///
+/// assert!(is_legacy_no_semicolon(b"copy"));
+/// assert!(is_legacy_no_semicolon(b"copy;"));
+/// assert!(!is_legacy_no_semicolon(b"mdash;"));
+/// ```
+pub fn is_legacy_no_semicolon(name: &[u8]) -> bool {
+  let name = name.strip_suffix(b";").unwrap_or(name);
+  !name.is_empty() && decode_named(name).is_some()
+}
+
+/// Returns all names, sorted, including the legacy forms without a semicolon.
+pub fn names() -> impl Iterator<Item = &'static [u8]> {
+  ENTITY_NAMES.iter().copied()
+}
+
+/// Returns the named character reference for `decoded`, including the leading `&`, if it's shorter than `decoded` as UTF-8. The minifier writes these characters using this reference. Only a few references are shorter than the characters they decode to, such as `&nGt;` for U+226B U+20D2.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::entities::shortest_encoding;
+///
+/// assert_eq!(shortest_encoding("\u{226B}\u{20D2}"), Some(b"&nGt;".as_ref()));
+/// assert_eq!(shortest_encoding("&"), None);
+/// ```
+pub fn shortest_encoding(decoded: &str) -> Option<&'static [u8]> {
+  SHORTER_ENCODED_ENTITIES_DECODED
+    .iter()
+    .position(|&d| d == decoded.as_bytes())
+    .map(|i| SHORTER_ENCODED_ENTITIES_ENCODED[i])
+}
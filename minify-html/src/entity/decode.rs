@@ -11,8 +11,11 @@
 // - Browsers match longest sequence of characters that would form a valid entity.
 // - Names must match case sensitively.
 // - For a numeric entity, browsers actually consume an unlimited amount of digits, but decode to 0xFFFD if not a valid
-//   Unicode Scalar Value.
+//   Unicode Scalar Value or if zero.
+// - Numeric entities in the C1 control range are decoded as if they were windows-1252 bytes.
 
+use alloc::vec::Vec;
+use core::char::from_u32;
 use memchr::memchr;
 use minify_html_common::gen::codepoints::Lookup;
 use minify_html_common::gen::codepoints::ALPHANUMERIC_OR_EQUALS;
@@ -23,7 +26,7 @@ use minify_html_common::gen::codepoints::UPPER_HEX_ALPHA;
 use minify_html_common::gen::entities::EntityType;
 use minify_html_common::gen::entities::ENTITY;
 use minify_html_common::pattern::TrieNodeMatch;
-use std::char::from_u32;
+use minify_html_common::whitespace::normalize_line_endings;
 
 enum Decoded {
   Ignored,
@@ -31,6 +34,14 @@ enum Decoded {
   Numeric(char),
 }
 
+// Replacements for U+0080 to U+009F, where zero means no replacement.
+// https://html.spec.whatwg.org/multipage/parsing.html#numeric-character-reference-end-state.
+const C1_REPLACEMENTS: [u32; 32] = [
+  0x20ac, 0, 0x201a, 0x0192, 0x201e, 0x2026, 0x2020, 0x2021, 0x02c6, 0x2030, 0x0160, 0x2039,
+  0x0152, 0, 0x017d, 0, 0, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014, 0x02dc, 0x2122,
+  0x0161, 0x203a, 0x0153, 0, 0x017e, 0x0178,
+];
+
 struct ParsedEntity {
   decoded: Decoded,
   read_len: usize,
@@ -69,7 +80,13 @@ fn parse_numeric_entity(
   };
   // Browsers decode to a replacement character (U+FFFD) if malformed.
   let char = Some(value)
-    .filter(|_| digits <= max_digits)
+    .filter(|&v| digits <= max_digits && v != 0)
+    .map(|v| match v {
+      0x80..=0x9f if C1_REPLACEMENTS[(v - 0x80) as usize] != 0 => {
+        C1_REPLACEMENTS[(v - 0x80) as usize]
+      }
+      v => v,
+    })
     .and_then(from_u32)
     .unwrap_or('\u{FFFD}');
   ParsedEntity {
@@ -138,6 +155,7 @@ fn parse_entity(code: &[u8], in_attr_val: bool) -> ParsedEntity {
   }
 }
 
+// Raw CR and CRLF are normalised to LF, as browsers do when preprocessing the input stream. A CR decoded from a character reference is kept, and `encode_entities` writes it back as a reference.
 pub fn decode_entities(mut code: &[u8], in_attr_val: bool) -> Vec<u8> {
   let mut res = Vec::<u8>::new();
   while !code.is_empty() {
@@ -145,7 +163,11 @@ pub fn decode_entities(mut code: &[u8], in_attr_val: bool) -> Vec<u8> {
       None => (code.len(), false),
       Some(n) => (n, true),
     };
-    res.extend_from_slice(&code[..before]);
+    if memchr(b'\r', &code[..before]).is_some() {
+      res.extend_from_slice(&normalize_line_endings(&code[..before], b"\n"));
+    } else {
+      res.extend_from_slice(&code[..before]);
+    };
     code = &code[before..];
     if matched {
       let ParsedEntity { decoded, read_len } = parse_entity(code, in_attr_val);
@@ -1,16 +1,21 @@
+use crate::cfg::OutputEncoding;
 use aho_corasick::AhoCorasick;
 use aho_corasick::AhoCorasickBuilder;
 use aho_corasick::AhoCorasickKind;
 use aho_corasick::MatchKind;
+use alloc::format;
+use alloc::vec::Vec;
 use memchr::memchr;
 use minify_html_common::gen::codepoints::ALPHANUMERIC_OR_EQUALS;
 use minify_html_common::gen::entities::EntityType;
 use minify_html_common::gen::entities::ENTITY;
 use minify_html_common::gen::entities::SHORTER_ENCODED_ENTITIES_DECODED;
 use minify_html_common::gen::entities::SHORTER_ENCODED_ENTITIES_ENCODED;
+use minify_html_common::lazy::Lazy;
 use minify_html_common::pattern::TrieNodeMatch;
-use once_cell::sync::Lazy;
 
+// Matchers are built from static patterns, so building them never fails.
+#[allow(clippy::unwrap_used)]
 static SHORTER_ENCODED_ENTITIES_ENCODED_SEARCHER: Lazy<AhoCorasick> = Lazy::new(|| {
   AhoCorasickBuilder::new()
     .kind(Some(AhoCorasickKind::DFA))
@@ -67,6 +72,57 @@ pub fn encode_entities(
       code = &code[end..];
     };
   }
-  SHORTER_ENCODED_ENTITIES_ENCODED_SEARCHER
-    .replace_all_bytes(&res, SHORTER_ENCODED_ENTITIES_ENCODED)
+  let res = SHORTER_ENCODED_ENTITIES_ENCODED_SEARCHER
+    .replace_all_bytes(&res, SHORTER_ENCODED_ENTITIES_ENCODED);
+  // A raw CR would be normalised to LF when parsed, so a CR decoded from a character reference must be written as one.
+  if memchr(b'\r', &res).is_none() {
+    return res;
+  };
+  let mut out = Vec::with_capacity(res.len() + 4);
+  for &c in &res {
+    if c == b'\r' {
+      out.extend_from_slice(b"&#13;");
+    } else {
+      out.push(c);
+    };
+  }
+  out
+}
+
+// Replaces characters that can't be represented in `encoding` with decimal character references, and writes the rest in that encoding. Invalid UTF-8 is left as is.
+pub fn encode_for_output(code: Vec<u8>, encoding: OutputEncoding) -> Vec<u8> {
+  let max = match encoding {
+    OutputEncoding::Utf8 => return code,
+    OutputEncoding::Latin1 => 0xff,
+    OutputEncoding::AsciiSafe => 0x7f,
+  };
+  if code.is_ascii() {
+    return code;
+  };
+  let mut res = Vec::<u8>::with_capacity(code.len());
+  let mut rest = code.as_slice();
+  while !rest.is_empty() {
+    let (valid_len, invalid_len) = match core::str::from_utf8(rest) {
+      Ok(_) => (rest.len(), 0),
+      Err(e) => (
+        e.valid_up_to(),
+        e.error_len().unwrap_or(rest.len() - e.valid_up_to()),
+      ),
+    };
+    for c in core::str::from_utf8(&rest[..valid_len])
+      .unwrap_or_default()
+      .chars()
+    {
+      let cp = c as u32;
+      if cp <= max {
+        // Code points up to U+00FF are the same as their Latin-1 byte.
+        res.push(cp as u8);
+      } else {
+        res.extend_from_slice(format!("&#{};", cp).as_bytes());
+      };
+    }
+    res.extend_from_slice(&rest[valid_len..valid_len + invalid_len]);
+    rest = &rest[valid_len + invalid_len..];
+  }
+  res
 }
@@ -0,0 +1,115 @@
+use crate::entity::decode::decode_entities;
+use std::str::from_utf8;
+
+#[test]
+fn test_decode_entities_handles_invalid_numeric_references() {
+  for (src, expected) in [
+    ("&#0;", "\u{FFFD}"),
+    ("&#x0", "\u{FFFD}"),
+    ("&#xD800;", "\u{FFFD}"),
+    ("&#xDFFF;", "\u{FFFD}"),
+    ("&#x110000;", "\u{FFFD}"),
+    ("&#x10FFFF;", "\u{10FFFF}"),
+    ("&#1114112;", "\u{FFFD}"),
+    ("&#000000000000065;", "A"),
+    ("&#x00000000000000041;", "A"),
+    ("&#99999999999999999999999;", "\u{FFFD}"),
+    ("&#xFFFFFFFFFFFFFFFFFFFF;", "\u{FFFD}"),
+    ("&#x80;", "\u{20AC}"),
+    ("&#128;", "\u{20AC}"),
+    ("&#x81;", "\u{81}"),
+    ("&#x9F;", "\u{178}"),
+    ("&#1", "\u{1}"),
+    ("&#X41;", "A"),
+  ] {
+    assert_eq!(
+      from_utf8(&decode_entities(src.as_bytes(), false)).unwrap(),
+      expected,
+      "decoding {:?}",
+      src
+    );
+  }
+}
+
+// The numeric character reference end state from https://html.spec.whatwg.org/multipage/parsing.html#numeric-character-reference-end-state, written out step by step.
+fn reference_numeric(radix: u32, digits: &str) -> char {
+  let mut value = 0u64;
+  for c in digits.chars() {
+    value = (value * radix as u64 + c.to_digit(radix).unwrap() as u64).min(0x110000);
+  }
+  let value = match value {
+    0x80 => 0x20ac,
+    0x82 => 0x201a,
+    0x83 => 0x0192,
+    0x84 => 0x201e,
+    0x85 => 0x2026,
+    0x86 => 0x2020,
+    0x87 => 0x2021,
+    0x88 => 0x02c6,
+    0x89 => 0x2030,
+    0x8a => 0x0160,
+    0x8b => 0x2039,
+    0x8c => 0x0152,
+    0x8e => 0x017d,
+    0x91 => 0x2018,
+    0x92 => 0x2019,
+    0x93 => 0x201c,
+    0x94 => 0x201d,
+    0x95 => 0x2022,
+    0x96 => 0x2013,
+    0x97 => 0x2014,
+    0x98 => 0x02dc,
+    0x99 => 0x2122,
+    0x9a => 0x0161,
+    0x9b => 0x203a,
+    0x9c => 0x0153,
+    0x9e => 0x017e,
+    0x9f => 0x0178,
+    v => v,
+  };
+  if value == 0 || value > 0x10ffff || (0xd800..=0xdfff).contains(&value) {
+    '\u{FFFD}'
+  } else {
+    char::from_u32(value as u32).unwrap()
+  }
+}
+
+#[test]
+fn test_decode_entities_matches_reference_for_random_numeric_references() {
+  // Deterministic xorshift so failures are reproducible.
+  let mut state = 0x2545f4914f6cdd1du64;
+  let mut next = |n: u64| {
+    state ^= state << 13;
+    state ^= state >> 7;
+    state ^= state << 17;
+    state % n
+  };
+  for _ in 0..100_000 {
+    let hex = next(2) == 0;
+    let (radix, prefix, alphabet) = if hex {
+      (
+        16,
+        if next(2) == 0 { "&#x" } else { "&#X" },
+        "0123456789abcdefABCDEF",
+      )
+    } else {
+      (10, "&#", "0123456789")
+    };
+    let zeros = next(4) * next(8);
+    let mut digits = "0".repeat(zeros as usize);
+    let max_len = if next(4) == 0 { 30 } else { 7 };
+    for _ in 0..1 + next(max_len) {
+      digits.push(alphabet.as_bytes()[next(alphabet.len() as u64) as usize] as char);
+    }
+    let semicolon = if next(2) == 0 { ";" } else { "" };
+    let in_attr_val = next(2) == 0;
+    let src = format!("a{}{}{} z", prefix, digits, semicolon);
+    let expected = format!("a{} z", reference_numeric(radix, &digits));
+    assert_eq!(
+      from_utf8(&decode_entities(src.as_bytes(), in_attr_val)).unwrap(),
+      expected,
+      "decoding {:?}",
+      src
+    );
+  }
+}
@@ -0,0 +1,22 @@
+use core::fmt::Display;
+use core::fmt::Formatter;
+
+/// An error returned by `try_minify` when the source can't be fully minified within the limits set in `Cfg`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MinifyError {
+  /// An element was nested more deeply than `Cfg::max_depth`.
+  MaxDepthExceeded { max_depth: usize },
+}
+
+impl Display for MinifyError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    match self {
+      MinifyError::MaxDepthExceeded { max_depth } => {
+        write!(f, "elements are nested more than {} levels deep", max_depth)
+      }
+    }
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MinifyError {}
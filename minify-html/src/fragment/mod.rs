@@ -0,0 +1,95 @@
+use crate::cfg::Cfg;
+use crate::minify::content::minify_content_before_unknown;
+use crate::parse::content::parse_content;
+use crate::parse::element::parse_element;
+use crate::parse::element::parse_tag;
+use crate::parse::Code;
+use crate::parse::ParseOpts;
+use crate::stats::MinifyStats;
+use alloc::vec;
+use alloc::vec::Vec;
+use minify_html_common::spec::tag::ns::Namespace;
+use minify_html_common::spec::tag::EMPTY_SLICE;
+use minify_html_common::whitespace::normalize_line_endings;
+
+#[cfg(test)]
+mod tests;
+
+/// The element that a fragment passed to `minify_fragment_with_context` will be inserted into, which affects how its tags are implicitly closed, whether its whitespace can be trimmed, and which of its closing tags can be omitted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FragmentContext {
+  /// Block content, such as the contents of `<body>` or a `<div>`. This is also how `minify` treats content at the top level.
+  InBody,
+  /// Inline content, such as the contents of a `<p>` or `<span>`, where whitespace at the start and end may be rendered.
+  InsideInline,
+  /// The contents of a `<pre>`, where whitespace is always preserved.
+  InsidePre,
+  /// The contents of a `<select>`, like `<option>` and `<optgroup>` elements.
+  InsideSelect,
+  /// The contents of an `<svg>`, which are parsed and minified as SVG elements.
+  InsideSvg,
+  /// The contents of a `<table>`, like `<tr>` and `<tbody>` elements.
+  InsideTable,
+  /// The contents of a `<tr>`, like `<td>` and `<th>` elements.
+  InsideTableRow,
+}
+
+impl FragmentContext {
+  fn parent(self) -> (Namespace, &'static [u8]) {
+    match self {
+      FragmentContext::InBody => (Namespace::Html, b"body"),
+      FragmentContext::InsideInline => (Namespace::Html, b"span"),
+      FragmentContext::InsidePre => (Namespace::Html, b"pre"),
+      FragmentContext::InsideSelect => (Namespace::Html, b"select"),
+      FragmentContext::InsideSvg => (Namespace::Svg, b"svg"),
+      FragmentContext::InsideTable => (Namespace::Html, b"table"),
+      FragmentContext::InsideTableRow => (Namespace::Html, b"tr"),
+    }
+  }
+}
+
+/// Minifies UTF-8 HTML code that will be inserted into an element described by `context`, rather than being a document of its own. For example, `<td>` elements can be minified as the contents of a `<tr>`, and text can be minified as the contents of a `<span>` without trimming whitespace that would be rendered between it and its surrounding content.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, FragmentContext, minify_fragment_with_context};
+///
+/// let cfg = Cfg::new();
+/// let minified = minify_fragment_with_context(b" Hello,  <b>world</b>! ", &cfg, FragmentContext::InsideInline);
+/// assert_eq!(minified, b" Hello, <b>world</b>! ".to_vec());
+/// ```
+pub fn minify_fragment_with_context(src: &[u8], cfg: &Cfg, context: FragmentContext) -> Vec<u8> {
+  let (ns, parent) = context.parent();
+  let mut code = Code::new_with_opts(src, ParseOpts::from_cfg(cfg));
+  let mut nodes = Vec::new();
+  loop {
+    nodes.extend(parse_content(&mut code, ns, EMPTY_SLICE, parent).children);
+    if code.at_end() {
+      break;
+    };
+    // Parsing stops at a tag that would close the context element, which can't happen inside a fragment.
+    if code.as_slice().starts_with(b"</") {
+      parse_tag(&mut code);
+    } else {
+      nodes.push(parse_element(&mut code, ns, parent));
+    };
+  }
+  let mut out = Vec::with_capacity(src.len());
+  // The fragment may be followed by other content in its parent, so its last element must be explicitly closed.
+  minify_content_before_unknown(
+    cfg,
+    &mut out,
+    &mut MinifyStats::default(),
+    ns,
+    context == FragmentContext::InsidePre,
+    &mut vec![parent.to_vec()],
+    parent,
+    nodes,
+    true,
+  );
+  match cfg.normalize_line_endings {
+    Some(ending) => normalize_line_endings(&out, ending.as_bytes()),
+    None => out,
+  }
+}
@@ -0,0 +1,75 @@
+use crate::cfg::Cfg;
+use crate::fragment::minify_fragment_with_context;
+use crate::fragment::FragmentContext;
+use crate::minify;
+use std::str::from_utf8;
+
+fn eval(src: &[u8], expected: &[u8], context: FragmentContext) {
+  let min = minify_fragment_with_context(src, &Cfg::new(), context);
+  assert_eq!(from_utf8(&min).unwrap(), from_utf8(expected).unwrap());
+}
+
+#[test]
+fn test_minify_fragment_with_context() {
+  eval(
+    b"\n  <p> a </p>\n  <div> b </div>\n",
+    b"<p>a<div>b</div>",
+    FragmentContext::InBody,
+  );
+  eval(
+    b" a  <b> b </b> ",
+    b" a <b> b </b> ",
+    FragmentContext::InsideInline,
+  );
+  eval(
+    b"  a  <b> b </b>\n",
+    b"  a  <b> b </b>\n",
+    FragmentContext::InsidePre,
+  );
+  eval(
+    b"<option> a </option>\n<option>b</option>",
+    b"<option> a <option>b</option>",
+    FragmentContext::InsideSelect,
+  );
+  eval(
+    b"<path d=\"a\" />\n  <circle r=\"1\"></circle>",
+    b"<path d=a /><circle r=1></circle>",
+    FragmentContext::InsideSvg,
+  );
+  eval(
+    b"<tr>\n  <td>a</td>\n</tr>\n<tr><td>b</td></tr>",
+    b"<tr><td>a<tr><td>b</tr>",
+    FragmentContext::InsideTable,
+  );
+  eval(
+    b"<td> a </td>\n<td>b</td>",
+    b"<td>a<td>b</td>",
+    FragmentContext::InsideTableRow,
+  );
+}
+
+#[test]
+fn test_minify_fragment_with_context_differs_from_document() {
+  // Whitespace at the start and end of a document is trimmed, but not in an inline fragment.
+  assert_eq!(minify(b" a ", &Cfg::new()), b"a");
+  eval(b" a ", b" a ", FragmentContext::InsideInline);
+  // The closing tag of the last element in a document can be omitted, but not in a fragment, as content may follow it.
+  assert_eq!(minify(b"<p>a</p>", &Cfg::new()), b"<p>a");
+  eval(b"<p>a</p>", b"<p>a</p>", FragmentContext::InBody);
+}
+
+#[test]
+fn test_minify_fragment_with_context_ignores_context_tags() {
+  // A closing tag for the context element is dropped instead of ending the fragment.
+  eval(
+    b"<td>a</tr><td>b",
+    b"<td>a<td>b",
+    FragmentContext::InsideTableRow,
+  );
+  // An opening tag that would implicitly close the context element is kept.
+  eval(
+    b"<td>a<tr><td>b",
+    b"<td>a<tr><td>b",
+    FragmentContext::InsideTableRow,
+  );
+}
@@ -1,26 +1,100 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(unsafe_code)]
+// Input can be anything, so never panic because of it, and instead keep the original code or skip the minification. `unwrap` must be allowed explicitly where failure doesn't depend on input, such as when building static matchers.
+#![cfg_attr(
+  not(test),
+  deny(clippy::expect_used, clippy::panic, clippy::unwrap_used)
+)]
 
+extern crate alloc;
+
+pub use crate::amp::check_amp_html;
+pub use crate::amp::AmpWarning;
+#[cfg(feature = "std")]
 use crate::ast::c14n::c14n_serialise_ast;
+pub use crate::ast::AttrVal;
+pub use crate::ast::ElementClosingTag;
+pub use crate::ast::NodeData;
+pub use crate::ast::ParseElementClosingTagError;
+pub use crate::ast::RcdataContentType;
+pub use crate::ast::ScriptOrStyleLang;
 pub use crate::cfg::Cfg;
-use crate::minify::content::minify_content;
+pub use crate::cfg::LineEnding;
+pub use crate::cfg::OutputEncoding;
+pub use crate::cfg::VoidSelfClosingStyle;
+pub use crate::cfg::WhitespaceMode;
+pub use crate::charset::detect_charset;
+pub use crate::document::Document;
+pub use crate::error::MinifyError;
+pub use crate::fragment::minify_fragment_with_context;
+pub use crate::fragment::FragmentContext;
+#[cfg(feature = "css")]
+pub use crate::minify::css::minify_css_str;
+#[cfg(feature = "js")]
+pub use crate::minify::js::minify_js_str;
+#[cfg(feature = "parallel")]
+pub use crate::parallel::minify_parallel;
+#[cfg(feature = "parallel")]
+pub use crate::parallel::minify_parallel_files;
 use crate::parse::content::parse_content;
 use crate::parse::Code;
-use minify_html_common::spec::tag::ns::Namespace;
+pub use crate::selector::ParseSelectorError;
+pub use crate::selector::Selector;
+pub use crate::selector::SimpleSelector;
+pub use crate::stats::MinifyStats;
+#[cfg(feature = "std")]
+pub use crate::stream::minify::minify_streaming;
+#[cfg(feature = "std")]
+pub use crate::stream::minify::minify_streaming_from_reader;
+#[cfg(feature = "std")]
+pub use crate::stream::minify::MinifyReader;
+pub use crate::stream::tokenize;
+pub use crate::stream::Attribute;
+pub use crate::stream::Event;
+pub use crate::stream::Tokenizer;
+pub use crate::visitor::ElementView;
+pub use crate::visitor::ElementVisitor;
+pub use crate::visitor::TextContext;
+pub use crate::visitor::TextVisitor;
+pub use crate::xml::minify_atom;
+pub use crate::xml::minify_rss;
+pub use crate::xml::minify_xml;
+pub use crate::xml::XmlCfg;
+use alloc::vec::Vec;
+pub use minify_html_common::spec::tag::ns::Namespace;
 use minify_html_common::spec::tag::EMPTY_SLICE;
 use parse::ParseOpts;
-use std::io::Write;
 
+mod amp;
 mod ast;
+#[cfg(feature = "capi")]
+pub mod capi;
 mod cfg;
+mod charset;
+mod document;
+pub mod encode;
+pub mod entities;
 mod entity;
+mod error;
+mod fragment;
 mod minify;
+#[cfg(feature = "parallel")]
+mod parallel;
 mod parse;
+mod selector;
+pub mod spec;
+mod stats;
+mod stream;
 mod tag;
 #[cfg(test)]
 mod tests;
+mod visitor;
+mod xml;
 
 /// Minifies UTF-8 HTML code, represented as an array of bytes.
 ///
+/// The output only depends on `src` and `cfg`, so it's the same on every platform, run, and thread.
+///
 /// # Arguments
 ///
 /// * `code` - A slice of bytes representing the source code to minify.
@@ -38,28 +112,125 @@ mod tests;
 /// assert_eq!(minified, b"<p>Hello, world!".to_vec());
 /// ```
 pub fn minify(src: &[u8], cfg: &Cfg) -> Vec<u8> {
-  let mut code = Code::new_with_opts(src, ParseOpts {
-    treat_brace_as_opaque: cfg.preserve_brace_template_syntax,
-    treat_chevron_percent_as_opaque: cfg.preserve_chevron_percent_template_syntax,
-  });
+  minify_with_stats(src, cfg).0
+}
+
+/// Minifies UTF-8 HTML code, represented as an array of bytes, and returns statistics about the minification alongside the minified code.
+///
+/// # Arguments
+///
+/// * `code` - A slice of bytes representing the source code to minify.
+/// * `cfg` - Configuration object to adjust minification approach.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, minify_with_stats};
+///
+/// let code: &[u8] = b"<p>  Hello, world!  </p>";
+/// let (minified, stats) = minify_with_stats(&code, &Cfg::new());
+/// assert_eq!(minified, b"<p>Hello, world!".to_vec());
+/// assert_eq!(stats.input_bytes, 24);
+/// assert_eq!(stats.output_bytes, 16);
+/// assert_eq!(stats.bytes_saved, 8);
+/// assert_eq!(stats.closing_tags_omitted, 1);
+/// ```
+pub fn minify_with_stats(src: &[u8], cfg: &Cfg) -> (Vec<u8>, MinifyStats) {
+  let (out, mut stats) = parse_with_cfg(src, cfg).serialize_minified_as(cfg, true);
+  stats.record_output(src, &out);
+  (out, stats)
+}
+
+/// Alias of `minify_with_stats`.
+pub fn minify_verbose(src: &[u8], cfg: &Cfg) -> (Vec<u8>, MinifyStats) {
+  minify_with_stats(src, cfg)
+}
+
+/// Minifies UTF-8 HTML code like `minify`, resolving relative URLs against `base_url`, the absolute URL of the document, when `normalize_urls` is enabled. See `Cfg::base_url`.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, minify_with_base_url};
+///
+/// let mut cfg = Cfg::new();
+/// cfg.normalize_urls = true;
+/// let code = b"<a href=\"https://example.com/blog/index.html\">a</a><img src=\"../../img/a.png\">";
+/// assert_eq!(
+///   minify_with_base_url(code, &cfg, "https://example.com/blog/post/"),
+///   b"<a href=../index.html>a</a><img src=/img/a.png>".to_vec(),
+/// );
+/// ```
+pub fn minify_with_base_url(src: &[u8], cfg: &Cfg, base_url: &str) -> Vec<u8> {
+  let cfg = Cfg {
+    base_url: Some(base_url.as_bytes().to_vec()),
+    ..cfg.clone()
+  };
+  minify(src, &cfg)
+}
+
+/// Minifies UTF-8 HTML code like `minify`, but returns an error instead of partially minified code if the source exceeds a limit set in `cfg`, such as `max_depth`.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, MinifyError, try_minify};
+///
+/// let mut cfg = Cfg::new();
+/// cfg.max_depth = Some(2);
+/// assert_eq!(try_minify(b"<div><p>  a  </p></div>", &cfg), Ok(b"<div><p>a</div>".to_vec()));
+/// assert_eq!(
+///   try_minify(b"<div><p><b>a</b></p></div>", &cfg),
+///   Err(MinifyError::MaxDepthExceeded { max_depth: 2 }),
+/// );
+/// ```
+pub fn try_minify(src: &[u8], cfg: &Cfg) -> Result<Vec<u8>, MinifyError> {
+  let mut code = Code::new_with_opts(src, ParseOpts::from_cfg(cfg));
+  let parsed = parse_content(&mut code, Namespace::Html, EMPTY_SLICE, EMPTY_SLICE);
+  if code.max_depth_exceeded {
+    return Err(MinifyError::MaxDepthExceeded {
+      max_depth: cfg.max_depth.unwrap_or_default(),
+    });
+  };
+  Ok(
+    Document::new(parsed.children, src, cfg.preserve_prolog)
+      .serialize_minified_as(cfg, true)
+      .0,
+  )
+}
+
+/// Parses UTF-8 HTML code into a `Document`, which can then be minified one or more times.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, parse};
+///
+/// let doc = parse(b"<!DOCTYPE html><meta charset=utf-8><p>  Hello, world!  </p>");
+/// assert_eq!(doc.charset(), Some(b"utf-8".as_ref()));
+/// assert_eq!(doc.doctype(), Some(b"".as_ref()));
+/// assert_eq!(doc.serialize(&Cfg::new()), b"<!doctype html><meta charset=utf-8><p>Hello, world!".to_vec());
+/// ```
+pub fn parse(src: &[u8]) -> Document {
+  parse_with_cfg(src, &Cfg::new())
+}
+
+/// Parses UTF-8 HTML code into a `Document`, applying options in `cfg` that affect parsing, such as `preserve_brace_template_syntax` and `remove_elements`.
+pub fn parse_with_cfg(src: &[u8], cfg: &Cfg) -> Document {
+  let mut code = Code::new_with_opts(src, ParseOpts::from_cfg(cfg));
   let parsed = parse_content(&mut code, Namespace::Html, EMPTY_SLICE, EMPTY_SLICE);
-  let mut out = Vec::with_capacity(src.len());
-  minify_content(
-    cfg,
-    &mut out,
-    Namespace::Html,
-    false,
-    EMPTY_SLICE,
-    parsed.children,
-  );
-  out
+  Document::new(parsed.children, src, cfg.preserve_prolog)
 }
 
-pub fn canonicalise<T: Write>(out: &mut T, src: &[u8]) -> std::io::Result<()> {
+#[cfg(feature = "std")]
+pub fn canonicalise<T: std::io::Write>(out: &mut T, src: &[u8]) -> std::io::Result<()> {
   let mut code = Code::new(src);
   let parsed = parse_content(&mut code, Namespace::Html, EMPTY_SLICE, EMPTY_SLICE);
+  let mut buf = Vec::new();
   for c in parsed.children {
-    c14n_serialise_ast(out, &c)?;
+    buf.clear();
+    c14n_serialise_ast(&mut buf, &c);
+    out.write_all(&buf)?;
   }
   Ok(())
 }
@@ -1,24 +1,49 @@
 use crate::entity::encode::encode_entities;
+use crate::entity::encode::encode_for_output;
+#[cfg(feature = "css")]
+use crate::minify::catch_minifier_panic;
+use crate::minify::content::minify_content;
+use crate::minify::url::normalize_url;
+use crate::minify::url::normalize_url_with_base;
+use crate::parse::content::parse_content;
+use crate::parse::Code;
+use crate::parse::ParseOpts;
+use crate::stats::MinifyStats;
 use crate::Cfg;
 use aho_corasick::AhoCorasickBuilder;
 use aho_corasick::AhoCorasickKind;
 use aho_corasick::MatchKind;
+use alloc::vec;
+use alloc::vec::Vec;
+#[cfg(feature = "css")]
+use core::str::from_utf8;
+#[cfg(feature = "css")]
 use lightningcss::stylesheet::MinifyOptions;
+#[cfg(feature = "css")]
 use lightningcss::stylesheet::ParserOptions;
+#[cfg(feature = "css")]
 use lightningcss::stylesheet::PrinterOptions;
+#[cfg(feature = "css")]
 use lightningcss::stylesheet::StyleAttribute;
 use minify_html_common::gen::attrs::ATTRS;
 use minify_html_common::gen::codepoints::DIGIT;
+use minify_html_common::gen::codepoints::WHITESPACE;
+use minify_html_common::lazy::Lazy;
 use minify_html_common::pattern::Replacer;
 use minify_html_common::spec::script::JAVASCRIPT_MIME_TYPES;
+use minify_html_common::spec::svg::svg_attr_default;
 use minify_html_common::spec::tag::ns::Namespace;
+use minify_html_common::spec::tag::EMPTY_SLICE;
+use minify_html_common::spec::url::is_url_attr;
+use minify_html_common::spec::url::is_url_list_attr;
 use minify_html_common::whitespace::collapse_whitespace;
 use minify_html_common::whitespace::left_trim;
 use minify_html_common::whitespace::remove_all_whitespace;
 use minify_html_common::whitespace::right_trim;
-use once_cell::sync::Lazy;
-use std::str::from_utf8;
+use minify_html_common::whitespace::trimmed;
 
+// Matchers are built from static patterns, so building them never fails.
+#[allow(clippy::unwrap_used)]
 fn build_double_quoted_replacer() -> Replacer {
   let mut patterns = Vec::<Vec<u8>>::new();
   let mut replacements = Vec::<Vec<u8>>::new();
@@ -43,6 +68,7 @@ fn build_double_quoted_replacer() -> Replacer {
 }
 
 // To pass validation, entities MUST end with a semicolon.
+#[allow(clippy::unwrap_used)]
 fn build_whatwg_double_quoted_replacer() -> Replacer {
   Replacer::new(
     AhoCorasickBuilder::new()
@@ -53,6 +79,7 @@ fn build_whatwg_double_quoted_replacer() -> Replacer {
   )
 }
 
+#[allow(clippy::unwrap_used)]
 fn build_single_quoted_replacer() -> Replacer {
   let mut patterns = Vec::<Vec<u8>>::new();
   let mut replacements = Vec::<Vec<u8>>::new();
@@ -77,6 +104,7 @@ fn build_single_quoted_replacer() -> Replacer {
 }
 
 // To pass validation, entities MUST end with a semicolon.
+#[allow(clippy::unwrap_used)]
 fn build_whatwg_single_quoted_replacer() -> Replacer {
   Replacer::new(
     AhoCorasickBuilder::new()
@@ -96,6 +124,7 @@ static WS: &[(u8, &[u8])] = &[
   (b'\x20', b"&#32"),
 ];
 
+#[allow(clippy::unwrap_used)]
 fn build_unquoted_replacer() -> Replacer {
   let mut patterns = Vec::<Vec<u8>>::new();
   let mut replacements = Vec::<Vec<u8>>::new();
@@ -146,6 +175,7 @@ static WHATWG_UNQUOTED: &[(u8, &[u8])] = &[
 ];
 
 // This encodes more characters in the value but may not end those entities with semicolons.
+#[allow(clippy::unwrap_used)]
 fn build_semi_whatwg_unquoted_replacer() -> Replacer {
   let mut patterns = Vec::<Vec<u8>>::new();
   let mut replacements = Vec::<Vec<u8>>::new();
@@ -213,6 +243,7 @@ fn build_semi_whatwg_unquoted_replacer() -> Replacer {
 }
 
 // To pass validation, entities MUST end with a semicolon.
+#[allow(clippy::unwrap_used)]
 fn build_whatwg_unquoted_replacer() -> Replacer {
   let mut patterns = Vec::<Vec<u8>>::new();
   let mut replacements = Vec::<Vec<u8>>::new();
@@ -255,16 +286,16 @@ fn build_whatwg_unquoted_replacer() -> Replacer {
   )
 }
 
-static DOUBLE_QUOTED_REPLACER: Lazy<Replacer> = Lazy::new(|| build_double_quoted_replacer());
-static SINGLE_QUOTED_REPLACER: Lazy<Replacer> = Lazy::new(|| build_single_quoted_replacer());
-static UNQUOTED_REPLACER: Lazy<Replacer> = Lazy::new(|| build_unquoted_replacer());
+static DOUBLE_QUOTED_REPLACER: Lazy<Replacer> = Lazy::new(build_double_quoted_replacer);
+static SINGLE_QUOTED_REPLACER: Lazy<Replacer> = Lazy::new(build_single_quoted_replacer);
+static UNQUOTED_REPLACER: Lazy<Replacer> = Lazy::new(build_unquoted_replacer);
 static SEMI_WHATWG_UNQUOTED_REPLACER: Lazy<Replacer> =
-  Lazy::new(|| build_semi_whatwg_unquoted_replacer());
+  Lazy::new(build_semi_whatwg_unquoted_replacer);
 static WHATWG_DOUBLE_QUOTED_REPLACER: Lazy<Replacer> =
-  Lazy::new(|| build_whatwg_double_quoted_replacer());
+  Lazy::new(build_whatwg_double_quoted_replacer);
 static WHATWG_SINGLE_QUOTED_REPLACER: Lazy<Replacer> =
-  Lazy::new(|| build_whatwg_single_quoted_replacer());
-static WHATWG_UNQUOTED_REPLACER: Lazy<Replacer> = Lazy::new(|| build_whatwg_unquoted_replacer());
+  Lazy::new(build_whatwg_single_quoted_replacer);
+static WHATWG_UNQUOTED_REPLACER: Lazy<Replacer> = Lazy::new(build_whatwg_unquoted_replacer);
 
 pub struct AttrMinifiedValue {
   quoted: bool,
@@ -370,38 +401,277 @@ pub fn encode_unquoted(
   }
 }
 
+// Returns true if the attribute should be dropped entirely due to `Cfg::remove_data_attrs`.
+pub fn is_removed_attr(cfg: &Cfg, name: &[u8]) -> bool {
+  cfg
+    .remove_data_attrs
+    .iter()
+    .any(|pat| match pat.strip_suffix(b"*") {
+      Some(prefix) => {
+        name.len() >= prefix.len() && name[..prefix.len()].eq_ignore_ascii_case(prefix)
+      }
+      None => name.eq_ignore_ascii_case(pat),
+    })
+}
+
+pub fn is_framework_binding_attr(cfg: &Cfg, name: &[u8]) -> bool {
+  cfg
+    .framework_binding_attr_prefixes
+    .iter()
+    .any(|prefix| name.starts_with(prefix))
+}
+
+pub fn is_preserved_microdata_attr(cfg: &Cfg, ns: Namespace, name: &[u8]) -> bool {
+  cfg.preserve_microdata_attributes
+    && ns == Namespace::Html
+    && matches!(
+      name,
+      b"itemid" | b"itemprop" | b"itemref" | b"itemscope" | b"itemtype"
+    )
+}
+
 pub enum AttrMinified {
   Redundant,
   NoValue,
   Value(AttrMinifiedValue),
 }
 
+fn normalize_url_with_cfg(cfg: &Cfg, url: &[u8]) -> Option<Vec<u8>> {
+  match &cfg.base_url {
+    Some(base) => normalize_url_with_base(url, base),
+    None => normalize_url(url),
+  }
+}
+
+// Removes trailing zeros after the decimal point of a number, like `1.0` or `0.50`. Other values are left as is.
+fn normalize_viewport_number(value: &[u8]) -> &[u8] {
+  let digits = value.strip_prefix(b"-").unwrap_or(value);
+  let dot = match digits.iter().position(|&c| c == b'.') {
+    Some(dot) => dot,
+    None => return value,
+  };
+  let is_number = digits.len() > 1
+    && digits
+      .iter()
+      .enumerate()
+      .all(|(i, c)| i == dot || c.is_ascii_digit());
+  if !is_number {
+    return value;
+  };
+  // Only digits after the decimal point can be removed, as there's at least the decimal point before any trailing zeros.
+  let end = value.len() - value.iter().rev().take_while(|&&c| c == b'0').count();
+  let end = if value[end - 1] == b'.' { end - 1 } else { end };
+  match &value[..end] {
+    b"" | b"-" => b"0",
+    v => v,
+  }
+}
+
+// Rewrites the `content` of a `<meta name=viewport>`, which has already had its whitespace removed, like `width=device-width,initial-scale=1.0,` into `width=device-width,initial-scale=1`.
+fn normalize_viewport(value: &[u8]) -> Vec<u8> {
+  let mut out = Vec::with_capacity(value.len());
+  for prop in value.split(|&c| c == b',').filter(|p| !p.is_empty()) {
+    if !out.is_empty() {
+      out.push(b',');
+    };
+    match prop.iter().position(|&c| c == b'=') {
+      Some(eq) => {
+        out.extend_from_slice(&prop[..=eq]);
+        out.extend_from_slice(normalize_viewport_number(&prop[eq + 1..]));
+      }
+      None => out.extend_from_slice(prop),
+    };
+  }
+  out
+}
+
+fn skip_whitespace(code: &[u8]) -> &[u8] {
+  let start = code.iter().take_while(|&&c| WHITESPACE[c]).count();
+  &code[start..]
+}
+
+// Rewrites the `content` of a `<meta http-equiv=refresh>` like `5; URL = 'https://example.com/'` into `5;url=https://example.com/`, using the same parsing steps as browsers from https://html.spec.whatwg.org/multipage/semantics.html#shared-declarative-refresh-steps. Returns `None` if browsers would ignore the value, in which case it's left as is.
+fn normalize_refresh(value: &[u8]) -> Option<Vec<u8>> {
+  let rest = skip_whitespace(value);
+  let digits = rest.iter().take_while(|&&c| DIGIT[c]).count();
+  if digits == 0 && rest.first() != Some(&b'.') {
+    return None;
+  };
+  // Leading zeros and any fractional part are ignored.
+  let mut out = match rest[..digits].iter().position(|&c| c != b'0') {
+    Some(start) => rest[start..digits].to_vec(),
+    None => b"0".to_vec(),
+  };
+  let rest = &rest[digits..];
+  let rest = &rest[rest.iter().take_while(|&&c| DIGIT[c] || c == b'.').count()..];
+  match rest.first() {
+    None => return Some(out),
+    Some(&c) if c == b';' || c == b',' || WHITESPACE[c] => {}
+    Some(_) => return None,
+  };
+  let mut rest = skip_whitespace(rest);
+  if let Some(b';' | b',') = rest.first() {
+    rest = skip_whitespace(&rest[1..]);
+  };
+  // The `URL=` prefix is optional, and is only skipped if complete.
+  if rest.len() >= 3 && rest[..3].eq_ignore_ascii_case(b"url") {
+    let after_keyword = skip_whitespace(&rest[3..]);
+    if let Some(b'=') = after_keyword.first() {
+      rest = skip_whitespace(&after_keyword[1..]);
+    };
+  };
+  // The URL can be quoted, in which case everything from the closing quote is ignored.
+  if let Some(&quote @ (b'"' | b'\'')) = rest.first() {
+    rest = &rest[1..];
+    if let Some(end) = rest.iter().position(|&c| c == quote) {
+      rest = &rest[..end];
+    };
+  };
+  // The URL parser ignores leading and trailing whitespace.
+  let url = trimmed(rest);
+  if !url.is_empty() {
+    out.extend_from_slice(b";url=");
+    out.extend_from_slice(url);
+  };
+  Some(out)
+}
+
+// Returns whether the end of `value` isn't inside a string or escaped.
+fn ends_outside_css_string(value: &[u8]) -> bool {
+  let mut quote = None;
+  let mut escaped = false;
+  for &c in value {
+    if escaped {
+      escaped = false;
+      continue;
+    };
+    match (c, quote) {
+      (b'\\', _) => escaped = true,
+      (b'"' | b'\'', None) => quote = Some(c),
+      (c, Some(q)) if c == q => quote = None,
+      _ => {}
+    };
+  }
+  quote.is_none() && !escaped
+}
+
+// Removes whitespace around a `style` attribute value and its trailing semicolons, which is always safe, unlike minifying it, as long as they aren't escaped or inside an unterminated string.
+fn trim_style_attr(value: &[u8]) -> &[u8] {
+  let mut value = trimmed(value);
+  while let Some(rest) = value.strip_suffix(b";") {
+    if !ends_outside_css_string(rest) {
+      break;
+    };
+    value = trimmed(rest);
+  }
+  value
+}
+
+// Returns the minified `style` attribute value, or `None` if it can't be minified safely or the `css` feature is disabled. Old IE `filter: progid:...` and `expression(...)` values aren't valid CSS and must be kept byte for byte, and minified output must still parse as a declaration list.
+#[cfg(feature = "css")]
+fn minify_style_attr(value: &[u8]) -> Option<Vec<u8>> {
+  let is_ie_specific = value.windows(7).any(|w| w.eq_ignore_ascii_case(b"progid:"))
+    || value
+      .windows(11)
+      .any(|w| w.eq_ignore_ascii_case(b"expression("));
+  if is_ie_specific {
+    return None;
+  };
+  catch_minifier_panic(|| {
+    // TODO Collect error as warning.
+    let value = from_utf8(value).ok()?;
+    let mut sty = StyleAttribute::parse(value, ParserOptions::default()).ok()?;
+    sty.minify(MinifyOptions::default());
+    let mut popt = PrinterOptions::default();
+    popt.minify = true;
+    let min = sty.to_css(popt).ok()?.code;
+    StyleAttribute::parse(&min, ParserOptions::default()).ok()?;
+    Some(min.into_bytes())
+  })
+}
+
+#[cfg(not(feature = "css"))]
+fn minify_style_attr(_value: &[u8]) -> Option<Vec<u8>> {
+  None
+}
+
+// Minifies HTML in an attribute value like `Cfg::html_attributes` as content at the top level. Passes that only apply to whole documents, like `remove_unreferenced_ids` and `inject_charset_meta`, aren't run, as the HTML will be inserted into another document, which may reference its IDs.
+fn minify_html_attr(cfg: &Cfg, value: &[u8]) -> Vec<u8> {
+  let mut code = Code::new_with_opts(value, ParseOpts::from_cfg(cfg));
+  let nodes = parse_content(&mut code, Namespace::Html, EMPTY_SLICE, EMPTY_SLICE).children;
+  let mut out = Vec::with_capacity(value.len());
+  minify_content(
+    cfg,
+    &mut out,
+    &mut MinifyStats::default(),
+    Namespace::Html,
+    false,
+    &mut Vec::new(),
+    EMPTY_SLICE,
+    nodes,
+  );
+  out
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn minify_attr(
   cfg: &Cfg,
+  stats: &mut MinifyStats,
   ns: Namespace,
   tag: &[u8],
   // True if element is <meta> and has an attribute `name` equal to `viewport`.
   is_meta_viewport: bool,
+  // True if element is <meta> and has an attribute `http-equiv` equal to `refresh`.
+  is_meta_refresh: bool,
   name: &[u8],
   mut value_raw: Vec<u8>,
 ) -> AttrMinified {
-  let attr_cfg = ATTRS.get(ns, tag, name);
-
-  let do_not_omit = cfg.keep_input_type_text_attr
-    && tag == b"input"
-    && name == b"type"
-    && value_raw.eq_ignore_ascii_case(b"text");
+  // Framework binding values are expressions that the framework may not parse the same way if unquoted or if whitespace is changed.
+  if is_framework_binding_attr(cfg, name) {
+    if value_raw.is_empty() {
+      return AttrMinified::NoValue;
+    };
+    return AttrMinified::Value(encode_value(cfg, &value_raw, false));
+  };
 
-  let should_collapse = attr_cfg.filter(|attr| attr.collapse).is_some();
-  let should_trim = attr_cfg.filter(|attr| attr.trim).is_some();
+  let attr_cfg = ATTRS.get(ns, tag, name);
+  let len_before = value_raw.len();
+
+  let is_preserved_microdata = is_preserved_microdata_attr(cfg, ns, name);
+  let do_not_omit = is_preserved_microdata
+    || (cfg.keep_input_type_text_attr
+      && tag == b"input"
+      && name == b"type"
+      && value_raw.eq_ignore_ascii_case(b"text"));
+
+  // Browsers strip whitespace around URLs, and split URL lists on whitespace.
+  let is_url = ns == Namespace::Html && is_url_attr(tag, name);
+  let is_url_list = ns == Namespace::Html && is_url_list_attr(tag, name);
+  // Microdata item IDs and types are also URLs, but identify things rather than being fetched, so only their whitespace is minified and they're never normalized.
+  let is_microdata_url =
+    ns == Namespace::Html && !is_preserved_microdata && matches!(name, b"itemid" | b"itemtype");
+  let should_collapse = is_url_list
+    || (is_microdata_url && name == b"itemtype")
+    || attr_cfg.filter(|attr| attr.collapse).is_some();
+  let should_trim =
+    is_url || is_url_list || is_microdata_url || attr_cfg.filter(|attr| attr.trim).is_some();
   let should_lowercase = attr_cfg.filter(|attr| attr.case_insensitive).is_some();
-  let is_boolean = attr_cfg.filter(|attr| attr.boolean).is_some();
+  // `hidden=until-found` is a different state to `hidden`, so its value must be kept.
+  let is_boolean = attr_cfg.filter(|attr| attr.boolean).is_some()
+    && !(name == b"hidden" && trimmed(&value_raw).eq_ignore_ascii_case(b"until-found"));
   // An attribute can have both redundant_if_empty and default_value, which means it has two default values: "" and default_value.
   let redundant_if_empty = attr_cfg.filter(|attr| attr.redundant_if_empty).is_some();
   let default_value = attr_cfg.and_then(|attr| attr.default_value);
 
   if is_meta_viewport {
     remove_all_whitespace(&mut value_raw);
+    if cfg.normalize_meta_viewport && name == b"content" {
+      value_raw = normalize_viewport(&value_raw);
+    };
+  } else if is_meta_refresh && name == b"content" {
+    if let Some(v) = normalize_refresh(&value_raw) {
+      value_raw = v;
+    };
   } else {
     // Trim before checking is_boolean as the entire attribute could be redundant post-minification.
     if should_trim {
@@ -413,27 +683,33 @@ pub fn minify_attr(
     };
   };
 
-  if name == b"style" && cfg.minify_css {
-    let result = match StyleAttribute::parse(
-      from_utf8(&value_raw).expect("`style` attribute value contains non-UTF-8"),
-      ParserOptions::default(),
-    ) {
-      Ok(mut sty) => {
-        sty.minify(MinifyOptions::default());
-        let mut popt = PrinterOptions::default();
-        popt.minify = true;
-        match sty.to_css(popt) {
-          Ok(out) => Some(out.code),
-          // TODO Collect error as warning.
-          Err(_err) => None,
-        }
-      }
-      // TODO Collect error as warning.
-      Err(_err) => None,
+  if ns == Namespace::Html && cfg.html_attributes.iter().any(|a| a.as_slice() == name) {
+    let min = minify_html_attr(cfg, &value_raw);
+    if min.len() < value_raw.len() {
+      value_raw = min;
     };
-    if let Some(min) = result {
-      value_raw = min.into_bytes();
+  };
+
+  if cfg.normalize_urls && is_url {
+    if let Some(url) = normalize_url_with_cfg(cfg, &value_raw) {
+      value_raw = url;
     };
+  };
+  if cfg.normalize_urls && is_url_list {
+    value_raw = value_raw
+      .split(|&c| c == b' ')
+      .map(|url| normalize_url_with_cfg(cfg, url).unwrap_or_else(|| url.to_vec()))
+      .collect::<Vec<_>>()
+      .join(&b' ');
+  };
+
+  if name == b"style" && cfg.minify_css {
+    let min = (!cfg.skip_style_attr_minification)
+      .then(|| minify_style_attr(&value_raw))
+      .flatten()
+      .unwrap_or_else(|| trim_style_attr(&value_raw).to_vec());
+    stats.css_bytes_saved += value_raw.len().saturating_sub(min.len());
+    value_raw = min;
   }
 
   // Make lowercase before checking against default value or JAVASCRIPT_MIME_TYPES.
@@ -444,9 +720,15 @@ pub fn minify_attr(
   if !do_not_omit
     && ((value_raw.is_empty() && redundant_if_empty)
       || default_value.filter(|dv| dv == &value_raw).is_some()
+      || (cfg.minify_svg_default_attributes
+        && ns == Namespace::Svg
+        && svg_attr_default(tag, name)
+          .filter(|dv| *dv == trimmed(&value_raw))
+          .is_some())
       || (tag == b"script"
         && name == b"type"
-        && JAVASCRIPT_MIME_TYPES.contains(value_raw.as_slice())))
+        && (JAVASCRIPT_MIME_TYPES.contains(value_raw.as_slice())
+          || (cfg.remove_type_module_attribute && value_raw.eq_ignore_ascii_case(b"module")))))
   {
     return AttrMinified::Redundant;
   };
@@ -455,8 +737,24 @@ pub fn minify_attr(
     return AttrMinified::NoValue;
   };
 
+  if value_raw.len() < len_before {
+    stats.attributes_minified += 1;
+  };
+
+  AttrMinified::Value(encode_value(
+    cfg,
+    &value_raw,
+    !cfg.never_unquote_attributes.contains(name),
+  ))
+}
+
+// Encodes a non-empty attribute value in whichever of double quotes, single quotes, or unquoted (if `allow_unquoted`) is shortest.
+pub fn encode_value(cfg: &Cfg, value: &[u8], allow_unquoted: bool) -> AttrMinifiedValue {
   let must_end_with_semicolon = !cfg.allow_optimal_entities;
-  let encoded = encode_entities(&value_raw, true, must_end_with_semicolon);
+  let encoded = encode_for_output(
+    encode_entities(value, true, must_end_with_semicolon),
+    cfg.output_encoding,
+  );
 
   // When lengths are equal, prefer double quotes to all and single quotes to unquoted.
   let mut min = encode_using_double_quotes(&encoded, must_end_with_semicolon);
@@ -464,13 +762,15 @@ pub fn minify_attr(
   if sq.len() < min.len() {
     min = sq;
   };
-  let uq = encode_unquoted(
-    &encoded,
-    must_end_with_semicolon,
-    !cfg.allow_noncompliant_unquoted_attribute_values,
-  );
-  if uq.len() < min.len() {
-    min = uq;
+  if allow_unquoted {
+    let uq = encode_unquoted(
+      &encoded,
+      must_end_with_semicolon,
+      !cfg.allow_noncompliant_unquoted_attribute_values,
+    );
+    if uq.len() < min.len() {
+      min = uq;
+    };
   };
-  AttrMinified::Value(min)
+  min
 }
@@ -1,7 +1,16 @@
 use crate::cfg::Cfg;
+use alloc::vec::Vec;
+use minify_html_common::spec::tag::ns::Namespace;
 
-pub fn minify_bang(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8], ended: bool) {
-  if !cfg.remove_bangs {
+// CDATA sections in foreign content contain text, so are never removed.
+pub fn is_kept_bang(cfg: &Cfg, ns: Namespace, code: &[u8]) -> bool {
+  !cfg.remove_bangs
+    || (ns != Namespace::Html && code.starts_with(b"[CDATA["))
+    || cfg.keep_bangs_matching.iter().any(|p| code.starts_with(p))
+}
+
+pub fn minify_bang(cfg: &Cfg, out: &mut Vec<u8>, ns: Namespace, code: &[u8], ended: bool) {
+  if is_kept_bang(cfg, ns, code) {
     out.extend_from_slice(b"<!");
     out.extend_from_slice(code);
     if ended {
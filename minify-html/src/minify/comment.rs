@@ -1,12 +1,105 @@
 use crate::cfg::Cfg;
+use crate::entity::decode::decode_entities;
+use crate::parse::script::is_html5_shiv_src;
+use crate::stats::MinifyStats;
+use crate::stream::tokenize;
+use crate::stream::Event;
+use alloc::vec::Vec;
+use minify_html_common::gen::codepoints::WHITESPACE;
+use minify_html_common::whitespace::collapse_whitespace;
+use minify_html_common::whitespace::is_all_whitespace;
+use minify_html_common::whitespace::trimmed;
 
-pub fn minify_comment(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8], ended: bool) {
+// Knockout.js virtual elements are comments like `<!-- ko if: a -->` and `<!-- /ko -->`.
+fn is_ko_comment(code: &[u8]) -> bool {
+  let code = trimmed(code);
+  code == b"/ko"
+    || code
+      .strip_prefix(b"ko")
+      .and_then(|rest| rest.first())
+      .filter(|&&c| WHITESPACE[c])
+      .is_some()
+}
+
+// License comments start with `!` or contain `@license` or `@preserve`. `code` is the comment's contents, such as what's between `/*` and `*/` in JS and CSS.
+pub fn is_license_comment(code: &[u8]) -> bool {
+  let contains = |needle: &[u8]| code.windows(needle.len()).any(|w| w == needle);
+  code.starts_with(b"!") || contains(b"@license") || contains(b"@preserve")
+}
+
+// Comments whose whitespace may be significant: SSI directives, conditional comments like `<!--[if IE]>` and `<![endif]-->`, and license comments like `<!--! ... -->`.
+fn is_special_comment(code: &[u8]) -> bool {
+  code.starts_with(b"#")
+    || code.starts_with(b"[if")
+    || code.ends_with(b"<![endif]")
+    || is_license_comment(code)
+}
+
+// A conditional comment like `<!--[if lt IE 9]><script src=html5shiv.js></script><![endif]-->` that only contains scripts matched by `is_html5_shiv_src` and whitespace.
+fn is_html5_shiv_conditional_comment(code: &[u8]) -> bool {
+  let inner = match code
+    .strip_prefix(b"[if")
+    .and_then(|c| c.strip_suffix(b"<![endif]"))
+    .and_then(|c| c.iter().position(|&b| b == b']').map(|i| &c[i + 1..]))
+    .and_then(|c| c.strip_prefix(b">"))
+  {
+    Some(inner) => inner,
+    None => return false,
+  };
+  let mut has_shiv = false;
+  for event in tokenize(inner) {
+    match event {
+      Event::StartTag {
+        name, attributes, ..
+      } if name.eq_ignore_ascii_case(b"script") => {
+        let is_shiv = attributes.iter().any(|a| {
+          a.name.eq_ignore_ascii_case(b"src")
+            && a
+              .value
+              .is_some_and(|v| is_html5_shiv_src(trimmed(&decode_entities(v, true))))
+        });
+        if !is_shiv {
+          return false;
+        };
+        has_shiv = true;
+      }
+      Event::EndTag { name } if name.eq_ignore_ascii_case(b"script") => {}
+      Event::RawText { code } | Event::Text { code } if is_all_whitespace(code) => {}
+      _ => return false,
+    };
+  }
+  has_shiv
+}
+
+pub fn is_kept_comment(cfg: &Cfg, code: &[u8]) -> bool {
   let is_ssi = code.starts_with(b"#");
-  if cfg.keep_comments || (is_ssi && cfg.keep_ssi_comments) {
+  (cfg.keep_comments
+    || (is_ssi && cfg.keep_ssi_comments)
+    || (cfg.keep_balancing_comments && trimmed(code).starts_with(b"/"))
+    || (cfg.preserve_ko_comments && is_ko_comment(code)))
+    && !(cfg.remove_html5_shiv && is_html5_shiv_conditional_comment(code))
+}
+
+pub fn minify_comment(
+  cfg: &Cfg,
+  out: &mut Vec<u8>,
+  stats: &mut MinifyStats,
+  code: &[u8],
+  ended: bool,
+) {
+  if is_kept_comment(cfg, code) {
     out.extend_from_slice(b"<!--");
-    out.extend_from_slice(code);
+    if cfg.minify_kept_comment_whitespace && !is_special_comment(code) {
+      let mut code = code.to_vec();
+      collapse_whitespace(&mut code);
+      out.extend_from_slice(&code);
+    } else {
+      out.extend_from_slice(code);
+    };
     if ended {
       out.extend_from_slice(b"-->");
     };
+  } else {
+    stats.comments_removed += 1;
   };
 }
@@ -2,18 +2,32 @@ use super::rcdata::minify_rcdata;
 use crate::ast::NodeData;
 use crate::ast::ScriptOrStyleLang;
 use crate::cfg::Cfg;
+use crate::cfg::WhitespaceMode;
 use crate::entity::encode::encode_entities;
+use crate::entity::encode::encode_for_output;
+use crate::minify::bang::is_kept_bang;
 use crate::minify::bang::minify_bang;
+use crate::minify::comment::is_kept_comment;
 use crate::minify::comment::minify_comment;
 use crate::minify::css::minify_css;
 use crate::minify::doctype::minify_doctype;
 use crate::minify::element::minify_element;
+use crate::minify::instruction::is_kept_instruction;
 use crate::minify::instruction::minify_instruction;
 use crate::minify::js::minify_js;
+use crate::minify::unwrap::unwrap_redundant_wrappers;
+use crate::stats::MinifyStats;
+use crate::visitor::TextContext;
 use aho_corasick::AhoCorasickBuilder;
 use aho_corasick::AhoCorasickKind;
 use aho_corasick::MatchKind;
+use alloc::vec;
+use alloc::vec::Vec;
+use memchr::memchr;
+use memchr::memmem::find;
+use memchr::memrchr;
 use minify_html_common::gen::codepoints::TAG_NAME_CHAR;
+use minify_html_common::lazy::Lazy;
 use minify_html_common::pattern::Replacer;
 use minify_html_common::spec::tag::ns::Namespace;
 use minify_html_common::spec::tag::whitespace::get_whitespace_minification_for_tag;
@@ -22,8 +36,10 @@ use minify_html_common::whitespace::collapse_whitespace;
 use minify_html_common::whitespace::is_all_whitespace;
 use minify_html_common::whitespace::left_trim;
 use minify_html_common::whitespace::right_trim;
-use once_cell::sync::Lazy;
+use minify_html_common::whitespace::trimmed;
 
+// Matchers are built from static patterns, so building them never fails.
+#[allow(clippy::unwrap_used)]
 fn build_optimal_chevron_replacer() -> Replacer {
   let mut patterns = Vec::<Vec<u8>>::new();
   let mut replacements = Vec::<Vec<u8>>::new();
@@ -47,6 +63,7 @@ fn build_optimal_chevron_replacer() -> Replacer {
   )
 }
 
+#[allow(clippy::unwrap_used)]
 fn build_whatwg_chevron_replacer() -> Replacer {
   Replacer::new(
     AhoCorasickBuilder::new()
@@ -57,25 +74,187 @@ fn build_whatwg_chevron_replacer() -> Replacer {
   )
 }
 
-static OPTIMAL_CHEVRON_REPLACER: Lazy<Replacer> = Lazy::new(|| build_optimal_chevron_replacer());
-static WHATWG_CHEVRON_REPLACER: Lazy<Replacer> = Lazy::new(|| build_whatwg_chevron_replacer());
+static OPTIMAL_CHEVRON_REPLACER: Lazy<Replacer> = Lazy::new(build_optimal_chevron_replacer);
+static WHATWG_CHEVRON_REPLACER: Lazy<Replacer> = Lazy::new(build_whatwg_chevron_replacer);
 
+// Returns the content inside a `<!-- ... -->` wrapper around an entire script or style, if there is one. In JS, the rest of the line after `<!--` is a comment, and the line with `-->` must be commented out with `//`. In CSS, `<!--` and `-->` are ignored.
+fn strip_legacy_comment(lang: ScriptOrStyleLang, code: &[u8]) -> Option<&[u8]> {
+  let inner = trimmed(code).strip_prefix(b"<!--")?.strip_suffix(b"-->")?;
+  if lang == ScriptOrStyleLang::CSS {
+    return Some(trimmed(inner));
+  };
+  let start = memchr(b'\n', inner)? + 1;
+  let inner = &inner[start..];
+  let end = memrchr(b'\n', inner).unwrap_or(0);
+  if !trimmed(&inner[end..]).starts_with(b"//") {
+    return None;
+  };
+  Some(trimmed(&inner[..end]))
+}
+
+// Replaces soft hyphens (U+00AD) with `&shy;`. They're invisible, so the raw character is easily missed or mangled by tools that process the output.
+fn encode_soft_hyphens(value: Vec<u8>) -> Vec<u8> {
+  let shy = "\u{AD}".as_bytes();
+  if find(&value, shy).is_none() {
+    return value;
+  };
+  let mut out = Vec::with_capacity(value.len() + 8);
+  let mut rest = value.as_slice();
+  while let Some(i) = find(rest, shy) {
+    out.extend_from_slice(&rest[..i]);
+    out.extend_from_slice(b"&shy;");
+    rest = &rest[i + shy.len()..];
+  }
+  out.extend_from_slice(rest);
+  out
+}
+
+pub fn encode_text(cfg: &Cfg, value: &[u8]) -> Vec<u8> {
+  let min = encode_for_output(
+    encode_entities(value, false, !cfg.allow_optimal_entities),
+    cfg.output_encoding,
+  );
+  let min = if cfg.allow_optimal_entities {
+    OPTIMAL_CHEVRON_REPLACER.replace_all(&min)
+  } else {
+    WHATWG_CHEVRON_REPLACER.replace_all(&min)
+  };
+  if cfg.keep_shy_entity {
+    encode_soft_hyphens(min)
+  } else {
+    min
+  }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn minify_content(
   cfg: &Cfg,
   out: &mut Vec<u8>,
+  stats: &mut MinifyStats,
   ns: Namespace,
   descendant_of_pre: bool,
+  // Tag names of all ancestor elements, only tracked if there's a text visitor.
+  ancestors: &mut Vec<Vec<u8>>,
   // Use empty slice if none.
   parent: &[u8],
-  mut nodes: Vec<NodeData>,
+  nodes: Vec<NodeData>,
 ) {
+  minify_content_before_unknown(
+    cfg,
+    out,
+    stats,
+    ns,
+    descendant_of_pre,
+    ancestors,
+    parent,
+    nodes,
+    false,
+  );
+}
+
+// Returns how to minify whitespace in the content of `parent`, after applying `Cfg::whitespace_mode`.
+pub fn whitespace_minification(
+  cfg: &Cfg,
+  ns: Namespace,
+  parent: &[u8],
+  descendant_of_pre: bool,
+) -> WhitespaceMinification {
   let &WhitespaceMinification {
     collapse,
     destroy_whole,
     trim,
   } = get_whitespace_minification_for_tag(ns, parent, descendant_of_pre);
+  // Whitespace at the ends of option and button text could be displayed, e.g. in a dropdown with `white-space: pre`.
+  let trim = trim
+    && (cfg.trim_form_control_text
+      || ns != Namespace::Html
+      || !matches!(parent, b"option" | b"button"));
+  match cfg.whitespace_mode {
+    WhitespaceMode::Default => WhitespaceMinification {
+      collapse,
+      destroy_whole,
+      trim,
+    },
+    // Only elements whose content is trimmed aren't inline.
+    WhitespaceMode::Aggressive => WhitespaceMinification {
+      collapse,
+      destroy_whole: destroy_whole || trim,
+      trim,
+    },
+    WhitespaceMode::Preserve => WhitespaceMinification {
+      collapse: false,
+      destroy_whole: false,
+      trim: false,
+    },
+  }
+}
 
-  // TODO Document or fix: even though bangs/comments/etc. don't affect layout, we don't collapse/destroy-whole/trim combined text nodes across bangs/comments/etc., as that's too complex and is ambiguous about which nodes should whitespace be deleted from.
+// Drops comments, bangs, and instructions that won't be output, and merges the text around them, so that whitespace is collapsed and trimmed as if the text was contiguous, e.g. `a <!-- b --> c` becomes `a c`.
+fn remove_hidden_markup(
+  cfg: &Cfg,
+  stats: &mut MinifyStats,
+  ns: Namespace,
+  nodes: Vec<NodeData>,
+) -> Vec<NodeData> {
+  let mut out = Vec::<NodeData>::with_capacity(nodes.len());
+  for n in nodes {
+    match &n {
+      NodeData::Bang { code, .. } if !is_kept_bang(cfg, ns, code) => continue,
+      NodeData::Comment { code, .. } if !is_kept_comment(cfg, code) => {
+        stats.comments_removed += 1;
+        continue;
+      }
+      NodeData::Instruction { code, .. } if !is_kept_instruction(cfg, code) => continue,
+      _ => {}
+    };
+    match (out.last_mut(), n) {
+      (Some(NodeData::Text { value: prev, .. }), NodeData::Text { value, .. }) => {
+        prev.extend_from_slice(&value)
+      }
+      (_, n) => out.push(n),
+    };
+  }
+  out
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn minify_content_before_unknown(
+  cfg: &Cfg,
+  out: &mut Vec<u8>,
+  stats: &mut MinifyStats,
+  ns: Namespace,
+  descendant_of_pre: bool,
+  ancestors: &mut Vec<Vec<u8>>,
+  parent: &[u8],
+  mut nodes: Vec<NodeData>,
+  // If the content is a fragment that may be followed by other nodes when inserted into its parent, so the closing tag of its last element can't be omitted.
+  followed_by_unknown: bool,
+) {
+  let WhitespaceMinification {
+    collapse,
+    destroy_whole,
+    trim,
+  } = whitespace_minification(cfg, ns, parent, descendant_of_pre);
+
+  if cfg.unwrap_redundant_wrappers && ns == Namespace::Html && !descendant_of_pre {
+    unwrap_redundant_wrappers(&mut nodes);
+  };
+
+  let mut nodes = remove_hidden_markup(cfg, stats, ns, nodes);
+
+  if let Some(visitor) = &cfg.text_visitor {
+    let ctx = TextContext {
+      ancestors: ancestors.as_slice(),
+      whitespace_sensitive: !collapse && !destroy_whole && !trim,
+    };
+    for n in nodes.iter_mut() {
+      if let NodeData::Text { value, .. } = n {
+        visitor(&ctx, value);
+      };
+    }
+  };
+
+  // Whitespace isn't collapsed, destroyed, or trimmed across kept comments, bangs, and instructions, as it's ambiguous which of the text nodes around them it should be deleted from.
   let mut found_first_text_or_elem = false;
   let mut index_of_last_nonempty_text_or_elem: isize = -1;
   let mut index_of_last_text_or_elem: isize = -1;
@@ -98,7 +277,8 @@ pub fn minify_content(
         index_of_last_nonempty_text_or_elem = i as isize;
         index_of_last_text_or_elem = i as isize;
       }
-      NodeData::Text { value } => {
+      NodeData::Text { value, .. } => {
+        let len_before = value.len();
         if !found_first_text_or_elem {
           // This is the first element or text node, and it's a text node.
           found_first_text_or_elem = true;
@@ -114,6 +294,7 @@ pub fn minify_content(
         } else if collapse {
           collapse_whitespace(value);
         };
+        stats.whitespace_bytes_removed += len_before - value.len();
         // Set AFTER processing.
         index_of_last_text_or_elem = i as isize;
         if !value.is_empty() {
@@ -124,16 +305,19 @@ pub fn minify_content(
     };
   }
   if trim && index_of_last_text_or_elem > -1 {
-    if let NodeData::Text { value } = nodes.get_mut(index_of_last_text_or_elem as usize).unwrap() {
+    if let Some(NodeData::Text { value, .. }) = nodes.get_mut(index_of_last_text_or_elem as usize) {
+      let len_before = value.len();
       right_trim(value);
+      stats.whitespace_bytes_removed += len_before - value.len();
     };
   }
 
+  let mut previous_sibling_element_name = Vec::new();
   for (i, c) in nodes.into_iter().enumerate() {
     match c {
-      NodeData::Bang { code, ended } => minify_bang(cfg, out, &code, ended),
-      NodeData::Comment { code, ended } => minify_comment(cfg, out, &code, ended),
-      NodeData::Doctype { legacy, ended } => minify_doctype(cfg, out, &legacy, ended),
+      NodeData::Bang { code, ended, .. } => minify_bang(cfg, out, ns, &code, ended),
+      NodeData::Comment { code, ended, .. } => minify_comment(cfg, out, stats, &code, ended),
+      NodeData::Doctype { legacy, ended, .. } => minify_doctype(cfg, out, &legacy, ended),
       NodeData::Element {
         attributes,
         children,
@@ -141,37 +325,45 @@ pub fn minify_content(
         name,
         namespace: child_ns,
         next_sibling_element_name,
-      } => minify_element(
-        cfg,
-        out,
-        descendant_of_pre,
-        child_ns,
-        parent,
-        &next_sibling_element_name,
-        (i as isize) == index_of_last_nonempty_text_or_elem,
-        &name,
-        attributes,
-        closing_tag,
-        children,
-      ),
-      NodeData::Instruction { code, ended } => minify_instruction(cfg, out, &code, ended),
-      NodeData::RcdataContent { typ, text } => minify_rcdata(cfg, out, typ, &text),
-      NodeData::ScriptOrStyleContent { code, lang } => match lang {
-        ScriptOrStyleLang::CSS => minify_css(cfg, out, &code),
-        ScriptOrStyleLang::Data => out.extend_from_slice(&code),
-        ScriptOrStyleLang::JS => minify_js(cfg, minify_js::TopLevelMode::Global, out, &code),
-        ScriptOrStyleLang::JSModule => minify_js(cfg, minify_js::TopLevelMode::Module, out, &code),
-      },
-      NodeData::Text { value } => {
-        let min = encode_entities(&value, false, !cfg.allow_optimal_entities);
-        let min = if cfg.allow_optimal_entities {
-          OPTIMAL_CHEVRON_REPLACER.replace_all(&min)
-        } else {
-          WHATWG_CHEVRON_REPLACER.replace_all(&min)
+        ..
+      } => {
+        minify_element(
+          cfg,
+          out,
+          stats,
+          descendant_of_pre,
+          child_ns,
+          ancestors,
+          parent,
+          &previous_sibling_element_name,
+          &next_sibling_element_name,
+          !followed_by_unknown && (i as isize) == index_of_last_nonempty_text_or_elem,
+          &name,
+          attributes,
+          closing_tag,
+          children,
+        );
+        previous_sibling_element_name = name;
+      }
+      NodeData::Instruction { code, ended, .. } => minify_instruction(cfg, out, &code, ended),
+      NodeData::RcdataContent { typ, text, .. } => minify_rcdata(cfg, out, typ, &text),
+      NodeData::ScriptOrStyleContent { mut code, lang, .. } => {
+        if (cfg.strip_legacy_script_comments || cfg.strip_code_comments)
+          && lang != ScriptOrStyleLang::Data
+        {
+          if let Some(stripped) = strip_legacy_comment(lang, &code) {
+            code = stripped.to_vec();
+          };
         };
-        out.extend_from_slice(&min);
+        match lang {
+          ScriptOrStyleLang::CSS => minify_css(cfg, out, stats, &code),
+          ScriptOrStyleLang::Data => out.extend_from_slice(&code),
+          ScriptOrStyleLang::JS => minify_js(cfg, false, out, stats, &code),
+          ScriptOrStyleLang::JSModule => minify_js(cfg, true, out, stats, &code),
+        }
       }
-      NodeData::Opaque { raw_source } => out.extend_from_slice(&raw_source),
+      NodeData::Text { value, .. } => out.extend_from_slice(&encode_text(cfg, &value)),
+      NodeData::Opaque { raw_source, .. } => out.extend_from_slice(&raw_source),
     };
   }
 }
@@ -1,19 +1,126 @@
 use crate::cfg::Cfg;
+#[cfg(feature = "css")]
+use crate::minify::catch_minifier_panic;
+use crate::minify::comment::is_license_comment;
+use crate::stats::MinifyStats;
+use alloc::string::String;
+use alloc::vec::Vec;
+#[cfg(feature = "css")]
+use core::str::from_utf8;
+#[cfg(feature = "css")]
 use lightningcss::stylesheet::MinifyOptions;
+#[cfg(feature = "css")]
 use lightningcss::stylesheet::ParserOptions;
+#[cfg(feature = "css")]
 use lightningcss::stylesheet::PrinterOptions;
+#[cfg(feature = "css")]
 use lightningcss::stylesheet::StyleSheet;
+use memchr::memmem::find;
 use minify_html_common::whitespace::trimmed;
-use std::str::from_utf8;
 
-pub fn minify_css(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8]) {
-  if cfg.minify_css {
-    let mut popt = PrinterOptions::default();
-    popt.minify = true;
-    let result = match StyleSheet::parse(
-      from_utf8(code).expect("<style> content contains non-UTF-8"),
-      ParserOptions::default(),
-    ) {
+// `@import` rules are ignored by browsers unless they come before all other rules (except `@charset` and `@layer` statements), so reject any minified output that doesn't preserve them in the same order at the top.
+fn imports_preserved(src: &[u8], min: &str) -> bool {
+  let imports_in = src
+    .windows(7)
+    .filter(|w| w.eq_ignore_ascii_case(b"@import"))
+    .count();
+  let imports_out: Vec<usize> = min.match_indices("@import").map(|(i, _)| i).collect();
+  if imports_in != imports_out.len() {
+    return false;
+  };
+  match imports_out.last() {
+    Some(&last) => !min[..last].contains('{'),
+    None => true,
+  }
+}
+
+fn is_css_identifier_char(c: u8) -> bool {
+  c.is_ascii_alphanumeric() || c == b'-' || c == b'_' || c == b'\\' || c >= 0x80
+}
+
+// Removing a comment next to one of these can't merge or split tokens, unlike e.g. `html>/**/body`, where the comment is part of a hack.
+fn is_css_separator(c: u8) -> bool {
+  c.is_ascii_whitespace() || matches!(c, b'{' | b'}' | b';' | b',')
+}
+
+// Returns the index after the string literal starting at `start`.
+fn skip_css_string(code: &[u8], start: usize) -> Option<usize> {
+  let quote = code[start];
+  let mut i = start + 1;
+  while i < code.len() {
+    match code[i] {
+      b'\\' => i += 2,
+      c if c == quote => return Some(i + 1),
+      b'\n' | b'\r' | b'\x0c' => return None,
+      _ => i += 1,
+    };
+  }
+  None
+}
+
+// Returns the index after an unquoted `url(...)` whose contents start at `start`, or `start` if the URL is quoted.
+fn skip_css_url(code: &[u8], start: usize) -> Option<usize> {
+  let mut i = start;
+  while code.get(i).is_some_and(|c| c.is_ascii_whitespace()) {
+    i += 1;
+  }
+  if matches!(code.get(i), Some(b'"' | b'\'')) {
+    return Some(start);
+  };
+  while i < code.len() {
+    match code[i] {
+      b'\\' => i += 2,
+      b')' => return Some(i + 1),
+      _ => i += 1,
+    };
+  }
+  None
+}
+
+/// Removes comments from CSS code without otherwise changing it. License comments like `/*! ... */` and source map comments like `/*# sourceMappingURL=... */` are kept, as are comments that aren't next to whitespace or a delimiter like `;`, as removing them could change how the code is tokenized. Returns `None` if the code is malformed, such as when it has an unterminated string or comment.
+pub fn strip_css_comments(code: &[u8]) -> Option<Vec<u8>> {
+  let mut out = Vec::with_capacity(code.len());
+  let mut i = 0;
+  while i < code.len() {
+    let end = match code[i] {
+      b'"' | b'\'' => skip_css_string(code, i)?,
+      b'u' | b'U'
+        if code.len() >= i + 4
+          && code[i..i + 4].eq_ignore_ascii_case(b"url(")
+          && !out.last().is_some_and(|&c| is_css_identifier_char(c)) =>
+      {
+        skip_css_url(code, i + 4)?
+      }
+      b'/' if code.get(i + 1) == Some(&b'*') => {
+        let end = find(&code[i + 2..], b"*/")? + i + 4;
+        let comment = &code[i + 2..end - 2];
+        let is_removable = !is_license_comment(comment)
+          && !comment.starts_with(b"#")
+          && (out.last().is_none_or(|&c| is_css_separator(c))
+            || code.get(end).is_none_or(|&c| is_css_separator(c)));
+        if is_removable {
+          i = end;
+          continue;
+        };
+        end
+      }
+      _ => i + 1,
+    };
+    out.extend_from_slice(&code[i..end]);
+    i = end;
+  }
+  Some(out)
+}
+
+// Returns the minified stylesheet, or `None` if it can't be parsed or the `css` feature is disabled.
+#[cfg(feature = "css")]
+fn try_minify(code: &[u8]) -> Option<String> {
+  let mut popt = PrinterOptions::default();
+  popt.minify = true;
+  catch_minifier_panic(|| {
+    // TODO Collect error as warning.
+    let code = from_utf8(code).ok()?;
+    match StyleSheet::parse(code, ParserOptions::default()) {
       Ok(mut sty) => match sty.minify(MinifyOptions::default()) {
         Ok(()) => match sty.to_css(popt) {
           Ok(out) => Some(out.code),
@@ -25,13 +132,38 @@ pub fn minify_css(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8]) {
       },
       // TODO Collect error as warning.
       Err(_err) => None,
-    };
-    if let Some(min) = result {
+    }
+  })
+}
+
+#[cfg(not(feature = "css"))]
+fn try_minify(_code: &[u8]) -> Option<String> {
+  None
+}
+
+pub fn minify_css(cfg: &Cfg, out: &mut Vec<u8>, stats: &mut MinifyStats, code: &[u8]) {
+  if cfg.minify_css {
+    if let Some(min) = try_minify(code).filter(|min| imports_preserved(code, min)) {
       if min.len() < code.len() {
+        stats.css_bytes_saved += code.len() - min.len();
         out.extend_from_slice(min.as_bytes());
         return;
       };
     };
   }
+  if cfg.strip_code_comments {
+    if let Some(stripped) = strip_css_comments(code) {
+      out.extend_from_slice(trimmed(&stripped));
+      return;
+    };
+  }
   out.extend_from_slice(trimmed(code));
 }
+
+/// Minifies a standalone stylesheet, such as the contents of a `.css` file, with the same minifier used for `<style>` contents. As with `<style>` contents, `minify_css` must be enabled in `cfg`, otherwise the code is only trimmed. It's also only trimmed if it fails to parse.
+#[cfg(feature = "css")]
+pub fn minify_css_str(src: &[u8], cfg: &Cfg) -> Vec<u8> {
+  let mut out = Vec::new();
+  minify_css(cfg, &mut out, &mut MinifyStats::default(), src);
+  out
+}
@@ -1,4 +1,5 @@
 use crate::cfg::Cfg;
+use alloc::vec::Vec;
 
 pub fn minify_doctype(cfg: &Cfg, out: &mut Vec<u8>, legacy: &[u8], ended: bool) {
   out.extend_from_slice(b"<!doctype");
@@ -6,7 +7,7 @@ pub fn minify_doctype(cfg: &Cfg, out: &mut Vec<u8>, legacy: &[u8], ended: bool)
     out.push(b' ');
   }
   out.extend_from_slice(b"html");
-  if !legacy.is_empty() {
+  if !legacy.is_empty() && !cfg.normalize_doctype_to_html5 {
     out.push(b' ');
     out.extend_from_slice(legacy);
   };
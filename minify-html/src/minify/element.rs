@@ -2,32 +2,189 @@ use crate::ast::AttrVal;
 use crate::ast::ElementClosingTag;
 use crate::ast::NodeData;
 use crate::cfg::Cfg;
+use crate::cfg::VoidSelfClosingStyle;
+use crate::minify::attr::is_preserved_microdata_attr;
+use crate::minify::attr::is_removed_attr;
 use crate::minify::attr::minify_attr;
 use crate::minify::attr::AttrMinified;
+use crate::minify::attr::AttrMinifiedValue;
+use crate::minify::comment::is_kept_comment;
 use crate::minify::content::minify_content;
-use ahash::AHashMap;
+use crate::stats::MinifyStats;
+use crate::visitor::ElementView;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use minify_html_common::spec::tag::ns::namespace_for;
 use minify_html_common::spec::tag::ns::Namespace;
 use minify_html_common::spec::tag::omission::can_omit_as_before;
 use minify_html_common::spec::tag::omission::can_omit_as_last_node;
+use minify_html_common::whitespace::is_all_whitespace;
+use minify_html_common::whitespace::trimmed;
 
-#[allow(clippy::too_many_arguments)]
-pub fn minify_element(
+// A `<body>` opening tag may be omitted if the element is empty, or if the first thing inside it is not whitespace or a comment, except if it's a `<meta>`, `<noscript>`, `<link>`, `<script>`, `<style>`, or `<template>` element.
+// https://html.spec.whatwg.org/multipage/syntax.html#optional-tags.
+// `<title>`, `<base>`, `<basefont>`, `<bgsound>`, and `<noframes>` are also excluded, as without the opening tag the parser would move them into `<head>` too.
+fn can_omit_body_opening_tag(cfg: &Cfg, children: &[NodeData]) -> bool {
+  for c in children {
+    match c {
+      // Whitespace-only text is removed in `<body>`, and leading whitespace of the first text is trimmed.
+      NodeData::Text { value, .. } if is_all_whitespace(value) => continue,
+      NodeData::Text { .. } => return true,
+      NodeData::Comment { code, .. } if !is_kept_comment(cfg, code) => continue,
+      NodeData::Element { name, .. } => {
+        return !matches!(
+          name.as_slice(),
+          b"base"
+            | b"basefont"
+            | b"bgsound"
+            | b"link"
+            | b"meta"
+            | b"noframes"
+            | b"noscript"
+            | b"script"
+            | b"style"
+            | b"template"
+            | b"title"
+        )
+      }
+      _ => return false,
+    };
+  }
+  true
+}
+
+// The opening tags of `<tbody>` and `<colgroup>` can be omitted if their first child is a `<tr>` or `<col>` respectively, as the parser will insert them implicitly. However, if the previous sibling is a table section whose closing tag may have been omitted, the children would instead be parsed as part of that previous element.
+fn can_omit_table_section_opening_tag(
   cfg: &Cfg,
-  out: &mut Vec<u8>,
-  descendant_of_pre: bool,
+  previous_sibling_element_name: &[u8],
+  first_child_name: &[u8],
+  children: &[NodeData],
+) -> bool {
+  if matches!(
+    previous_sibling_element_name,
+    b"colgroup" | b"tbody" | b"tfoot" | b"thead"
+  ) {
+    return false;
+  };
+  for c in children {
+    match c {
+      // Whitespace-only text is removed in table sections.
+      NodeData::Text { value, .. } if is_all_whitespace(value) => continue,
+      NodeData::Comment { code, .. } if !is_kept_comment(cfg, code) => continue,
+      NodeData::Element { name, .. } => return name.as_slice() == first_child_name,
+      _ => return false,
+    };
+  }
+  false
+}
+
+// `async` takes precedence over `defer`, and module scripts are always deferred. Neither has an effect on inline classic scripts.
+// https://html.spec.whatwg.org/multipage/scripting.html#attr-script-async.
+fn remove_redundant_script_attrs(
+  cfg: &Cfg,
+  stats: &mut MinifyStats,
+  attributes: &mut HashMap<Vec<u8>, AttrVal>,
+) {
+  // A module script whose type will be removed becomes a classic script.
+  let is_module = !cfg.remove_type_module_attribute
+    && attributes
+      .get(b"type".as_ref())
+      .filter(|t| t.value.eq_ignore_ascii_case(b"module"))
+      .is_some();
+  let has_src = attributes.contains_key(b"src".as_ref());
+  let has_async = attributes.contains_key(b"async".as_ref());
+  let mut redundant: &[&[u8]] = &[];
+  if !is_module && !has_src {
+    redundant = &[b"async", b"defer"];
+  } else if is_module || has_async {
+    redundant = &[b"defer"];
+  };
+  for name in redundant {
+    if attributes.remove(*name).is_some() {
+      stats.attributes_removed += 1;
+    };
+  }
+}
+
+// Infers the destination of a preloaded resource from its URL's file extension, or None if it's unknown.
+// https://html.spec.whatwg.org/multipage/links.html#link-type-preload.
+fn preload_destination(href: &[u8]) -> Option<&'static [u8]> {
+  let path = href
+    .split(|&c| c == b'?' || c == b'#')
+    .next()
+    .unwrap_or(href);
+  let file = path.rsplit(|&c| c == b'/').next().unwrap_or(path);
+  let (dot, _) = file.iter().enumerate().rfind(|(_, &c)| c == b'.')?;
+  Some(match file[dot + 1..].to_ascii_lowercase().as_slice() {
+    b"js" | b"mjs" => b"script",
+    b"css" => b"style",
+    b"eot" | b"otf" | b"ttf" | b"woff" | b"woff2" => b"font",
+    b"avif" | b"bmp" | b"gif" | b"ico" | b"jpeg" | b"jpg" | b"png" | b"svg" | b"webp" => b"image",
+    b"vtt" => b"track",
+    _ => return None,
+  })
+}
+
+// A preload without an `as` attribute is fetched with a low priority and can't be matched with the request that uses it, so browsers fetch the resource twice.
+fn add_preload_destination(attributes: &mut HashMap<Vec<u8>, AttrVal>) {
+  let is_preload = attributes
+    .get(b"rel".as_ref())
+    .filter(|rel| {
+      rel
+        .value
+        .split(|c| c.is_ascii_whitespace())
+        .any(|t| t.eq_ignore_ascii_case(b"preload"))
+    })
+    .is_some();
+  if !is_preload || attributes.contains_key(b"as".as_ref()) {
+    return;
+  };
+  let dest = attributes
+    .get(b"href".as_ref())
+    .and_then(|href| preload_destination(trimmed(&href.value)));
+  if let Some(dest) = dest {
+    attributes.insert(b"as".to_vec(), AttrVal {
+      quote: None,
+      value: dest.to_vec(),
+    });
+  };
+}
+
+// Output quoted attributes, followed by unquoted, to optimise space omission between attributes.
+pub struct MinifiedAttrs {
+  quoted: Vec<(Vec<u8>, AttrMinifiedValue)>,
+  unquoted: Vec<(Vec<u8>, AttrMinified)>,
+}
+
+impl MinifiedAttrs {
+  pub fn is_empty(&self) -> bool {
+    self.quoted.is_empty() && self.unquoted.is_empty()
+  }
+}
+
+pub fn minify_attrs(
+  cfg: &Cfg,
+  stats: &mut MinifyStats,
   ns: Namespace,
-  // Use an empty slice if none.
-  parent: &[u8],
-  // Use an empty slice if the next element or text sibling node is not an element.
-  next_sibling_as_element_tag_name: &[u8],
-  // If the last node of the parent is an element and it's this one.
-  is_last_child_text_or_element_node: bool,
   tag_name: &[u8],
-  attributes: AHashMap<Vec<u8>, AttrVal>,
-  closing_tag: ElementClosingTag,
-  children: Vec<NodeData>,
-) {
-  // Output quoted attributes, followed by unquoted, to optimise space omission between attributes.
+  mut attributes: HashMap<Vec<u8>, AttrVal>,
+) -> MinifiedAttrs {
+  if let Some(visitor) = &cfg.element_visitor {
+    visitor(&mut ElementView {
+      tag_name,
+      namespace: ns,
+      attributes: &mut attributes,
+    });
+  };
+
+  if cfg.remove_redundant_script_attributes && ns == Namespace::Html && tag_name == b"script" {
+    remove_redundant_script_attrs(cfg, stats, &mut attributes);
+  };
+
+  if cfg.validate_preload_links && ns == Namespace::Html && tag_name == b"link" {
+    add_preload_destination(&mut attributes);
+  };
+
   let mut quoted = Vec::new();
   let mut unquoted = Vec::new();
 
@@ -36,16 +193,49 @@ pub fn minify_element(
       .get(b"name".as_ref())
       .filter(|a| a.value.eq_ignore_ascii_case(b"viewport"))
       .is_some();
+  let is_meta_refresh = tag_name == b"meta"
+    && attributes
+      .get(b"http-equiv".as_ref())
+      .filter(|a| trimmed(&a.value).eq_ignore_ascii_case(b"refresh"))
+      .is_some();
+  // An empty `value` is the default for text inputs, but not for these, where it's submitted instead of `on` or displayed instead of the default label.
+  let keeps_empty_value = ns == Namespace::Html
+    && tag_name == b"input"
+    && attributes
+      .get(b"type".as_ref())
+      .filter(|a| {
+        [b"checkbox".as_ref(), b"radio", b"reset", b"submit"]
+          .iter()
+          .any(|t| trimmed(&a.value).eq_ignore_ascii_case(t))
+      })
+      .is_some();
 
   for (name, value) in attributes {
-    match minify_attr(cfg, ns, tag_name, is_meta_viewport, &name, value.value) {
-      AttrMinified::Redundant => {}
+    if is_removed_attr(cfg, &name) && !is_preserved_microdata_attr(cfg, ns, &name) {
+      stats.attributes_removed += 1;
+      continue;
+    };
+    match minify_attr(
+      cfg,
+      stats,
+      ns,
+      tag_name,
+      is_meta_viewport,
+      is_meta_refresh,
+      &name,
+      value.value,
+    ) {
+      AttrMinified::Redundant if keeps_empty_value && name == b"value" => {
+        unquoted.push((name, AttrMinified::NoValue))
+      }
+      AttrMinified::Redundant => stats.attributes_removed += 1,
       a @ AttrMinified::NoValue => unquoted.push((name, a)),
       AttrMinified::Value(v) => {
         debug_assert!(v.len() > 0);
         if v.quoted() {
           quoted.push((name, v));
         } else {
+          stats.attributes_unquoted += 1;
           unquoted.push((name, AttrMinified::Value(v)));
         }
       }
@@ -56,49 +246,126 @@ pub fn minify_element(
   quoted.sort_unstable_by(|a, b| a.0.cmp(&b.0));
   unquoted.sort_unstable_by(|a, b| a.0.cmp(&b.0));
 
+  MinifiedAttrs { quoted, unquoted }
+}
+
+pub fn write_opening_tag(
+  cfg: &Cfg,
+  out: &mut Vec<u8>,
+  tag_name: &[u8],
+  attrs: &MinifiedAttrs,
+  self_closing: bool,
+  is_void: bool,
+) {
+  let MinifiedAttrs { quoted, unquoted } = attrs;
+  out.push(b'<');
+  out.extend_from_slice(tag_name);
+
+  for (i, (name, value)) in quoted.iter().enumerate() {
+    if i == 0 || !cfg.allow_removing_spaces_between_attributes {
+      out.push(b' ');
+    };
+    out.extend_from_slice(name);
+    out.push(b'=');
+    debug_assert!(value.quoted());
+    value.out(out);
+  }
+  for (i, (name, value)) in unquoted.iter().enumerate() {
+    // Write a space between unquoted attributes,
+    // or after the tag name if it wasn't written already during `quoted` processing,
+    // or if forced by Cfg.
+    if i > 0 || (i == 0 && quoted.is_empty()) || !cfg.allow_removing_spaces_between_attributes {
+      out.push(b' ');
+    };
+    out.extend_from_slice(name);
+    if let AttrMinified::Value(v) = value {
+      out.push(b'=');
+      v.out(out);
+    };
+  }
+
+  let style = if is_void {
+    cfg.void_self_closing_style
+  } else {
+    VoidSelfClosingStyle::None
+  };
+  if style == VoidSelfClosingStyle::SpaceSlash {
+    out.extend_from_slice(b" /");
+  } else if self_closing || style == VoidSelfClosingStyle::Slash {
+    // Write a space only if the last attribute is unquoted.
+    if !unquoted.is_empty() {
+      out.push(b' ');
+    };
+    out.push(b'/');
+  };
+  out.push(b'>');
+}
+
+// Any value other than `false` (including the empty string and `plaintext-only`) makes the element editable.
+pub fn is_contenteditable(attributes: &HashMap<Vec<u8>, AttrVal>) -> bool {
+  attributes
+    .get(b"contenteditable".as_ref())
+    .is_some_and(|a| !trimmed(&a.value).eq_ignore_ascii_case(b"false"))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn minify_element(
+  cfg: &Cfg,
+  out: &mut Vec<u8>,
+  stats: &mut MinifyStats,
+  descendant_of_pre: bool,
+  ns: Namespace,
+  ancestors: &mut Vec<Vec<u8>>,
+  // Use an empty slice if none.
+  parent: &[u8],
+  // Use an empty slice if there is no previous sibling element.
+  previous_sibling_element_name: &[u8],
+  // Use an empty slice if the next element or text sibling node is not an element.
+  next_sibling_as_element_tag_name: &[u8],
+  // If the last node of the parent is an element and it's this one.
+  is_last_child_text_or_element_node: bool,
+  tag_name: &[u8],
+  attributes: HashMap<Vec<u8>, AttrVal>,
+  closing_tag: ElementClosingTag,
+  children: Vec<NodeData>,
+) {
+  stats.elements_minified += 1;
+
+  let preserve_whitespace = descendant_of_pre
+    || (ns == Namespace::Html && matches!(tag_name, b"pre" | b"listing"))
+    || (cfg.respect_contenteditable && is_contenteditable(&attributes));
+
+  let attrs = minify_attrs(cfg, stats, ns, tag_name, attributes);
+
   // Attributes list could become empty after minification, so check opening tag omission eligibility after attributes minification.
-  let can_omit_opening_tag = (tag_name == b"html" || tag_name == b"head")
-    && quoted.len() + unquoted.len() == 0
-    && !cfg.keep_html_and_head_opening_tags;
+  let can_omit_opening_tag = attrs.is_empty()
+    && match tag_name {
+      b"html" => !cfg.keep_html_and_head_opening_tags && !cfg.keep_html_opening_tag,
+      b"head" => !cfg.keep_html_and_head_opening_tags && !cfg.keep_head_opening_tag,
+      b"body" => cfg.omit_body_opening_tag && can_omit_body_opening_tag(cfg, &children),
+      b"colgroup" => {
+        can_omit_table_section_opening_tag(cfg, previous_sibling_element_name, b"col", &children)
+      }
+      b"tbody" => {
+        can_omit_table_section_opening_tag(cfg, previous_sibling_element_name, b"tr", &children)
+      }
+      _ => false,
+    };
   let can_omit_closing_tag = !cfg.keep_closing_tags
     && (can_omit_as_before(tag_name, next_sibling_as_element_tag_name)
       || (is_last_child_text_or_element_node && can_omit_as_last_node(parent, tag_name)));
 
-  if !can_omit_opening_tag {
-    out.push(b'<');
-    out.extend_from_slice(tag_name);
-
-    for (i, (name, value)) in quoted.iter().enumerate() {
-      if i == 0 || !cfg.allow_removing_spaces_between_attributes {
-        out.push(b' ');
-      };
-      out.extend_from_slice(name);
-      out.push(b'=');
-      debug_assert!(value.quoted());
-      value.out(out);
-    }
-    for (i, (name, value)) in unquoted.iter().enumerate() {
-      // Write a space between unquoted attributes,
-      // or after the tag name if it wasn't written already during `quoted` processing,
-      // or if forced by Cfg.
-      if i > 0 || (i == 0 && quoted.is_empty()) || !cfg.allow_removing_spaces_between_attributes {
-        out.push(b' ');
-      };
-      out.extend_from_slice(name);
-      if let AttrMinified::Value(v) = value {
-        out.push(b'=');
-        v.out(out);
-      };
-    }
-
-    if closing_tag == ElementClosingTag::SelfClosing {
-      // Write a space only if the last attribute is unquoted.
-      if !unquoted.is_empty() {
-        out.push(b' ');
-      };
-      out.push(b'/');
-    };
-    out.push(b'>');
+  if can_omit_opening_tag {
+    stats.opening_tags_omitted += 1;
+  } else {
+    write_opening_tag(
+      cfg,
+      out,
+      tag_name,
+      &attrs,
+      closing_tag == ElementClosingTag::SelfClosing,
+      closing_tag == ElementClosingTag::Void,
+    );
   }
 
   if closing_tag == ElementClosingTag::SelfClosing || closing_tag == ElementClosingTag::Void {
@@ -106,20 +373,28 @@ pub fn minify_element(
     return;
   };
 
+  let track_ancestors = cfg.text_visitor.is_some();
+  if track_ancestors {
+    ancestors.push(tag_name.to_vec());
+  };
   minify_content(
     cfg,
     out,
-    if tag_name == b"svg" {
-      Namespace::Svg
-    } else {
-      ns
-    },
-    descendant_of_pre || (ns == Namespace::Html && tag_name == b"pre"),
+    stats,
+    namespace_for(tag_name).unwrap_or(ns),
+    preserve_whitespace,
+    ancestors,
     tag_name,
     children,
   );
+  if track_ancestors {
+    ancestors.pop();
+  };
 
   if closing_tag != ElementClosingTag::Present || can_omit_closing_tag {
+    if closing_tag == ElementClosingTag::Present {
+      stats.closing_tags_omitted += 1;
+    };
     return;
   };
   out.extend_from_slice(b"</");
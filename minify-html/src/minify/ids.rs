@@ -0,0 +1,128 @@
+use crate::ast::NodeData;
+use alloc::vec::Vec;
+use hashbrown::HashSet;
+use memchr::memmem::find;
+
+// Attributes whose values are IDs or space-separated lists of IDs of other elements. All `aria-*` attributes are included, even those that aren't ID references, as that only keeps more IDs.
+fn is_idref_attr(name: &[u8]) -> bool {
+  matches!(
+    name,
+    b"anchor"
+      | b"commandfor"
+      | b"for"
+      | b"form"
+      | b"headers"
+      | b"itemref"
+      | b"list"
+      | b"popovertarget"
+  ) || name.starts_with(b"aria-")
+}
+
+fn is_plain_id_char(c: u8) -> bool {
+  c.is_ascii_alphanumeric() || matches!(c, b'-' | b'_')
+}
+
+// IDs that aren't valid CSS identifiers as is are always kept, as references to them could be escaped or percent-encoded, e.g. `#a\.b`, `#\31 a`, or `href=#caf%C3%A9`.
+fn is_plain_id(id: &[u8]) -> bool {
+  // A leading digit, a leading hyphen followed by a digit, or a lone hyphen must be escaped.
+  let needs_escape = matches!(
+    id,
+    [] | [b'0'..=b'9', ..] | [b'-'] | [b'-', b'0'..=b'9', ..]
+  );
+  !needs_escape && id.iter().all(|&c| is_plain_id_char(c))
+}
+
+// Collects the IDs of elements, IDs referenced by attributes, and code that could reference IDs in any way, such as `getElementById("a")` in a script or `#a` in a stylesheet.
+fn collect<'a>(
+  nodes: &'a [NodeData],
+  ids: &mut Vec<&'a [u8]>,
+  refs: &mut HashSet<&'a [u8]>,
+  code: &mut Vec<&'a [u8]>,
+) {
+  for n in nodes {
+    match n {
+      NodeData::Element {
+        attributes,
+        children,
+        ..
+      } => {
+        for (name, value) in attributes {
+          let value = value.as_slice();
+          if name == b"id" {
+            ids.push(value);
+            continue;
+          };
+          if is_idref_attr(name) {
+            refs.extend(value.split(|c| c.is_ascii_whitespace()));
+          };
+          if name == b"style" || name.starts_with(b"on") {
+            code.push(value);
+          };
+          // Fragments, like `href=#a`, `usemap=#a`, or `fill=url(#a)`.
+          for (i, _) in value.iter().enumerate().filter(|(_, c)| **c == b'#') {
+            let rest = &value[i + 1..];
+            let end = rest
+              .iter()
+              .position(|&c| !is_plain_id_char(c))
+              .unwrap_or(rest.len());
+            refs.insert(&rest[..end]);
+          }
+        }
+        collect(children, ids, refs, code);
+      }
+      NodeData::ScriptOrStyleContent { code: c, .. } => code.push(c),
+      _ => {}
+    };
+  }
+}
+
+fn remove_ids(nodes: &mut [NodeData], removed: &HashSet<Vec<u8>>) {
+  for n in nodes {
+    if let NodeData::Element {
+      attributes,
+      children,
+      ..
+    } = n
+    {
+      if attributes
+        .get(b"id".as_slice())
+        .is_some_and(|id| removed.contains(id.as_slice()))
+      {
+        attributes.remove(b"id".as_slice());
+      };
+      remove_ids(children, removed);
+    };
+  }
+}
+
+// Returns whether `id` appears in `code` as a whole identifier, i.e. not as part of a longer one like `d` in `document`.
+fn mentions(code: &[u8], id: &[u8]) -> bool {
+  let mut from = 0;
+  while let Some(i) = find(&code[from..], id) {
+    let start = from + i;
+    let end = start + id.len();
+    let is_bounded = |pos: Option<&u8>| pos.is_none_or(|&c| !is_plain_id_char(c));
+    if is_bounded(start.checked_sub(1).and_then(|p| code.get(p))) && is_bounded(code.get(end)) {
+      return true;
+    };
+    from = start + 1;
+  }
+  false
+}
+
+/// Removes `id` attributes that aren't referenced anywhere in the document, by a fragment like `href=#a`, an ID reference attribute like `for` or `aria-labelledby`, or any mention as a whole identifier in a script, stylesheet, `style` attribute, or event handler attribute.
+pub fn remove_unreferenced_ids(nodes: &mut [NodeData]) {
+  // The borrows of `nodes` must end before they're changed, including the ones in `refs`, as hashbrown sets are considered to use their contents when dropped.
+  let removed = {
+    let mut ids = Vec::new();
+    let mut refs = HashSet::default();
+    let mut code = Vec::new();
+    collect(nodes, &mut ids, &mut refs, &mut code);
+    ids
+      .into_iter()
+      .filter(|id| is_plain_id(id) && !refs.contains(id) && !code.iter().any(|c| mentions(c, id)))
+      .map(|id| id.to_vec())
+      .collect::<HashSet<_>>()
+  };
+  remove_ids(nodes, &removed);
+}
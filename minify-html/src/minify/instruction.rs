@@ -1,7 +1,30 @@
 use crate::cfg::Cfg;
+use alloc::vec::Vec;
+
+// The target is the name immediately after `<?`, e.g. `xml-stylesheet` in `<?xml-stylesheet href="a.xsl"?>`.
+fn instruction_target(code: &[u8]) -> &[u8] {
+  let end = code
+    .iter()
+    .position(|c| c.is_ascii_whitespace() || *c == b'?')
+    .unwrap_or(code.len());
+  &code[..end]
+}
+
+// An XML declaration like `<?xml version="1.0"?>`, which isn't a processing instruction in XML but is parsed as one in HTML.
+pub fn is_xml_declaration(code: &[u8]) -> bool {
+  instruction_target(code) == b"xml"
+}
+
+pub fn is_kept_instruction(cfg: &Cfg, code: &[u8]) -> bool {
+  !cfg.remove_processing_instructions
+    || cfg
+      .keep_processing_instructions_matching
+      .iter()
+      .any(|t| t.as_slice() == instruction_target(code))
+}
 
 pub fn minify_instruction(cfg: &Cfg, out: &mut Vec<u8>, code: &[u8], ended: bool) {
-  if !cfg.remove_processing_instructions {
+  if is_kept_instruction(cfg, code) {
     out.extend_from_slice(b"<?");
     out.extend_from_slice(code);
     if ended {
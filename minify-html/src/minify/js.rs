@@ -1,20 +1,289 @@
 use crate::cfg::Cfg;
+#[cfg(feature = "js")]
+use crate::minify::catch_minifier_panic;
+use crate::minify::comment::is_license_comment;
+use crate::stats::MinifyStats;
+use alloc::vec::Vec;
+use memchr::memmem::find;
 use minify_html_common::whitespace::trimmed;
+#[cfg(feature = "js")]
 use minify_js::minify as minifier;
+#[cfg(feature = "js")]
 use minify_js::Session;
+#[cfg(feature = "js")]
 use minify_js::TopLevelMode;
 
-pub fn minify_js(cfg: &Cfg, mode: TopLevelMode, out: &mut Vec<u8>, code: &[u8]) {
-  if cfg.minify_js {
-    // TODO Write to `out` directly, but only if we can guarantee that the length will never exceed the input.
+// Minification can produce sequences that end the `<script>` element early or change how its end is found, e.g. by unescaping `"<\/script>"` to `"</script>"`. Escaping them isn't safe in every JS context (e.g. `a</script/.test(b)`), so the original code, which is known to parse correctly, is kept instead.
+fn is_unsafe_in_script(code: &[u8]) -> bool {
+  code.windows(8).any(|w| w.eq_ignore_ascii_case(b"</script")) || find(code, b"<!--").is_some()
+}
+
+// Returns the minified code if it's shorter than the source, or `None` if it can't be parsed or the `js` feature is disabled.
+#[cfg(feature = "js")]
+fn try_minify(is_module: bool, code: &[u8]) -> Option<Vec<u8>> {
+  let mode = if is_module {
+    TopLevelMode::Module
+  } else {
+    TopLevelMode::Global
+  };
+  // TODO Write to the output directly, but only if we can guarantee that the length will never exceed the input.
+  catch_minifier_panic(|| {
     let mut output = Vec::new();
     let session = Session::new();
-    let result = minifier(&session, mode, code, &mut output);
     // TODO Collect error as warning.
-    if result.is_ok() && output.len() < code.len() {
+    let result = minifier(&session, mode, code, &mut output);
+    Some(output).filter(|output| result.is_ok() && output.len() < code.len())
+  })
+}
+
+#[cfg(not(feature = "js"))]
+fn try_minify(_is_module: bool, _code: &[u8]) -> Option<Vec<u8>> {
+  None
+}
+
+// After these keywords, a `/` starts a regular expression literal rather than being division.
+const REGEX_PRECEDING_KEYWORDS: &[&[u8]] = &[
+  b"await",
+  b"case",
+  b"delete",
+  b"do",
+  b"else",
+  b"in",
+  b"instanceof",
+  b"new",
+  b"return",
+  b"throw",
+  b"typeof",
+  b"void",
+  b"yield",
+];
+
+// A `(` after these keywords is closed by a `)` that can be followed by a statement, which can start with a regular expression literal.
+const CONTROL_KEYWORDS: &[&[u8]] = &[b"for", b"if", b"while", b"with"];
+
+fn is_js_identifier_char(c: u8) -> bool {
+  c.is_ascii_alphanumeric() || c == b'_' || c == b'$' || c >= 0x80
+}
+
+fn is_js_line_terminator(c: u8) -> bool {
+  c == b'\n' || c == b'\r'
+}
+
+// Returns the keyword or identifier that `code` ends with, ignoring trailing whitespace, unless it's a property name like `a.in`.
+fn trailing_word(code: &[u8]) -> &[u8] {
+  let end = code
+    .iter()
+    .rposition(|c| !c.is_ascii_whitespace())
+    .map_or(0, |i| i + 1);
+  let start = code[..end]
+    .iter()
+    .rposition(|&c| !is_js_identifier_char(c))
+    .map_or(0, |i| i + 1);
+  if code[..start].last() == Some(&b'.') {
+    return b"";
+  };
+  &code[start..end]
+}
+
+// Returns whether a `/` after `code` starts a regular expression literal, or `None` if that can't be determined without parsing, such as after a `}`, which could close a block or an object literal.
+fn starts_regex(code: &[u8], closed_control_paren: bool) -> Option<bool> {
+  let end = match code.iter().rposition(|c| !c.is_ascii_whitespace()) {
+    Some(end) => end,
+    None => return Some(true),
+  };
+  match code[end] {
+    b')' => Some(closed_control_paren),
+    b'}' | b'/' => None,
+    c @ (b'+' | b'-') if end > 0 && code[end - 1] == c => None,
+    b']' | b'"' | b'\'' | b'`' => Some(false),
+    c if is_js_identifier_char(c) => Some(REGEX_PRECEDING_KEYWORDS.contains(&trailing_word(code))),
+    _ => Some(true),
+  }
+}
+
+// Returns the index after the string literal starting at `start`.
+fn skip_js_string(code: &[u8], start: usize) -> Option<usize> {
+  let quote = code[start];
+  let mut i = start + 1;
+  while i < code.len() {
+    match code[i] {
+      b'\\' => i += 2,
+      c if c == quote => return Some(i + 1),
+      c if is_js_line_terminator(c) => return None,
+      _ => i += 1,
+    };
+  }
+  None
+}
+
+// Returns the index after the part of a template literal starting at `start`, which is just after the opening `` ` `` or the `}` of a substitution, and whether that part ends with the start of a substitution (`${`) rather than the closing `` ` ``.
+fn skip_js_template(code: &[u8], start: usize) -> Option<(usize, bool)> {
+  let mut i = start;
+  while i < code.len() {
+    match code[i] {
+      b'\\' => i += 2,
+      b'`' => return Some((i + 1, false)),
+      b'$' if code.get(i + 1) == Some(&b'{') => return Some((i + 2, true)),
+      _ => i += 1,
+    };
+  }
+  None
+}
+
+// Returns the index after the body of the regular expression literal starting at `start`, not including any flags.
+fn skip_js_regex(code: &[u8], start: usize) -> Option<usize> {
+  let mut in_class = false;
+  let mut i = start + 1;
+  while i < code.len() {
+    match code[i] {
+      b'\\' => i += 1,
+      b'[' => in_class = true,
+      b']' => in_class = false,
+      b'/' if !in_class => return Some(i + 1),
+      c if is_js_line_terminator(c) => return None,
+      _ => {}
+    };
+    i += 1;
+  }
+  None
+}
+
+/// Removes comments from JS code without otherwise changing it. License comments like `/*! ... */` and source map comments like `//# sourceMappingURL=...` are kept. A removed block comment is replaced with a space or line break if necessary, as that's what JS treats it as. Returns `None` if the code can't be processed safely without fully parsing it, such as when a `/` could either be division or start a regular expression literal, or when the code contains `<!--` or `-->`, which can start comments in scripts.
+pub fn strip_js_comments(code: &[u8]) -> Option<Vec<u8>> {
+  if find(code, b"<!--").is_some() || find(code, b"-->").is_some() {
+    return None;
+  };
+  let mut out = Vec::with_capacity(code.len());
+  // For each open template literal substitution, how many `{` are open within it.
+  let mut substitutions = Vec::<usize>::new();
+  // For each open `(`, whether it follows a control keyword like `if`.
+  let mut parens = Vec::<bool>::new();
+  let mut closed_control_paren = false;
+  let mut i = 0;
+  while i < code.len() {
+    let c = code[i];
+    let next = code.get(i + 1).copied();
+    let end = match c {
+      b'"' | b'\'' => skip_js_string(code, i)?,
+      b'`' => {
+        let (end, substitution) = skip_js_template(code, i + 1)?;
+        if substitution {
+          substitutions.push(0);
+        };
+        end
+      }
+      b'{' => {
+        if let Some(depth) = substitutions.last_mut() {
+          *depth += 1;
+        };
+        i + 1
+      }
+      b'}' => match substitutions.last().copied() {
+        Some(0) => {
+          let (end, substitution) = skip_js_template(code, i + 1)?;
+          if !substitution {
+            substitutions.pop();
+          };
+          end
+        }
+        Some(_) => {
+          if let Some(depth) = substitutions.last_mut() {
+            *depth -= 1;
+          };
+          i + 1
+        }
+        None => i + 1,
+      },
+      b'(' => {
+        parens.push(CONTROL_KEYWORDS.contains(&trailing_word(&out)));
+        i + 1
+      }
+      b')' => {
+        closed_control_paren = parens.pop().unwrap_or(false);
+        i + 1
+      }
+      b'/' if next == Some(b'/') => {
+        let end = code[i..]
+          .iter()
+          .position(|&c| is_js_line_terminator(c))
+          .map_or(code.len(), |p| i + p);
+        if matches!(code.get(i + 2), Some(b'#' | b'@')) {
+          end
+        } else {
+          while out.last().is_some_and(|&c| c == b' ' || c == b'\t') {
+            out.pop();
+          }
+          i = end;
+          continue;
+        }
+      }
+      b'/' if next == Some(b'*') => {
+        let end = find(&code[i + 2..], b"*/")? + i + 4;
+        let comment = &code[i + 2..end - 2];
+        if is_license_comment(comment) || comment.starts_with(b"#") {
+          end
+        } else {
+          let sep = if comment.iter().any(|&c| is_js_line_terminator(c)) {
+            b'\n'
+          } else {
+            b' '
+          };
+          let is_separated = |c: Option<&u8>| {
+            c.is_some_and(|&c| {
+              if sep == b'\n' {
+                is_js_line_terminator(c)
+              } else {
+                c.is_ascii_whitespace()
+              }
+            })
+          };
+          if !is_separated(out.last()) && !is_separated(code.get(end)) {
+            out.push(sep);
+          };
+          i = end;
+          continue;
+        }
+      }
+      b'/' if starts_regex(&out, closed_control_paren)? => skip_js_regex(code, i)?,
+      _ => i + 1,
+    };
+    out.extend_from_slice(&code[i..end]);
+    i = end;
+  }
+  Some(out)
+}
+
+pub fn minify_js(
+  cfg: &Cfg,
+  is_module: bool,
+  out: &mut Vec<u8>,
+  stats: &mut MinifyStats,
+  code: &[u8],
+) {
+  if cfg.minify_js {
+    if let Some(output) = try_minify(is_module, code).filter(|output| !is_unsafe_in_script(output))
+    {
+      stats.js_bytes_saved += code.len() - output.len();
       out.extend_from_slice(output.as_slice());
       return;
     };
   }
+  if cfg.strip_code_comments {
+    if let Some(stripped) = strip_js_comments(code) {
+      out.extend_from_slice(trimmed(&stripped));
+      return;
+    };
+  }
   out.extend_from_slice(trimmed(code));
 }
+
+/// Minifies a standalone JS script, such as the contents of a `.js` file, with the same minifier used for `<script>` contents. As with `<script>` contents, `minify_js` must be enabled in `cfg`, otherwise the code is only trimmed. It's also only trimmed if it fails to parse.
+#[cfg(feature = "js")]
+pub fn minify_js_str(src: &[u8], cfg: &Cfg) -> Vec<u8> {
+  if cfg.minify_js {
+    if let Some(output) = try_minify(false, src) {
+      return output;
+    };
+  }
+  trimmed(src).to_vec()
+}
@@ -1,3 +1,8 @@
+#[cfg(any(feature = "css", feature = "js"))]
+use std::panic::catch_unwind;
+#[cfg(any(feature = "css", feature = "js"))]
+use std::panic::AssertUnwindSafe;
+
 pub mod attr;
 pub mod bang;
 pub mod comment;
@@ -5,8 +10,17 @@ pub mod content;
 pub mod css;
 pub mod doctype;
 pub mod element;
+pub mod ids;
 pub mod instruction;
 pub mod js;
 pub mod rcdata;
 #[cfg(test)]
 mod tests;
+pub mod unwrap;
+pub mod url;
+
+// Runs a third-party minifier, treating a panic like a syntax error. They can panic on some malformed input, and the original code can always be kept instead. The panic hook still runs, so the panic message is printed to stderr, and nothing can be caught when built with `panic = "abort"`, as WebAssembly targets are, in which case the process aborts.
+#[cfg(any(feature = "css", feature = "js"))]
+pub fn catch_minifier_panic<T>(f: impl FnOnce() -> Option<T>) -> Option<T> {
+  catch_unwind(AssertUnwindSafe(f)).ok().flatten()
+}
@@ -1,12 +1,17 @@
 use crate::ast::RcdataContentType;
 use crate::entity::encode::encode_entities;
+use crate::entity::encode::encode_for_output;
 use crate::tag::TAG_TEXTAREA_END;
 use crate::tag::TAG_TITLE_END;
 use crate::Cfg;
+use alloc::vec::Vec;
 
 pub fn minify_rcdata(cfg: &Cfg, out: &mut Vec<u8>, typ: RcdataContentType, text: &[u8]) {
   // Encode entities, since they're still decoded by the browser.
-  let html = encode_entities(text, false, !cfg.allow_optimal_entities);
+  let html = encode_for_output(
+    encode_entities(text, false, !cfg.allow_optimal_entities),
+    cfg.output_encoding,
+  );
 
   // Since the text has been decoded, there may be unintentional matches to end tags that we must escape.
   let html = match typ {
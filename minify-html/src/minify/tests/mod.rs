@@ -1 +1,2 @@
 mod attr;
+mod url;
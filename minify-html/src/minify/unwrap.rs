@@ -0,0 +1,123 @@
+use crate::ast::NodeData;
+use minify_html_common::spec::tag::ns::Namespace;
+use minify_html_common::whitespace::is_all_whitespace;
+
+// Elements that are always laid out as blocks by default, so a `<div>` containing only one of them can be removed without affecting layout.
+fn is_block(tag_name: &[u8]) -> bool {
+  matches!(
+    tag_name,
+    b"address"
+      | b"article"
+      | b"aside"
+      | b"blockquote"
+      | b"details"
+      | b"div"
+      | b"dl"
+      | b"fieldset"
+      | b"figure"
+      | b"footer"
+      | b"form"
+      | b"h1"
+      | b"h2"
+      | b"h3"
+      | b"h4"
+      | b"h5"
+      | b"h6"
+      | b"header"
+      | b"hgroup"
+      | b"main"
+      | b"nav"
+      | b"ol"
+      | b"p"
+      | b"section"
+      | b"table"
+      | b"ul"
+  )
+}
+
+// Inline text semantics elements, so a `<span>` containing only one of them can be removed without affecting layout.
+fn is_inline(tag_name: &[u8]) -> bool {
+  matches!(
+    tag_name,
+    b"a"
+      | b"abbr"
+      | b"b"
+      | b"bdi"
+      | b"cite"
+      | b"del"
+      | b"dfn"
+      | b"em"
+      | b"i"
+      | b"ins"
+      | b"kbd"
+      | b"mark"
+      | b"s"
+      | b"samp"
+      | b"small"
+      | b"span"
+      | b"strong"
+      | b"sub"
+      | b"sup"
+      | b"u"
+      | b"var"
+  )
+}
+
+// Returns the index of the only child to keep if `node` is a `<div>` or `<span>` without attributes that can be replaced by it.
+fn redundant_wrapper_child(node: &NodeData) -> Option<usize> {
+  let (children, name) = match node {
+    NodeData::Element {
+      attributes,
+      children,
+      name,
+      namespace: Namespace::Html,
+      ..
+    } if attributes.is_empty() => (children, name),
+    _ => return None,
+  };
+  match name.as_slice() {
+    // Whitespace around a block in a `<div>` is never rendered, so it can be ignored.
+    b"div" => {
+      let mut elems = children
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| !matches!(c, NodeData::Text { value, .. } if is_all_whitespace(value)));
+      match (elems.next(), elems.next()) {
+        (
+          Some((
+            i,
+            NodeData::Element {
+              name,
+              namespace: Namespace::Html,
+              ..
+            },
+          )),
+          None,
+        ) if is_block(name) => Some(i),
+        _ => None,
+      }
+    }
+    // Whitespace in a `<span>` is rendered, so the child must be the only node.
+    b"span" => match children.as_slice() {
+      [NodeData::Element {
+        name,
+        namespace: Namespace::Html,
+        ..
+      }] if is_inline(name) => Some(0),
+      _ => None,
+    },
+    _ => None,
+  }
+}
+
+// Replaces each attributeless `<div>` wrapping only a block element, or `<span>` wrapping only an inline element, with that element. Only the given nodes are checked, as descendants are handled when their parent's content is minified.
+pub fn unwrap_redundant_wrappers(nodes: &mut [NodeData]) {
+  for n in nodes.iter_mut() {
+    while let Some(i) = redundant_wrapper_child(n) {
+      if let NodeData::Element { children, .. } = n {
+        let child = children.swap_remove(i);
+        *n = child;
+      };
+    }
+  }
+}
@@ -0,0 +1,207 @@
+use alloc::vec::Vec;
+use minify_html_common::spec::url::default_port_for_scheme;
+
+// Normalises an absolute URL with a known scheme by lowercasing the scheme and host, removing the port if it's the default, and removing `.` path segments.
+// Returns None if the value isn't such a URL, in which case it should be left as is. Relative URLs are ambiguous without knowing the base URL, so aren't touched.
+pub fn normalize_url(url: &[u8]) -> Option<Vec<u8>> {
+  let scheme_end = url.windows(3).position(|w| w == b"://")?;
+  let mut scheme = url[..scheme_end].to_vec();
+  scheme.make_ascii_lowercase();
+  let default_port = default_port_for_scheme(&scheme)?;
+
+  let authority_start = scheme_end + 3;
+  let authority_end = url[authority_start..]
+    .iter()
+    .position(|c| matches!(c, b'/' | b'?' | b'#'))
+    .map_or(url.len(), |p| authority_start + p);
+  let authority = &url[authority_start..authority_end];
+  // Don't touch user info, as it could contain anything.
+  if authority.contains(&b'@') {
+    return None;
+  };
+  // The port separator is the last colon, unless it's inside an IPv6 address.
+  let port_sep = authority
+    .iter()
+    .rposition(|&c| c == b':')
+    .filter(|&p| !authority[p..].contains(&b']'));
+  let (host, port) = match port_sep {
+    Some(p) => (&authority[..p], Some(&authority[p + 1..])),
+    None => (authority, None),
+  };
+  if host.is_empty() || port.filter(|p| !p.iter().all(u8::is_ascii_digit)).is_some() {
+    return None;
+  };
+
+  let mut out = Vec::with_capacity(url.len());
+  out.extend_from_slice(&scheme);
+  out.extend_from_slice(b"://");
+  out.extend(host.iter().map(u8::to_ascii_lowercase));
+  match port {
+    // An empty port is equivalent to the default port.
+    Some(port) if !port.is_empty() && port != default_port => {
+      out.push(b':');
+      out.extend_from_slice(port);
+    }
+    _ => {}
+  };
+
+  let rest = &url[authority_end..];
+  let path_end = rest
+    .iter()
+    .position(|c| matches!(c, b'?' | b'#'))
+    .unwrap_or(rest.len());
+  let (path, suffix) = rest.split_at(path_end);
+  let segments: Vec<&[u8]> = path.split(|&c| c == b'/').collect();
+  for (i, seg) in segments.iter().enumerate().skip(1) {
+    if *seg == b"." {
+      // A trailing `.` segment still implies a trailing slash.
+      if i == segments.len() - 1 {
+        out.push(b'/');
+      };
+      continue;
+    };
+    out.push(b'/');
+    out.extend_from_slice(seg);
+  }
+  out.extend_from_slice(suffix);
+  Some(out)
+}
+
+// Splits a URL normalised by `normalize_url` into its scheme and authority, and the rest.
+fn split_origin(url: &[u8]) -> (&[u8], &[u8]) {
+  let authority_start = url
+    .windows(3)
+    .position(|w| w == b"://")
+    .map_or(0, |p| p + 3);
+  let end = url[authority_start..]
+    .iter()
+    .position(|c| matches!(c, b'/' | b'?' | b'#'))
+    .map_or(url.len(), |p| authority_start + p);
+  url.split_at(end)
+}
+
+// Splits the path from the query and fragment.
+fn split_path(url: &[u8]) -> (&[u8], &[u8]) {
+  let end = url
+    .iter()
+    .position(|c| matches!(c, b'?' | b'#'))
+    .unwrap_or(url.len());
+  url.split_at(end)
+}
+
+// Removes `.` and `..` segments from an absolute path, as browsers do when resolving a URL.
+fn remove_dot_segments(path: &[u8]) -> Vec<u8> {
+  let segments: Vec<&[u8]> = path.split(|&c| c == b'/').skip(1).collect();
+  let mut kept = Vec::<&[u8]>::new();
+  for (i, &seg) in segments.iter().enumerate() {
+    let is_last = i == segments.len() - 1;
+    if seg == b".." {
+      kept.pop();
+    };
+    if seg == b"." || seg == b".." {
+      // A trailing dot segment still implies a trailing slash.
+      if is_last {
+        kept.push(b"");
+      };
+    } else {
+      kept.push(seg);
+    };
+  }
+  if kept.is_empty() {
+    return b"/".to_vec();
+  };
+  let mut out = Vec::with_capacity(path.len());
+  for seg in kept {
+    out.push(b'/');
+    out.extend_from_slice(seg);
+  }
+  out
+}
+
+// Returns a relative reference to the absolute path `target` from a document at the absolute path `base`, both without dot segments.
+fn relative_path(base: &[u8], target: &[u8]) -> Option<Vec<u8>> {
+  let base_dir = &base[..base.iter().rposition(|&c| c == b'/')? + 1];
+  let same = base_dir
+    .iter()
+    .zip(target)
+    .take_while(|(a, b)| a == b)
+    .count();
+  let common = base_dir[..same].iter().rposition(|&c| c == b'/')? + 1;
+  let rest = &target[common..];
+  // An empty segment at the start would be interpreted as an absolute path.
+  if rest.starts_with(b"/") {
+    return None;
+  };
+  let ups = base_dir[common..].iter().filter(|&&c| c == b'/').count();
+  let mut out = b"../".repeat(ups);
+  // A colon in the first segment would be interpreted as a scheme.
+  if out.is_empty() && (rest.is_empty() || rest.split(|&c| c == b'/').next()?.contains(&b':')) {
+    out.extend_from_slice(b"./");
+  };
+  out.extend_from_slice(rest);
+  Some(out)
+}
+
+// Like `normalize_url`, but also resolves relative URLs against the absolute URL of the document, `base`, and then uses whichever of the URL as is, its absolute path, or a path relative to `base` is shortest. Absolute URLs with the same origin as `base` are shortened the same way.
+// Returns None if the value isn't a URL that can be resolved, in which case it should be left as is.
+pub fn normalize_url_with_base(url: &[u8], base: &[u8]) -> Option<Vec<u8>> {
+  let normalized = normalize_url(url);
+  let base = match normalize_url(base) {
+    Some(base) => base,
+    None => return normalized,
+  };
+  let (base_origin, base_rest) = split_origin(&base);
+  let base_path = match split_path(base_rest).0 {
+    b"" => b"/".to_vec(),
+    p => remove_dot_segments(p),
+  };
+
+  let (path, suffix) = match &normalized {
+    Some(abs) => {
+      let (origin, rest) = split_origin(abs);
+      if origin != base_origin {
+        return normalized;
+      };
+      let (path, suffix) = split_path(rest);
+      let path = if path.is_empty() { b"/".as_ref() } else { path };
+      (path.to_vec(), suffix.to_vec())
+    }
+    None => {
+      let (path, suffix) = split_path(url);
+      // Leave alone other schemes, scheme-relative URLs, references to the document itself, and backslashes that browsers may treat as slashes.
+      if path.is_empty()
+        || path.starts_with(b"//")
+        || path.split(|&c| c == b'/').next()?.contains(&b':')
+        || url.contains(&b'\\')
+      {
+        return None;
+      };
+      if path.starts_with(b"/") {
+        (path.to_vec(), suffix.to_vec())
+      } else {
+        let dir_end = base_path.iter().rposition(|&c| c == b'/')? + 1;
+        let mut merged = base_path[..dir_end].to_vec();
+        merged.extend_from_slice(path);
+        (merged, suffix.to_vec())
+      }
+    }
+  };
+  let path = remove_dot_segments(&path);
+
+  let mut best = normalized.unwrap_or_else(|| url.to_vec());
+  let mut candidates = Vec::new();
+  // A path starting with `//` would be interpreted as a scheme-relative URL.
+  if !path.starts_with(b"//") {
+    candidates.push(path.clone());
+  };
+  if let Some(rel) = relative_path(&base_path, &path) {
+    candidates.push(rel);
+  };
+  for mut candidate in candidates {
+    candidate.extend_from_slice(&suffix);
+    if candidate.len() < best.len() {
+      best = candidate;
+    };
+  }
+  Some(best)
+}
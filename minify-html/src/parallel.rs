@@ -0,0 +1,32 @@
+use crate::minify;
+use crate::Cfg;
+use rayon::iter::IntoParallelRefIterator;
+use rayon::iter::ParallelIterator;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Minifies each of `inputs` in parallel with the same configuration, using rayon's global thread pool. The results are in the same order as `inputs`, and are the same as calling `minify` on each.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, minify_parallel};
+///
+/// let inputs: &[&[u8]] = &[b"<p>  a  </p>", b"<div>  b  </div>"];
+/// assert_eq!(minify_parallel(inputs, &Cfg::new()), vec![b"<p>a".to_vec(), b"<div>b</div>".to_vec()]);
+/// ```
+pub fn minify_parallel(inputs: &[&[u8]], cfg: &Cfg) -> Vec<Vec<u8>> {
+  inputs.par_iter().map(|src| minify(src, cfg)).collect()
+}
+
+/// Minifies each file in `paths` in place in parallel with the same configuration, using rayon's global thread pool. Each file is read, minified like `minify`, and overwritten. The results are in the same order as `paths`, and a file that fails to be read or written doesn't stop the others from being minified.
+pub fn minify_parallel_files(paths: &[&Path], cfg: &Cfg) -> Vec<io::Result<()>> {
+  paths
+    .par_iter()
+    .map(|path| {
+      let src = fs::read(path)?;
+      fs::write(path, minify(&src, cfg))
+    })
+    .collect()
+}
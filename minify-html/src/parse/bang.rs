@@ -1,13 +1,26 @@
 use crate::ast::NodeData;
 use crate::parse::Code;
 use memchr::memchr;
+use memchr::memmem;
+use minify_html_common::spec::tag::ns::Namespace;
 
-pub fn parse_bang(code: &mut Code) -> NodeData {
+pub fn parse_bang(code: &mut Code, ns: Namespace) -> NodeData {
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position();
   debug_assert!(code.as_slice().starts_with(b"<!"));
   code.shift(2);
-  let (len, matched) = match memchr(b'>', code.as_slice()) {
-    Some(m) => (m, 1),
-    None => (code.rem(), 0),
+  // In foreign content, a CDATA section ends at `]]>` and can contain `>`. Elsewhere, it's a bogus comment like any other bang.
+  // https://html.spec.whatwg.org/multipage/parsing.html#markup-declaration-open-state.
+  let (len, matched) = if ns != Namespace::Html && code.as_slice().starts_with(b"[CDATA[") {
+    match memmem::find(code.as_slice(), b"]]>") {
+      Some(m) => (m + 2, 1),
+      None => (code.rem(), 0),
+    }
+  } else {
+    match memchr(b'>', code.as_slice()) {
+      Some(m) => (m, 1),
+      None => (code.rem(), 0),
+    }
   };
   let data = code.copy_and_shift(len);
   // It might be EOF.
@@ -15,5 +28,9 @@ pub fn parse_bang(code: &mut Code) -> NodeData {
   NodeData::Bang {
     code: data,
     ended: matched > 0,
+    #[cfg(feature = "tracking")]
+    source_offset,
+    #[cfg(feature = "tracking")]
+    source_line,
   }
 }
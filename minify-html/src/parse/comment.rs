@@ -3,8 +3,13 @@ use crate::parse::Code;
 use aho_corasick::AhoCorasick;
 use aho_corasick::AhoCorasickBuilder;
 use aho_corasick::AhoCorasickKind;
-use once_cell::sync::Lazy;
+use alloc::vec::Vec;
+use memchr::memmem;
+use minify_html_common::lazy::Lazy;
+use minify_html_common::whitespace::trimmed;
 
+// Matchers are built from static patterns, so building them never fails.
+#[allow(clippy::unwrap_used)]
 static COMMENT_END: Lazy<AhoCorasick> = Lazy::new(|| {
   AhoCorasickBuilder::new()
     .kind(Some(AhoCorasickKind::DFA))
@@ -13,6 +18,8 @@ static COMMENT_END: Lazy<AhoCorasick> = Lazy::new(|| {
 });
 
 pub fn parse_comment(code: &mut Code) -> NodeData {
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position();
   debug_assert!(code.as_slice().starts_with(b"<!--"));
   code.shift(4);
   let (len, matched) = match COMMENT_END.find(code.as_slice()) {
@@ -25,5 +32,51 @@ pub fn parse_comment(code: &mut Code) -> NodeData {
   NodeData::Comment {
     code: data,
     ended: matched > 0,
+    #[cfg(feature = "tracking")]
+    source_offset,
+    #[cfg(feature = "tracking")]
+    source_line,
+  }
+}
+
+// Returns the end marker if the comment with `content` starts an ignored region.
+pub fn ignored_region_end_marker(code: &Code, content: &[u8]) -> Option<Vec<u8>> {
+  code
+    .opts
+    .ignored_region_markers
+    .as_ref()
+    .filter(|(start, _)| trimmed(content) == start.as_slice())
+    .map(|(_, end)| end.clone())
+}
+
+// Called after a comment that starts an ignored region. Returns the source until the next comment whose trimmed content is `end_marker` as is, and skips that comment. If there isn't one, the rest of the source is returned.
+pub fn parse_ignored_region(code: &mut Code, end_marker: &[u8]) -> NodeData {
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position();
+  let src = code.as_slice();
+  let mut from = 0;
+  let (len, end_len) = loop {
+    let start = match memmem::find(&src[from..], b"<!--") {
+      Some(i) => from + i,
+      None => break (src.len(), 0),
+    };
+    let m = match COMMENT_END.find(&src[start + 4..]) {
+      Some(m) => m,
+      None => break (src.len(), 0),
+    };
+    let end = start + 4 + m.end();
+    if trimmed(&src[start + 4..start + 4 + m.start()]) == end_marker {
+      break (start, end - start);
+    };
+    from = end;
+  };
+  let raw_source = code.copy_and_shift(len);
+  code.shift(end_len);
+  NodeData::Opaque {
+    raw_source,
+    #[cfg(feature = "tracking")]
+    source_offset,
+    #[cfg(feature = "tracking")]
+    source_line,
   }
 }
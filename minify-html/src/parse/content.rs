@@ -1,36 +1,132 @@
+use crate::ast::AttrVal;
+use crate::ast::ElementClosingTag;
 use crate::ast::NodeData;
 use crate::entity::decode::decode_entities;
 use crate::parse::bang::parse_bang;
+use crate::parse::comment::ignored_region_end_marker;
 use crate::parse::comment::parse_comment;
+use crate::parse::comment::parse_ignored_region;
 use crate::parse::content::ContentType::*;
 use crate::parse::doctype::parse_doctype;
 use crate::parse::element::parse_element;
+use crate::parse::element::parse_element_content;
 use crate::parse::element::parse_tag;
 use crate::parse::element::peek_tag_name;
 use crate::parse::instruction::parse_instruction;
+use crate::parse::script::is_html5_shiv_src;
+use crate::parse::Checkpoint;
 use crate::parse::Code;
+use crate::parse::ParseOpts;
 use aho_corasick::AhoCorasick;
 use aho_corasick::AhoCorasickBuilder;
 use aho_corasick::AhoCorasickKind;
 use aho_corasick::MatchKind;
+use alloc::vec;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
 use minify_html_common::gen::codepoints::TAG_NAME_CHAR;
+use minify_html_common::lazy::Lazy;
 use minify_html_common::spec::tag::ns::Namespace;
 use minify_html_common::spec::tag::omission::can_omit_as_before;
 use minify_html_common::spec::tag::omission::can_omit_as_last_node;
 use minify_html_common::spec::tag::void::VOID_TAGS;
-use once_cell::sync::Lazy;
+use minify_html_common::whitespace::trimmed;
+
+// Whether a `display` declaration in a `style` attribute value is `none`, ignoring case, whitespace, and `!important`.
+fn style_has_display_none(style: &[u8]) -> bool {
+  style.split(|&c| c == b';').any(|decl| {
+    let colon = match decl.iter().position(|&c| c == b':') {
+      Some(i) => i,
+      None => return false,
+    };
+    let (prop, val) = (trimmed(&decl[..colon]), trimmed(&decl[colon + 1..]));
+    let val = match val.iter().position(|&c| c == b'!') {
+      Some(i) => trimmed(&val[..i]),
+      None => val,
+    };
+    prop.eq_ignore_ascii_case(b"display") && val.eq_ignore_ascii_case(b"none")
+  })
+}
+
+fn has_id(attributes: &HashMap<Vec<u8>, AttrVal>, children: &[NodeData]) -> bool {
+  attributes.contains_key(b"id".as_ref())
+    || children.iter().any(|c| match c {
+      NodeData::Element {
+        attributes,
+        children,
+        ..
+      } => has_id(attributes, children),
+      _ => false,
+    })
+}
+
+// `<template>` and `<datalist>` are also never rendered, but their contents are still used. Content with `hidden=until-found` is still shown by find-in-page and fragment navigation. Elements with an `id` in their subtree are kept too, as they could be referenced elsewhere, e.g. a `<symbol>` in a hidden SVG sprite sheet rendered by `<use href=#icon>`, or an element shown by a script.
+fn is_hidden(
+  ns: Namespace,
+  name: &[u8],
+  attributes: &HashMap<Vec<u8>, AttrVal>,
+  children: &[NodeData],
+) -> bool {
+  if ns == Namespace::Html && matches!(name, b"template" | b"datalist") {
+    return false;
+  };
+  let has_hidden_attr = ns == Namespace::Html
+    && attributes
+      .get(b"hidden".as_ref())
+      .is_some_and(|v| !trimmed(v.as_slice()).eq_ignore_ascii_case(b"until-found"));
+  let has_display_none = attributes
+    .get(b"style".as_ref())
+    .is_some_and(|v| style_has_display_none(v.as_slice()));
+  (has_hidden_attr || has_display_none) && !has_id(attributes, children)
+}
+
+// `<meta http-equiv=X-UA-Compatible>`, `<meta name=generator>`, and `<script>` polyfills for old versions of Internet Explorer, if removal is enabled for them.
+fn is_removed_legacy_element(
+  opts: &ParseOpts,
+  ns: Namespace,
+  name: &[u8],
+  attributes: &HashMap<Vec<u8>, AttrVal>,
+) -> bool {
+  let has = |attr: &[u8], val: &[u8]| {
+    attributes
+      .get(attr)
+      .is_some_and(|v| trimmed(&v.value).eq_ignore_ascii_case(val))
+  };
+  ns == Namespace::Html
+    && match name {
+      b"meta" => {
+        (opts.remove_x_ua_compatible_meta && has(b"http-equiv", b"x-ua-compatible"))
+          || (opts.remove_generator_meta && has(b"name", b"generator"))
+      }
+      b"script" => {
+        opts.remove_html5_shiv
+          && attributes
+            .get(b"src".as_ref())
+            .is_some_and(|v| is_html5_shiv_src(trimmed(&v.value)))
+      }
+      _ => false,
+    }
+}
 
 #[derive(Copy, Clone, Eq, PartialEq)]
 enum ContentType {
+  // A closing tag of the parent formatting element while the current element is also a formatting element e.g. `<b><i>(current position)</b>`. Browsers' adoption agency algorithm closes both, then reopens the current element after the parent.
+  AdoptedClosingTag,
   Bang,
+  // `</br>`, which browsers treat as `<br>`.
+  ClosingBrTag,
   ClosingTag,
   Comment,
+  // `</form>` without an open `form` element, which must be kept as it resets the form element pointer.
+  FormPointerClosingTag,
   Doctype,
   IgnoredTag,
   Instruction,
   MalformedLeftChevronSlash,
   OmittedClosingTag,
   OpeningTag,
+  // `</p>` without an open `p` element, which browsers treat as `<p></p>`.
+  StrayClosingPTag,
   Text,
   // Pebble, Mustache, Django, Go, Jinja, Twix, Nunjucks, Handlebars, Liquid.
   OpaqueBraceBrace,
@@ -87,6 +183,57 @@ fn maybe_ignore_html_head_body(
   }
 }
 
+// Elements that are reconstructed by browsers when mis-nested.
+pub(crate) fn is_formatting_element(name: &[u8]) -> bool {
+  matches!(
+    name,
+    b"a"
+      | b"b"
+      | b"big"
+      | b"code"
+      | b"em"
+      | b"font"
+      | b"i"
+      | b"nobr"
+      | b"s"
+      | b"small"
+      | b"strike"
+      | b"strong"
+      | b"tt"
+      | b"u"
+  )
+}
+
+// Whether `name` is an open element other than the innermost one, without crossing a scope boundary.
+fn is_ancestor_in_scope(open_elements: &[Vec<u8>], name: &[u8]) -> bool {
+  for elem in open_elements.iter().rev().skip(1) {
+    if elem == name {
+      return true;
+    };
+    if matches!(
+      elem.as_slice(),
+      b"applet"
+        | b"caption"
+        | b"html"
+        | b"marquee"
+        | b"object"
+        | b"table"
+        | b"td"
+        | b"th"
+        | b"template"
+    ) {
+      return false;
+    };
+  }
+  false
+}
+
+fn is_in_template(code: &Code) -> bool {
+  code.open_elements.iter().any(|e| e == b"template")
+}
+
+// Matchers are built from static patterns, so building them never fails.
+#[allow(clippy::unwrap_used)]
 fn build_content_type_matcher(
   with_opaque_brace: bool,
   with_opaque_chevron_percent: bool,
@@ -155,24 +302,28 @@ static CONTENT_TYPE_MATCHER_OPAQUE_CP: Lazy<(AhoCorasick, Vec<ContentType>)> =
 static CONTENT_TYPE_MATCHER_OPAQUE_BRACE_CP: Lazy<(AhoCorasick, Vec<ContentType>)> =
   Lazy::new(|| build_content_type_matcher(true, true));
 
+#[allow(clippy::unwrap_used)]
 static CLOSING_BRACE_BRACE: Lazy<AhoCorasick> = Lazy::new(|| {
   AhoCorasickBuilder::new()
     .kind(Some(AhoCorasickKind::DFA))
     .build(["}}"])
     .unwrap()
 });
+#[allow(clippy::unwrap_used)]
 static CLOSING_BRACE_HASH: Lazy<AhoCorasick> = Lazy::new(|| {
   AhoCorasickBuilder::new()
     .kind(Some(AhoCorasickKind::DFA))
     .build(["#}"])
     .unwrap()
 });
+#[allow(clippy::unwrap_used)]
 static CLOSING_BRACE_PERCENT: Lazy<AhoCorasick> = Lazy::new(|| {
   AhoCorasickBuilder::new()
     .kind(Some(AhoCorasickKind::DFA))
     .build(["%}"])
     .unwrap()
 });
+#[allow(clippy::unwrap_used)]
 static CLOSING_CHEVRON_PERCENT: Lazy<AhoCorasick> = Lazy::new(|| {
   AhoCorasickBuilder::new()
     .kind(Some(AhoCorasickKind::DFA))
@@ -180,6 +331,20 @@ static CLOSING_CHEVRON_PERCENT: Lazy<AhoCorasick> = Lazy::new(|| {
     .unwrap()
 });
 
+// Unterminated constructs at EOF are passed through as is, as we can't know where they were meant to end, and closing them ourselves could change their meaning or make the output longer than the source.
+pub fn parse_unterminated(code: &mut Code, cp: Checkpoint) -> NodeData {
+  code.restore_checkpoint(cp);
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position();
+  NodeData::Opaque {
+    raw_source: code.copy_and_shift(code.rem()),
+    #[cfg(feature = "tracking")]
+    source_offset,
+    #[cfg(feature = "tracking")]
+    source_line,
+  }
+}
+
 pub struct ParsedContent {
   pub children: Vec<NodeData>,
   pub closing_tag_omitted: bool,
@@ -212,19 +377,31 @@ pub fn parse_content(
     // Due to dropped malformed code, it's possible for two or more text nodes to be contiguous. Ensure they always get merged into one.
     // NOTE: Even though bangs/comments/etc. have no effect on layout, they still split text (e.g. `&am<!-- -->p`).
     if text_len > 0 {
+      #[cfg(feature = "tracking")]
+      let (source_offset, source_line) = code.source_position();
       let text = decode_entities(code.slice_and_shift(text_len), false);
       match nodes.last_mut() {
-        Some(NodeData::Text { value }) => value.extend_from_slice(&text),
-        _ => nodes.push(NodeData::Text { value: text }),
+        Some(NodeData::Text { value, .. }) => value.extend_from_slice(&text),
+        _ => nodes.push(NodeData::Text {
+          value: text,
+          #[cfg(feature = "tracking")]
+          source_offset,
+          #[cfg(feature = "tracking")]
+          source_line,
+        }),
       };
     };
     // Check using Parsing.md tag rules.
     #[allow(clippy::if_same_then_else)] // For readability.
     if typ == OpeningTag || typ == ClosingTag {
       let name = peek_tag_name(code);
+      let is_html_form = ns == Namespace::Html && name == b"form" && !is_in_template(code);
       if typ == OpeningTag {
         debug_assert!(!name.is_empty());
-        if can_omit_as_before(parent, &name) {
+        if is_html_form && code.form_element_pointer {
+          // Browsers ignore a form start tag while the form element pointer is set.
+          typ = IgnoredTag;
+        } else if can_omit_as_before(parent, &name) {
           // The upcoming opening tag implicitly closes the current element e.g. `<tr><td>(current position)<td>`.
           typ = OmittedClosingTag;
         };
@@ -235,35 +412,170 @@ pub fn parse_content(
         // The upcoming closing tag implicitly closes the current element e.g. `<tr><td>(current position)</tr>`.
         // This DOESN'T handle when grandparent doesn't exist (represented by an empty slice). However, in that case it's irrelevant, as it would mean we would be at EOF, and our parser simply auto-closes everything anyway. (Normally we'd have to determine if `<p>Hello` is an error or allowed.)
         typ = OmittedClosingTag;
+      } else if ns == Namespace::Html && name == b"br" {
+        typ = ClosingBrTag;
       } else if VOID_TAGS.contains(name.as_slice()) {
         // Closing tag for void element, drop.
         typ = IgnoredTag;
-      } else if parent.is_empty() || parent != name.as_slice() {
+      } else if !parent.is_empty() && parent == name.as_slice() {
+        // Matching closing tag.
+      } else if ns == Namespace::Html
+        && grandparent == name.as_slice()
+        && is_formatting_element(grandparent)
+        && is_formatting_element(parent)
+      {
+        typ = AdoptedClosingTag;
+      } else if !matches!(name.as_slice(), b"body" | b"head" | b"html")
+        && is_ancestor_in_scope(&code.open_elements, &name)
+      {
+        // The upcoming closing tag of an ancestor implicitly closes the current element e.g. `<div><span>(current position)</div>`.
+        typ = OmittedClosingTag;
+      } else if ns == Namespace::Html && name == b"p" {
+        typ = StrayClosingPTag;
+      } else if is_html_form && code.form_element_pointer {
+        typ = FormPointerClosingTag;
+      } else {
         // Closing tag mismatch, drop.
         typ = IgnoredTag;
       };
       typ = maybe_ignore_html_head_body(code, typ, parent, &name);
+      if is_html_form {
+        match typ {
+          OpeningTag => code.form_element_pointer = true,
+          ClosingTag | FormPointerClosingTag => code.form_element_pointer = false,
+          _ => {}
+        };
+      };
     };
     match typ {
       Text => break,
-      OpeningTag => nodes.push(parse_element(code, ns, parent)),
+      OpeningTag => {
+        if code
+          .opts
+          .max_depth
+          .filter(|&max| code.open_elements.len() >= max)
+          .is_some()
+        {
+          // Stop parsing entirely instead of recursing any deeper.
+          code.max_depth_exceeded = true;
+          code.shift(code.rem());
+          break;
+        };
+        let elem = parse_element(code, ns, parent);
+        // Drop removed elements entirely; any following text will be merged into the preceding text node, so the whitespace around the element collapses as if it was never there.
+        let removed = match &elem {
+          NodeData::Element {
+            name,
+            namespace,
+            attributes,
+            children,
+            ..
+          } => {
+            (code.opts.remove_hidden_elements && is_hidden(*namespace, name, attributes, children))
+              || is_removed_legacy_element(&code.opts, *namespace, name, attributes)
+              || code
+                .opts
+                .remove_elements
+                .iter()
+                .any(|sel| sel.matches(name, attributes))
+          }
+          _ => false,
+        };
+        if !removed {
+          nodes.push(elem);
+        };
+        if let Some((name, attributes)) = code.reopen.take() {
+          let start = code.take_checkpoint();
+          let elem = parse_element_content(code, ns, parent, name, attributes, start);
+          // Browsers only create the reopened element if there's content before its closing tag.
+          if !matches!(&elem, NodeData::Element { children, .. } if children.is_empty()) {
+            nodes.push(elem);
+          };
+        };
+      }
+      AdoptedClosingTag => {
+        code.adopted = true;
+        break;
+      }
       ClosingTag => {
         closing_tag_omitted = false;
         break;
       }
-      Instruction => nodes.push(parse_instruction(code)),
-      Bang => nodes.push(parse_bang(code)),
-      Comment => nodes.push(parse_comment(code)),
-      Doctype => nodes.push(parse_doctype(code)),
-      MalformedLeftChevronSlash => code.shift(match memchr::memchr(b'>', code.as_slice()) {
-        Some(m) => m + 1,
-        None => code.rem(),
-      }),
+      Instruction | Bang | Comment | Doctype => {
+        let cp = code.take_checkpoint();
+        let node = match typ {
+          Instruction => parse_instruction(code),
+          Bang => parse_bang(code, ns),
+          Comment => parse_comment(code),
+          Doctype => parse_doctype(code),
+          _ => unreachable!(),
+        };
+        let node = match node {
+          NodeData::Bang { ended: false, .. }
+          | NodeData::Comment { ended: false, .. }
+          | NodeData::Doctype { ended: false, .. }
+          | NodeData::Instruction { ended: false, .. } => parse_unterminated(code, cp),
+          node => node,
+        };
+        let ignored_region_end = match &node {
+          NodeData::Comment { code: comment, .. } => ignored_region_end_marker(code, comment),
+          _ => None,
+        };
+        nodes.push(match ignored_region_end {
+          Some(end_marker) => parse_ignored_region(code, &end_marker),
+          None => node,
+        });
+      }
+      MalformedLeftChevronSlash => match memchr::memchr(b'>', code.as_slice()) {
+        Some(m) => code.shift(m + 1),
+        None => {
+          let cp = code.take_checkpoint();
+          nodes.push(parse_unterminated(code, cp));
+        }
+      },
       OmittedClosingTag => {
         closing_tag_omitted = true;
         break;
       }
-      IgnoredTag => drop(parse_tag(code)),
+      IgnoredTag => {
+        let cp = code.take_checkpoint();
+        if !parse_tag(code).ended {
+          nodes.push(parse_unterminated(code, cp));
+        };
+      }
+      FormPointerClosingTag => {
+        #[cfg(feature = "tracking")]
+        let (source_offset, source_line) = code.source_position();
+        drop(parse_tag(code));
+        nodes.push(NodeData::Opaque {
+          raw_source: b"</form>".to_vec(),
+          #[cfg(feature = "tracking")]
+          source_offset,
+          #[cfg(feature = "tracking")]
+          source_line,
+        });
+      }
+      ClosingBrTag | StrayClosingPTag => {
+        #[cfg(feature = "tracking")]
+        let (source_offset, source_line) = code.source_position();
+        let name = parse_tag(code).name;
+        nodes.push(NodeData::Element {
+          attributes: HashMap::default(),
+          children: Vec::new(),
+          closing_tag: if typ == ClosingBrTag {
+            ElementClosingTag::Void
+          } else {
+            ElementClosingTag::Present
+          },
+          name,
+          namespace: ns,
+          next_sibling_element_name: Vec::new(),
+          #[cfg(feature = "tracking")]
+          source_offset,
+          #[cfg(feature = "tracking")]
+          source_line,
+        });
+      }
       e @ (OpaqueBraceBrace | OpaqueBraceHash | OpaqueBracePercent | OpaqueChevronPercent) => {
         let closing_matcher = match e {
           OpaqueBraceBrace => &CLOSING_BRACE_BRACE,
@@ -272,14 +584,20 @@ pub fn parse_content(
           OpaqueChevronPercent => &CLOSING_CHEVRON_PERCENT,
           _ => unreachable!(),
         };
+        #[cfg(feature = "tracking")]
+        let (source_offset, source_line) = code.source_position();
         // We must skip past opening as otherwise something like `{%}` matches both opening and closing delimiters.
         let len = match closing_matcher.find(&code.as_slice()[2..]) {
           // It's probably safer to assume it's implicitly closed by EOF instead of reinterpreting as literal HTML text and possibly mangling template code.
-          Some(m) => m.end(),
+          Some(m) => 2 + m.end(),
           None => code.rem(),
         };
         nodes.push(NodeData::Opaque {
           raw_source: code.copy_and_shift(len),
+          #[cfg(feature = "tracking")]
+          source_offset,
+          #[cfg(feature = "tracking")]
+          source_line,
         });
       }
     };
@@ -4,6 +4,8 @@ use memchr::memchr;
 use minify_html_common::gen::codepoints::WHITESPACE;
 
 pub fn parse_doctype(code: &mut Code) -> NodeData {
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position();
   debug_assert!(code.as_slice()[..9].eq_ignore_ascii_case(b"<!doctype"));
   code.shift(9);
   code.shift_while_in_lookup(WHITESPACE);
@@ -19,5 +21,9 @@ pub fn parse_doctype(code: &mut Code) -> NodeData {
   NodeData::Doctype {
     legacy: data,
     ended: matched > 0,
+    #[cfg(feature = "tracking")]
+    source_offset,
+    #[cfg(feature = "tracking")]
+    source_line,
   }
 }
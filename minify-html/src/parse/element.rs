@@ -4,13 +4,21 @@ use crate::ast::NodeData;
 use crate::ast::ScriptOrStyleLang;
 use crate::entity::decode::decode_entities;
 use crate::parse::content::parse_content;
+use crate::parse::content::parse_unterminated;
 use crate::parse::content::ParsedContent;
+use crate::parse::plaintext::parse_plaintext_content;
 use crate::parse::script::parse_script_content;
 use crate::parse::style::parse_style_content;
 use crate::parse::textarea::parse_textarea_content;
 use crate::parse::title::parse_title_content;
+use crate::parse::xmp::parse_xmp_content;
+use crate::parse::Checkpoint;
 use crate::parse::Code;
-use ahash::AHashMap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::fmt::Formatter;
+use hashbrown::HashMap;
 use minify_html_common::gen::codepoints::ATTR_QUOTE;
 use minify_html_common::gen::codepoints::DOUBLE_QUOTE;
 use minify_html_common::gen::codepoints::NOT_UNQUOTED_ATTR_VAL_CHAR;
@@ -20,11 +28,9 @@ use minify_html_common::gen::codepoints::WHITESPACE;
 use minify_html_common::gen::codepoints::WHITESPACE_OR_SLASH;
 use minify_html_common::gen::codepoints::WHITESPACE_OR_SLASH_OR_EQUALS_OR_RIGHT_CHEVRON;
 use minify_html_common::spec::script::JAVASCRIPT_MIME_TYPES;
+use minify_html_common::spec::tag::ns::namespace_for;
 use minify_html_common::spec::tag::ns::Namespace;
 use minify_html_common::spec::tag::void::VOID_TAGS;
-use std::fmt::Debug;
-use std::fmt::Formatter;
-use std::str::from_utf8;
 
 fn parse_tag_name(code: &mut Code) -> Vec<u8> {
   debug_assert!(code.as_slice().starts_with(b"<"));
@@ -45,37 +51,42 @@ pub fn peek_tag_name(code: &mut Code) -> Vec<u8> {
 // Derive Eq for testing.
 #[derive(Eq, PartialEq)]
 pub struct ParsedTag {
-  pub attributes: AHashMap<Vec<u8>, AttrVal>,
+  pub attributes: HashMap<Vec<u8>, AttrVal>,
   pub name: Vec<u8>,
   pub self_closing: bool,
+  // False if EOF was reached before the closing `>`.
+  pub ended: bool,
 }
 
 impl Debug for ParsedTag {
-  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-    f.write_fmt(format_args!("<{}", from_utf8(&self.name).unwrap()))?;
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    f.write_fmt(format_args!("<{}", String::from_utf8_lossy(&self.name)))?;
     let mut attrs = self.attributes.iter().collect::<Vec<_>>();
     attrs.sort_unstable_by(|a, b| a.0.cmp(b.0));
     for (n, v) in attrs {
-      f.write_fmt(format_args!(" {}={:?}", from_utf8(n).unwrap(), v))?;
+      f.write_fmt(format_args!(" {}={:?}", String::from_utf8_lossy(n), v))?;
     }
     if self.self_closing {
       f.write_str(" />")?;
     };
-    std::fmt::Result::Ok(())
+    core::fmt::Result::Ok(())
   }
 }
 
 // While not valid, attributes in closing tags still need to be parsed (and then discarded) as attributes e.g. `</div x=">">`, which is why this function is used for both opening and closing tags.
-// TODO Use generics to create version that doesn't create an AHashMap.
+// TODO Use generics to create version that doesn't create an HashMap.
 pub fn parse_tag(code: &mut Code) -> ParsedTag {
   let elem_name = parse_tag_name(code);
-  let mut attributes = AHashMap::default();
+  let mut attributes = HashMap::default();
   let self_closing;
+  let ended;
   loop {
     // At the beginning of this loop, the last parsed unit was either the tag name or an attribute (including its value, if it had one).
     let last = code.shift_while_in_lookup(WHITESPACE_OR_SLASH);
-    if code.at_end() || code.shift_if_next(b'>') {
+    let closed = code.shift_if_next(b'>');
+    if closed || code.at_end() {
       self_closing = last.filter(|&c| c == b'/').is_some();
+      ended = closed;
       // End of tag.
       break;
     };
@@ -127,24 +138,28 @@ pub fn parse_tag(code: &mut Code) -> ParsedTag {
     attributes,
     name: elem_name,
     self_closing,
+    ended,
   }
 }
 
 // `<` must be next. `parent` should be an empty slice if it doesn't exist.
 pub fn parse_element(code: &mut Code, ns: Namespace, parent: &[u8]) -> NodeData {
+  let cp = code.take_checkpoint();
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position_at(cp);
   let ParsedTag {
     name: elem_name,
     attributes,
     self_closing,
+    ended,
   } = parse_tag(code);
-
-  // Embedded svg tags are immediately in the svg namespace and must be parsed as such.
-  let ns = if elem_name == b"svg" {
-    Namespace::Svg
-  } else {
-    ns
+  if !ended {
+    return parse_unterminated(code, cp);
   };
 
+  // Embedded svg and math tags are immediately in their respective namespaces and must be parsed as such.
+  let ns = namespace_for(&elem_name).unwrap_or(ns);
+
   // Only foreign elements can be self closed.
   if self_closing && ns != Namespace::Html {
     return NodeData::Element {
@@ -154,6 +169,10 @@ pub fn parse_element(code: &mut Code, ns: Namespace, parent: &[u8]) -> NodeData
       name: elem_name,
       namespace: ns,
       next_sibling_element_name: Vec::new(),
+      #[cfg(feature = "tracking")]
+      source_offset,
+      #[cfg(feature = "tracking")]
+      source_line,
     };
   };
   if VOID_TAGS.contains(elem_name.as_slice()) {
@@ -164,9 +183,28 @@ pub fn parse_element(code: &mut Code, ns: Namespace, parent: &[u8]) -> NodeData
       name: elem_name,
       namespace: ns,
       next_sibling_element_name: Vec::new(),
+      #[cfg(feature = "tracking")]
+      source_offset,
+      #[cfg(feature = "tracking")]
+      source_line,
     };
   };
 
+  parse_element_content(code, ns, parent, elem_name, attributes, cp)
+}
+
+// Parses the content and closing tag of a non-void element whose opening tag has already been consumed, and which started at `start`. Also used to reopen a formatting element that wasn't in the source; see `Code::reopen`.
+pub fn parse_element_content(
+  code: &mut Code,
+  ns: Namespace,
+  parent: &[u8],
+  elem_name: Vec<u8>,
+  attributes: HashMap<Vec<u8>, AttrVal>,
+  #[cfg_attr(not(feature = "tracking"), allow(unused_variables))] start: Checkpoint,
+) -> NodeData {
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position_at(start);
+  code.open_elements.push(elem_name.clone());
   let ParsedContent {
     closing_tag_omitted,
     children,
@@ -183,24 +221,36 @@ pub fn parse_element(code: &mut Code, ns: Namespace, parent: &[u8]) -> NodeData
     (_, b"style") => parse_style_content(code),
     (Namespace::Html, b"textarea") => parse_textarea_content(code),
     (Namespace::Html, b"title") => parse_title_content(code),
+    (Namespace::Html, b"plaintext") => parse_plaintext_content(code),
+    (Namespace::Html, b"xmp") => parse_xmp_content(code),
     _ => parse_content(code, ns, parent, &elem_name),
   };
 
-  if !closing_tag_omitted {
+  code.open_elements.pop();
+
+  let closing_tag = if code.adopted {
+    // Browsers close this element where its parent's closing tag is, so it must be closed explicitly, and then reopen it after its parent.
+    code.adopted = false;
+    code.reopen = Some((elem_name.clone(), attributes.clone()));
+    ElementClosingTag::Present
+  } else if closing_tag_omitted {
+    ElementClosingTag::Omitted
+  } else {
     let closing_tag = parse_tag(code);
     debug_assert_eq!(closing_tag.name, elem_name);
+    ElementClosingTag::Present
   };
 
   NodeData::Element {
     attributes,
     children,
-    closing_tag: if closing_tag_omitted {
-      ElementClosingTag::Omitted
-    } else {
-      ElementClosingTag::Present
-    },
+    closing_tag,
     name: elem_name,
     namespace: ns,
     next_sibling_element_name: Vec::new(),
+    #[cfg(feature = "tracking")]
+    source_offset,
+    #[cfg(feature = "tracking")]
+    source_line,
   }
 }
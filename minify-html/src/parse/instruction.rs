@@ -3,8 +3,10 @@ use crate::parse::Code;
 use aho_corasick::AhoCorasick;
 use aho_corasick::AhoCorasickBuilder;
 use aho_corasick::AhoCorasickKind;
-use once_cell::sync::Lazy;
+use minify_html_common::lazy::Lazy;
 
+// Matchers are built from static patterns, so building them never fails.
+#[allow(clippy::unwrap_used)]
 static INSTRUCTION_END: Lazy<AhoCorasick> = Lazy::new(|| {
   AhoCorasickBuilder::new()
     .kind(Some(AhoCorasickKind::DFA))
@@ -13,6 +15,8 @@ static INSTRUCTION_END: Lazy<AhoCorasick> = Lazy::new(|| {
 });
 
 pub fn parse_instruction(code: &mut Code) -> NodeData {
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position();
   debug_assert!(code.as_slice().starts_with(b"<?"));
   code.shift(2);
   let (len, matched) = match INSTRUCTION_END.find(code.as_slice()) {
@@ -25,5 +29,9 @@ pub fn parse_instruction(code: &mut Code) -> NodeData {
   NodeData::Instruction {
     code: data,
     ended: matched > 0,
+    #[cfg(feature = "tracking")]
+    source_offset,
+    #[cfg(feature = "tracking")]
+    source_line,
   }
 }
@@ -1,4 +1,11 @@
+use crate::ast::AttrVal;
+use crate::cfg::Cfg;
+use crate::selector::SimpleSelector;
+use aho_corasick::AhoCorasick;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
 use minify_html_common::gen::codepoints::Lookup;
+use minify_html_common::gen::codepoints::WHITESPACE_OR_SLASH;
 
 pub mod bang;
 pub mod comment;
@@ -6,19 +13,52 @@ pub mod content;
 pub mod doctype;
 pub mod element;
 pub mod instruction;
+pub mod plaintext;
 pub mod script;
 pub mod style;
 #[cfg(test)]
 mod tests;
 pub mod textarea;
 pub mod title;
+pub mod xmp;
 
 #[derive(Default, Clone, Debug)]
 pub struct ParseOpts {
   pub treat_brace_as_opaque: bool,
   pub treat_chevron_percent_as_opaque: bool,
+  pub remove_elements: Vec<SimpleSelector>,
+  pub remove_hidden_elements: bool,
+  pub remove_x_ua_compatible_meta: bool,
+  pub remove_generator_meta: bool,
+  pub remove_html5_shiv: bool,
+  pub max_depth: Option<usize>,
+  // The start and end comment contents of regions to keep as is, if enabled.
+  pub ignored_region_markers: Option<(Vec<u8>, Vec<u8>)>,
 }
 
+impl ParseOpts {
+  pub fn from_cfg(cfg: &Cfg) -> ParseOpts {
+    ParseOpts {
+      treat_brace_as_opaque: cfg.preserve_brace_template_syntax,
+      treat_chevron_percent_as_opaque: cfg.preserve_chevron_percent_template_syntax,
+      remove_elements: cfg.remove_elements.clone(),
+      remove_hidden_elements: cfg.remove_hidden_elements,
+      remove_x_ua_compatible_meta: cfg.remove_x_ua_compatible_meta,
+      remove_generator_meta: cfg.remove_generator_meta,
+      remove_html5_shiv: cfg.remove_html5_shiv,
+      max_depth: cfg.max_depth,
+      ignored_region_markers: cfg.preserve_ignored_regions.then(|| {
+        cfg
+          .ignored_region_markers
+          .clone()
+          .unwrap_or_else(|| (b"htmlmin:ignore".to_vec(), b"htmlmin:endignore".to_vec()))
+      }),
+    }
+  }
+}
+
+type Attributes = HashMap<Vec<u8>, AttrVal>;
+
 pub struct Code<'c> {
   code: &'c [u8],
   next: usize,
@@ -28,6 +68,21 @@ pub struct Code<'c> {
   pub seen_head_open: bool,
   pub seen_head_close: bool,
   pub seen_body_open: bool,
+
+  // Names of the elements currently being parsed, innermost last.
+  pub open_elements: Vec<Vec<u8>>,
+  // Set when the innermost element was closed by a mis-nested closing tag of its parent formatting element; see `AdoptedClosingTag`.
+  pub adopted: bool,
+  // A formatting element to reopen as the next sibling of its just-closed parent e.g. the second `<i>` in `<b><i>a</b>b</i>`.
+  pub reopen: Option<(Vec<u8>, Attributes)>,
+  // The spec's form element pointer: set from a `<form>` until a `</form>`, even if the form element is implicitly closed before then. Both are ignored inside `<template>`.
+  pub form_element_pointer: bool,
+  // Set if an element nested more deeply than `ParseOpts::max_depth` was found, in which case it and the rest of the source were dropped.
+  pub max_depth_exceeded: bool,
+
+  // The last position returned by `source_position_at`, so that newlines are only counted once when positions are requested in source order.
+  #[cfg(feature = "tracking")]
+  last_position: (usize, u32),
 }
 
 #[derive(Copy, Clone)]
@@ -43,9 +98,17 @@ impl<'c> Code<'c> {
       seen_head_open: false,
       seen_head_close: false,
       seen_body_open: false,
+      open_elements: Vec::new(),
+      adopted: false,
+      reopen: None,
+      form_element_pointer: false,
+      max_depth_exceeded: false,
+      #[cfg(feature = "tracking")]
+      last_position: (0, 1),
     }
   }
 
+  #[cfg(any(feature = "std", test))]
   pub fn new(code: &[u8]) -> Code {
     Code::new_with_opts(code, ParseOpts::default())
   }
@@ -160,4 +223,34 @@ impl<'c> Code<'c> {
   pub fn rem(&self) -> usize {
     self.code.len() - self.next
   }
+
+  // Returns the offset of the closing tag that ends raw text, given a matcher for `</name`. Browsers only end raw text at a closing tag with exactly the element's name, so a match must be followed by whitespace, `/`, or `>`; `</xmpl>` doesn't end an `<xmp>`.
+  pub fn find_raw_text_end(&self, end: &AhoCorasick) -> Option<usize> {
+    let src = self.as_slice();
+    end
+      .find_iter(src)
+      .find(|m| {
+        src
+          .get(m.end())
+          .is_some_and(|&c| WHITESPACE_OR_SLASH[c] || c == b'>')
+      })
+      .map(|m| m.start())
+  }
+
+  // Returns the byte offset and 1-based line number of the next character.
+  #[cfg(feature = "tracking")]
+  pub fn source_position(&mut self) -> (usize, u32) {
+    self.source_position_at(self.take_checkpoint())
+  }
+
+  #[cfg(feature = "tracking")]
+  pub fn source_position_at(&mut self, cp: Checkpoint) -> (usize, u32) {
+    let (mut from, mut line) = self.last_position;
+    if cp.0 < from {
+      (from, line) = (0, 1);
+    };
+    line += memchr::memchr_iter(b'\n', &self.code[from..cp.0]).count() as u32;
+    self.last_position = (cp.0, line);
+    self.last_position
+  }
 }
@@ -0,0 +1,21 @@
+use crate::ast::NodeData;
+use crate::parse::content::ParsedContent;
+use crate::parse::Code;
+use alloc::vec;
+
+// Everything after a `<plaintext>` opening tag is raw text, including anything that looks like a closing tag, so the element and all its ancestors can never be closed.
+pub fn parse_plaintext_content(code: &mut Code) -> ParsedContent {
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position();
+  let len = code.rem();
+  ParsedContent {
+    closing_tag_omitted: true,
+    children: vec![NodeData::Opaque {
+      raw_source: code.copy_and_shift(len),
+      #[cfg(feature = "tracking")]
+      source_offset,
+      #[cfg(feature = "tracking")]
+      source_line,
+    }],
+  }
+}
@@ -5,8 +5,11 @@ use crate::parse::Code;
 use aho_corasick::AhoCorasick;
 use aho_corasick::AhoCorasickBuilder;
 use aho_corasick::AhoCorasickKind;
-use once_cell::sync::Lazy;
+use alloc::vec;
+use minify_html_common::lazy::Lazy;
 
+// Matchers are built from static patterns, so building them never fails.
+#[allow(clippy::unwrap_used)]
 static END: Lazy<AhoCorasick> = Lazy::new(|| {
   AhoCorasickBuilder::new()
     .ascii_case_insensitive(true)
@@ -16,15 +19,33 @@ static END: Lazy<AhoCorasick> = Lazy::new(|| {
 });
 
 pub fn parse_style_content(code: &mut Code) -> ParsedContent {
-  let (len, closing_tag_omitted) = match END.find(code.as_slice()) {
-    Some(m) => (m.start(), false),
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position();
+  let (len, closing_tag_omitted) = match code.find_raw_text_end(&END) {
+    Some(len) => (len, false),
     None => (code.rem(), true),
   };
+  let code = code.copy_and_shift(len);
   ParsedContent {
     closing_tag_omitted,
-    children: vec![NodeData::ScriptOrStyleContent {
-      code: code.copy_and_shift(len),
-      lang: ScriptOrStyleLang::CSS,
+    children: vec![if closing_tag_omitted {
+      // Unterminated at EOF, so pass it through as is; see `parse_unterminated`.
+      NodeData::Opaque {
+        raw_source: code,
+        #[cfg(feature = "tracking")]
+        source_offset,
+        #[cfg(feature = "tracking")]
+        source_line,
+      }
+    } else {
+      NodeData::ScriptOrStyleContent {
+        code,
+        lang: ScriptOrStyleLang::CSS,
+        #[cfg(feature = "tracking")]
+        source_offset,
+        #[cfg(feature = "tracking")]
+        source_line,
+      }
     }],
   }
 }
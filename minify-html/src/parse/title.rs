@@ -4,10 +4,13 @@ use crate::entity::decode::decode_entities;
 use crate::parse::content::ParsedContent;
 use crate::parse::Code;
 use crate::tag::TAG_TITLE_END;
+use alloc::vec;
 
 pub fn parse_title_content(code: &mut Code) -> ParsedContent {
-  let (len, closing_tag_omitted) = match TAG_TITLE_END.find(code.as_slice()) {
-    Some(m) => (m.start(), false),
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position();
+  let (len, closing_tag_omitted) = match code.find_raw_text_end(&TAG_TITLE_END) {
+    Some(len) => (len, false),
     None => (code.rem(), true),
   };
   ParsedContent {
@@ -15,6 +18,10 @@ pub fn parse_title_content(code: &mut Code) -> ParsedContent {
     children: vec![NodeData::RcdataContent {
       typ: RcdataContentType::Title,
       text: decode_entities(code.slice_and_shift(len), false),
+      #[cfg(feature = "tracking")]
+      source_offset,
+      #[cfg(feature = "tracking")]
+      source_line,
     }],
   }
 }
@@ -0,0 +1,38 @@
+use crate::ast::NodeData;
+use crate::parse::content::ParsedContent;
+use crate::parse::Code;
+use aho_corasick::AhoCorasick;
+use aho_corasick::AhoCorasickBuilder;
+use aho_corasick::AhoCorasickKind;
+use alloc::vec;
+use minify_html_common::lazy::Lazy;
+
+// Matchers are built from static patterns, so building them never fails.
+#[allow(clippy::unwrap_used)]
+static END: Lazy<AhoCorasick> = Lazy::new(|| {
+  AhoCorasickBuilder::new()
+    .ascii_case_insensitive(true)
+    .kind(Some(AhoCorasickKind::DFA))
+    .build(["</xmp"])
+    .unwrap()
+});
+
+// `<xmp>` content is raw text, so it's kept as is without decoding entities.
+pub fn parse_xmp_content(code: &mut Code) -> ParsedContent {
+  #[cfg(feature = "tracking")]
+  let (source_offset, source_line) = code.source_position();
+  let (len, closing_tag_omitted) = match code.find_raw_text_end(&END) {
+    Some(len) => (len, false),
+    None => (code.rem(), true),
+  };
+  ParsedContent {
+    closing_tag_omitted,
+    children: vec![NodeData::Opaque {
+      raw_source: code.copy_and_shift(len),
+      #[cfg(feature = "tracking")]
+      source_offset,
+      #[cfg(feature = "tracking")]
+      source_line,
+    }],
+  }
+}
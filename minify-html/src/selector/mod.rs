@@ -0,0 +1,262 @@
+use crate::ast::AttrVal;
+use crate::ast::NodeData;
+use alloc::vec::Vec;
+use core::fmt::Display;
+use core::fmt::Formatter;
+use core::str::FromStr;
+use hashbrown::HashMap;
+use minify_html_common::whitespace::trimmed;
+
+#[cfg(test)]
+mod tests;
+
+/// A compound CSS selector without combinators, such as `script[data-env=dev]`, `div.debug-panel`, or `#banner`.
+///
+/// Supported forms are a tag name (or `*`), `#id`, `.class`, `[attr]`, and `[attr=value]`, where the value can optionally be quoted. An element matches if it matches every part of the selector.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SimpleSelector {
+  /// Lowercase tag name, or `None` to match any element.
+  pub tag: Option<Vec<u8>>,
+  pub ids: Vec<Vec<u8>>,
+  pub classes: Vec<Vec<u8>>,
+  /// Lowercase attribute names, with the exact value to match if any.
+  pub attributes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+}
+
+impl SimpleSelector {
+  pub fn matches(&self, tag: &[u8], attributes: &HashMap<Vec<u8>, AttrVal>) -> bool {
+    if self
+      .tag
+      .as_ref()
+      .filter(|t| !t.eq_ignore_ascii_case(tag))
+      .is_some()
+    {
+      return false;
+    };
+    if !self.ids.iter().all(|id| {
+      attributes
+        .get(b"id".as_ref())
+        .filter(|v| v.as_slice() == id.as_slice())
+        .is_some()
+    }) {
+      return false;
+    };
+    if !self.classes.is_empty() {
+      let class = match attributes.get(b"class".as_ref()) {
+        Some(class) => class,
+        None => return false,
+      };
+      let class_names = class
+        .as_slice()
+        .split(|c| c.is_ascii_whitespace())
+        .filter(|c| !c.is_empty());
+      if !self
+        .classes
+        .iter()
+        .all(|c| class_names.clone().any(|n| n == c.as_slice()))
+      {
+        return false;
+      };
+    };
+    self
+      .attributes
+      .iter()
+      .all(|(name, value)| match (attributes.get(name), value) {
+        (None, _) => false,
+        (Some(_), None) => true,
+        (Some(actual), Some(expected)) => actual.as_slice() == expected.as_slice(),
+      })
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseSelectorError {
+  pub message: &'static str,
+  pub position: usize,
+}
+
+impl Display for ParseSelectorError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+    write!(f, "{} at position {}", self.message, self.position)
+  }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseSelectorError {}
+
+fn is_ident_char(c: u8) -> bool {
+  c.is_ascii_alphanumeric() || c == b'-' || c == b'_' || c >= 0x80
+}
+
+fn parse_ident(src: &[u8], pos: &mut usize) -> Result<Vec<u8>, ParseSelectorError> {
+  let start = *pos;
+  while src.get(*pos).filter(|&&c| is_ident_char(c)).is_some() {
+    *pos += 1;
+  }
+  if *pos == start {
+    return Err(ParseSelectorError {
+      message: "expected name",
+      position: start,
+    });
+  };
+  Ok(src[start..*pos].to_vec())
+}
+
+impl FromStr for SimpleSelector {
+  type Err = ParseSelectorError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let src = trimmed(s.as_bytes());
+    if src.is_empty() {
+      return Err(ParseSelectorError {
+        message: "empty selector",
+        position: 0,
+      });
+    };
+    let mut sel = SimpleSelector::default();
+    let mut pos = 0;
+    if src[0] == b'*' {
+      pos += 1;
+    } else if is_ident_char(src[0]) {
+      let mut tag = parse_ident(src, &mut pos)?;
+      tag.make_ascii_lowercase();
+      sel.tag = Some(tag);
+    };
+    while let Some(&c) = src.get(pos) {
+      pos += 1;
+      match c {
+        b'#' => sel.ids.push(parse_ident(src, &mut pos)?),
+        b'.' => sel.classes.push(parse_ident(src, &mut pos)?),
+        b'[' => {
+          let len = match src[pos..].iter().position(|&c| c == b']') {
+            Some(len) => len,
+            None => {
+              return Err(ParseSelectorError {
+                message: "unterminated attribute selector",
+                position: pos - 1,
+              })
+            }
+          };
+          let inner = &src[pos..pos + len];
+          let (name, value) = match inner.iter().position(|&c| c == b'=') {
+            Some(eq) => (&inner[..eq], Some(trimmed(&inner[eq + 1..]))),
+            None => (inner, None),
+          };
+          let mut name = trimmed(name).to_vec();
+          if name.is_empty() || !name.iter().all(|&c| is_ident_char(c)) {
+            return Err(ParseSelectorError {
+              message: "invalid attribute name",
+              position: pos,
+            });
+          };
+          name.make_ascii_lowercase();
+          let value = value.map(|v| match v {
+            [q @ (b'"' | b'\''), unquoted @ .., l] if l == q => unquoted.to_vec(),
+            _ => v.to_vec(),
+          });
+          sel.attributes.push((name, value));
+          pos += len + 1;
+        }
+        _ => {
+          return Err(ParseSelectorError {
+            message: "unexpected character",
+            position: pos - 1,
+          })
+        }
+      };
+    }
+    Ok(sel)
+  }
+}
+
+/// A CSS selector made of simple selectors separated by descendant combinators, such as `article .note img[alt]`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Selector {
+  /// The simple selectors, starting from the outermost ancestor and ending with the one the matched element itself must match.
+  pub parts: Vec<SimpleSelector>,
+}
+
+type Attributes = HashMap<Vec<u8>, AttrVal>;
+
+fn element_parts(node: &NodeData) -> Option<(&[u8], &Attributes)> {
+  match node {
+    NodeData::Element {
+      name, attributes, ..
+    } => Some((name, attributes)),
+    _ => None,
+  }
+}
+
+impl Selector {
+  /// Returns whether `node` is an element that matches this selector, given its ancestors starting from the outermost.
+  pub fn matches(&self, ancestors: &[&NodeData], node: &NodeData) -> bool {
+    let (last, rest) = match self.parts.split_last() {
+      Some(p) => p,
+      None => return false,
+    };
+    if element_parts(node)
+      .filter(|(name, attrs)| last.matches(name, attrs))
+      .is_none()
+    {
+      return false;
+    };
+    // With only descendant combinators, greedily matching each remaining part against the nearest possible ancestor is sufficient.
+    let mut ancestors = ancestors.iter().rev().filter_map(|a| element_parts(a));
+    rest
+      .iter()
+      .rev()
+      .all(|part| ancestors.any(|(name, attrs)| part.matches(name, attrs)))
+  }
+}
+
+impl FromStr for Selector {
+  type Err = ParseSelectorError;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    let src = s.as_bytes();
+    let mut parts = Vec::new();
+    let mut start = None;
+    let mut in_brackets = false;
+    let mut quote = None;
+    // Append a space so that the last simple selector is terminated.
+    for (i, &c) in src.iter().chain(b" ").enumerate() {
+      match (quote, c) {
+        (Some(q), c) if c == q => quote = None,
+        (Some(_), _) => {}
+        (None, b'"' | b'\'') if in_brackets => quote = Some(c),
+        (None, b'[') => in_brackets = true,
+        (None, b']') => in_brackets = false,
+        (None, c) if c.is_ascii_whitespace() && !in_brackets => {
+          if let Some(start) = start.take() {
+            // Parts are split at ASCII whitespace, so they're on character boundaries.
+            let part = &s[start..i];
+            parts.push(
+              part
+                .parse()
+                .map_err(|err: ParseSelectorError| ParseSelectorError {
+                  message: err.message,
+                  position: start + err.position,
+                })?,
+            );
+          };
+          continue;
+        }
+        _ => {}
+      };
+      start.get_or_insert(i);
+    }
+    if start.is_some() || in_brackets || quote.is_some() {
+      return Err(ParseSelectorError {
+        message: "unterminated attribute selector",
+        position: start.unwrap_or(0),
+      });
+    };
+    if parts.is_empty() {
+      return Err(ParseSelectorError {
+        message: "empty selector",
+        position: 0,
+      });
+    };
+    Ok(Selector { parts })
+  }
+}
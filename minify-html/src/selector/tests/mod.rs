@@ -0,0 +1,58 @@
+use crate::selector::Selector;
+use crate::selector::SimpleSelector;
+
+fn parse(src: &str) -> SimpleSelector {
+  src.parse().unwrap()
+}
+
+#[test]
+fn test_parse_simple_selector() {
+  assert_eq!(parse("DIV"), SimpleSelector {
+    tag: Some(b"div".to_vec()),
+    ..Default::default()
+  });
+  assert_eq!(parse("*"), SimpleSelector::default());
+  assert_eq!(parse("#banner.a.b"), SimpleSelector {
+    ids: vec![b"banner".to_vec()],
+    classes: vec![b"a".to_vec(), b"b".to_vec()],
+    ..Default::default()
+  });
+  assert_eq!(parse("script[data-env=dev][Async]"), SimpleSelector {
+    tag: Some(b"script".to_vec()),
+    attributes: vec![
+      (b"data-env".to_vec(), Some(b"dev".to_vec())),
+      (b"async".to_vec(), None),
+    ],
+    ..Default::default()
+  });
+  assert_eq!(parse("[ title = \"a b\" ]"), SimpleSelector {
+    attributes: vec![(b"title".to_vec(), Some(b"a b".to_vec()))],
+    ..Default::default()
+  });
+}
+
+#[test]
+fn test_parse_invalid_simple_selector() {
+  for src in ["", "  ", "div p", "div > p", ".", "#", "[]", "[a", "a,b"] {
+    assert!(src.parse::<SimpleSelector>().is_err(), "{:?}", src);
+  }
+}
+
+#[test]
+fn test_parse_selector() {
+  let sel: Selector = " article  .note\timg[alt] ".parse().unwrap();
+  assert_eq!(sel.parts, vec![
+    parse("article"),
+    parse(".note"),
+    parse("img[alt]")
+  ]);
+  let sel: Selector = "[title=\"a b\"] p".parse().unwrap();
+  assert_eq!(sel.parts, vec![parse("[title=\"a b\"]"), parse("p")]);
+}
+
+#[test]
+fn test_parse_invalid_selector() {
+  for src in ["", "  ", "div > p", "a,b", "a, b", "[a", "[a=\"b]"] {
+    assert!(src.parse::<Selector>().is_err(), "{:?}", src);
+  }
+}
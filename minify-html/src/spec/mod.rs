@@ -0,0 +1,3 @@
+//! Facts about HTML that the minifier relies on, for tools that need to agree with it.
+
+pub mod tag;
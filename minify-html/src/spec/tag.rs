@@ -0,0 +1,63 @@
+//! Classifications of HTML elements by tag name. Names must be lowercase, as they are after parsing.
+
+use minify_html_common::spec::tag::ns;
+pub use minify_html_common::spec::tag::ns::Namespace;
+use minify_html_common::spec::tag::rawtext::RAW_TEXT_TAGS;
+use minify_html_common::spec::tag::void::VOID_TAGS;
+use minify_html_common::spec::tag::whitespace::is_formatting_tag;
+
+/// Returns whether the element is a [void element](https://html.spec.whatwg.org/multipage/syntax.html#void-elements), which has no content or closing tag.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::spec::tag::is_void;
+///
+/// assert!(is_void(b"br"));
+/// assert!(!is_void(b"p"));
+/// ```
+pub fn is_void(name: &[u8]) -> bool {
+  VOID_TAGS.contains(name)
+}
+
+/// Returns whether the element is an inline formatting element, like `<a>`, `<b>`, or `<span>`. Whitespace in these is collapsed but never trimmed or removed, as it's rendered between the words around it.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::spec::tag::is_formatting;
+///
+/// assert!(is_formatting(b"span"));
+/// assert!(!is_formatting(b"div"));
+/// ```
+pub fn is_formatting(name: &[u8]) -> bool {
+  is_formatting_tag(name)
+}
+
+/// Returns whether the content of the HTML element is text up to its closing tag, rather than HTML, like `<script>`, `<style>`, `<textarea>`, and `<title>`. In SVG and MathML, only `<script>` and `<style>` are like this.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::spec::tag::is_raw_text;
+///
+/// assert!(is_raw_text(b"textarea"));
+/// assert!(!is_raw_text(b"noscript"));
+/// ```
+pub fn is_raw_text(name: &[u8]) -> bool {
+  RAW_TEXT_TAGS.contains(name)
+}
+
+/// Returns the namespace that the element and its descendants are in if it starts foreign content, which is SVG for `<svg>` and MathML for `<math>`. Other elements are in the same namespace as their parent.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::spec::tag::{namespace_for, Namespace};
+///
+/// assert_eq!(namespace_for(b"svg"), Some(Namespace::Svg));
+/// assert_eq!(namespace_for(b"div"), None);
+/// ```
+pub fn namespace_for(name: &[u8]) -> Option<Namespace> {
+  ns::namespace_for(name)
+}
@@ -0,0 +1,55 @@
+/// Statistics about a single minification, returned by `minify_with_stats`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MinifyStats {
+  /// Length of the source code in bytes.
+  pub input_bytes: usize,
+  /// Length of the minified code in bytes.
+  pub output_bytes: usize,
+  /// Difference between `input_bytes` and `output_bytes`.
+  pub bytes_saved: usize,
+  /// `bytes_saved` as a percentage of `input_bytes`. This is zero if the source is empty.
+  pub percent_saved: f64,
+  /// Whether the minified code differs from the source code.
+  pub changed: bool,
+  /// Number of elements minified. Elements removed by `Cfg::remove_elements` are not included.
+  pub elements_minified: usize,
+  /// Bytes of whitespace removed from text by trimming, collapsing, or removing whitespace-only text.
+  pub whitespace_bytes_removed: usize,
+  /// Number of opening tags omitted, such as `<html>` and `<tbody>`.
+  pub opening_tags_omitted: usize,
+  /// Number of closing tags present in the source that were omitted.
+  pub closing_tags_omitted: usize,
+  /// Number of attributes removed, either because they were redundant or matched `Cfg::remove_data_attrs`.
+  pub attributes_removed: usize,
+  /// Number of attribute values made shorter, for example by trimming whitespace or minifying CSS.
+  pub attributes_minified: usize,
+  /// Number of attribute values written without quotes.
+  pub attributes_unquoted: usize,
+  /// Number of comments removed.
+  pub comments_removed: usize,
+  /// Bytes saved by minifying CSS in `<style>` tags and `style` attributes.
+  pub css_bytes_saved: usize,
+  /// Bytes saved by minifying JavaScript in `<script>` tags.
+  pub js_bytes_saved: usize,
+}
+
+impl MinifyStats {
+  pub(crate) fn record_output(&mut self, input: &[u8], output: &[u8]) {
+    // Slices of different lengths are compared without reading their bytes.
+    self.changed = input != output;
+    self.record_sizes(input.len(), output.len());
+  }
+
+  pub(crate) fn record_sizes(&mut self, input_bytes: usize, output_bytes: usize) {
+    // Minified output should never be longer than the source, but don't underflow if it somehow is.
+    let bytes_saved = input_bytes.saturating_sub(output_bytes);
+    self.input_bytes = input_bytes;
+    self.output_bytes = output_bytes;
+    self.bytes_saved = bytes_saved;
+    self.percent_saved = if input_bytes == 0 {
+      0.0
+    } else {
+      bytes_saved as f64 * 100.0 / input_bytes as f64
+    };
+  }
+}
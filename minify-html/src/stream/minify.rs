@@ -0,0 +1,236 @@
+use crate::ast::AttrVal;
+use crate::cfg::Cfg;
+use crate::entity::decode::decode_entities;
+use crate::minify::bang::minify_bang;
+use crate::minify::comment::minify_comment;
+use crate::minify::content::encode_text;
+use crate::minify::content::whitespace_minification;
+use crate::minify::doctype::minify_doctype;
+use crate::minify::element::is_contenteditable;
+use crate::minify::element::minify_attrs;
+use crate::minify::element::write_opening_tag;
+use crate::minify::instruction::is_xml_declaration;
+use crate::minify::instruction::minify_instruction;
+use crate::parse::content::is_formatting_element;
+use crate::stats::MinifyStats;
+use crate::stream::tokenize;
+use crate::stream::Event;
+use crate::stream::Tokenizer;
+use hashbrown::HashMap;
+use minify_html_common::spec::tag::ns::namespace_for;
+use minify_html_common::spec::tag::ns::Namespace;
+use minify_html_common::spec::tag::void::VOID_TAGS;
+use minify_html_common::whitespace::collapse_whitespace;
+use std::io::Read;
+use std::io::Write;
+
+// State carried between events, which is bounded by the depth of element nesting.
+#[derive(Default)]
+struct StreamingMinifier {
+  // Names and namespaces of open elements, and whether they preserve whitespace, to determine how to minify text and child elements.
+  open: Vec<(Vec<u8>, Namespace, bool)>,
+  // Statistics aren't reported, but are required by the shared minification functions.
+  stats: MinifyStats,
+  buf: Vec<u8>,
+}
+
+impl StreamingMinifier {
+  fn write<W: Write>(&mut self, cfg: &Cfg, out: &mut W, event: Event) -> std::io::Result<()> {
+    let StreamingMinifier { open, stats, buf } = self;
+    buf.clear();
+    let ns = open.last().map_or(Namespace::Html, |(_, ns, _)| *ns);
+    match event {
+      Event::Bang { code, ended } => minify_bang(cfg, buf, ns, code, ended),
+      Event::Comment { code, ended } => minify_comment(cfg, buf, stats, code, ended),
+      Event::Doctype { legacy, ended } => minify_doctype(cfg, buf, legacy, ended),
+      Event::EndTag { name } => {
+        let name = name.to_ascii_lowercase();
+        // Like `parse`, closing an ancestor implicitly closes its descendants, and other closing tags that don't match an open element are dropped.
+        let matched = open.iter().rposition(|(n, _, _)| *n == name);
+        if let Some(i) = matched {
+          open.truncate(i);
+        };
+        // Browsers don't simply ignore these when unmatched (e.g. `</br>` is a `<br>`, and `</i>` may end a reconstructed `<i>`), and without a tree it can't be determined what they would do, so they're kept as is.
+        let recovered = ns == Namespace::Html
+          && (matches!(name.as_slice(), b"br" | b"form" | b"p") || is_formatting_element(&name));
+        if matched.is_some() || recovered {
+          buf.extend_from_slice(b"</");
+          buf.extend_from_slice(&name);
+          buf.push(b'>');
+        };
+      }
+      Event::Instruction { code, .. }
+        if cfg.remove_xml_declaration && open.is_empty() && is_xml_declaration(code) => {}
+      Event::Instruction { code, ended } => minify_instruction(cfg, buf, code, ended),
+      Event::RawText { code } => buf.extend_from_slice(code),
+      Event::StartTag {
+        name,
+        attributes,
+        self_closing,
+      } => {
+        let name = name.to_ascii_lowercase();
+        let ns = namespace_for(&name).unwrap_or(ns);
+        let mut attrs = HashMap::<Vec<u8>, AttrVal>::default();
+        for a in attributes {
+          attrs.insert(a.name.to_ascii_lowercase(), AttrVal {
+            quote: None,
+            value: a.value.map_or_else(Vec::new, |v| decode_entities(v, true)),
+          });
+        }
+        // Only foreign elements can be self closed.
+        let self_closing = self_closing && ns != Namespace::Html;
+        let preserve_whitespace = (ns == Namespace::Html
+          && matches!(name.as_slice(), b"pre" | b"listing"))
+          || (cfg.respect_contenteditable && is_contenteditable(&attrs));
+        let attrs = minify_attrs(cfg, stats, ns, &name, attrs);
+        let is_void = ns == Namespace::Html && VOID_TAGS.contains(name.as_slice());
+        write_opening_tag(cfg, buf, &name, &attrs, self_closing, is_void);
+        if !self_closing && !VOID_TAGS.contains(name.as_slice()) {
+          open.push((name, ns, preserve_whitespace));
+        };
+      }
+      Event::Text { code } => {
+        let mut value = decode_entities(code, false);
+        let descendant_of_pre = open.iter().any(|(_, _, preserve)| *preserve);
+        let parent = open.last().map_or(b"".as_ref(), |(n, _, _)| n.as_slice());
+        if whitespace_minification(cfg, ns, parent, descendant_of_pre).collapse {
+          collapse_whitespace(&mut value);
+        };
+        buf.extend_from_slice(&encode_text(cfg, &value));
+      }
+    };
+    out.write_all(buf)
+  }
+}
+
+/// Minifies HTML code event by event using `tokenize`, writing the output as it goes. Unlike `minify`, no tree is built, so memory usage beyond the source is bounded by the depth of element nesting rather than the document size. Use `minify_streaming_from_reader` to avoid loading the source into memory too.
+///
+/// Comment, bang, and processing instruction removal, doctype minification, attribute minification (including `element_visitor`), and collapsing of whitespace within text are applied with the same rules as `minify`. Optimisations that need to look ahead or at sibling nodes are skipped:
+///
+/// - Opening and closing tags are never omitted.
+/// - Whitespace is collapsed but never trimmed or removed, even between block elements.
+/// - The contents of `<script>` and `<style>` are not minified, and `<textarea>` and `<title>` contents are left as is.
+/// - `preserve_ignored_regions`, `remove_elements`, `remove_generator_meta`, `remove_hidden_elements`, `remove_x_ua_compatible_meta`, `text_visitor`, `unwrap_redundant_wrappers`, and opaque template syntax options are not supported.
+/// - `normalize_line_endings` is not supported.
+/// - `remove_html5_shiv` only removes conditional comments, not `<script>` elements outside them.
+/// - CDATA sections in SVG and MathML end at the first `>`, so those containing `>` are split.
+/// - The tree construction recovery done by `parse` is not applied. Nested `<form>` start tags, and unmatched `</br>`, `</p>`, `</form>`, and formatting element closing tags like `</b>`, are written as they appear for the browser to recover from.
+/// - `max_depth` is ignored, as no recursion is needed regardless of how deeply elements are nested.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, minify_streaming};
+///
+/// let mut out = Vec::new();
+/// minify_streaming(b"<p  class=' a '>Hello,   world!</p>", &Cfg::new(), &mut out).unwrap();
+/// assert_eq!(out, b"<p class=a>Hello, world!</p>");
+/// ```
+pub fn minify_streaming<W: Write>(src: &[u8], cfg: &Cfg, out: &mut W) -> std::io::Result<()> {
+  let mut minifier = StreamingMinifier::default();
+  for event in tokenize(src) {
+    minifier.write(cfg, out, event)?;
+  }
+  Ok(())
+}
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Like `minify_streaming`, but reads the source in chunks, so memory usage is bounded by the size of the largest single event (e.g. a tag, text, or the contents of a `<script>`) rather than the document size.
+pub fn minify_streaming_from_reader<R: Read, W: Write>(
+  src: R,
+  cfg: &Cfg,
+  out: &mut W,
+) -> std::io::Result<()> {
+  minify_chunks(src, cfg, out, CHUNK_SIZE)
+}
+
+pub(crate) fn minify_chunks<R: Read, W: Write>(
+  mut src: R,
+  cfg: &Cfg,
+  out: &mut W,
+  chunk_size: usize,
+) -> std::io::Result<()> {
+  let mut minifier = StreamingMinifier::default();
+  let mut buf = Vec::<u8>::new();
+  let mut raw_text_end = None;
+  loop {
+    // Read at least as much as is already buffered, so that re-lexing an event spanning many chunks takes amortised linear time.
+    let len = buf.len();
+    buf.resize(len + chunk_size.max(len), 0);
+    let read = src.read(&mut buf[len..])?;
+    buf.truncate(len + read);
+    let eof = read == 0;
+
+    let mut tokenizer = Tokenizer {
+      src: &buf,
+      pos: 0,
+      raw_text_end,
+    };
+    loop {
+      let (pos, prev_raw_text_end) = (tokenizer.pos, tokenizer.raw_text_end.clone());
+      match tokenizer.next() {
+        None => break,
+        // An event reaching the end of the buffer may continue in the next chunk, so lex it again once more has been read.
+        Some(_) if !eof && tokenizer.pos == buf.len() => {
+          tokenizer.pos = pos;
+          tokenizer.raw_text_end = prev_raw_text_end;
+          break;
+        }
+        Some(event) => minifier.write(cfg, out, event)?,
+      };
+    }
+    let consumed = tokenizer.pos;
+    raw_text_end = tokenizer.raw_text_end;
+    if eof {
+      return Ok(());
+    };
+    buf.drain(..consumed);
+  }
+}
+
+/// A reader that yields the minified form of the HTML read from another reader, for pipelines that expect a `Read`. The source is read to the end and minified with `minify` on the first `read`, and the minified bytes are then served from memory.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, MinifyReader};
+/// use std::io::Read;
+///
+/// let cfg = Cfg::new();
+/// let mut out = String::new();
+/// MinifyReader::new(&b"<p>  Hello,   world!  </p>"[..], &cfg).read_to_string(&mut out).unwrap();
+/// assert_eq!(out, "<p>Hello, world!");
+/// ```
+pub struct MinifyReader<'c, R: Read> {
+  // `None` once read and minified.
+  src: Option<R>,
+  cfg: &'c Cfg,
+  minified: Vec<u8>,
+  pos: usize,
+}
+
+impl<'c, R: Read> MinifyReader<'c, R> {
+  pub fn new(src: R, cfg: &'c Cfg) -> MinifyReader<'c, R> {
+    MinifyReader {
+      src: Some(src),
+      cfg,
+      minified: Vec::new(),
+      pos: 0,
+    }
+  }
+}
+
+impl<'c, R: Read> Read for MinifyReader<'c, R> {
+  fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+    if let Some(src) = self.src.as_mut() {
+      let mut code = Vec::new();
+      src.read_to_end(&mut code)?;
+      self.minified = crate::minify(&code, self.cfg);
+      self.src = None;
+    };
+    let n = (&self.minified[self.pos..]).read(buf)?;
+    self.pos += n;
+    Ok(n)
+  }
+}
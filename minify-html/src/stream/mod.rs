@@ -0,0 +1,296 @@
+use alloc::vec::Vec;
+use memchr::memchr;
+use memchr::memmem::find;
+use minify_html_common::gen::codepoints::NOT_UNQUOTED_ATTR_VAL_CHAR;
+use minify_html_common::gen::codepoints::TAG_NAME_CHAR;
+use minify_html_common::gen::codepoints::WHITESPACE;
+use minify_html_common::gen::codepoints::WHITESPACE_OR_SLASH;
+use minify_html_common::gen::codepoints::WHITESPACE_OR_SLASH_OR_EQUALS_OR_RIGHT_CHEVRON;
+use minify_html_common::spec::tag::rawtext::RAW_TEXT_TAGS;
+
+#[cfg(feature = "std")]
+pub mod minify;
+#[cfg(test)]
+mod tests;
+
+/// An attribute of a start tag, borrowed from the source. The name is not lowercased and the value is not decoded.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Attribute<'a> {
+  pub name: &'a [u8],
+  /// `None` if the attribute has no `=`.
+  pub value: Option<&'a [u8]>,
+}
+
+/// A lexical event produced by `tokenize`. All slices are borrowed from the source and are not decoded or lowercased.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Event<'a> {
+  /// A `<!...>` that isn't a comment or doctype, without the delimiters.
+  Bang {
+    code: &'a [u8],
+    ended: bool,
+  },
+  /// A comment, without the `<!--` and `-->` delimiters.
+  Comment {
+    code: &'a [u8],
+    ended: bool,
+  },
+  /// A doctype, with only the legacy string following `<!doctype html` retained.
+  Doctype {
+    legacy: &'a [u8],
+    ended: bool,
+  },
+  /// A closing tag. The name may be empty for malformed input like `</>`.
+  EndTag {
+    name: &'a [u8],
+  },
+  /// A processing instruction, without the `<?` and `?>` delimiters.
+  Instruction {
+    code: &'a [u8],
+    ended: bool,
+  },
+  /// The raw contents of a `<plaintext>`, `<script>`, `<style>`, `<textarea>`, `<title>`, or `<xmp>` element.
+  RawText {
+    code: &'a [u8],
+  },
+  StartTag {
+    name: &'a [u8],
+    attributes: Vec<Attribute<'a>>,
+    self_closing: bool,
+  },
+  Text {
+    code: &'a [u8],
+  },
+}
+
+/// An iterator over the lexical events of some HTML source. Created by `tokenize`.
+// `parse` keeps its own lexer, as it needs the tree to decide some of the cases listed on `tokenize`. `test_tokenize_matches_parse_on_corpus` checks that the two agree on the benchmark corpus.
+pub struct Tokenizer<'a> {
+  src: &'a [u8],
+  pos: usize,
+  // The name of the raw text element whose contents are next, if any. This is owned so that it can be carried over when lexing a source in chunks.
+  raw_text_end: Option<Vec<u8>>,
+}
+
+/// Lexes HTML source into a flat sequence of events without building a tree. This requires memory proportional to the largest single tag rather than the whole document, and uses the same lexical rules as `parse`, except that:
+///
+/// - Template syntax like `{{ }}`, `{% %}`, and `<% %>` is lexed as text and tags, as if the `preserve_*_template_syntax` options were off.
+/// - CDATA sections are lexed as `Bang` events ending at the first `>`.
+/// - Tags are emitted as they appear regardless of context, so e.g. a `<form>` start tag inside an open form, which `parse` drops, or a `</br>`, which `parse` treats as `<br>`, is emitted as is.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{tokenize, Event};
+///
+/// let events = tokenize(b"<p class=a>Hi</p>").collect::<Vec<_>>();
+/// assert_eq!(events.len(), 3);
+/// assert_eq!(events[1], Event::Text { code: b"Hi" });
+/// assert_eq!(events[2], Event::EndTag { name: b"p" });
+/// ```
+pub fn tokenize(src: &[u8]) -> Tokenizer<'_> {
+  Tokenizer {
+    src,
+    pos: 0,
+    raw_text_end: None,
+  }
+}
+
+impl<'a> Tokenizer<'a> {
+  fn skip_while(&mut self, pred: impl Fn(u8) -> bool) -> Option<u8> {
+    let mut last = None;
+    while let Some(&c) = self.src.get(self.pos).filter(|&&c| pred(c)) {
+      last = Some(c);
+      self.pos += 1;
+    }
+    last
+  }
+
+  fn slice_while(&mut self, pred: impl Fn(u8) -> bool) -> &'a [u8] {
+    let start = self.pos;
+    self.skip_while(pred);
+    &self.src[start..self.pos]
+  }
+
+  // Returns the position of the next `<` that starts a tag, comment, bang, or instruction, or the end of the source. A `<` followed by anything else is text.
+  fn next_markup_start(&self, from: usize) -> usize {
+    let mut i = from;
+    while let Some(off) = memchr(b'<', &self.src[i..]) {
+      let p = i + off;
+      match self.src.get(p + 1) {
+        Some(&c) if TAG_NAME_CHAR[c] || c == b'/' || c == b'!' || c == b'?' => return p,
+        _ => i = p + 1,
+      };
+    }
+    self.src.len()
+  }
+
+  // Returns the position of the next `</name` followed by whitespace, `/`, or `>`, compared case insensitively, or the end of the source.
+  fn next_raw_text_end(&self, name: &[u8]) -> usize {
+    // `<plaintext>` can never be closed.
+    if name.eq_ignore_ascii_case(b"plaintext") {
+      return self.src.len();
+    };
+    let mut i = self.pos;
+    while let Some(off) = memchr(b'<', &self.src[i..]) {
+      let p = i + off;
+      let rest = &self.src[p + 1..];
+      if rest.len() > name.len()
+        && rest[0] == b'/'
+        && rest[1..=name.len()].eq_ignore_ascii_case(name)
+        && rest
+          .get(name.len() + 1)
+          .is_some_and(|&c| WHITESPACE_OR_SLASH[c] || c == b'>')
+      {
+        return p;
+      };
+      i = p + 1;
+    }
+    self.src.len()
+  }
+
+  // Returns the content up to `delim` and whether it was found, skipping past the delimiter.
+  fn slice_until(&mut self, delim: &[u8]) -> (&'a [u8], bool) {
+    let rest = &self.src[self.pos..];
+    match find(rest, delim) {
+      Some(len) => {
+        self.pos += len + delim.len();
+        (&rest[..len], true)
+      }
+      None => {
+        self.pos = self.src.len();
+        (rest, false)
+      }
+    }
+  }
+
+  // While not valid, attributes in closing tags still need to be lexed (and then discarded) as attributes e.g. `</div x=">">`, which is why this is used for both opening and closing tags. Keep in sync with `parse_tag`.
+  fn tag(&mut self) -> (&'a [u8], Vec<Attribute<'a>>, bool) {
+    let name = self.slice_while(|c| TAG_NAME_CHAR[c]);
+    let mut attributes = Vec::new();
+    let self_closing;
+    loop {
+      let last = self.skip_while(|c| WHITESPACE_OR_SLASH[c]);
+      if self.pos == self.src.len() || self.src[self.pos] == b'>' {
+        self.pos = (self.pos + 1).min(self.src.len());
+        self_closing = last == Some(b'/');
+        break;
+      };
+      let name_start = self.pos;
+      // An attribute name can start with `=`, but ends at the next whitespace, `=`, `/`, or `>`.
+      self.pos += 1;
+      self.skip_while(|c| !WHITESPACE_OR_SLASH_OR_EQUALS_OR_RIGHT_CHEVRON[c]);
+      let attr_name = &self.src[name_start..self.pos];
+      self.skip_while(|c| WHITESPACE[c]);
+      let has_value = self.src.get(self.pos) == Some(&b'=');
+      let value = if has_value {
+        self.pos += 1;
+        self.skip_while(|c| WHITESPACE[c]);
+        match self.src.get(self.pos) {
+          Some(&q @ (b'"' | b'\'')) => {
+            self.pos += 1;
+            let value = self.slice_while(|c| c != q);
+            // It might not be next if EOF (i.e. attribute value not closed).
+            if self.pos < self.src.len() {
+              self.pos += 1;
+            };
+            Some(value)
+          }
+          _ => Some(self.slice_while(|c| !NOT_UNQUOTED_ATTR_VAL_CHAR[c])),
+        }
+      } else {
+        None
+      };
+      attributes.push(Attribute {
+        name: attr_name,
+        value,
+      });
+    }
+    (name, attributes, self_closing)
+  }
+}
+
+// The length of everything up to and including the `<html>` start tag, if only a doctype, comments, bangs, instructions, and whitespace precede it.
+pub(crate) fn prolog_len(src: &[u8]) -> Option<usize> {
+  let mut tokenizer = tokenize(src);
+  loop {
+    match tokenizer.next()? {
+      Event::StartTag { name, .. } if name.eq_ignore_ascii_case(b"html") => {
+        return Some(tokenizer.pos)
+      }
+      Event::Bang { .. }
+      | Event::Comment { .. }
+      | Event::Doctype { .. }
+      | Event::Instruction { .. } => {}
+      Event::Text { code } if code.iter().all(|&c| WHITESPACE[c]) => {}
+      _ => return None,
+    };
+  }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+  type Item = Event<'a>;
+
+  fn next(&mut self) -> Option<Event<'a>> {
+    if let Some(name) = self.raw_text_end.take() {
+      let end = self.next_raw_text_end(&name);
+      if end > self.pos {
+        let code = &self.src[self.pos..end];
+        self.pos = end;
+        return Some(Event::RawText { code });
+      };
+    };
+
+    let rest = &self.src[self.pos..];
+    if rest.is_empty() {
+      return None;
+    };
+    let end = self.next_markup_start(self.pos);
+    if end > self.pos {
+      let code = &self.src[self.pos..end];
+      self.pos = end;
+      return Some(Event::Text { code });
+    };
+
+    Some(if rest.starts_with(b"<!--") {
+      self.pos += 4;
+      let (code, ended) = self.slice_until(b"-->");
+      Event::Comment { code, ended }
+    } else if rest.len() >= 9 && rest[..9].eq_ignore_ascii_case(b"<!doctype") {
+      self.pos += 9;
+      self.skip_while(|c| WHITESPACE[c]);
+      if self.src[self.pos..]
+        .get(..4)
+        .filter(|s| s.eq_ignore_ascii_case(b"html"))
+        .is_some()
+      {
+        self.pos += 4;
+      };
+      self.skip_while(|c| WHITESPACE[c]);
+      let (legacy, ended) = self.slice_until(b">");
+      Event::Doctype { legacy, ended }
+    } else if rest.starts_with(b"<!") {
+      self.pos += 2;
+      let (code, ended) = self.slice_until(b">");
+      Event::Bang { code, ended }
+    } else if rest.starts_with(b"<?") {
+      self.pos += 2;
+      let (code, ended) = self.slice_until(b"?>");
+      Event::Instruction { code, ended }
+    } else if rest.starts_with(b"</") {
+      self.pos += 2;
+      let (name, _, _) = self.tag();
+      Event::EndTag { name }
+    } else {
+      self.pos += 1;
+      let (name, attributes, self_closing) = self.tag();
+      if !self_closing && RAW_TEXT_TAGS.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+        self.raw_text_end = Some(name.to_vec());
+      };
+      Event::StartTag {
+        name,
+        attributes,
+        self_closing,
+      }
+    })
+  }
+}
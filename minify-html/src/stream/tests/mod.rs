@@ -0,0 +1,364 @@
+use crate::ast::ElementClosingTag;
+use crate::ast::NodeData;
+use crate::cfg::Cfg;
+use crate::entity::decode::decode_entities;
+use crate::minify;
+use crate::parse;
+use crate::stream::minify::minify_chunks;
+use crate::stream::minify::minify_streaming;
+use crate::stream::minify::minify_streaming_from_reader;
+use crate::stream::minify::MinifyReader;
+use crate::stream::tokenize;
+use crate::stream::Attribute;
+use crate::stream::Event;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+fn stream(src: &[u8], cfg: &Cfg) -> Vec<u8> {
+  let mut out = Vec::new();
+  minify_streaming(src, cfg, &mut out).unwrap();
+  out
+}
+
+#[test]
+fn test_tokenize() {
+  let events = tokenize(
+    b"<!DOCTYPE html><P Class=\"a\" hidden id=b>x &amp; y<!-- c --></P><svg><path d=1 /></svg><script>a</b>1</SCRIPT><?php 1 ?>a < b</>",
+  )
+  .collect::<Vec<_>>();
+  assert_eq!(events, vec![
+    Event::Doctype {
+      legacy: b"",
+      ended: true
+    },
+    Event::StartTag {
+      name: b"P",
+      attributes: vec![
+        Attribute {
+          name: b"Class",
+          value: Some(b"a"),
+        },
+        Attribute {
+          name: b"hidden",
+          value: None,
+        },
+        Attribute {
+          name: b"id",
+          value: Some(b"b"),
+        },
+      ],
+      self_closing: false,
+    },
+    Event::Text { code: b"x &amp; y" },
+    Event::Comment {
+      code: b" c ",
+      ended: true
+    },
+    Event::EndTag { name: b"P" },
+    Event::StartTag {
+      name: b"svg",
+      attributes: vec![],
+      self_closing: false,
+    },
+    Event::StartTag {
+      name: b"path",
+      attributes: vec![Attribute {
+        name: b"d",
+        value: Some(b"1"),
+      }],
+      self_closing: true,
+    },
+    Event::EndTag { name: b"svg" },
+    Event::StartTag {
+      name: b"script",
+      attributes: vec![],
+      self_closing: false,
+    },
+    Event::RawText { code: b"a</b>1" },
+    Event::EndTag { name: b"SCRIPT" },
+    Event::Instruction {
+      code: b"php 1 ",
+      ended: true
+    },
+    Event::Text { code: b"a < b" },
+    Event::EndTag { name: b"" },
+  ]);
+}
+
+#[test]
+fn test_tokenize_unterminated() {
+  assert_eq!(tokenize(b"<!-- a").collect::<Vec<_>>(), vec![
+    Event::Comment {
+      code: b" a",
+      ended: false
+    }
+  ]);
+  assert_eq!(tokenize(b"<style>a{}").collect::<Vec<_>>(), vec![
+    Event::StartTag {
+      name: b"style",
+      attributes: vec![],
+      self_closing: false,
+    },
+    Event::RawText { code: b"a{}" },
+  ]);
+}
+
+#[test]
+fn test_minify_streaming_matches_minify() {
+  let mut cfg = Cfg::new();
+  cfg.respect_contenteditable = true;
+  for src in [
+    b"<div><span class=\" a  b \">x   y</span><!-- c --><a href=\"x\">z</a><img src=\"a.png\" alt=\"\"></div>".as_ref(),
+    b"<div><pre>  a   b  <b> c   d </b></pre></div>",
+    b"<div><svg><circle r=\"1\" /></svg><br/></div>",
+    b"<div><span>a &lt; b &amp;amp; c</span></div>",
+    b"<div><div contenteditable>  a  <b> c   d </b></div></div>",
+    b"<div><xmp></xmpl> a </xmp ><listing>  a   b </listing></div>",
+  ] {
+    assert_eq!(
+      String::from_utf8(stream(src, &cfg)).unwrap(),
+      String::from_utf8(minify(src, &cfg)).unwrap(),
+    );
+  }
+}
+
+#[test]
+fn test_minify_streaming_skipped_optimizations() {
+  let cfg = Cfg::new();
+  // Closing tags are never omitted, whitespace is only collapsed, and script contents are kept as is.
+  assert_eq!(
+    stream(
+      b"<ul>\n  <li>a</li>\n</ul><script> let a = 1; </script>",
+      &cfg
+    ),
+    b"<ul> <li>a</li> </ul><script> let a = 1; </script>",
+  );
+  // Unmatched closing tags are dropped.
+  assert_eq!(stream(b"<div>a</span></div>", &cfg), b"<div>a</div>");
+}
+
+#[test]
+fn test_minify_streaming_differences_from_minify() {
+  let mut cfg = Cfg::new();
+  cfg.keep_html_and_head_opening_tags = true;
+  cfg.preserve_brace_template_syntax = true;
+  for (src, streamed, minified) in [
+    // Tags that browsers recover from specially are written as they appear.
+    (b"a</br>b".as_ref(), b"a</br>b".as_ref(), b"a<br>b".as_ref()),
+    (
+      b"<div>a</p>b</div>",
+      b"<div>a</p>b</div>",
+      b"<div>a<p></p>b</div>",
+    ),
+    (
+      b"<b><i>x</b>y</i>z",
+      b"<b><i>x</b>y</i>z",
+      b"<b><i>x</i></b><i>y</i>z",
+    ),
+    (
+      b"<form a><input><form b><input></form><input>",
+      b"<form a><input><form b><input></form><input>",
+      b"<form a><input><input></form><input>",
+    ),
+    // Other unmatched closing tags are dropped by both.
+    (b"a</div>b</span>c", b"abc", b"abc"),
+    // Template syntax is treated as text.
+    (
+      b"<div>{{  a  }}</div>",
+      b"<div>{{ a }}</div>",
+      b"<div>{{  a  }}</div>",
+    ),
+  ] {
+    assert_eq!(
+      String::from_utf8(stream(src, &cfg)).unwrap(),
+      String::from_utf8(streamed.to_vec()).unwrap(),
+    );
+    assert_eq!(
+      String::from_utf8(minify(src, &cfg)).unwrap(),
+      String::from_utf8(minified.to_vec()).unwrap(),
+    );
+  }
+  // CDATA sections are bangs ending at the first `>`.
+  assert_eq!(
+    tokenize(b"<svg><![CDATA[a>b]]></svg>").collect::<Vec<_>>(),
+    vec![
+      Event::StartTag {
+        name: b"svg",
+        attributes: vec![],
+        self_closing: false,
+      },
+      Event::Bang {
+        code: b"[CDATA[a",
+        ended: true
+      },
+      Event::Text { code: b"b]]>" },
+      Event::EndTag { name: b"svg" },
+    ]
+  );
+}
+
+#[test]
+fn test_minify_chunks_matches_minify_streaming() {
+  let cfg = Cfg::new();
+  let src = b"<!doctype html><div title='a &amp; b'>x   <!-- c -->  y<script>let a = '</scr' + 'ipt>';</script><pre>  a  </pre><?x ?></div>";
+  for chunk_size in [1, 2, 3, 7, 64] {
+    let mut out = Vec::new();
+    minify_chunks(src.as_ref(), &cfg, &mut out, chunk_size).unwrap();
+    assert_eq!(
+      String::from_utf8(out).unwrap(),
+      String::from_utf8(stream(src, &cfg)).unwrap(),
+    );
+  }
+}
+
+#[test]
+fn test_minify_streaming_from_reader_large_input() {
+  let cfg = Cfg::new();
+  let mut src = Vec::new();
+  for i in 0..100_000 {
+    src.extend_from_slice(
+      format!(
+        "<div class=\" row \"><span title=\"a &amp; b\">item   {}</span><!-- c --><a href=\"x\">l</a></div>",
+        i
+      )
+      .as_bytes(),
+    );
+  }
+  let mut out = Vec::new();
+  minify_streaming_from_reader(src.as_slice(), &cfg, &mut out).unwrap();
+  assert!(out == minify(&src, &cfg));
+}
+
+#[test]
+fn test_minify_reader() {
+  let cfg = Cfg::new();
+  let src = b"<div>  <p>  a   b  </p>  <!-- c -->  </div>".repeat(100);
+  let mut reader = MinifyReader::new(src.as_slice(), &cfg);
+  // Read in small pieces to check the position is kept between reads.
+  let mut out = Vec::new();
+  let mut buf = [0u8; 7];
+  loop {
+    let n = reader.read(&mut buf).unwrap();
+    if n == 0 {
+      break;
+    };
+    out.extend_from_slice(&buf[..n]);
+  }
+  assert_eq!(out, minify(&src, &cfg));
+}
+
+// A token as both `tokenize` and `parse` see it, with names lowercased and text and attribute values decoded.
+#[derive(Debug, PartialEq)]
+enum Token {
+  Bang(Vec<u8>),
+  Comment(Vec<u8>),
+  Doctype(Vec<u8>),
+  EndTag(Vec<u8>),
+  Instruction(Vec<u8>),
+  RawText(Vec<u8>),
+  StartTag(Vec<u8>, BTreeMap<Vec<u8>, Vec<u8>>),
+  Text(Vec<u8>),
+}
+
+// Adjacent text is merged, as the lexers may split it at different points.
+fn push_text(tokens: &mut Vec<Token>, text: &[u8]) {
+  match tokens.last_mut() {
+    Some(Token::Text(prev)) => prev.extend_from_slice(text),
+    _ => tokens.push(Token::Text(text.to_vec())),
+  };
+}
+
+fn tokens_from_events(src: &[u8]) -> Vec<Token> {
+  let mut tokens = Vec::new();
+  // `<title>` and `<textarea>` contents are decoded by `parse`.
+  let mut in_rcdata = false;
+  for event in tokenize(src) {
+    match event {
+      Event::Bang { code, .. } => tokens.push(Token::Bang(code.to_vec())),
+      Event::Comment { code, .. } => tokens.push(Token::Comment(code.to_vec())),
+      Event::Doctype { legacy, .. } => tokens.push(Token::Doctype(legacy.to_vec())),
+      Event::EndTag { name } => tokens.push(Token::EndTag(name.to_ascii_lowercase())),
+      Event::Instruction { code, .. } => tokens.push(Token::Instruction(code.to_vec())),
+      Event::RawText { code } if in_rcdata => {
+        tokens.push(Token::RawText(decode_entities(code, false)))
+      }
+      Event::RawText { code } => tokens.push(Token::RawText(code.to_vec())),
+      Event::StartTag {
+        name, attributes, ..
+      } => {
+        let name = name.to_ascii_lowercase();
+        in_rcdata = matches!(name.as_slice(), b"title" | b"textarea");
+        let attributes = attributes
+          .iter()
+          .map(|a| {
+            (
+              a.name.to_ascii_lowercase(),
+              a.value.map_or(Vec::new(), |v| decode_entities(v, true)),
+            )
+          })
+          .collect();
+        tokens.push(Token::StartTag(name, attributes));
+      }
+      Event::Text { code } => push_text(&mut tokens, &decode_entities(code, false)),
+    };
+  }
+  tokens
+}
+
+// Only closing tags that are in the source are emitted, like `tokenize`.
+fn tokens_from_tree(nodes: &[NodeData], tokens: &mut Vec<Token>) {
+  for n in nodes {
+    match n {
+      NodeData::Bang { code, .. } => tokens.push(Token::Bang(code.clone())),
+      NodeData::Comment { code, .. } => tokens.push(Token::Comment(code.clone())),
+      NodeData::Doctype { legacy, .. } => tokens.push(Token::Doctype(legacy.clone())),
+      NodeData::Element {
+        attributes,
+        children,
+        closing_tag,
+        name,
+        ..
+      } => {
+        let attributes = attributes
+          .iter()
+          .map(|(k, v)| (k.clone(), v.value.clone()))
+          .collect();
+        tokens.push(Token::StartTag(name.clone(), attributes));
+        tokens_from_tree(children, tokens);
+        if *closing_tag == ElementClosingTag::Present {
+          tokens.push(Token::EndTag(name.clone()));
+        };
+      }
+      NodeData::Instruction { code, .. } => tokens.push(Token::Instruction(code.clone())),
+      NodeData::RcdataContent { text, .. } => tokens.push(Token::RawText(text.clone())),
+      NodeData::ScriptOrStyleContent { code, .. } => tokens.push(Token::RawText(code.clone())),
+      NodeData::Text { value, .. } => push_text(tokens, value),
+      NodeData::Opaque { raw_source, .. } => push_text(tokens, raw_source),
+    };
+  }
+}
+
+// `parse` has its own lexer, so check that it agrees with `tokenize` on real documents, which don't use the cases where they differ that are listed on `tokenize`.
+#[test]
+fn test_tokenize_matches_parse_on_corpus() {
+  let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("benches/corpus");
+  let mut pages = fs::read_dir(dir)
+    .unwrap()
+    .map(|e| e.unwrap().path())
+    .filter(|p| p.extension().is_some_and(|e| e == "html"))
+    .collect::<Vec<_>>();
+  pages.sort();
+  assert!(!pages.is_empty());
+  for page in pages {
+    let src = fs::read(&page).unwrap();
+    let mut parsed = Vec::new();
+    tokens_from_tree(parse(&src).children(), &mut parsed);
+    assert!(
+      tokens_from_events(&src) == parsed,
+      "tokens differ for {}",
+      page.display()
+    );
+  }
+}
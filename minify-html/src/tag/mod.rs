@@ -1,8 +1,10 @@
 use aho_corasick::AhoCorasick;
 use aho_corasick::AhoCorasickBuilder;
 use aho_corasick::AhoCorasickKind;
-use once_cell::sync::Lazy;
+use minify_html_common::lazy::Lazy;
 
+// Matchers are built from static patterns, so building them never fails.
+#[allow(clippy::unwrap_used)]
 pub static TAG_TEXTAREA_END: Lazy<AhoCorasick> = Lazy::new(|| {
   AhoCorasickBuilder::new()
     .ascii_case_insensitive(true)
@@ -10,6 +12,7 @@ pub static TAG_TEXTAREA_END: Lazy<AhoCorasick> = Lazy::new(|| {
     .build(["</textarea"])
     .unwrap()
 });
+#[allow(clippy::unwrap_used)]
 pub static TAG_TITLE_END: Lazy<AhoCorasick> = Lazy::new(|| {
   AhoCorasickBuilder::new()
     .ascii_case_insensitive(true)
@@ -0,0 +1,96 @@
+use crate::cfg::Cfg;
+use crate::minify;
+use html5ever::parse_document;
+use html5ever::tendril::TendrilSink;
+use markup5ever_rcdom::Handle;
+use markup5ever_rcdom::NodeData;
+use markup5ever_rcdom::RcDom;
+use std::str::from_utf8;
+
+fn write_text(out: &mut String, text: &mut String, depth: usize) {
+  let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+  if !collapsed.is_empty() {
+    out.push_str(&format!("{}{:?}\n", "  ".repeat(depth), collapsed));
+  };
+  text.clear();
+}
+
+// Serialises a DOM, ignoring differences minification is allowed to make: comments, whitespace-only text, and whitespace runs within text.
+fn dump(out: &mut String, node: &Handle, depth: usize) {
+  if let NodeData::Element { name, attrs, .. } = &node.data {
+    let mut attrs = attrs
+      .borrow()
+      .iter()
+      .map(|a| format!(" {}={:?}", a.name.local, a.value.to_string()))
+      .collect::<Vec<_>>();
+    attrs.sort();
+    out.push_str(&format!(
+      "{}<{}{}>\n",
+      "  ".repeat(depth),
+      name.local,
+      attrs.concat()
+    ));
+  };
+  // Removing comments can merge adjacent text nodes.
+  let mut text = String::new();
+  for child in node.children.borrow().iter() {
+    match &child.data {
+      NodeData::Text { contents } => text.push_str(&contents.borrow()),
+      NodeData::Comment { .. } => {}
+      _ => {
+        write_text(out, &mut text, depth + 1);
+        dump(out, child, depth + 1);
+      }
+    };
+  }
+  write_text(out, &mut text, depth + 1);
+}
+
+fn browser_dom(src: &[u8]) -> String {
+  let dom = parse_document(RcDom::default(), Default::default())
+    .from_utf8()
+    .read_from(&mut &src[..])
+    .unwrap();
+  let mut out = String::new();
+  dump(&mut out, &dom.document, 0);
+  out
+}
+
+#[test]
+fn test_minified_dom_matches_source_dom() {
+  let cfg = Cfg::default();
+  for src in [
+    // Foster parenting.
+    "<table>foo<tr><td>a</td></tr></table>",
+    "<table><tr><td>a</td>b</tr>c</table>",
+    "<table><tr><td>a</td></tr><div>x</div></table>",
+    "<tr><td>a</td></tr>",
+    "<table><tr><td>a</table>b",
+    // Adoption agency.
+    "<b><i>x</b>y</i>z",
+    "<b><i>x</b></i>z",
+    "<a href=x><em class=y>1</a>2</em>3",
+    "<p><b>x</p>y",
+    // Closing tags of ancestors.
+    "<div><span>x</div><p>y</p>",
+    "<ul><li><span>a</ul>b",
+    "<body><div>a</body>b",
+    // Stray closing tags.
+    "a</br>b",
+    "a</p>b",
+    "<div>a</p>b</div>",
+    "a</div>b</span>c",
+    // Form element pointer.
+    "<form a><input><form b><input></form><input>",
+    "<div><form><input></div><form><input></form><form><input></form>",
+  ] {
+    let min = minify(src.as_bytes(), &cfg);
+    assert_eq!(
+      browser_dom(src.as_bytes()),
+      browser_dom(&min),
+      "source {:?} minified to {:?}",
+      src,
+      from_utf8(&min).unwrap()
+    );
+  }
+}
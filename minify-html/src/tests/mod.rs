@@ -1,10 +1,40 @@
+use crate::ast::ElementClosingTag;
+use crate::ast::NodeData;
 use crate::cfg::Cfg;
+use crate::detect_charset;
+use crate::encode::encode_attr_value;
+use crate::encode::encode_text;
+use crate::encode::AttrEncoded;
+use crate::entities;
 use crate::minify;
+use crate::minify_css_str;
+use crate::minify_js_str;
+use crate::minify_verbose;
+use crate::minify_with_stats;
+use crate::parse;
+use crate::parse::content::parse_content;
+use crate::parse::Code;
+use crate::spec;
+use crate::try_minify;
+use crate::ElementView;
+use crate::LineEnding;
+use crate::MinifyError;
+use crate::MinifyStats;
+use crate::Namespace;
+use crate::OutputEncoding;
+use crate::TextContext;
+use crate::VoidSelfClosingStyle;
+use crate::WhitespaceMode;
+use minify_html_common::spec::tag::EMPTY_SLICE;
 use minify_html_common::tests::create_common_css_test_data;
 use minify_html_common::tests::create_common_js_test_data;
 use minify_html_common::tests::create_common_noncompliant_test_data;
 use minify_html_common::tests::create_common_test_data;
 use std::str::from_utf8;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+mod dom;
 
 pub fn eval_with_cfg(src: &'static [u8], expected: &'static [u8], cfg: &Cfg) {
   let min = minify(&src, cfg);
@@ -59,175 +89,2149 @@ fn test_common() {
 
 #[test]
 fn test_keep_ssi_comments() {
-  eval(b"<!--#include >", b"");
+  eval(b"<!--#include -->", b"");
   let mut cfg = Cfg::default();
   cfg.keep_ssi_comments = true;
-  eval_with_cfg(b"<!--#include >", b"<!--#include >", &cfg);
+  eval_with_cfg(b"<!--#include -->", b"<!--#include -->", &cfg);
 }
 
 #[test]
-fn test_keep_input_type_text_attr() {
-  eval(b"<input type=\"text\">", b"<input>");
+fn test_keep_balancing_comments() {
+  eval(b"<div>a</div><!-- /container -->", b"<div>a</div>");
   let mut cfg = Cfg::default();
-  cfg.keep_input_type_text_attr = true;
-  eval_with_cfg(b"<input type=\"TExt\">", b"<input type=text>", &cfg);
+  cfg.keep_balancing_comments = true;
+  eval_with_cfg(
+    b"<div>a</div><!-- /container --><!--/--><!-- a/b -->",
+    b"<div>a</div><!-- /container --><!--/-->",
+    &cfg,
+  );
 }
 
 #[test]
-fn test_preserve_template_brace_syntax() {
-  eval_with_js_min(
-    b"<p> {{   hello    world! %}  {%}{#} echo '  </p><P><script>  let x = 1; //'  }} </p>",
-    b"<p>{{ hello world! %} {%}{#} echo '<p><script>let x=1",
+fn test_preserve_ko_comments() {
+  eval(b"<!-- ko if: a --><b>x</b><!-- /ko -->", b"<b>x</b>");
+  let mut cfg = Cfg::default();
+  cfg.preserve_ko_comments = true;
+  eval_with_cfg(
+    b"<!-- ko if: a --><b>x</b><!--/ko  --><!-- c --><!--kobe-->",
+    b"<!-- ko if: a --><b>x</b><!--/ko  -->",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<!--\n\tko\tforeach: items\n-->",
+    b"<!--\n\tko\tforeach: items\n-->",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_framework_binding_attrs() {
+  let src = b"<div :class=\"{ a: b }\" @click=\"go()\" v-if=\"x\" *ngIf=\"show\" [title]=\"t\" (click)=\"f($event)\" on:click=\"h\" data-x=\"y\"></div>";
+  eval(
+    src,
+    b"<div :class=\"{ a: b }\" (click)=f($event) *ngif=show @click=go() [title]=t data-x=y on:click=h v-if=x></div>",
   );
   let mut cfg = Cfg::default();
-  cfg.preserve_brace_template_syntax = true;
+  cfg.enable_framework_bindings();
   eval_with_cfg(
-    b"<p> {{   hello    world! %}  {%}{#} echo '  </p><P><script>  let x = 1; //'  }} </p>",
-    b"<p>{{   hello    world! %}  {%}{#} echo '  </p><P><script>  let x = 1; //'  }}",
+    src,
+    b"<div (click)=\"f($event)\" *ngif=\"show\" :class=\"{ a: b }\" @click=\"go()\" [title]=\"t\" on:click=\"h\" v-if=\"x\" data-x=y></div>",
     &cfg,
   );
+  // Whitespace is kept, and empty values like Svelte's or Alpine.js's are written without a value.
   eval_with_cfg(
-    b"<p> {%   hello    world! %}  {%}{#} echo '  </p><P><script>  let x = 1; //'  %} </p>",
-    b"<p>{%   hello    world! %} {%}{#} echo '  </p><P><script>  let x = 1; //'  %}",
+    b"<input bind:value=\" name \" x-cloak=\"\" #ref>",
+    b"<input bind:value=\" name \" #ref x-cloak>",
     &cfg,
   );
+}
+
+#[test]
+fn test_html_attributes() {
+  let mut cfg = Cfg::new();
+  cfg.html_attributes.push(b"data-content".to_vec());
   eval_with_cfg(
-    b"<p> {#   hello    world! #}  {#}{# echo '  </p><P><script>  let x = 1; //'  #} </p>",
-    b"<p>{#   hello    world! #} {#}{# echo '  </p><P><script>  let x = 1; //'  #}",
+    b"<button data-content=\"<table><!-- c --><tr><td> a </td><td>b</td></tr></table>\" title=\"<b>  x  </b>\">x</button>",
+    b"<button data-content=\"<table><tr><td>a<td>b</table>\" title=\"<b>  x  </b>\">x</button>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<a data-content=\"&lt;b&gt;  bold  &lt;/b&gt;\">x</a>",
+    b"<a data-content=\"<b> bold </b>\">x</a>",
+    &cfg,
+  );
+  // Values aren't replaced if minifying them as HTML would make them longer.
+  eval_with_cfg(
+    b"<a data-content=\"a < b\">x</a>",
+    b"<a data-content=\"a < b\">x</a>",
+    &cfg,
+  );
+  // Document-level passes don't run on the attribute's HTML, so IDs in it are kept for the main document to reference.
+  cfg.remove_unreferenced_ids = true;
+  cfg.preserve_prolog = true;
+  eval_with_cfg(
+    b"<button data-content=\"<html lang='en'><span id=tip>  Hi  </span>\">x</button><p aria-describedby=tip>y</p>",
+    b"<button data-content=\"<html lang=en><span id=tip> Hi </span>\">x</button><p aria-describedby=tip>y",
     &cfg,
   );
 }
 
 #[test]
-fn test_preserve_template_chevron_percent_syntax() {
-  let mut cfg = Cfg::default();
-  cfg.preserve_chevron_percent_template_syntax = true;
+fn test_noscript_content_is_minified_as_html() {
+  eval(
+    b"<div><noscript>  <p>  a  </p>  <!-- c --></noscript></div>",
+    b"<div><noscript><p>a</p></noscript></div>",
+  );
+}
+
+#[test]
+fn test_legacy_raw_text_elements() {
+  // `<xmp>` content is raw text that ends at the first `</xmp`, even inside what looks like an attribute.
+  eval(
+    b"<xmp>  a &amp;  <b>  </xmp>",
+    b"<xmp>  a &amp;  <b>  </xmp>",
+  );
+  eval(
+    b"<xmp><a title=\"</xmp>\">x</a>",
+    b"<xmp><a title=\"</xmp>\">x",
+  );
+  // Raw text only ends at a closing tag with exactly the element's name.
+  eval(b"<xmp></xmpl> a </XMP >", b"<xmp></xmpl> a </xmp>");
+  eval(
+    b"<script>a = \"</scripts>\"</script>",
+    b"<script>a = \"</scripts>\"</script>",
+  );
+  // `<listing>` is parsed like `<pre>`.
+  eval(
+    b"<listing>  a   <b> b  </b>  </listing>",
+    b"<listing>  a   <b> b  </b>  </listing>",
+  );
+  // Everything after `<plaintext>` is raw text, and it and its ancestors are never closed.
+  eval(
+    b"<div><plaintext>a &amp; <b>  </plaintext></div>",
+    b"<div><plaintext>a &amp; <b>  </plaintext></div>",
+  );
+}
+
+#[test]
+fn test_remove_redundant_script_attributes() {
+  eval(
+    b"<script async defer src=a.js></script>",
+    b"<script async defer src=a.js></script>",
+  );
+  let mut cfg = Cfg::new();
+  cfg.remove_redundant_script_attributes = true;
+  for (src, expected) in [
+    (
+      "<script async defer src=a.js></script>",
+      "<script async src=a.js></script>",
+    ),
+    (
+      "<script defer src=a.js></script>",
+      "<script defer src=a.js></script>",
+    ),
+    (
+      "<script async src=a.js></script>",
+      "<script async src=a.js></script>",
+    ),
+    (
+      "<script defer type=module src=a.js></script>",
+      "<script src=a.js type=module></script>",
+    ),
+    (
+      "<script async type=module src=a.js></script>",
+      "<script async src=a.js type=module></script>",
+    ),
+    (
+      "<script async defer type=module>x</script>",
+      "<script async type=module>x</script>",
+    ),
+    ("<script async defer>x</script>", "<script>x</script>"),
+    ("<script defer>x</script>", "<script>x</script>"),
+  ] {
+    assert_eq!(
+      from_utf8(&minify(src.as_bytes(), &cfg)).unwrap(),
+      expected,
+      "{}",
+      src
+    );
+  }
+}
+
+#[test]
+fn test_strip_legacy_script_comments() {
+  let src: &[u8] = b"<script><!-- hide\nalert(1);\n// end hiding -->\n</script><style>  <!--\np{color:red}\n-->  </style><script type=text/template><!--\nx\n//--></script>";
+  eval(
+    src,
+    b"<script><!-- hide\nalert(1);\n// end hiding --></script><style><!--\np{color:red}\n--></style><script type=text/template><!--\nx\n//--></script>",
+  );
+  let mut cfg = Cfg::new();
+  cfg.strip_legacy_script_comments = true;
   eval_with_cfg(
-    b"<p> <%   hello    world! #}  {#}{# echo '  </p><P><script>  let x = 1; //'  %> </p>",
-    b"<p><%   hello    world! #}  {#}{# echo '  </p><P><script>  let x = 1; //'  %>",
+    src,
+    b"<script>alert(1);</script><style>p{color:red}</style><script type=text/template><!--\nx\n//--></script>",
+    &cfg,
+  );
+  // The line with `-->` must be a JS comment, otherwise the content is only trimmed.
+  eval_with_cfg(
+    b"<script><!--\nalert(1); -->\n</script>",
+    b"<script><!--\nalert(1); --></script>",
     &cfg,
   );
 }
 
 #[test]
-fn test_minification_of_doctype() {
+fn test_strip_code_comments() {
   let mut cfg = Cfg::new();
-  cfg.minify_doctype = true;
-  eval_with_cfg(b"<!DOCTYPE html><div>", b"<!doctypehtml><div>", &cfg);
+  cfg.strip_code_comments = true;
   eval_with_cfg(
-    b"<!DOCTYPE html SYSTEM 'about:legacy-compat'><div>",
-    b"<!doctypehtml SYSTEM 'about:legacy-compat'><div>",
+    b"<script>var s = \"/* no */\"; // x\nvar re = /\\/*a/g; /* drop */ var t = `//${b /* c */}`;\n/*! keep */\n//# sourceMappingURL=a.js.map\n</script>",
+    b"<script>var s = \"/* no */\";\nvar re = /\\/*a/g;  var t = `//${b }`;\n/*! keep */\n//# sourceMappingURL=a.js.map</script>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<script><!--\nalert(1); // a\n//--></script><script>a = (b) / 2 // half\nif (a) /=/.test(b) /* c */</script>",
+    b"<script>alert(1);</script><script>a = (b) / 2\nif (a) /=/.test(b)</script>",
+    &cfg,
+  );
+  // A `/` after `}` could be division or start a regular expression, so the code is left as is.
+  eval_with_cfg(
+    b"<script>x = {} /* c */ / 2</script>",
+    b"<script>x = {} /* c */ / 2</script>",
+    &cfg,
+  );
+  // Comments that are part of hacks like `html>/**/body` are kept.
+  eval_with_cfg(
+    b"<style>/* a */ p { background: url(//example.com/a.png) } /*! keep */ q{color:red}/* b */ html>/**/body{x:\"/*\"}</style>",
+    b"<style>p { background: url(//example.com/a.png) } /*! keep */ q{color:red} html>/**/body{x:\"/*\"}</style>",
     &cfg,
   );
 }
 
 #[test]
-fn test_removal_of_empty_closing_tag() {
-  eval(b"<body><p>1</><p>2</body>", b"<body><p>1<p>2");
+fn test_mis_nested_tags() {
+  eval(b"<div><span>x</div><p>y</p>", b"<div><span>x</div><p>y");
+  eval(b"<b><i>x</b>y</i>z", b"<b><i>x</i></b><i>y</i>z");
+  eval(b"<b><i>x</b></i>z", b"<b><i>x</i></b>z");
+  eval(b"a</br>b", b"a<br>b");
+  eval(b"<div>a</p>b</div>", b"<div>a<p></p>b</div>");
+  eval(b"a</div>b</span>c", b"abc");
 }
 
 #[test]
-fn test_parsing_extra_head_tag() {
-  // Extra `<head>` in `<label>` should be dropped, so whitespace around `<head>` should be joined and therefore trimmed due to `<label>` whitespace rules.
+fn test_nested_forms() {
   eval(
-    b"<html><head><meta><head><link><head><body><label>  <pre> </pre> <head>  </label>",
-    b"<html><head><meta><link><body><label><pre> </pre></label>",
+    b"<form a><input><form b><input></form><input>",
+    b"<form a><input><input></form><input>",
   );
-  // Same as above except it's a `</head>`, which should get reinterpreted as a `<head>`.
   eval(
-    b"<html><head><meta><head><link><head><body><label>  <pre> </pre> </head>  </label>",
-    b"<html><head><meta><link><body><label><pre> </pre></label>",
+    b"<form><input></form></form><input>",
+    b"<form><input></form><input>",
   );
-  // `<head>` gets implicitly closed by `<body>`, so any following `</head>` should be ignored. (They should be anyway, since `</head>` would not be a valid closing tag.)
+  // The form element pointer outlives the implicitly closed form, so the second form start tag is ignored, and the stray `</form>` must be kept as it resets the pointer.
   eval(
-    b"<html><head><body><label> </head> </label>",
-    b"<html><head><body><label></label>",
+    b"<div><form><input></div><form><input></form><form><input></form>",
+    b"<div><form><input></div><input></form><form><input></form>",
+  );
+  // Nested forms are allowed in templates.
+  eval(
+    b"<form><template><form><input></form></template></form>",
+    b"<form><template><form><input></form></template></form>",
   );
 }
 
 #[test]
-fn test_removal_of_html_and_head_opening_tags() {
-  // Even though `<head>` is dropped, it's still parsed, so its content is still subject to `<head>` whitespace minification rules.
-  eval_without_keep_html_head(
-    b"<!DOCTYPE html><html><head>  <meta> <body>",
-    b"<!doctype html><meta><body>",
+fn test_preserve_microdata_attributes() {
+  let mut cfg = Cfg::new();
+  cfg.remove_data_attrs = vec![b"item*".to_vec()];
+  eval_with_cfg(
+    b"<div itemscope=\"\" itemtype=\"https://schema.org/Person\"><span itemprop=\"\">a</span></div>",
+    b"<div><span>a</span></div>",
+    &cfg,
   );
-  // The tag should not be dropped if it has attributes.
-  eval_without_keep_html_head(
-    b"<!DOCTYPE html><html lang=en><head>  <meta> <body>",
-    b"<!doctype html><html lang=en><meta><body>",
+  cfg.preserve_microdata_attributes = true;
+  eval_with_cfg(
+    b"<div itemscope=\"\" itemtype=\"https://schema.org/Person\"><span itemprop=\"\">a</span></div>",
+    b"<div itemscope itemtype=https://schema.org/Person><span itemprop>a</span></div>",
+    &cfg,
   );
-  // The tag should be dropped if it has no attributes after minification.
-  eval_without_keep_html_head(
-    b"<!DOCTYPE html><html style='  '><head>  <meta> <body>",
-    b"<!doctype html><meta><body>",
+}
+
+#[test]
+fn test_js_minification_never_ends_script_early() {
+  let mut cfg = Cfg::new();
+  cfg.minify_js = true;
+  for src in [
+    b"<script>let a = \"<\\/script>\";  alert( a );</script>".as_slice(),
+    b"<script>let a = '<\\/SCRIPT>';  alert( a );</script>",
+    b"<script>let a = `<\\/script ${1}`;  alert( a );</script>",
+    b"<script>let a = \"<\\!-- <script>\";  alert( a );</script>",
+  ] {
+    let min = minify(src, &cfg);
+    let closing_tags = min
+      .windows(8)
+      .filter(|w| w.eq_ignore_ascii_case(b"</script"))
+      .count();
+    assert_eq!(closing_tags, 1, "{}", from_utf8(&min).unwrap());
+    assert!(
+      !min.windows(4).any(|w| w == b"<!--"),
+      "{}",
+      from_utf8(&min).unwrap()
+    );
+  }
+}
+
+#[test]
+fn test_preserve_prolog() {
+  let mut cfg = Cfg::new();
+  cfg.preserve_prolog = true;
+  eval_with_cfg(
+    b"<!DOCTYPE HTML>\n<!-- c -->\n<html lang='en'  dir=ltr>\n<head><meta charset=\"utf-8\"></head><body><p>  x  </p></body></html>",
+    b"<!DOCTYPE HTML>\n<!-- c -->\n<html lang='en'  dir=ltr><meta charset=utf-8><body><p>x",
+    &cfg,
   );
+  // Content after the `<html>` element stays outside it.
+  cfg.keep_comments = true;
+  eval_with_cfg(
+    b"<HTML>  <p>x</p></html>  <!-- c -->",
+    b"<HTML><p>x</html><!-- c -->",
+    &cfg,
+  );
+  // Without an `<html>` start tag, or with an element before it, everything is minified.
+  eval_with_cfg(b"<!DOCTYPE HTML>  <p>x</p>", b"<!doctype html><p>x", &cfg);
 }
 
 #[test]
-fn test_unmatched_closing_tag() {
-  eval(b"Hello</p>Goodbye", b"HelloGoodbye");
-  eval(b"Hello<br></br>Goodbye", b"Hello<br>Goodbye");
-  eval(b"<div>Hello</p>Goodbye", b"<div>HelloGoodbye");
-  eval(b"<ul><li>a</p>", b"<ul><li>a");
-  eval(b"<ul><li><rt>a</p>", b"<ul><li><rt>a");
-  eval(
-    b"<html><head><body><ul><li><rt>a</p>",
-    b"<html><head><body><ul><li><rt>a",
+fn test_for_email_html() {
+  eval_with_cfg(
+    b"<!DOCTYPE html>\n<html>\n<head><style> td { color : red } </style></head>\n<body>\n  <!--[if mso]><table><tr><td><![endif]-->\n  <div>  <p>Hi</p>  </div>\n  <input type=\"text\">\n</body>\n</html>",
+    b"<!doctype html><html><head><style>td { color : red }</style></head><body><!--[if mso]><table><tr><td><![endif]--><div><p>Hi</p></div><input type=text></body></html>",
+    &Cfg::for_email_html(),
   );
 }
 
 #[test]
-// NOTE: Keep inputs in sync with onepass variant. Outputs are different as main variant reorders attributes.
-fn test_space_between_attrs_minification() {
-  eval_with_noncompliant(
-    b"<div a=\" \" b=\" \"></div>",
-    b"<div a=\" \"b=\" \"></div>",
+fn test_unterminated_constructs_at_eof() {
+  // Comment, even if comments are removed.
+  eval(b"a<!-- b", b"a<!-- b");
+  // Bang.
+  eval(b"a<!b", b"a<!b");
+  eval_with_cfg(b"a<!b", b"a<!b", &Cfg {
+    remove_bangs: true,
+    ..Cfg::default()
+  });
+  // Instruction.
+  eval(b"a<?b ?", b"a<?b ?");
+  // Doctype.
+  eval(b"<!DOCTYPE  html", b"<!DOCTYPE  html");
+  // Start tag.
+  eval(b"a<div  b", b"a<div  b");
+  eval(b"a<div  /", b"a<div  /");
+  // Attribute value.
+  eval(b"a<div b=\"c  d", b"a<div b=\"c  d");
+  eval(b"a<div b='c>", b"a<div b='c>");
+  eval(b"a<div b=c", b"a<div b=c");
+  // Script and style.
+  eval_with_js_min(b"<script>let a = 1;", b"<script>let a = 1;");
+  eval_with_css_min(b"<style>a { color: red }", b"<style>a { color: red }");
+  // CDATA.
+  eval(b"<svg><![CDATA[a  b", b"<svg><![CDATA[a  b");
+  // A trailing `<` is just text.
+  eval(b"a <", b"a &lt;");
+  // Malformed closing tag.
+  eval(b"a</ b", b"a</ b");
+  // Ignored tags.
+  eval(b"a</div  b", b"a</div  b");
+}
+
+#[test]
+fn test_minify_css_str() {
+  let src = b"\n  a { color: red; }\n";
+  assert_eq!(minify_css_str(src, &Cfg::new()), b"a { color: red; }");
+  assert_eq!(
+    minify_css_str(src, &Cfg {
+      minify_css: true,
+      ..Cfg::default()
+    }),
+    b"a{color:red}"
   );
-  eval_with_noncompliant(b"<div a=' ' b=\" \"></div>", b"<div a=\" \"b=\" \"></div>");
-  eval_with_noncompliant(
-    b"<div a=&#x20 b=\" \"></div>",
-    b"<div a=\" \"b=\" \"></div>",
+}
+
+#[test]
+fn test_minify_js_str() {
+  let src = b"\n  let a = 1;\n";
+  assert_eq!(minify_js_str(src, &Cfg::new()), b"let a = 1;");
+  assert_eq!(
+    minify_js_str(src, &Cfg {
+      minify_js: true,
+      ..Cfg::default()
+    }),
+    b"let a=1"
   );
-  eval_with_noncompliant(b"<div a=\"1\" b=\" \"></div>", b"<div b=\" \"a=1></div>");
-  eval_with_noncompliant(b"<div a='1' b=\" \"></div>", b"<div b=\" \"a=1></div>");
-  eval_with_noncompliant(b"<div a=\"a\"b=\"b\"></div>", b"<div a=a b=b></div>");
 }
 
 #[test]
-fn test_attr_whatwg_unquoted_value_minification() {
-  eval(b"<a b==></a>", br#"<a b="="></a>"#);
-  eval(br#"<a b=`'"<<==/`/></a>"#, br#"<a b="`'&#34<<==/`/"></a>"#);
+fn test_no_panic_on_non_utf8() {
+  let mut cfg = Cfg::new();
+  cfg.minify_css = true;
+  cfg.minify_js = true;
+  assert_eq!(
+    minify(b"<style> a{\xff} </style>", &cfg),
+    b"<style>a{\xff}</style>"
+  );
+  assert_eq!(
+    minify(b"<div style=\"\xff\"></div>", &cfg),
+    b"<div style=\xff></div>"
+  );
+  let doc = parse(b"<p \xff=\"\xfe\"><!--\xfd-->\xfc");
+  assert!(!format!("{:?}", doc.children()).is_empty());
 }
 
+#[cfg(feature = "tracking")]
 #[test]
-fn test_alt_attr_minification() {
-  eval(br#"<img alt="  ">"#, br#"<img alt="  ">"#);
-  eval(br#"<img alt=" ">"#, br#"<img alt=" ">"#);
-  eval(br#"<img alt="">"#, br#"<img alt>"#);
-  eval(br#"<img alt=''>"#, br#"<img alt>"#);
-  eval(br#"<img alt>"#, br#"<img alt>"#);
-  eval(br#"<x-any-tag alt>"#, br#"<x-any-tag alt>"#);
+fn test_source_positions() {
+  let doc = parse(b"<p>a\n<!-- b -->\n  <b>c</b>");
+  let p = &doc.children()[0];
+  assert_eq!(p.source_position(), (0, 1));
+  let children = match p {
+    NodeData::Element { children, .. } => children,
+    _ => unreachable!(),
+  };
+  assert_eq!(
+    children
+      .iter()
+      .map(|c| c.source_position())
+      .collect::<Vec<_>>(),
+    vec![(3, 1), (5, 2), (15, 2), (18, 3)]
+  );
 }
 
 #[test]
-fn test_viewport_attr_minification() {
-  eval_with_noncompliant(
-    b"<meta name=viewport content='width=device-width, initial-scale=1'>",
-    b"<meta content=width=device-width,initial-scale=1 name=viewport>",
+fn test_respect_contenteditable() {
+  let mut cfg = Cfg::new();
+  eval_with_cfg(
+    b"<div contenteditable>  a  </div>",
+    b"<div contenteditable>a</div>",
+    &cfg,
   );
-  eval(
-    b"<meta name=viewport content='width=device-width, initial-scale=1'>",
-    br#"<meta content="width=device-width,initial-scale=1" name=viewport>"#,
+  cfg.respect_contenteditable = true;
+  eval_with_cfg(
+    b"<div contenteditable>  a  <p>  b  </p>  </div><section contenteditable=plaintext-only>  c  </section>",
+    b"<div contenteditable>  a  <p>  b  </p>  </div><section contenteditable=plaintext-only>  c  </section>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<div contenteditable=false>  a  </div><div contenteditable=\" False \">  b  </div>",
+    b"<div contenteditable=false>a</div><div contenteditable=\" False \">b</div>",
+    &cfg,
   );
 }
 
 #[test]
-fn test_style_attr_minification() {
-  eval_with_css_min(
-    br#"<div style="color: yellow;"></div>"#,
-    br#"<div style=color:#ff0></div>"#,
+fn test_unwrap_redundant_wrappers() {
+  let mut cfg = Cfg::new();
+  cfg.unwrap_redundant_wrappers = true;
+  // Nested wrappers are removed, and whitespace around a block in a `<div>` is ignored.
+  eval_with_cfg(
+    b"<section><div>\n  <div><p>a</p></div>\n</div></section>",
+    b"<section><p>a</section>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<p><span><span><b>a</b></span></span> b</p>",
+    b"<p><b>a</b> b",
+    &cfg,
+  );
+  // Wrappers with attributes, more than one child, or a child that would change layout are kept.
+  eval_with_cfg(
+    b"<div class=a><p>a</p></div><div><p>a</p><p>b</p></div><div><b>a</b></div><div><!-- c --><p>a</p></div>",
+    b"<div class=a><p>a</div><div><p>a<p>b</div><div><b>a</b></div><div><p>a</div>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<p><span> <b>a</b></span><span><img></span></p>",
+    b"<p><span> <b>a</b></span><span><img></span>",
+    &cfg,
+  );
+  // Whitespace-sensitive content is left as is.
+  eval_with_cfg(
+    b"<pre><div> <p>a</p> </div></pre>",
+    b"<pre><div> <p>a</p> </div></pre>",
+    &cfg,
+  );
+  cfg.unwrap_redundant_wrappers = false;
+  eval_with_cfg(
+    b"<section><div><p>a</p></div></section>",
+    b"<section><div><p>a</div></section>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_output_encoding() {
+  let src = "<p title=\"é €\">é € 😀</p>".as_bytes();
+  let mut cfg = Cfg::new();
+  assert_eq!(minify(src, &cfg), "<p title=\"é €\">é € 😀".as_bytes());
+  cfg.output_encoding = OutputEncoding::AsciiSafe;
+  assert_eq!(
+    minify(src, &cfg),
+    b"<p title=\"&#233; &#8364;\">&#233; &#8364; &#128512;"
+  );
+  eval_with_cfg(
+    "<textarea>é€</textarea><script>let a = \"é\";</script>".as_bytes(),
+    b"<textarea>&#233;&#8364;</textarea><script>let a = \"\xc3\xa9\";</script>",
+    &cfg,
+  );
+  cfg.output_encoding = OutputEncoding::Latin1;
+  assert_eq!(
+    minify(src, &cfg),
+    b"<p title=\"\xe9 &#8364;\">\xe9 &#8364; &#128512;"
+  );
+}
+
+#[test]
+fn test_void_self_closing_style() {
+  let src =
+    b"<div>a<br>b<img src=a.png><input disabled><img alt=\"x y\"><svg><path d=a /></svg></div>";
+  let mut cfg = Cfg::new();
+  eval_with_cfg(
+    src,
+    b"<div>a<br>b<img src=a.png><input disabled><img alt=\"x y\"><svg><path d=a /></svg></div>",
+    &cfg,
+  );
+  cfg.void_self_closing_style = VoidSelfClosingStyle::Slash;
+  eval_with_cfg(
+    src,
+    b"<div>a<br/>b<img src=a.png /><input disabled /><img alt=\"x y\"/><svg><path d=a /></svg></div>",
+    &cfg,
+  );
+  cfg.void_self_closing_style = VoidSelfClosingStyle::SpaceSlash;
+  eval_with_cfg(
+    src,
+    b"<div>a<br />b<img src=a.png /><input disabled /><img alt=\"x y\" /><svg><path d=a /></svg></div>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_preserve_ignored_regions() {
+  let src = b"<section>\n  <div>  a  </div>\n  <!-- htmlmin:ignore -->\n  <div>  b  </div>\n  <!-- c -->\n  <!-- htmlmin:endignore -->\n  <div>  c  </div>\n</section>";
+  let mut cfg = Cfg::new();
+  eval_with_cfg(
+    src,
+    b"<section><div>a</div><div>b</div><div>c</div></section>",
+    &cfg,
+  );
+  cfg.preserve_ignored_regions = true;
+  eval_with_cfg(
+    src,
+    b"<section><div>a</div>\n  <div>  b  </div>\n  <!-- c -->\n  <div>c</div></section>",
+    &cfg,
+  );
+  // Without an end comment, the rest of the source is kept.
+  eval_with_cfg(
+    b"<div>  a  <!-- htmlmin:ignore -->  b  ",
+    b"<div>a  b  ",
+    &cfg,
+  );
+  cfg.ignored_region_markers = Some((b"keep".to_vec(), b"/keep".to_vec()));
+  eval_with_cfg(
+    b"<div><!-- htmlmin:ignore --><!--keep-->  a  <!--/keep--></div>",
+    b"<div>  a  </div>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_remove_hidden_elements() {
+  let mut cfg = Cfg::new();
+  cfg.remove_hidden_elements = true;
+  eval_with_cfg(
+    b"<div><span hidden>a</span><span>b</span></div>",
+    b"<div><span>b</span></div>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<div><p style=\"color: red; DISPLAY : None !important\">a</p><p style=display:none>b</p><p style=\"display: block\">c</p></div>",
+    b"<div><p style=\"display: block\">c</div>",
+    &cfg,
+  );
+  // Nested.
+  eval_with_cfg(
+    b"<div><section><div><span hidden>a</span>b</div></section></div>",
+    b"<div><section><div>b</div></section></div>",
+    &cfg,
+  );
+  // Never removed.
+  eval_with_cfg(
+    b"<div><template hidden>a</template><datalist style=display:none><option>b</datalist></div>",
+    b"<div><template hidden>a</template><datalist style=display:none><option>b</datalist></div>",
+    &cfg,
+  );
+  // Found by find-in-page and fragment navigation.
+  eval_with_cfg(
+    b"<div><p hidden=until-found>a</p><p hidden=\"\">b</p></div>",
+    b"<div><p hidden=until-found>a</div>",
+    &cfg,
+  );
+  // Could be referenced elsewhere, like a sprite sheet's symbols.
+  eval_with_cfg(
+    b"<svg style=display:none><symbol id=icon><path d=M0 /></symbol></svg><svg><use href=#icon /></svg><div hidden><p id=a>a</p></div><div hidden id=b></div>",
+    b"<svg style=display:none><symbol id=icon><path d=M0 /></symbol></svg><svg><use href=#icon /></svg><div hidden><p id=a>a</div><div hidden id=b></div>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_remove_legacy_meta() {
+  let src = b"<head><meta http-equiv=\" X-UA-Compatible \" content=a><meta name=Generator content=b><meta name=viewport content=c><title>d</title></head>";
+  let mut cfg = Cfg::new();
+  cfg.remove_x_ua_compatible_meta = true;
+  eval_with_cfg(
+    src,
+    b"<meta content=b name=Generator><meta content=c name=viewport><title>d</title>",
+    &cfg,
+  );
+  cfg.remove_generator_meta = true;
+  eval_with_cfg(src, b"<meta content=c name=viewport><title>d</title>", &cfg);
+}
+
+#[test]
+fn test_remove_html5_shiv() {
+  let mut cfg = Cfg::new();
+  cfg.keep_comments = true;
+  cfg.remove_html5_shiv = true;
+  eval_with_cfg(
+    b"<div><!--[if lt IE 9]>\n  <script src=\"js/HTML5Shiv.min.js\"></script>\n  <script src=respond.js?v=1></script>\n<![endif]--><script src=\"https://oss.maxcdn.com/html5shiv/3.7.3/html5shiv.min.js\"></script><script src=/js/correspond.js></script></div>",
+    b"<div><script src=/js/correspond.js></script></div>",
+    &cfg,
+  );
+  // Conditional comments with other content are kept as is.
+  eval_with_cfg(
+    b"<div><!--[if IE]><script src=selectivizr.js></script><link href=ie.css><![endif]--></div>",
+    b"<div><!--[if IE]><script src=selectivizr.js></script><link href=ie.css><![endif]--></div>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_minify_svg_default_attributes() {
+  let mut cfg = Cfg::new();
+  cfg.minify_svg_default_attributes = true;
+  eval_with_cfg(
+    b"<svg preserveAspectRatio=\"xMidYMid meet\" viewBox=\"0 0 10 10\"><rect x=\"0\" y=\"1\" width=\"10\" opacity=\"1\" fill-opacity=\"1\"/><circle cx=\"0\" cy=\" 0 \" r=\"2\" stroke-width=\"1\"/><line x1=\"0\" y1=\"0\" x2=\"5\" y2=\"0\"/></svg>",
+    b"<svg viewbox=\"0 0 10 10\"><rect fill-opacity=1 width=10 y=1 /><circle r=2 stroke-width=1 /><line x2=5 /></svg>",
+    &cfg,
+  );
+  // Only SVG elements.
+  eval_with_cfg(
+    b"<div opacity=1 x=0></div>",
+    b"<div opacity=1 x=0></div>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_max_depth() {
+  let mut cfg = Cfg::new();
+  cfg.max_depth = Some(100);
+  let src = "<div>".repeat(1_000_000) + "a";
+  // Everything from the first element that is too deep is dropped.
+  assert_eq!(
+    minify(src.as_bytes(), &cfg),
+    "<div>".repeat(100).into_bytes()
+  );
+  assert_eq!(
+    try_minify(src.as_bytes(), &cfg),
+    Err(MinifyError::MaxDepthExceeded { max_depth: 100 })
+  );
+  let src = "<div>".repeat(100) + "a";
+  assert_eq!(try_minify(src.as_bytes(), &cfg), Ok(src.into_bytes()));
+}
+
+#[test]
+fn test_whitespace_mode() {
+  let src = b"<ul>\n  <li><a>1</a> <a>2</a></li>\n</ul><p>  a  <b>b</b>  </p><span><a>3</a> <a>4</a></span>";
+  let mut cfg = Cfg::new();
+  eval_with_cfg(
+    src,
+    b"<ul><li><a>1</a> <a>2</a></ul><p>a <b>b</b></p><span><a>3</a> <a>4</a></span>",
+    &cfg,
+  );
+  cfg.whitespace_mode = WhitespaceMode::Aggressive;
+  // Whitespace in inline elements is still kept.
+  eval_with_cfg(
+    src,
+    b"<ul><li><a>1</a><a>2</a></ul><p>a <b>b</b></p><span><a>3</a> <a>4</a></span>",
+    &cfg,
+  );
+  cfg.whitespace_mode = WhitespaceMode::Preserve;
+  eval_with_cfg(
+    b"<div>  <span> a   b </span>\n</div>",
+    b"<div>  <span> a   b </span>\n</div>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_encode_matches_minify() {
+  fn escape(v: &str) -> String {
+    v.replace('&', "&amp;")
+      .replace('"', "&quot;")
+      .replace('<', "&lt;")
+  }
+  let values = [
+    "a", "a b", "a\"b", "a'b", "a\"'b", "<a>", "a&b", "&amp;", "a=b", "a`b", "ä", "a/",
+  ];
+  let mut cfg = Cfg::new();
+  for optimal in [false, true] {
+    cfg.allow_optimal_entities = optimal;
+    cfg.allow_noncompliant_unquoted_attribute_values = optimal;
+    for v in values {
+      let src = format!("<span data-x=\"{}\">{}</span>", escape(v), escape(v));
+      let attr = encode_attr_value(v.as_bytes(), &cfg);
+      let mut expected = b"<span data-x=".to_vec();
+      expected.extend_from_slice(&attr.quoted);
+      expected.push(b'>');
+      expected.extend_from_slice(&encode_text(v.as_bytes(), &cfg));
+      expected.extend_from_slice(b"</span>");
+      assert_eq!(
+        String::from_utf8(minify(src.as_bytes(), &cfg)).unwrap(),
+        String::from_utf8(expected).unwrap(),
+        "{}",
+        v
+      );
+    }
+  }
+  assert_eq!(encode_attr_value(b"", &cfg), AttrEncoded {
+    quoted: Vec::new(),
+    needs_quotes: false,
+  });
+}
+
+#[test]
+fn test_normalize_line_endings() {
+  let src = b"<pre>a\r\nb\rc\n</pre>\r\n<p title=\"x\r\ny\">d\r\ne</p>";
+  let mut cfg = Cfg::new();
+  eval_with_cfg(src, b"<pre>a\nb\nc\n</pre><p title=\"x\ny\">d e", &cfg);
+  cfg.normalize_line_endings = Some(LineEnding::Lf);
+  eval_with_cfg(src, b"<pre>a\nb\nc\n</pre><p title=\"x\ny\">d e", &cfg);
+  cfg.normalize_line_endings = Some(LineEnding::CrLf);
+  eval_with_cfg(
+    src,
+    b"<pre>a\r\nb\r\nc\r\n</pre><p title=\"x\r\ny\">d e",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_carriage_return_references_are_kept() {
+  eval(b"<pre>a&#13;b</pre>", b"<pre>a&#13;b</pre>");
+  eval(b"<pre>a&#xD;b\r\nc</pre>", b"<pre>a&#13;b\nc</pre>");
+  eval(b"<p title=\"a&#13;b\">c</p>", b"<p title=a&#13;b>c");
+}
+
+#[test]
+fn test_remove_type_module_attribute() {
+  let src = b"<script type=module src=a.js defer></script><script type=MODULE>a</script><script type=text/javascript>b</script>";
+  let mut cfg = Cfg::new();
+  cfg.remove_redundant_script_attributes = true;
+  eval_with_cfg(
+    src,
+    b"<script src=a.js type=module></script><script type=module>a</script><script>b</script>",
+    &cfg,
+  );
+  cfg.remove_type_module_attribute = true;
+  // `defer` is no longer redundant once the script isn't a module.
+  eval_with_cfg(
+    src,
+    b"<script defer src=a.js></script><script>a</script><script>b</script>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_detect_charset() {
+  assert_eq!(
+    detect_charset(b"<!doctype html><html><head><meta charset=\"Shift_JIS\">"),
+    Some(b"shift_jis".to_vec())
+  );
+  assert_eq!(
+    detect_charset(b"<meta content=\"text/html; charset=windows-1252\" http-equiv=content-type>"),
+    Some(b"windows-1252".to_vec())
+  );
+  // `content` without the pragma is ignored.
+  assert_eq!(
+    detect_charset(b"<meta content=\"text/html; charset=windows-1252\">"),
+    None
+  );
+  // Duplicate attributes are ignored.
+  assert_eq!(
+    detect_charset(b"<meta charset=koi8-r charset=utf-8>"),
+    Some(b"koi8-r".to_vec())
+  );
+  // Comments and attribute values of other tags are skipped.
+  assert_eq!(
+    detect_charset(
+      b"<!-- <meta charset=big5> --><div title=\"<meta charset=big5>\"><meta charset=gbk>"
+    ),
+    Some(b"gbk".to_vec())
+  );
+  assert_eq!(
+    detect_charset(b"<meta charset=utf-16le>"),
+    Some(b"utf-8".to_vec())
+  );
+  assert_eq!(
+    detect_charset(b"\xEF\xBB\xBF<meta charset=gbk>"),
+    Some(b"utf-8".to_vec())
+  );
+  // Only the first 1024 bytes are prescanned.
+  let mut src = vec![b' '; 1024];
+  src.extend_from_slice(b"<meta charset=gbk>");
+  assert_eq!(detect_charset(&src), None);
+  // An unterminated tag aborts the prescan.
+  assert_eq!(detect_charset(b"<meta charset=gbk"), None);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn test_minify_parallel() {
+  use crate::minify_parallel;
+  use crate::minify_parallel_files;
+  use std::fs;
+
+  let cfg = Cfg::new();
+  let inputs = (0..100)
+    .map(|i| format!("<p>  {}  </p>", i).into_bytes())
+    .collect::<Vec<_>>();
+  let inputs = inputs.iter().map(|i| i.as_slice()).collect::<Vec<_>>();
+  let expected = inputs.iter().map(|i| minify(i, &cfg)).collect::<Vec<_>>();
+  assert_eq!(minify_parallel(&inputs, &cfg), expected);
+
+  let dir = std::env::temp_dir().join(format!("minify-html-parallel-{}", std::process::id()));
+  fs::create_dir_all(&dir).unwrap();
+  let a = dir.join("a.html");
+  let b = dir.join("b.html");
+  let missing = dir.join("missing.html");
+  fs::write(&a, "<p>  a  </p>").unwrap();
+  fs::write(&b, "<div>  b  </div>").unwrap();
+  let results = minify_parallel_files(&[a.as_path(), missing.as_path(), b.as_path()], &cfg);
+  assert!(results[0].is_ok());
+  assert!(results[1].is_err());
+  assert!(results[2].is_ok());
+  assert_eq!(fs::read(&a).unwrap(), b"<p>a");
+  assert_eq!(fs::read(&b).unwrap(), b"<div>b</div>");
+  fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_remove_unreferenced_ids() {
+  let src: &[u8] = b"<div id=a><a href=#b>x</a><h2 id=b>b</h2><label for=c>c</label><input id=c><p id=d aria-labelledby=\"e f\">d</p><span id=e></span><span id=f></span><svg><g id=g></g><use href=#g /></svg><i id=h></i><i id=i></i><i id=caf\xc3\xa9></i><script>document.getElementById('h')</script><style>#i{color:red}</style></div>";
+  eval(src, b"<div id=a><a href=#b>x</a><h2 id=b>b</h2><label for=c>c</label><input id=c><p aria-labelledby=\"e f\" id=d>d</p><span id=e></span><span id=f></span><svg><g id=g></g><use href=#g /></svg><i id=h></i><i id=i></i><i id=caf\xc3\xa9></i><script>document.getElementById('h')</script><style>#i{color:red}</style></div>");
+  let mut cfg = Cfg::new();
+  cfg.remove_unreferenced_ids = true;
+  eval_with_cfg(src, b"<div><a href=#b>x</a><h2 id=b>b</h2><label for=c>c</label><input id=c><p aria-labelledby=\"e f\">d</p><span id=e></span><span id=f></span><svg><g id=g></g><use href=#g /></svg><i id=h></i><i id=i></i><i id=caf\xc3\xa9></i><script>document.getElementById('h')</script><style>#i{color:red}</style></div>", &cfg);
+  // IDs that must be escaped in CSS are kept, as the escaped reference doesn't contain the ID as is.
+  eval_with_cfg(
+    br"<i id=a.b></i><i id=a:b></i><i id=1a></i><i id=-1></i><i id=-a></i><style>#a\.b,#a\:b,#\31 a,#\-1{color:red}</style>",
+    br"<i id=a.b></i><i id=a:b></i><i id=1a></i><i id=-1></i><i></i><style>#a\.b,#a\:b,#\31 a,#\-1{color:red}</style>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_keep_shy_entity() {
+  eval(
+    b"<p>hyphen&shy;ation</p>",
+    "<p>hyphen\u{AD}ation".as_bytes(),
+  );
+  let mut cfg = Cfg::new();
+  cfg.keep_shy_entity = true;
+  eval_with_cfg(
+    "<p>hyphen&shy;ation and hyphen\u{AD}ation&#173;</p>".as_bytes(),
+    b"<p>hyphen&shy;ation and hyphen&shy;ation&shy;",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_whitespace_around_wbr() {
+  // Whitespace around `<wbr>` is minified exactly like around an empty `<span>`, so line break opportunities are unchanged.
+  let cfg = Cfg::new();
+  for src in [
+    "<p>a  <wbr>  b</p>",
+    "<p>a<wbr> b</p>",
+    "<div> <wbr> a</div>",
+    "<div>a <wbr> </div>",
+    "<div><span>a</span> <wbr> <span>b</span></div>",
+  ] {
+    let wbr = minify(src.as_bytes(), &cfg);
+    let span = minify(src.replace("<wbr>", "<span></span>").as_bytes(), &cfg);
+    assert_eq!(
+      String::from_utf8(wbr).unwrap(),
+      String::from_utf8(span)
+        .unwrap()
+        .replace("<span></span>", "<wbr>"),
+    );
+  }
+  eval(
+    b"<p>See https://example.com/<wbr>a/<wbr>very/<wbr>long/<wbr>path?<wbr>q=1  <wbr>  for more.</p>",
+    b"<p>See https://example.com/<wbr>a/<wbr>very/<wbr>long/<wbr>path?<wbr>q=1 <wbr> for more.",
+  );
+}
+
+#[test]
+fn test_trim_form_control_text() {
+  eval(
+    b"<select><option> a </option>\n<option>b  c</option></select><button> Send </button><textarea>  d  </textarea>",
+    b"<select><option> a <option>b c</select><button> Send </button><textarea>  d  </textarea>",
+  );
+  let mut cfg = Cfg::new();
+  cfg.trim_form_control_text = true;
+  eval_with_cfg(
+    b"<select><option> a </option>\n<option>b  c</option></select><button> Send </button><textarea>  d  </textarea>",
+    b"<select><option>a<option>b c</select><button>Send</button><textarea>  d  </textarea>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_input_value_whitespace() {
+  eval(
+    b"<input value=\" a \"><input type=text value=\"\"><input type=checkbox value=\"\"><input type=SUBMIT value=\"\">",
+    b"<input value=\" a \"><input><input type=checkbox value><input type=submit value>",
+  );
+}
+
+#[test]
+fn test_remove_xml_declaration() {
+  let src: &[u8] = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html><?xml-stylesheet href=a.xsl?><p>a<?xml b?></p>";
+  eval_with_cfg(
+    src,
+    b"<?xml version=\"1.0\" encoding=\"UTF-8\"?><!doctype html><?xml-stylesheet href=a.xsl?><p>a<?xml b?>",
+    &Cfg::new(),
+  );
+  let mut cfg = Cfg::new();
+  cfg.remove_xml_declaration = true;
+  // Only XML declarations at the root are removed.
+  eval_with_cfg(
+    src,
+    b"<!doctype html><?xml-stylesheet href=a.xsl?><p>a<?xml b?>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_keep_processing_instructions_matching() {
+  let cfg = Cfg {
+    remove_processing_instructions: true,
+    keep_processing_instructions_matching: vec![b"xml-stylesheet".to_vec()],
+    ..Cfg::default()
+  };
+  eval_with_cfg(
+    b"<?xml-stylesheet  href=\"a.xsl\"  type=\"text/xsl\"?><?php echo 1; ?><p>a</p>",
+    b"<?xml-stylesheet  href=\"a.xsl\"  type=\"text/xsl\"?><p>a",
+    &cfg,
+  );
+  // Only the whole target is matched, case sensitively.
+  eval_with_cfg(b"<?xml-stylesheet2 a?><?XML-STYLESHEET a?>", b"", &cfg);
+  eval_with_cfg(b"<?xml-stylesheet?>", b"<?xml-stylesheet?>", &cfg);
+  // Has no effect if processing instructions aren't removed.
+  eval(b"<?php echo 1; ?>", b"<?php echo 1; ?>");
+}
+
+#[test]
+fn test_validate_preload_links() {
+  let cfg = Cfg {
+    validate_preload_links: true,
+    ..Cfg::default()
+  };
+  eval_with_cfg(
+    b"<link rel=preload href=/app.js>",
+    b"<link as=script href=/app.js rel=preload>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<link rel=\"Preload\" href=\"fonts/a.WOFF2?v=2#x\">",
+    b"<link href=\"fonts/a.WOFF2?v=2#x\" as=font rel=Preload>",
+    &cfg,
+  );
+  // Existing `as` attributes, unknown extensions, and other link types are left as is.
+  eval_with_cfg(
+    b"<link rel=preload href=a.js as=fetch>",
+    b"<link as=fetch href=a.js rel=preload>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<link rel=preload href=a.php>",
+    b"<link href=a.php rel=preload>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<link rel=stylesheet href=a.css>",
+    b"<link href=a.css rel=stylesheet>",
+    &cfg,
+  );
+  eval(
+    b"<link rel=preload href=a.css>",
+    b"<link href=a.css rel=preload>",
+  );
+}
+
+#[test]
+fn test_normalize_doctype_to_html5() {
+  let src: &[u8] = b"<!DOCTYPE HTML PUBLIC \"-//W3C//DTD HTML 4.01//EN\" \"http://www.w3.org/TR/html4/strict.dtd\"><p>a";
+  eval(
+    src,
+    b"<!doctype html PUBLIC \"-//W3C//DTD HTML 4.01//EN\" \"http://www.w3.org/TR/html4/strict.dtd\"><p>a",
+  );
+  let mut cfg = Cfg::new();
+  cfg.normalize_doctype_to_html5 = true;
+  eval_with_cfg(src, b"<!doctype html><p>a", &cfg);
+  eval_with_cfg(
+    b"<!DOCTYPE html PUBLIC \"-//W3C//DTD XHTML 1.0 Strict//EN\"\n  \"http://www.w3.org/TR/xhtml1/DTD/xhtml1-strict.dtd\">",
+    b"<!doctype html>",
+    &cfg,
+  );
+  cfg.minify_doctype = true;
+  eval_with_cfg(src, b"<!doctypehtml><p>a", &cfg);
+}
+
+#[test]
+fn test_remove_bangs() {
+  let cfg = Cfg {
+    remove_bangs: true,
+    ..Cfg::default()
+  };
+  eval_with_cfg(b"<!DOCTYPE html>", b"<!doctype html>", &cfg);
+  eval_with_cfg(b"<!doctypehtml><!FOO><p>a", b"<!doctype html><p>a", &cfg);
+  // CDATA sections in foreign content are text, and can contain `>`.
+  eval_with_cfg(
+    b"<svg><text><![CDATA[a > b]]></text></svg>",
+    b"<svg><text><![CDATA[a > b]]></text></svg>",
+    &cfg,
+  );
+  // Elsewhere, they're bogus comments ending at the first `>`.
+  eval_with_cfg(b"<div><![CDATA[a > b]]></div>", b"<div>b]]></div>", &cfg);
+  eval_with_cfg(
+    b"<!FOO><![if !IE]><p>a<![endif]>",
+    b"<![if !IE]><p>a<![endif]>",
+    &Cfg {
+      keep_bangs_matching: vec![b"[if".to_vec(), b"[endif".to_vec()],
+      ..cfg.clone()
+    },
+  );
+}
+
+#[test]
+fn test_whitespace_across_removed_comments() {
+  eval(b"<p>foo <!-- x --> bar</p>", b"<p>foo bar");
+  eval(b"<p> <!-- x --> foo <!-- y --> </p>", b"<p>foo");
+  eval(
+    b"<div>\n  <!-- x -->\n  <div>a</div>\n</div>",
+    b"<div><div>a</div></div>",
+  );
+  eval_with_cfg(b"<p>a <?php x ?> b <!y> c", b"<p>a b c", &Cfg {
+    remove_bangs: true,
+    remove_processing_instructions: true,
+    ..Cfg::default()
+  });
+  // Kept comments still separate text.
+  eval_with_cfg(
+    b"<p>foo <!-- x --> bar</p>",
+    b"<p>foo <!-- x --> bar",
+    &Cfg {
+      keep_comments: true,
+      ..Cfg::default()
+    },
+  );
+  // Text is merged before being encoded, as it's contiguous in the output.
+  eval(b"<p>a&amp;<!-- x -->amp;", b"<p>a&amp;amp;");
+}
+
+#[test]
+fn test_inject_charset_meta() {
+  let cfg = Cfg {
+    inject_charset_meta: true,
+    keep_html_and_head_opening_tags: true,
+    ..Cfg::default()
+  };
+  eval_with_cfg(
+    b"<!DOCTYPE html><html><head><title>a</title></head><body><p>b</p></body></html>",
+    b"<!doctype html><html><head><meta charset=utf-8><title>a</title><body><p>b",
+    &cfg,
+  );
+  // Inserted where the head is implied if there's no `<head>`, but never before the doctype.
+  eval_with_cfg(
+    b"<!-- c --><!DOCTYPE html><title>a</title>",
+    b"<!doctype html><meta charset=utf-8><title>a</title>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<html lang=en><p>a",
+    b"<html lang=en><meta charset=utf-8><p>a",
+    &cfg,
+  );
+  // Existing declarations are kept as is.
+  eval_with_cfg(
+    b"<head><meta charset=iso-8859-1><title>a</title>",
+    b"<head><meta charset=iso-8859-1><title>a</title>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<meta http-equiv=Content-Type content=\"text/html; charset=utf-8\">",
+    b"<meta content=\"text/html; charset=utf-8\" http-equiv=Content-Type>",
+    &cfg,
+  );
+  // The declared charset is kept when a parsed document is serialised, and single nodes never get one.
+  let doc = parse(b"<head><meta charset=iso-8859-1><title>a</title><p>b");
+  assert_eq!(
+    from_utf8(&doc.serialize(&cfg)).unwrap(),
+    "<head><meta charset=iso-8859-1><title>a</title><p>b"
+  );
+  assert_eq!(
+    from_utf8(&doc.select("p").unwrap()[0].serialize(&cfg)).unwrap(),
+    "<p>b"
+  );
+  assert_eq!(
+    from_utf8(&parse(b"<title>a</title>").serialize(&cfg)).unwrap(),
+    "<meta charset=utf-8><title>a</title>"
+  );
+}
+
+#[test]
+fn test_whitespace_around_inline_elements() {
+  // Empty inline elements are kept, so the whitespace on both sides is kept too.
+  eval(b"<p>foo <a></a> bar</p>", b"<p>foo <a></a> bar");
+  eval(
+    b"<p>foo <span>   </span> bar</p>",
+    b"<p>foo <span> </span> bar",
+  );
+  eval(b"<p>foo<span> </span>bar</p>", b"<p>foo<span> </span>bar");
+  eval(
+    b"<div>foo <b></b><i></i> bar</div>",
+    b"<div>foo <b></b><i></i> bar</div>",
+  );
+  // Whitespace at the edges of an inline element isn't trimmed, as it renders as a space between words.
+  eval(b"<p>foo<b> bar </b>baz</p>", b"<p>foo<b> bar </b>baz");
+  // Removed elements leave exactly one space if there was whitespace on either side, and none if there wasn't.
+  let mut cfg = Cfg::new();
+  cfg.remove_hidden_elements = true;
+  eval_with_cfg(b"<p>foo <span hidden>x</span> bar</p>", b"<p>foo bar", &cfg);
+  eval_with_cfg(b"<p>foo <a hidden></a>bar</p>", b"<p>foo bar", &cfg);
+  eval_with_cfg(b"<p>foo<a hidden></a> bar</p>", b"<p>foo bar", &cfg);
+  eval_with_cfg(b"<p>foo<a hidden></a>bar</p>", b"<p>foobar", &cfg);
+  eval_with_cfg(
+    b"<p>foo \n<span hidden> </span>\n <span hidden></span>\tbar</p>",
+    b"<p>foo bar",
+    &cfg,
+  );
+  // At the edges of the parent, the whitespace is trimmed as usual.
+  eval_with_cfg(
+    b"<p><span hidden>x</span> foo <b hidden></b></p>",
+    b"<p>foo",
+    &cfg,
+  );
+  // An inline element left empty by removal is kept along with the whitespace around it.
+  eval_with_cfg(
+    b"<p>foo <span><b hidden>x</b></span> bar</p>",
+    b"<p>foo <span></span> bar",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_entities() {
+  assert_eq!(entities::decode_named(b"lt;"), Some("<".as_bytes()));
+  assert_eq!(entities::decode_named(b"nbsp"), Some("\u{a0}".as_bytes()));
+  assert_eq!(
+    entities::decode_named(b"CounterClockwiseContourIntegral;"),
+    Some("\u{2233}".as_bytes())
+  );
+  assert_eq!(
+    entities::decode_named(b"NotEqualTilde;"),
+    Some("\u{2242}\u{338}".as_bytes())
+  );
+  assert_eq!(entities::decode_named(b"ltx;"), None);
+  assert_eq!(entities::decode_named(b"#60;"), None);
+  assert_eq!(entities::decode_named(b""), None);
+  assert!(entities::is_legacy_no_semicolon(b"lt"));
+  assert!(!entities::is_legacy_no_semicolon(b"hellip;"));
+  assert!(!entities::is_legacy_no_semicolon(b";"));
+  // https://html.spec.whatwg.org/entities.json has 2,231 names, 106 of which are legacy names without a semicolon.
+  assert_eq!(entities::names().count(), 2_231);
+  assert_eq!(
+    entities::names().filter(|n| !n.ends_with(b";")).count(),
+    106
+  );
+  assert!(entities::names().all(|n| entities::decode_named(n).is_some()));
+  assert_eq!(
+    entities::shortest_encoding("\u{226A}\u{20D2}"),
+    Some(b"&nLt;".as_ref())
+  );
+}
+
+#[test]
+fn test_spec_tag() {
+  for name in ["area", "br", "img", "input", "meta", "wbr"] {
+    assert!(spec::tag::is_void(name.as_bytes()), "{}", name);
+  }
+  for name in ["div", "p", "script", "svg", "BR"] {
+    assert!(!spec::tag::is_void(name.as_bytes()), "{}", name);
+  }
+  for name in ["a", "b", "code", "em", "span", "strong"] {
+    assert_eq!(
+      spec::tag::is_formatting(name.as_bytes()),
+      name != "code",
+      "{}",
+      name
+    );
+  }
+  assert!(!spec::tag::is_formatting(b"p"));
+  assert!(!spec::tag::is_formatting(b"pre"));
+  for name in ["plaintext", "script", "style", "textarea", "title", "xmp"] {
+    assert!(spec::tag::is_raw_text(name.as_bytes()), "{}", name);
+  }
+  for name in ["iframe", "noscript", "pre", "template"] {
+    assert!(!spec::tag::is_raw_text(name.as_bytes()), "{}", name);
+  }
+  assert_eq!(spec::tag::namespace_for(b"math"), Some(Namespace::MathML));
+  assert_eq!(spec::tag::namespace_for(b"svg"), Some(Namespace::Svg));
+  assert_eq!(spec::tag::namespace_for(b"foreignobject"), None);
+}
+
+#[cfg(feature = "capi")]
+#[test]
+#[allow(unsafe_code)]
+fn test_capi() {
+  use crate::capi::minify_html;
+  use crate::capi::minify_html_free;
+  use crate::capi::MinifyHtmlCfg;
+
+  let src = b"<div>  a  </div><!-- b -->";
+  let read = |cfg: *const MinifyHtmlCfg| unsafe {
+    let min = minify_html(src.as_ptr(), src.len(), cfg);
+    let out = std::slice::from_raw_parts(min.ptr, min.len).to_vec();
+    minify_html_free(min);
+    out
+  };
+  assert_eq!(read(std::ptr::null()), b"<div>a</div>");
+  let cfg = MinifyHtmlCfg {
+    keep_comments: true,
+    ..MinifyHtmlCfg::default()
+  };
+  assert_eq!(read(&cfg), b"<div>a</div><!-- b -->");
+  unsafe {
+    let min = minify_html(std::ptr::null(), 0, std::ptr::null());
+    assert_eq!(min.len, 0);
+    minify_html_free(min);
+    let min = minify_html(src.as_ptr(), src.len(), &MinifyHtmlCfg {
+      version: 0,
+      ..cfg
+    });
+    assert!(min.ptr.is_null());
+    minify_html_free(min);
+  };
+}
+
+#[cfg(feature = "capi")]
+#[test]
+fn test_capi_header_matches_cfg() {
+  use crate::capi::MINIFY_HTML_CFG_FIELDS;
+  use crate::capi::MINIFY_HTML_CFG_VERSION;
+
+  let header = include_str!("../../include/minify_html.h");
+  assert!(header.contains(&format!(
+    "#define MINIFY_HTML_CFG_VERSION {}\n",
+    MINIFY_HTML_CFG_VERSION
+  )));
+  let start = header.find("typedef struct MinifyHtmlCfg {").unwrap();
+  let end = start + header[start..].find("} MinifyHtmlCfg;").unwrap();
+  let fields = header[start..end]
+    .lines()
+    .skip(1)
+    .map(|l| l.trim())
+    .collect::<Vec<_>>();
+  let expected = std::iter::once("uint32_t version;".to_string())
+    .chain(
+      MINIFY_HTML_CFG_FIELDS
+        .iter()
+        .map(|f| format!("bool {};", f)),
+    )
+    .collect::<Vec<_>>();
+  assert_eq!(fields, expected);
+}
+
+#[test]
+fn test_minify_kept_comment_whitespace() {
+  let mut cfg = Cfg {
+    keep_comments: true,
+    ..Cfg::default()
+  };
+  let src = b"<div><!--\n  Main\n    navigation\n--><!--[if IE]>\n  <p>a</p>\n<![endif]--><!--! Copyright\n  b --><!--# include  virtual=\"c\" --></div>";
+  eval_with_cfg(src, src, &cfg);
+  cfg.minify_kept_comment_whitespace = true;
+  eval_with_cfg(
+    src,
+    b"<div><!-- Main navigation --><!--[if IE]>\n  <p>a</p>\n<![endif]--><!--! Copyright\n  b --><!--# include  virtual=\"c\" --></div>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_keep_input_type_text_attr() {
+  eval(b"<input type=\"text\">", b"<input>");
+  let mut cfg = Cfg::default();
+  cfg.keep_input_type_text_attr = true;
+  eval_with_cfg(b"<input type=\"TExt\">", b"<input type=text>", &cfg);
+}
+
+#[test]
+fn test_preserve_template_brace_syntax() {
+  eval_with_js_min(
+    b"<p> {{   hello    world! %}  {%}{#} echo '  </p><P><script>  let x = 1; //'  }} </p>",
+    b"<p>{{ hello world! %} {%}{#} echo '<p><script>  let x = 1; //'  }} </p>",
+  );
+  let mut cfg = Cfg::default();
+  cfg.preserve_brace_template_syntax = true;
+  eval_with_cfg(
+    b"<p> {{   hello    world! %}  {%}{#} echo '  </p><P><script>  let x = 1; //'  }} </p>",
+    b"<p>{{   hello    world! %}  {%}{#} echo '  </p><P><script>  let x = 1; //'  }}",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<p> {%   hello    world! %}  {%}{#} echo '  </p><P><script>  let x = 1; //'  %} </p>",
+    b"<p>{%   hello    world! %} {%}{#} echo '  </p><P><script>  let x = 1; //'  %}",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<p> {#   hello    world! #}  {#}{# echo '  </p><P><script>  let x = 1; //'  #} </p>",
+    b"<p>{#   hello    world! #} {#}{# echo '  </p><P><script>  let x = 1; //'  #}",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_preserve_template_chevron_percent_syntax() {
+  let mut cfg = Cfg::default();
+  cfg.preserve_chevron_percent_template_syntax = true;
+  eval_with_cfg(
+    b"<p> <%   hello    world! #}  {#}{# echo '  </p><P><script>  let x = 1; //'  %> </p>",
+    b"<p><%   hello    world! #}  {#}{# echo '  </p><P><script>  let x = 1; //'  %>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_minification_of_doctype() {
+  let mut cfg = Cfg::new();
+  cfg.minify_doctype = true;
+  eval_with_cfg(b"<!DOCTYPE html><div>", b"<!doctypehtml><div>", &cfg);
+  eval_with_cfg(
+    b"<!DOCTYPE html SYSTEM 'about:legacy-compat'><div>",
+    b"<!doctypehtml SYSTEM 'about:legacy-compat'><div>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_removal_of_empty_closing_tag() {
+  eval(b"<body><p>1</><p>2</body>", b"<body><p>1<p>2");
+}
+
+#[test]
+fn test_parsing_extra_head_tag() {
+  // Extra `<head>` in `<label>` should be dropped, so whitespace around `<head>` should be joined and therefore trimmed due to `<label>` whitespace rules.
+  eval(
+    b"<html><head><meta><head><link><head><body><label>  <pre> </pre> <head>  </label>",
+    b"<html><head><meta><link><body><label><pre> </pre></label>",
+  );
+  // Same as above except it's a `</head>`, which should get reinterpreted as a `<head>`.
+  eval(
+    b"<html><head><meta><head><link><head><body><label>  <pre> </pre> </head>  </label>",
+    b"<html><head><meta><link><body><label><pre> </pre></label>",
+  );
+  // `<head>` gets implicitly closed by `<body>`, so any following `</head>` should be ignored. (They should be anyway, since `</head>` would not be a valid closing tag.)
+  eval(
+    b"<html><head><body><label> </head> </label>",
+    b"<html><head><body><label></label>",
+  );
+}
+
+#[test]
+fn test_removal_of_html_and_head_opening_tags() {
+  // Even though `<head>` is dropped, it's still parsed, so its content is still subject to `<head>` whitespace minification rules.
+  eval_without_keep_html_head(
+    b"<!DOCTYPE html><html><head>  <meta> <body>",
+    b"<!doctype html><meta><body>",
+  );
+  // The tag should not be dropped if it has attributes.
+  eval_without_keep_html_head(
+    b"<!DOCTYPE html><html lang=en><head>  <meta> <body>",
+    b"<!doctype html><html lang=en><meta><body>",
+  );
+  // The tag should be dropped if it has no attributes after minification.
+  eval_without_keep_html_head(
+    b"<!DOCTYPE html><html style='  '><head>  <meta> <body>",
+    b"<!doctype html><meta><body>",
+  );
+}
+
+#[test]
+fn test_unmatched_closing_tag() {
+  // Browsers treat a stray `</p>` as `<p></p>` and `</br>` as `<br>`.
+  eval(b"Hello</p>Goodbye", b"Hello<p></p>Goodbye");
+  eval(b"Hello<br></br>Goodbye", b"Hello<br><br>Goodbye");
+  eval(b"<div>Hello</p>Goodbye", b"<div>Hello<p></p>Goodbye");
+  eval(b"<ul><li>a</p>", b"<ul><li>a<p>");
+  eval(b"<ul><li><rt>a</p>", b"<ul><li><rt>a<p>");
+  eval(
+    b"<html><head><body><ul><li><rt>a</p>",
+    b"<html><head><body><ul><li><rt>a<p>",
+  );
+  eval(b"Hello</span>Goodbye", b"HelloGoodbye");
+}
+
+#[test]
+// NOTE: Keep inputs in sync with onepass variant. Outputs are different as main variant reorders attributes.
+fn test_space_between_attrs_minification() {
+  eval_with_noncompliant(
+    b"<div a=\" \" b=\" \"></div>",
+    b"<div a=\" \"b=\" \"></div>",
+  );
+  eval_with_noncompliant(b"<div a=' ' b=\" \"></div>", b"<div a=\" \"b=\" \"></div>");
+  eval_with_noncompliant(
+    b"<div a=&#x20 b=\" \"></div>",
+    b"<div a=\" \"b=\" \"></div>",
+  );
+  eval_with_noncompliant(b"<div a=\"1\" b=\" \"></div>", b"<div b=\" \"a=1></div>");
+  eval_with_noncompliant(b"<div a='1' b=\" \"></div>", b"<div b=\" \"a=1></div>");
+  eval_with_noncompliant(b"<div a=\"a\"b=\"b\"></div>", b"<div a=a b=b></div>");
+}
+
+#[test]
+fn test_attr_whatwg_unquoted_value_minification() {
+  eval(b"<a b==></a>", br#"<a b="="></a>"#);
+  eval(br#"<a b=`'"<<==/`/></a>"#, br#"<a b="`'&#34<<==/`/"></a>"#);
+}
+
+#[test]
+fn test_alt_attr_minification() {
+  eval(br#"<img alt="  ">"#, br#"<img alt="  ">"#);
+  eval(br#"<img alt=" ">"#, br#"<img alt=" ">"#);
+  eval(br#"<img alt="">"#, br#"<img alt>"#);
+  eval(br#"<img alt=''>"#, br#"<img alt>"#);
+  eval(br#"<img alt>"#, br#"<img alt>"#);
+  eval(br#"<x-any-tag alt>"#, br#"<x-any-tag alt>"#);
+}
+
+#[test]
+fn test_viewport_attr_minification() {
+  eval_with_noncompliant(
+    b"<meta name=viewport content='width=device-width, initial-scale=1'>",
+    b"<meta content=width=device-width,initial-scale=1 name=viewport>",
+  );
+  eval(
+    b"<meta name=viewport content='width=device-width, initial-scale=1'>",
+    br#"<meta content="width=device-width,initial-scale=1" name=viewport>"#,
+  );
+}
+
+#[test]
+fn test_normalize_meta_viewport() {
+  let mut cfg = Cfg::new();
+  cfg.normalize_meta_viewport = true;
+  eval_with_cfg(
+    b"<meta name=viewport content='width = device-width , initial-scale=1.0, maximum-scale=2.50,, minimum-scale=0.0,'>",
+    br#"<meta content="width=device-width,initial-scale=1,maximum-scale=2.5,minimum-scale=0" name=viewport>"#,
+    &cfg,
+  );
+  // Values that aren't decimal numbers are left as is.
+  eval_with_cfg(
+    b"<meta name=viewport content='width=1000, initial-scale=.50, user-scalable=no, a=1.0x, b=1..0, c=.'>",
+    br#"<meta content="width=1000,initial-scale=.5,user-scalable=no,a=1.0x,b=1..0,c=." name=viewport>"#,
+    &cfg,
+  );
+  // Only applies to viewport declarations.
+  eval_with_cfg(
+    b"<meta name=description content='1.0,,'>",
+    b"<meta content=1.0,, name=description>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_meta_refresh_minification() {
+  let cfg = Cfg::new();
+  eval_with_cfg(
+    b"<meta http-equiv=Refresh content=\" 5; URL = 'https://example.com/' \">",
+    br#"<meta content="5;url=https://example.com/" http-equiv=Refresh>"#,
+    &cfg,
+  );
+  // Missing semicolon, quoted URL with trailing characters, and lowercase keyword.
+  eval_with_cfg(
+    b"<meta http-equiv=refresh content='3 url=\"/a b\" c'>",
+    br#"<meta content="3;url=/a b" http-equiv=refresh>"#,
+    &cfg,
+  );
+  // Without a complete `URL=`, the URL is everything after the separator.
+  eval_with_cfg(
+    b"<meta http-equiv=refresh content='1, urlx'><meta http-equiv=refresh content='01.5'>",
+    br#"<meta content="1;url=urlx" http-equiv=refresh><meta content=1 http-equiv=refresh>"#,
+    &cfg,
+  );
+  // Values that browsers ignore are left as is.
+  eval_with_cfg(
+    b"<meta http-equiv=refresh content='soon; url=/'><meta http-equiv=refresh content='5x;url=/'>",
+    br#"<meta content="soon; url=/" http-equiv=refresh><meta content="5x;url=/" http-equiv=refresh>"#,
+    &cfg,
+  );
+}
+
+#[test]
+fn test_microdata_url_whitespace_minification() {
+  let mut cfg = Cfg::new();
+  let src = b"<div itemid=' urn:isbn:1 ' itemscope itemtype=' https://schema.org/Book  https://schema.org/Thing '></div>";
+  eval_with_cfg(
+    src,
+    b"<div itemtype=\"https://schema.org/Book https://schema.org/Thing\" itemid=urn:isbn:1 itemscope></div>",
+    &cfg,
+  );
+  cfg.preserve_microdata_attributes = true;
+  eval_with_cfg(
+    src,
+    b"<div itemid=\" urn:isbn:1 \" itemtype=\" https://schema.org/Book  https://schema.org/Thing \" itemscope></div>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_style_attr_minification() {
+  eval_with_css_min(
+    br#"<div style="color: yellow;"></div>"#,
+    br#"<div style=color:#ff0></div>"#,
+  );
+  // `style` attributes are removed if fully minified away.
+  eval_with_css_min(br#"<div style="  /*  */   "></div>"#, br#"<div></div>"#);
+  // Values that can't be minified safely are only trimmed.
+  eval_with_css_min(
+    br#"<div style=" filter: progid:DXImageTransform.Microsoft.Alpha(Opacity=80); ;"></div>"#,
+    br#"<div style="filter: progid:DXImageTransform.Microsoft.Alpha(Opacity=80)"></div>"#,
+  );
+  eval_with_css_min(
+    br#"<div style="--a:1px;color:var(--a)"></div>"#,
+    br#"<div style=--a:1px;color:var(--a)></div>"#,
+  );
+  eval_with_css_min(
+    br#"<div style='content: "a;b"'></div>"#,
+    br#"<div style='content:"a;b"'></div>"#,
+  );
+  let mut cfg = Cfg::new();
+  cfg.minify_css = true;
+  cfg.skip_style_attr_minification = true;
+  eval_with_cfg(
+    br#"<div style=' content: "a;b"; '></div><style> a { color: red } </style>"#,
+    br#"<div style='content: "a;b"'></div><style>a{color:red}</style>"#,
+    &cfg,
+  );
+  // A semicolon that's escaped or inside an unterminated string isn't removed.
+  eval_with_cfg(
+    br#"<div style='content: "a;'></div><div style='a:b\;'></div>"#,
+    br#"<div style='content: "a;'></div><div style=a:b\;></div>"#,
+    &cfg,
+  );
+}
+
+#[test]
+fn test_head_children_order_and_charset_meta_are_preserved() {
+  // Removing a large comment before the charset `<meta>` is fine, as it only moves the `<meta>` earlier.
+  let src = format!(
+    "<!DOCTYPE html><html><head><!-- {} --><meta charset=\"utf-8\"><title>T</title></head><body>",
+    "x".repeat(2048)
+  );
+  let min = minify(src.as_bytes(), &Cfg::new());
+  assert_eq!(
+    from_utf8(&min).unwrap(),
+    "<!doctype html><meta charset=utf-8><title>T</title><body>",
+  );
+
+  // A charset `<meta>` after a large inline script must stay after it.
+  let script = "let a = 1;\n".repeat(200);
+  let src = format!(
+    "<!DOCTYPE html><html><head><script>{}</script><meta charset=\"utf-8\"><title>T</title></head><body>",
+    script
+  );
+  let min = minify(src.as_bytes(), &Cfg::new());
+  assert_eq!(
+    from_utf8(&min).unwrap(),
+    format!(
+      "<!doctype html><script>{}</script><meta charset=utf-8><title>T</title><body>",
+      script.trim_end()
+    ),
+  );
+
+  // `http-equiv` charset declarations are never removed, and sibling order survives dropped duplicate `<head>` tags.
+  eval(
+    b"<html><head><link rel=icon href=a.png><head><meta http-equiv=\"Content-Type\" content=\"text/html; charset=utf-8\"><head><script></script></head><body>",
+    b"<html><head><link href=a.png rel=icon><meta content=\"text/html; charset=utf-8\" http-equiv=Content-Type><script></script><body>",
+  );
+}
+
+#[test]
+fn test_minify_with_stats_stats() {
+  let (min, stats) = minify_with_stats(b"<div>  <p>  Hello  </p>  </div>       ", &Cfg::new());
+  assert_eq!(from_utf8(&min).unwrap(), "<div><p>Hello</div>");
+  assert_eq!(stats, MinifyStats {
+    input_bytes: 38,
+    output_bytes: 19,
+    bytes_saved: 19,
+    percent_saved: 50.0,
+    changed: true,
+    elements_minified: 2,
+    whitespace_bytes_removed: 15,
+    closing_tags_omitted: 1,
+    ..MinifyStats::default()
+  });
+
+  let (min, stats) = minify_with_stats(b"", &Cfg::new());
+  assert!(min.is_empty());
+  assert_eq!(stats, MinifyStats::default());
+
+  let src = b"<p>  a  </p>";
+  assert_eq!(
+    minify_verbose(src, &Cfg::new()),
+    minify_with_stats(src, &Cfg::new())
+  );
+}
+
+#[test]
+fn test_remove_data_attrs() {
+  let mut cfg = Cfg::new();
+  cfg.remove_data_attrs = vec![b"data-testid".to_vec(), b"data-qa*".to_vec()];
+  // Exact match.
+  eval_with_cfg(
+    b"<div data-testid=a data-TestID2=b class=c></div>",
+    b"<div class=c data-testid2=b></div>",
+    &cfg,
+  );
+  // Prefix match.
+  eval_with_cfg(
+    b"<button data-qa=a data-qa-id=b DATA-QA-name=c data-q=d></button>",
+    b"<button data-q=d></button>",
+    &cfg,
+  );
+  // Unrelated `data-*` attributes survive.
+  eval_with_cfg(
+    b"<p data-id=1 data-test=2>",
+    b"<p data-id=1 data-test=2>",
+    &cfg,
+  );
+  // Removal happens before opening tag omission eligibility is checked.
+  eval_with_cfg(b"<html data-testid=root><head><body>", b"<body>", &cfg);
+}
+
+#[test]
+fn test_remove_elements() {
+  let mut cfg = Cfg::new();
+  cfg.remove_elements = [
+    "aside",
+    "script[data-env=dev]",
+    ".debug",
+    "#banner",
+    "[hidden]",
+  ]
+  .iter()
+  .map(|s| s.parse().unwrap())
+  .collect();
+  // Tag.
+  eval_with_cfg(
+    b"<div><aside>x</aside><span>a</span></div>",
+    b"<div><span>a</span></div>",
+    &cfg,
+  );
+  // Tag and attribute value.
+  eval_with_cfg(
+    b"<div><script data-env=dev>a</script><script data-env=prod>b</script></div>",
+    b"<div><script data-env=prod>b</script></div>",
+    &cfg,
+  );
+  // Class.
+  eval_with_cfg(
+    b"<div class=\"x debug\">a</div><div class=debugger>b</div>",
+    b"<div class=debugger>b</div>",
+    &cfg,
+  );
+  // ID.
+  eval_with_cfg(
+    b"<p id=banner>a</p><p id=banners>b</p>",
+    b"<p id=banners>b",
+    &cfg,
+  );
+  // Attribute presence.
+  eval_with_cfg(
+    b"<span hidden>a</span><span>b</span>",
+    b"<span>b</span>",
+    &cfg,
+  );
+  // Whitespace around removed elements collapses as if they were never there.
+  eval_with_cfg(b"<p>a <span class=debug>b</span> c</p>", b"<p>a c", &cfg);
+  eval_with_cfg(
+    b"<ul>\n  <li>a</li>\n  <li class=debug>b</li>\n</ul>",
+    b"<ul><li>a</ul>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_table_whitespace_and_optional_tags() {
+  // Whitespace directly inside table elements is removed.
+  eval(
+    b"<table>\n  <thead>\n    <tr> <th>a</th> </tr>\n  </thead>\n  <tbody>\n    <tr> <td>b</td> </tr>\n  </tbody>\n  <tfoot>\n    <tr> <td>c</td> </tr>\n  </tfoot>\n</table>",
+    b"<table><thead><tr><th>a<tbody><tr><td>b<tfoot><tr><td>c</table>",
+  );
+  // The `<tbody>` opening tag is implied by a `<tr>`.
+  eval(
+    b"<table> <tbody> <tr> <td>a</td> </tr> </tbody> </table>",
+    b"<table><tr><td>a</table>",
+  );
+  // ...but not directly after another table section, as the rows would then be parsed as part of that section.
+  eval(
+    b"<table><tbody><tr><td>a</td></tr></tbody><tbody><tr><td>b</td></tr></tbody></table>",
+    b"<table><tr><td>a<tbody><tr><td>b</table>",
+  );
+  eval(
+    b"<table><tbody class=a><tr><td>a</td></tr></tbody></table>",
+    b"<table><tbody class=a><tr><td>a</table>",
+  );
+  eval(b"<table><tbody></tbody></table>", b"<table><tbody></table>");
+  // The `<colgroup>` opening tag is implied by a `<col>`.
+  eval(
+    b"<table><colgroup><col><col></colgroup><tr><td>a</td></tr></table>",
+    b"<table><col><col><tr><td>a</table>",
+  );
+  eval(
+    b"<table><colgroup span=2></colgroup><colgroup><col></colgroup></table>",
+    b"<table><colgroup span=2><colgroup><col></table>",
+  );
+}
+
+fn prefix_asset_urls(el: &mut ElementView) {
+  for name in [b"src".as_ref(), b"href".as_ref()] {
+    if let Some(url) = el.get_attribute(name) {
+      let mut prefixed = b"https://cdn.test".to_vec();
+      prefixed.extend_from_slice(url);
+      el.set_attribute(name, &prefixed);
+    };
+  }
+  if el.tag_name() == b"img" {
+    el.remove_attribute(b"data-original");
+  };
+}
+
+#[test]
+fn test_element_visitor() {
+  let mut cfg = Cfg::new();
+  cfg.element_visitor = Some(Arc::new(prefix_asset_urls));
+  eval_with_cfg(
+    b"<img src=\"/a.png\" data-original=\"/b.png\"><a href=\"/c\">x</a><div data-src=/d>y</div>",
+    b"<img src=https://cdn.test/a.png><a href=https://cdn.test/c>x</a><div data-src=/d>y</div>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_normalize_urls() {
+  let mut cfg = Cfg::new();
+  cfg.normalize_urls = true;
+  eval_with_cfg(
+    b"<a href=\"https://example.com:443/./path\">a</a><img src=\"http://example.com:8080/img.png\">",
+    b"<a href=https://example.com/path>a</a><img src=http://example.com:8080/img.png>",
+    &cfg,
+  );
+  // Relative URLs and non-URL attributes are left alone.
+  eval_with_cfg(
+    b"<a href=\"./a\" title=\"http://example.com:80/\">a</a>",
+    b"<a href=./a title=http://example.com:80/>a</a>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<video poster=\"HTTP://Example.com:80/p.png\"></video><object data=\"https://example.com:443/o\"></object><button formaction=\"http://example.com:80/f\"></button><q cite=\"https://example.com:443/c\"></q>",
+    b"<video poster=http://example.com/p.png></video><object data=https://example.com/o></object><button formaction=http://example.com/f></button><q cite=https://example.com/c></q>",
+    &cfg,
+  );
+  eval_with_cfg(
+    b"<a ping=\" http://example.com:80/a   ./b \">a</a>",
+    b"<a ping=\"http://example.com/a ./b\">a</a>",
+    &cfg,
+  );
+  // Disabled by default.
+  eval(
+    b"<a href=\"http://example.com:80/\">a</a>",
+    b"<a href=http://example.com:80/>a</a>",
+  );
+}
+
+#[test]
+fn test_url_attrs_are_trimmed() {
+  eval(
+    b"<a href=\" /a \" ping=\" /b   /c \">a</a><video poster=\"\n/p.png\n\" src=\" /v.mp4\"></video>",
+    b"<a ping=\"/b /c\" href=/a>a</a><video poster=/p.png src=/v.mp4></video>",
+  );
+  eval(
+    b"<body background=\" /bg.png \"><form action=\" /f \"></form></body>",
+    b"<body background=/bg.png><form action=/f></form>",
+  );
+}
+
+#[test]
+fn test_text_visitor() {
+  let mut cfg = Cfg::new();
+  cfg.text_visitor = Some(Arc::new(|ctx: &TextContext, text: &mut Vec<u8>| {
+    if !ctx.is_whitespace_sensitive() {
+      text.make_ascii_uppercase();
+    };
+  }));
+  eval_with_cfg(
+    b"<p title=abc>Hello <b>world</b></p><pre> keep <b>me</b> </pre>",
+    b"<p title=abc>HELLO <b>WORLD</b><pre> keep <b>me</b> </pre>",
+    &cfg,
+  );
+
+  // Text is decoded, and script, style, and preserved template content is never passed.
+  let seen = Arc::new(Mutex::new(Vec::new()));
+  let seen_by_visitor = seen.clone();
+  let mut cfg = Cfg::new();
+  cfg.preserve_brace_template_syntax = true;
+  cfg.text_visitor = Some(Arc::new(move |ctx: &TextContext, text: &mut Vec<u8>| {
+    let ancestors: Vec<_> = ctx.ancestors().map(|a| from_utf8(a).unwrap()).collect();
+    seen_by_visitor.lock().unwrap().push(format!(
+      "{}: {}",
+      ancestors.join(">"),
+      from_utf8(text).unwrap()
+    ));
+  }));
+  minify(
+    b"<div><p>a&amp;b<!-- c --><script>x</script><style>y</style>{{ t }}</p></div>",
+    &cfg,
+  );
+  assert_eq!(seen.lock().unwrap().as_slice(), ["div>p: a&b"]);
+}
+
+#[test]
+fn test_minify_with_stats_change_counters() {
+  let (min, stats) = minify_with_stats(
+    b"<p class=\" a  b \">  x   y  </p>\n<!-- c -->\n<div>\n  <span>z</span>\n</div>",
+    &Cfg::new(),
+  );
+  assert_eq!(min, b"<p class=\"a b\">x y<div><span>z</span></div>");
+  assert!(stats.changed);
+  assert_eq!(stats.elements_minified, 3);
+  assert_eq!(stats.whitespace_bytes_removed, 12);
+  assert_eq!(stats.attributes_minified, 1);
+  assert_eq!(stats.comments_removed, 1);
+  assert_eq!(stats.closing_tags_omitted, 1);
+
+  let (_, stats) = minify_with_stats(b"<p>a", &Cfg::new());
+  assert!(!stats.changed);
+  assert_eq!(stats.whitespace_bytes_removed, 0);
+
+  // The output can differ from the source without being shorter.
+  let (min, stats) = minify_with_stats(b"<P>a", &Cfg::new());
+  assert_eq!(min, b"<p>a");
+  assert!(stats.changed);
+  assert_eq!(stats.bytes_saved, 0);
+}
+
+#[test]
+fn test_document() {
+  let src: &[u8] =
+    b"<html><head><meta http-equiv=Content-Type content=\"text/html; charset=ISO-8859-1\"></head><body><!-- a --><p>b</p></body></html>";
+  let doc = parse(src);
+  assert_eq!(doc.charset(), Some(b"ISO-8859-1".as_ref()));
+  assert_eq!(doc.doctype(), None);
+  assert_eq!(doc.input_len(), src.len());
+
+  // The same document can be serialised multiple times with different configurations.
+  let mut cfg = Cfg::new();
+  assert_eq!(doc.serialize(&cfg), minify(src, &cfg));
+  cfg.keep_comments = true;
+  assert_eq!(doc.serialize(&cfg), minify(src, &cfg));
+  let (min, stats) = doc.serialize_minified(&cfg);
+  assert_eq!(min, minify(src, &cfg));
+  assert_eq!(stats.input_bytes, src.len());
+
+  assert_eq!(
+    parse(b"<!doctype html public \"-//W3C//DTD HTML 4.01//EN\"><meta charset=' utf-8 '>")
+      .doctype(),
+    Some(b"public \"-//W3C//DTD HTML 4.01//EN\"".as_ref())
+  );
+  assert_eq!(
+    parse(b"<meta charset=' utf-8 '>").charset(),
+    Some(b"utf-8".as_ref())
+  );
+}
+
+#[test]
+fn test_css_imports_stay_first() {
+  let mut cfg = Cfg::new();
+  cfg.minify_css = true;
+  let min = minify(
+    b"<style>\n@import url(\"a.css\");\n@import 'b.css' screen;\na { color: red; }\n</style>",
+    &cfg,
+  );
+  let min = from_utf8(&min).unwrap();
+  let a = min.find("a.css").unwrap();
+  let b = min.find("b.css").unwrap();
+  let rule = min.find("color:red").unwrap();
+  assert!(min.starts_with("<style>@import"));
+  assert!(a < b && b < rule);
+  assert_eq!(min.matches("@import").count(), 2);
+}
+
+#[test]
+fn test_document_select() {
+  let doc = parse(b"<article><p class=note>a <img src=a.png alt=x></p><img src=b.png></article><div class=note><img src=c.png alt=y></div>");
+  let srcs = |selector: &str| -> Vec<String> {
+    doc
+      .select(selector)
+      .unwrap()
+      .into_iter()
+      .map(|n| match n {
+        NodeData::Element { attributes, .. } => from_utf8(attributes[b"src".as_ref()].as_slice())
+          .unwrap()
+          .to_string(),
+        _ => unreachable!(),
+      })
+      .collect()
+  };
+  assert_eq!(srcs("img"), ["a.png", "b.png", "c.png"]);
+  assert_eq!(srcs("article img"), ["a.png", "b.png"]);
+  assert_eq!(srcs(".note img[alt]"), ["a.png", "c.png"]);
+  assert_eq!(srcs("article .note img"), ["a.png"]);
+  assert_eq!(srcs("img[alt=y]"), ["c.png"]);
+  assert!(srcs("div article img").is_empty());
+  assert_eq!(doc.select("p").unwrap()[0].tag_name(), Some(b"p".as_ref()));
+  assert!(doc.select("article > img").is_err());
+}
+
+#[test]
+fn test_node_serialize() {
+  let doc = parse(b"<div><script type=\"application/ld+json\">{\"a\": 1}</script><p class=\"x\">Hi <b>there</b></p></div>");
+  let cfg = Cfg::new();
+  assert_eq!(
+    from_utf8(&doc.select("script").unwrap()[0].serialize(&cfg)).unwrap(),
+    "<script type=application/ld+json>{\"a\": 1}</script>",
+  );
+  assert_eq!(
+    from_utf8(&doc.select("p").unwrap()[0].serialize_pretty()).unwrap(),
+    "<p class=\"x\">\n  Hi\n  <b>\n    there\n  </b>\n</p>\n",
+  );
+}
+
+#[test]
+fn test_entities_are_collapsed_to_shortest_form() {
+  // Named, decimal, and hexadecimal references to characters that aren't special are written raw.
+  eval(b"<p>&#x27;&#39;&apos;&quot;</p>", b"<p>'''\"");
+  eval(b"<p>&#65;&#x42;&eacute;</p>", b"<p>AB\xC3\xA9");
+  // `&` is only encoded if it would otherwise start a reference, and `<` is always encoded in text.
+  eval(b"<p>a &amp; b</p>", b"<p>a & b");
+  eval(b"<p>&amp;amp; &lt;b&gt;</p>", b"<p>&amp;amp; &lt;b>");
+  // Attribute values use whichever quoting is shortest after decoding.
+  eval(
+    b"<a title=\"&#x27;&quot;\" href=\"?a=1&amp;b=2\"></a>",
+    b"<a href=\"?a=1&b=2\" title=\"'&#34;\"></a>",
+  );
+}
+
+#[test]
+fn test_never_unquote_attributes() {
+  let mut cfg = Cfg::new();
+  cfg.never_unquote_attributes.insert(b"content".to_vec());
+  cfg.never_unquote_attributes.insert(b":class".to_vec());
+  assert_eq!(
+    from_utf8(&minify(
+      b"<meta name=\"description\" content=\"hello\"><div :class=\"a\" id=\"b\"></div>",
+      &cfg
+    ))
+    .unwrap(),
+    "<meta content=\"hello\" name=description><div :class=\"a\" id=b></div>",
+  );
+}
+
+#[test]
+fn test_legacy_named_references_depend_on_context() {
+  // Examples from https://html.spec.whatwg.org/multipage/parsing.html#named-character-reference-state. In text, the legacy `&not` is decoded even when followed by more alphanumerics.
+  eval(
+    b"<p>I'm &notit; I tell you</p>",
+    b"<p>I'm \xC2\xACit; I tell you",
+  );
+  eval(
+    b"<p>I'm &notin; I tell you</p>",
+    b"<p>I'm \xE2\x88\x89 I tell you",
+  );
+  eval(b"<p>&not;in</p>", b"<p>\xC2\xACin");
+  // In attribute values, it's left as is when followed by an alphanumeric or `=`, and must be written back unencoded.
+  eval(
+    b"<a title=\"I'm &notit; I tell you\"></a>",
+    b"<a title=\"I'm &notit; I tell you\"></a>",
+  );
+  eval(b"<a title=\"&notx\"></a>", b"<a title=&notx></a>");
+  eval(b"<a title=\"&not;in\"></a>", b"<a title=\xC2\xACin></a>");
+  eval(b"<a title=\"&notin;\"></a>", b"<a title=\xE2\x88\x89></a>");
+  // A decoded `&` that would start a legacy reference is encoded in both contexts.
+  eval(b"<p>&amp;notit;</p>", b"<p>&amp;notit;");
+  eval(
+    b"<a title=\"&amp;not x\"></a>",
+    b"<a title=\"&amp;not x\"></a>",
+  );
+}
+
+#[test]
+fn test_minify_with_stats_optimization_counters() {
+  let (min, stats) = minify_with_stats(
+    b"<html><head><!-- a --><!-- b --></head><body><input type=\"text\" class=\"a b\" id='x'><p>1</p><p>2</p></body></html>",
+    &Cfg::new(),
+  );
+  assert_eq!(
+    from_utf8(&min).unwrap(),
+    "<body><input class=\"a b\" id=x><p>1<p>2"
+  );
+  assert_eq!(stats.opening_tags_omitted, 2);
+  assert_eq!(stats.closing_tags_omitted, 5);
+  assert_eq!(stats.attributes_removed, 1);
+  assert_eq!(stats.attributes_unquoted, 1);
+  assert_eq!(stats.comments_removed, 2);
+  assert_eq!(stats.css_bytes_saved, 0);
+  assert_eq!(stats.js_bytes_saved, 0);
+
+  let mut cfg = Cfg::new();
+  cfg.minify_css = true;
+  let (_, stats) = minify_with_stats(b"<style> a { color: red; } </style>", &cfg);
+  assert!(stats.css_bytes_saved > 0);
+}
+
+#[test]
+fn test_namespace_display_and_from_str() {
+  for ns in [Namespace::Html, Namespace::Svg, Namespace::MathML] {
+    assert_eq!(ns.to_string().parse::<Namespace>(), Ok(ns));
+  }
+  assert_eq!(Namespace::MathML.to_string(), "mathml");
+  assert_eq!("SVG".parse::<Namespace>(), Ok(Namespace::Svg));
+  assert!("xml".parse::<Namespace>().is_err());
+}
+
+#[test]
+fn test_element_closing_tag_display_and_try_from() {
+  for tag in [
+    ElementClosingTag::Omitted,
+    ElementClosingTag::Present,
+    ElementClosingTag::SelfClosing,
+    ElementClosingTag::Void,
+  ] {
+    assert_eq!(
+      ElementClosingTag::try_from(tag.to_string().as_str()),
+      Ok(tag)
+    );
+  }
+  assert_eq!(ElementClosingTag::SelfClosing.to_string(), "self-closing");
+  assert!(ElementClosingTag::try_from("Present").is_err());
+  assert!(ElementClosingTag::try_from("self_closing").is_err());
+}
+
+#[test]
+fn test_node_data_helpers() {
+  let mut code = Code::new(b"<div id=a><p>b</p></div>");
+  let mut nodes = parse_content(&mut code, Namespace::Html, EMPTY_SLICE, EMPTY_SLICE).children;
+  let div = &mut nodes[0];
+  assert_eq!(div.tag_name(), Some(b"div".as_ref()));
+  assert!(div.set_attribute(b"class".to_vec(), b"c".to_vec()));
+  assert_eq!(div.remove_attribute(b"id"), Some(b"a".to_vec()));
+  assert_eq!(div.remove_attribute(b"id"), None);
+  let children = div.children_mut().unwrap();
+  assert_eq!(children.len(), 1);
+  let text = &mut children[0].children_mut().unwrap()[0];
+  assert_eq!(text.tag_name(), None);
+  assert!(text.children_mut().is_none());
+  assert!(!text.set_attribute(b"class".to_vec(), b"c".to_vec()));
+  assert_eq!(text.remove_attribute(b"class"), None);
+  match div {
+    NodeData::Element { attributes, .. } => {
+      assert_eq!(attributes.len(), 1);
+      assert_eq!(attributes[b"class".as_ref()].as_slice(), b"c");
+    }
+    _ => unreachable!(),
+  };
+}
+
+#[test]
+fn test_node_data_text_content() {
+  let mut code = Code::new(
+    b"<div>a &amp; <b>b</b><!-- c --><script>d</script><textarea> e </textarea><?f?></div>",
+  );
+  let nodes = parse_content(&mut code, Namespace::Html, EMPTY_SLICE, EMPTY_SLICE).children;
+  assert_eq!(nodes[0].text_content(), b"a & b e ");
+  let mut code = Code::new(b"<p> \n </p><p><br></p>");
+  let nodes = parse_content(&mut code, Namespace::Html, EMPTY_SLICE, EMPTY_SLICE).children;
+  assert!(nodes[0].text_content().iter().all(u8::is_ascii_whitespace));
+  assert!(nodes[1].text_content().is_empty());
+}
+
+#[test]
+fn test_mathml_elements_can_self_close() {
+  eval(
+    b"<math><mi> x </mi><mspace/></math>",
+    b"<math><mi> x </mi><mspace/></math>",
+  );
+}
+
+#[test]
+fn test_per_element_opening_tag_omission() {
+  let src = b"<html><head><title>a</title></head><body><p>b</body></html>";
+  eval_with_cfg(src, b"<title>a</title><body><p>b", &Cfg::new());
+  let mut cfg = Cfg::new();
+  cfg.keep_html_opening_tag = true;
+  eval_with_cfg(src, b"<html><title>a</title><body><p>b", &cfg);
+  let mut cfg = Cfg::new();
+  cfg.keep_head_opening_tag = true;
+  eval_with_cfg(src, b"<head><title>a</title><body><p>b", &cfg);
+  let mut cfg = Cfg::new();
+  cfg.omit_body_opening_tag = true;
+  eval_with_cfg(src, b"<title>a</title><p>b", &cfg);
+  let mut cfg = Cfg::new();
+  cfg.keep_html_and_head_opening_tags = true;
+  eval_with_cfg(src, b"<html><head><title>a</title><body><p>b", &cfg);
+}
+
+#[test]
+fn test_body_opening_tag_omission() {
+  eval_with_cfg(b"<body>  <div></div>", b"<body><div></div>", &Cfg::new());
+  let mut cfg = Cfg::new();
+  cfg.omit_body_opening_tag = true;
+  eval_with_cfg(b"<body>  <div></div>", b"<div></div>", &cfg);
+  eval_with_cfg(b"<body> a </body>", b"a", &cfg);
+  eval_with_cfg(b"<body><!-- a --><div></div>", b"<div></div>", &cfg);
+  // The first thing inside `<body>` must not be one of these elements, as the parser would move them into `<head>` without the opening tag.
+  eval_with_cfg(
+    b"<body><script>a</script>",
+    b"<body><script>a</script>",
+    &cfg,
+  );
+  eval_with_cfg(b"<body> <link>", b"<body><link>", &cfg);
+  eval_with_cfg(b"<body><title>a</title>", b"<body><title>a</title>", &cfg);
+  eval_with_cfg(b"<body> <base href=a>", b"<body><base href=a>", &cfg);
+  for tag in ["basefont", "bgsound", "noframes"] {
+    let min = minify(format!("<body> <{}>", tag).as_bytes(), &cfg);
+    assert!(
+      min.starts_with(format!("<body><{}", tag).as_bytes()),
+      "{}",
+      from_utf8(&min).unwrap()
+    );
+  }
+  // The first thing inside `<body>` must not be a comment.
+  cfg.keep_comments = true;
+  eval_with_cfg(
+    b"<body><!-- a --><div></div>",
+    b"<body><!-- a --><div></div>",
+    &cfg,
   );
-  // `style` attributes are removed if fully minified away.
-  eval_with_css_min(br#"<div style="  /*  */   "></div>"#, br#"<div></div>"#);
 }
@@ -0,0 +1,81 @@
+use crate::ast::AttrVal;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use hashbrown::HashMap;
+use minify_html_common::spec::tag::ns::Namespace;
+
+/// A callback invoked for every element before it's minified. See `Cfg::element_visitor`.
+pub type ElementVisitor = Arc<dyn Fn(&mut ElementView) + Send + Sync>;
+
+/// A callback invoked with the decoded content of every text node before it's minified. See `Cfg::text_visitor`.
+pub type TextVisitor = Arc<dyn Fn(&TextContext, &mut Vec<u8>) + Send + Sync>;
+
+/// A view of an element passed to an `ElementVisitor`, which allows reading and changing its attributes before they are minified.
+pub struct ElementView<'a> {
+  pub(crate) tag_name: &'a [u8],
+  pub(crate) namespace: Namespace,
+  pub(crate) attributes: &'a mut HashMap<Vec<u8>, AttrVal>,
+}
+
+impl<'a> ElementView<'a> {
+  /// The tag name. For HTML elements, this is lowercase.
+  pub fn tag_name(&self) -> &[u8] {
+    self.tag_name
+  }
+
+  pub fn namespace(&self) -> Namespace {
+    self.namespace
+  }
+
+  /// Returns the decoded value of an attribute, if it's present. For HTML elements, attribute names are lowercase.
+  pub fn get_attribute(&self, name: &[u8]) -> Option<&[u8]> {
+    self.attributes.get(name).map(|v| v.as_slice())
+  }
+
+  /// Adds or replaces an attribute. The value should not be encoded, as it will be encoded and quoted as necessary during minification.
+  pub fn set_attribute(&mut self, name: &[u8], value: &[u8]) {
+    self.attributes.insert(name.to_vec(), AttrVal {
+      quote: None,
+      value: value.to_vec(),
+    });
+  }
+
+  /// Removes an attribute, returning its decoded value if it was present.
+  pub fn remove_attribute(&mut self, name: &[u8]) -> Option<Vec<u8>> {
+    self.attributes.remove(name).map(|v| v.value)
+  }
+
+  /// The names of all attributes, sorted, so that visitors behave the same on every run.
+  pub fn attribute_names(&self) -> impl Iterator<Item = &[u8]> {
+    let mut names = self
+      .attributes
+      .keys()
+      .map(|k| k.as_slice())
+      .collect::<Vec<_>>();
+    names.sort_unstable();
+    names.into_iter()
+  }
+}
+
+/// Information about where a text node passed to a `TextVisitor` is located.
+pub struct TextContext<'a> {
+  pub(crate) ancestors: &'a [Vec<u8>],
+  pub(crate) whitespace_sensitive: bool,
+}
+
+impl<'a> TextContext<'a> {
+  /// Tag names of the elements containing the text node, starting from the outermost.
+  pub fn ancestors(&self) -> impl Iterator<Item = &[u8]> {
+    self.ancestors.iter().map(|a| a.as_slice())
+  }
+
+  /// Tag name of the element directly containing the text node, or `None` if it's at the root.
+  pub fn parent(&self) -> Option<&[u8]> {
+    self.ancestors.last().map(|a| a.as_slice())
+  }
+
+  /// Whether whitespace in the text node is significant, such as inside `<pre>`.
+  pub fn is_whitespace_sensitive(&self) -> bool {
+    self.whitespace_sensitive
+  }
+}
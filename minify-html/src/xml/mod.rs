@@ -0,0 +1,433 @@
+use crate::cfg::Cfg;
+use alloc::vec::Vec;
+use core::str::from_utf8;
+use memchr::memchr;
+use memchr::memmem::find;
+use minify_html_common::whitespace::collapse_whitespace;
+
+#[cfg(test)]
+mod tests;
+
+/// Configuration for `minify_xml`. The default keeps everything except comments and changes nothing else.
+#[derive(Clone, Default)]
+pub struct XmlCfg {
+  /// Collapse whitespace in text to a single space, and remove text that is entirely whitespace. This is unsafe for documents where whitespace is significant, such as those using `xml:space="preserve"`.
+  pub collapse_whitespace: bool,
+  /// Keep CDATA sections as is, instead of rewriting them as escaped text when that's shorter.
+  pub keep_cdata: bool,
+  /// Keep comments.
+  pub keep_comments: bool,
+  /// Rewrite all attribute values to use double quotes.
+  pub normalize_attribute_quotes: bool,
+}
+
+// Whitespace as defined by the XML spec, which unlike HTML doesn't include form feed.
+fn is_xml_whitespace(c: u8) -> bool {
+  matches!(c, b' ' | b'\t' | b'\n' | b'\r')
+}
+
+fn is_name_char(c: u8) -> bool {
+  !is_xml_whitespace(c) && !matches!(c, b'/' | b'>' | b'<' | b'=' | b'"' | b'\'')
+}
+
+struct Xml<'a> {
+  src: &'a [u8],
+  pos: usize,
+}
+
+impl<'a> Xml<'a> {
+  fn rest(&self) -> &'a [u8] {
+    &self.src[self.pos..]
+  }
+
+  fn skip_whitespace(&mut self) -> bool {
+    let start = self.pos;
+    while self.pos < self.src.len() && is_xml_whitespace(self.src[self.pos]) {
+      self.pos += 1;
+    }
+    self.pos > start
+  }
+
+  fn name(&mut self) -> Option<&'a [u8]> {
+    let start = self.pos;
+    while self.pos < self.src.len() && is_name_char(self.src[self.pos]) {
+      self.pos += 1;
+    }
+    Some(&self.src[start..self.pos]).filter(|n| !n.is_empty())
+  }
+
+  fn expect(&mut self, c: u8) -> Option<()> {
+    if self.src.get(self.pos) == Some(&c) {
+      self.pos += 1;
+      Some(())
+    } else {
+      None
+    }
+  }
+
+  // Returns the slice until `delim`, and moves past `delim`.
+  fn until(&mut self, delim: &[u8]) -> Option<&'a [u8]> {
+    let len = find(self.rest(), delim)?;
+    let slice = &self.src[self.pos..self.pos + len];
+    self.pos += len + delim.len();
+    Some(slice)
+  }
+
+  // `<!DOCTYPE` can have an internal subset in brackets, which can contain `>` in quoted literals and declarations.
+  fn doctype(&mut self) -> Option<&'a [u8]> {
+    let start = self.pos;
+    let mut quote = None;
+    let mut depth = 0usize;
+    while let Some(&c) = self.src.get(self.pos) {
+      self.pos += 1;
+      match (quote, c) {
+        (Some(q), c) if c == q => quote = None,
+        (Some(_), _) => {}
+        (None, b'"' | b'\'') => quote = Some(c),
+        (None, b'[') => depth += 1,
+        (None, b']') => depth = depth.checked_sub(1)?,
+        (None, b'>') if depth == 0 => return Some(&self.src[start..self.pos]),
+        _ => {}
+      };
+    }
+    None
+  }
+}
+
+// Start tags are written without their `>` until their first content is written, so that elements without content can be self closed instead.
+fn end_start_tag(out: &mut Vec<u8>, start_tag_open: &mut bool) {
+  if *start_tag_open {
+    out.push(b'>');
+    *start_tag_open = false;
+  };
+}
+
+fn write_text(
+  cfg: &XmlCfg,
+  out: &mut Vec<u8>,
+  start_tag_open: &mut bool,
+  text: &[u8],
+  in_root: bool,
+) -> Option<()> {
+  if text.iter().all(|&c| is_xml_whitespace(c)) {
+    // Whitespace outside the root element is insignificant.
+    if in_root && !cfg.collapse_whitespace {
+      end_start_tag(out, start_tag_open);
+      out.extend_from_slice(text);
+    };
+    return Some(());
+  };
+  if !in_root {
+    return None;
+  };
+  end_start_tag(out, start_tag_open);
+  if cfg.collapse_whitespace {
+    let mut text = text.to_vec();
+    collapse_whitespace(&mut text);
+    out.extend_from_slice(&text);
+  } else {
+    out.extend_from_slice(text);
+  };
+  Some(())
+}
+
+fn write_cdata(cfg: &XmlCfg, out: &mut Vec<u8>, data: &[u8]) {
+  let start = out.len();
+  if !cfg.keep_cdata {
+    for &c in data {
+      match c {
+        b'<' => out.extend_from_slice(b"&lt;"),
+        b'&' => out.extend_from_slice(b"&amp;"),
+        // `]]>` is not allowed in text, including when the `]]` was written before this section.
+        b'>' if out.ends_with(b"]]") => out.extend_from_slice(b"&gt;"),
+        c => out.push(c),
+      };
+    }
+  };
+  // Whitespace in CDATA is always significant, so it's never collapsed.
+  if (cfg.keep_cdata || out.len() - start >= data.len() + 12) && find(data, b"]]>").is_none() {
+    out.truncate(start);
+    out.extend_from_slice(b"<![CDATA[");
+    out.extend_from_slice(data);
+    out.extend_from_slice(b"]]>");
+  };
+}
+
+fn write_attr(cfg: &XmlCfg, out: &mut Vec<u8>, name: &[u8], quote: u8, value: &[u8]) {
+  out.push(b' ');
+  out.extend_from_slice(name);
+  out.push(b'=');
+  if cfg.normalize_attribute_quotes && quote == b'\'' {
+    out.push(b'"');
+    for &c in value {
+      if c == b'"' {
+        out.extend_from_slice(b"&quot;");
+      } else {
+        out.push(c);
+      };
+    }
+    out.push(b'"');
+  } else {
+    out.push(quote);
+    out.extend_from_slice(value);
+    out.push(quote);
+  };
+}
+
+// Decodes text that only uses the predefined XML entities and character references.
+fn decode_xml_text(text: &[u8]) -> Option<Vec<u8>> {
+  let mut out = Vec::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(amp) = memchr(b'&', rest) {
+    out.extend_from_slice(&rest[..amp]);
+    rest = &rest[amp + 1..];
+    let end = memchr(b';', rest)?;
+    let c = match &rest[..end] {
+      b"amp" => '&',
+      b"apos" => '\'',
+      b"gt" => '>',
+      b"lt" => '<',
+      b"quot" => '"',
+      r => {
+        let r = from_utf8(r.strip_prefix(b"#")?).ok()?;
+        let value = match r.strip_prefix('x') {
+          Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+          None => r.parse().ok()?,
+        };
+        char::from_u32(value)?
+      }
+    };
+    out.extend_from_slice(c.encode_utf8(&mut [0u8; 4]).as_bytes());
+    rest = &rest[end + 1..];
+  }
+  out.extend_from_slice(rest);
+  Some(out)
+}
+
+// Given an element's name and attributes, returns whether its content is HTML.
+type IsHtml = fn(&[u8], &[(&[u8], &[u8])]) -> bool;
+
+// Elements whose content is HTML, escaped or in CDATA, such as `<description>` in RSS.
+struct HtmlContent<'c> {
+  cfg: &'c Cfg,
+  is_html: IsHtml,
+}
+
+// Reads the content and end tag of an element containing HTML, returning the decoded HTML, or `None` if the content contains anything other than text and CDATA.
+fn read_html_content(xml: &mut Xml, name: &[u8]) -> Option<Vec<u8>> {
+  let mut html = Vec::new();
+  loop {
+    let text_len = memchr(b'<', xml.rest())?;
+    html.extend_from_slice(&decode_xml_text(&xml.rest()[..text_len])?);
+    xml.pos += text_len;
+    if xml.rest().starts_with(b"<![CDATA[") {
+      xml.pos += 9;
+      html.extend_from_slice(xml.until(b"]]>")?);
+    } else if xml.rest().starts_with(b"</") {
+      xml.pos += 2;
+      if xml.name()? != name {
+        return None;
+      };
+      xml.skip_whitespace();
+      xml.expect(b'>')?;
+      return Some(html);
+    } else {
+      return None;
+    };
+  }
+}
+
+fn minify_xml_or_none(
+  src: &[u8],
+  cfg: &XmlCfg,
+  html_content: Option<&HtmlContent>,
+) -> Option<Vec<u8>> {
+  let mut xml = Xml { src, pos: 0 };
+  let mut out = Vec::with_capacity(src.len());
+  let mut open = Vec::<&[u8]>::new();
+  let mut start_tag_open = false;
+  let mut seen_root = false;
+  while xml.pos < src.len() {
+    let text_len = memchr(b'<', xml.rest()).unwrap_or(xml.rest().len());
+    if text_len > 0 {
+      let text = &src[xml.pos..xml.pos + text_len];
+      xml.pos += text_len;
+      write_text(cfg, &mut out, &mut start_tag_open, text, !open.is_empty())?;
+      continue;
+    };
+    let rest = xml.rest();
+    if rest.starts_with(b"<!--") {
+      xml.pos += 4;
+      let code = xml.until(b"-->")?;
+      if cfg.keep_comments {
+        end_start_tag(&mut out, &mut start_tag_open);
+        out.extend_from_slice(b"<!--");
+        out.extend_from_slice(code);
+        out.extend_from_slice(b"-->");
+      };
+    } else if rest.starts_with(b"<![CDATA[") {
+      if open.is_empty() {
+        return None;
+      };
+      xml.pos += 9;
+      let data = xml.until(b"]]>")?;
+      end_start_tag(&mut out, &mut start_tag_open);
+      write_cdata(cfg, &mut out, data);
+    } else if rest.starts_with(b"<!DOCTYPE") {
+      if seen_root {
+        return None;
+      };
+      out.extend_from_slice(xml.doctype()?);
+    } else if rest.starts_with(b"<?") {
+      xml.pos += 2;
+      let code = xml.until(b"?>")?;
+      end_start_tag(&mut out, &mut start_tag_open);
+      out.extend_from_slice(b"<?");
+      out.extend_from_slice(code);
+      out.extend_from_slice(b"?>");
+    } else if rest.starts_with(b"<!") {
+      // Markup declarations are only allowed in the doctype's internal subset.
+      return None;
+    } else if rest.starts_with(b"</") {
+      xml.pos += 2;
+      let name = xml.name()?;
+      xml.skip_whitespace();
+      xml.expect(b'>')?;
+      if open.pop()? != name {
+        return None;
+      };
+      if start_tag_open {
+        // An element without content can always be self closed.
+        out.extend_from_slice(b"/>");
+        start_tag_open = false;
+      } else {
+        out.extend_from_slice(b"</");
+        out.extend_from_slice(name);
+        out.push(b'>');
+      };
+    } else {
+      xml.pos += 1;
+      let name = xml.name()?;
+      if open.is_empty() {
+        if seen_root {
+          return None;
+        };
+        seen_root = true;
+      };
+      end_start_tag(&mut out, &mut start_tag_open);
+      out.push(b'<');
+      out.extend_from_slice(name);
+      let mut attrs = Vec::<(&[u8], &[u8])>::new();
+      let self_closing = loop {
+        let had_whitespace = xml.skip_whitespace();
+        if xml.expect(b'>').is_some() {
+          break false;
+        };
+        if xml.expect(b'/').is_some() {
+          xml.expect(b'>')?;
+          break true;
+        };
+        if !had_whitespace {
+          return None;
+        };
+        let attr_name = xml.name()?;
+        if attrs.iter().any(|(n, _)| *n == attr_name) {
+          return None;
+        };
+        xml.skip_whitespace();
+        xml.expect(b'=')?;
+        xml.skip_whitespace();
+        let quote = *src.get(xml.pos).filter(|&&c| c == b'"' || c == b'\'')?;
+        xml.pos += 1;
+        let value = xml.until(&[quote])?;
+        if value.contains(&b'<') {
+          return None;
+        };
+        write_attr(cfg, &mut out, attr_name, quote, value);
+        attrs.push((attr_name, value));
+      };
+      let html = html_content
+        .filter(|h| !self_closing && (h.is_html)(name, &attrs))
+        .and_then(|h| {
+          let start = xml.pos;
+          let html = read_html_content(&mut xml, name).map(|html| crate::minify(&html, h.cfg));
+          if html.is_none() {
+            xml.pos = start;
+          };
+          html
+        });
+      if self_closing || html.as_ref().filter(|h| h.is_empty()).is_some() {
+        out.extend_from_slice(b"/>");
+      } else if let Some(html) = html {
+        out.push(b'>');
+        write_cdata(cfg, &mut out, &html);
+        out.extend_from_slice(b"</");
+        out.extend_from_slice(name);
+        out.push(b'>');
+      } else {
+        start_tag_open = true;
+        open.push(name);
+      };
+    };
+  }
+  if !open.is_empty() || !seen_root {
+    return None;
+  };
+  Some(out)
+}
+
+/// Minifies an XML document, such as a feed, sitemap, or XHTML page. Unlike `minify`, tag and attribute names are case sensitive, no tags are ever omitted, and entities are left as is.
+///
+/// If `src` isn't a well-formed document, for example because its tags aren't balanced, it's returned unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{XmlCfg, minify_xml};
+///
+/// let mut cfg = XmlCfg::default();
+/// cfg.collapse_whitespace = true;
+/// let minified = minify_xml(b"<feed>\n  <title> News </title>\n  <link href='/'></link>\n</feed>", &cfg);
+/// assert_eq!(minified, b"<feed><title> News </title><link href='/'/></feed>".to_vec());
+/// ```
+pub fn minify_xml(src: &[u8], cfg: &XmlCfg) -> Vec<u8> {
+  minify_xml_or_none(src, cfg, None).unwrap_or_else(|| src.to_vec())
+}
+
+fn minify_feed(src: &[u8], cfg: &Cfg, is_html: IsHtml) -> Vec<u8> {
+  let xml_cfg = XmlCfg {
+    collapse_whitespace: true,
+    keep_cdata: false,
+    keep_comments: cfg.keep_comments,
+    normalize_attribute_quotes: false,
+  };
+  minify_xml_or_none(src, &xml_cfg, Some(&HtmlContent { cfg, is_html }))
+    .unwrap_or_else(|| src.to_vec())
+}
+
+/// Minifies an RSS feed with `minify_xml`, collapsing whitespace in text. The HTML in `<description>` and `<content:encoded>` elements, whether escaped or in CDATA, is also minified with `minify` using `cfg`, and then written back in whichever of the two forms is shorter.
+///
+/// # Examples
+///
+/// ```
+/// use minify_html::{Cfg, minify_rss};
+///
+/// let src = b"<rss>\n  <item>\n    <description>&lt;p&gt;  Hello,   world!  &lt;/p&gt;</description>\n  </item>\n</rss>";
+/// let minified = minify_rss(src, &Cfg::new());
+/// assert_eq!(minified, b"<rss><item><description>&lt;p>Hello, world!</description></item></rss>".to_vec());
+/// ```
+pub fn minify_rss(src: &[u8], cfg: &Cfg) -> Vec<u8> {
+  minify_feed(src, cfg, |name, _| {
+    matches!(name, b"content:encoded" | b"description")
+  })
+}
+
+/// Minifies an Atom feed like `minify_rss`, where the HTML minified is in text constructs and `<content>` elements with `type="html"`.
+pub fn minify_atom(src: &[u8], cfg: &Cfg) -> Vec<u8> {
+  minify_feed(src, cfg, |name, attrs| {
+    matches!(
+      name,
+      b"content" | b"rights" | b"subtitle" | b"summary" | b"title"
+    ) && attrs.iter().any(|&(n, v)| n == b"type" && v == b"html")
+  })
+}
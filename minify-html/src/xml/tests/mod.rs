@@ -0,0 +1,92 @@
+use crate::cfg::Cfg;
+use crate::xml::minify_atom;
+use crate::xml::minify_rss;
+use crate::xml::minify_xml;
+use crate::xml::XmlCfg;
+use std::str::from_utf8;
+
+fn eval(src: &[u8], expected: &[u8], cfg: &XmlCfg) {
+  let min = minify_xml(src, cfg);
+  assert_eq!(from_utf8(&min).unwrap(), from_utf8(expected).unwrap());
+}
+
+#[test]
+fn test_minify_xml_defaults() {
+  let cfg = XmlCfg::default();
+  eval(
+    b"<?xml version=\"1.0\"?>\n<!-- feed -->\n<rss version='2.0'>\n  <channel><title>A &amp; B</title><image></image></channel>\n</rss>\n",
+    b"<?xml version=\"1.0\"?><rss version='2.0'>\n  <channel><title>A &amp; B</title><image/></channel>\n</rss>",
+    &cfg,
+  );
+  // Tag names are case sensitive, so `<A>` is not closed by `</a>`, and malformed documents are returned unchanged.
+  eval(b"<A>x</a>", b"<A>x</a>", &cfg);
+  eval(b"<a><b></a></b>", b"<a><b></a></b>", &cfg);
+  eval(b"<a>x", b"<a>x", &cfg);
+  eval(b"<a/><b/>", b"<a/><b/>", &cfg);
+  eval(b"<a x='1' x='2'/>", b"<a x='1' x='2'/>", &cfg);
+}
+
+#[test]
+fn test_minify_xml_options() {
+  let mut cfg = XmlCfg::default();
+  cfg.collapse_whitespace = true;
+  cfg.keep_comments = true;
+  cfg.normalize_attribute_quotes = true;
+  eval(
+    b"<urlset>\n  <!-- a -->\n  <url  a = 'x\"y'  b=\"z\" >\n    <loc>  https://example.com/  </loc>\n  </url>\n</urlset>",
+    b"<urlset><!-- a --><url a=\"x&quot;y\" b=\"z\"><loc> https://example.com/ </loc></url></urlset>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_minify_xml_cdata() {
+  let mut cfg = XmlCfg::default();
+  // Rewritten as text only when shorter.
+  eval(
+    b"<a><![CDATA[x < y]]><![CDATA[<<<<]]><![CDATA[]]]]><![CDATA[>]]></a>",
+    b"<a>x &lt; y<![CDATA[<<<<]]>]]&gt;</a>",
+    &cfg,
+  );
+  cfg.keep_cdata = true;
+  eval(
+    b"<a><![CDATA[x < y]]></a>",
+    b"<a><![CDATA[x < y]]></a>",
+    &cfg,
+  );
+}
+
+#[test]
+fn test_minify_xml_doctype_internal_subset() {
+  eval(
+    b"<!DOCTYPE a [\n<!ENTITY b \"<c>\">\n]>\n<a>&b;</a>",
+    b"<!DOCTYPE a [\n<!ENTITY b \"<c>\">\n]><a>&b;</a>",
+    &XmlCfg::default(),
+  );
+}
+
+#[test]
+fn test_minify_rss() {
+  let cfg = Cfg::new();
+  let min = minify_rss(
+    b"<?xml version=\"1.0\"?>\n<rss version=\"2.0\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\">\n  <channel>\n    <title>My   blog</title>\n    <item>\n      <description>&lt;p&gt;  A &amp;amp; B  &lt;/p&gt;</description>\n      <content:encoded><![CDATA[<div>  <p>  x  </p>  <!-- c --> <b>&lt;&gt;</b> </div>]]></content:encoded>\n      <description><![CDATA[<p>]]]]><![CDATA[>]]></description>\n    </item>\n  </channel>\n</rss>",
+    &cfg,
+  );
+  assert_eq!(
+    from_utf8(&min).unwrap(),
+    "<?xml version=\"1.0\"?><rss version=\"2.0\" xmlns:content=\"http://purl.org/rss/1.0/modules/content/\"><channel><title>My blog</title><item><description>&lt;p>A &amp; B</description><content:encoded><![CDATA[<div><p>x</p><b>&lt;></b></div>]]></content:encoded><description>&lt;p>]]&gt;</description></item></channel></rss>",
+  );
+}
+
+#[test]
+fn test_minify_atom() {
+  let cfg = Cfg::new();
+  let min = minify_atom(
+    b"<feed xmlns=\"http://www.w3.org/2005/Atom\">\n  <title type=\"html\">&lt;b&gt;  A  &lt;/b&gt;</title>\n  <entry><content type=\"text\">&lt;b&gt;  B  &lt;/b&gt;</content><summary type=\"html\"></summary></entry>\n</feed>",
+    &cfg,
+  );
+  assert_eq!(
+    from_utf8(&min).unwrap(),
+    "<feed xmlns=\"http://www.w3.org/2005/Atom\"><title type=\"html\">&lt;b> A &lt;/b></title><entry><content type=\"text\">&lt;b&gt; B &lt;/b&gt;</content><summary type=\"html\"/></entry></feed>",
+  );
+}
@@ -0,0 +1,40 @@
+use minify_html::minify;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+
+#[path = "../benches/common/mod.rs"]
+mod common;
+
+const SIZES_FILE: &str = "sizes.txt";
+
+// Checks that the minified size of each page in the corpus with each `Cfg` in `common::cfgs` hasn't changed, so that unintentional changes in effectiveness are noticed. Run with `UPDATE_CORPUS_SIZES=1` to update the expected sizes after an intentional change.
+#[test]
+fn test_corpus_sizes() {
+  let path = common::corpus_dir().join(SIZES_FILE);
+  let mut actual = BTreeMap::new();
+  for (cfg_name, cfg) in common::cfgs() {
+    for &page in common::PAGES {
+      let min = minify(&common::read_page(page), &cfg);
+      actual.insert(format!("{} {}", page, cfg_name), min.len());
+    }
+  }
+  if env::var_os("UPDATE_CORPUS_SIZES").is_some() {
+    let sizes = actual
+      .iter()
+      .map(|(k, v)| format!("{} {}\n", k, v))
+      .collect::<String>();
+    fs::write(&path, sizes).unwrap();
+    return;
+  };
+  let expected = fs::read_to_string(&path)
+    .unwrap()
+    .lines()
+    .filter_map(|l| l.rsplit_once(' '))
+    .map(|(k, v)| (k.to_string(), v.parse::<usize>().unwrap()))
+    .collect::<BTreeMap<_, _>>();
+  assert_eq!(
+    actual, expected,
+    "minified sizes of the corpus have changed; if this is intended, rerun with UPDATE_CORPUS_SIZES=1"
+  );
+}